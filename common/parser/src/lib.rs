@@ -19,8 +19,10 @@ pub const EVENT_KEY: &str = "event";
 
 // This regex is used to match the `${event.something}` pattern.
 // It literally matches the sequence `${` followed by one or more chars followed by `}`.
+// A `}` within double quotes, e.g. in the fallback literal of `${a ?? "}"}`, is not the end.
 lazy_static! {
-    static ref RE: Regex = Regex::new(r"(\$\{[^}]+})").expect("Accessor regex must be valid");
+    static ref RE: Regex =
+        Regex::new(r#"(\$\{(?:"[^"]*"|[^}])+})"#).expect("Accessor regex must be valid");
 }
 
 pub struct Template<'template> {
@@ -61,17 +63,28 @@ pub trait CustomParser: Sync + Send + Debug {
     fn parse_value<'o>(&'o self, value: &'o Value, context: &str) -> Option<Cow<'o, Value>>;
 }
 
+/// A single step of an accessor expression.
+/// - `ArrayFromEnd` accesses an array element counting from the end, where 1 is the last element.
+///   It is built from negative indexes, e.g. `[-1]`.
+/// - `Wildcard` projects all the elements of an array (`[*]`) or all the values of a map (`.*`).
 #[derive(PartialEq, Debug, Clone)]
 pub enum ValueGetter {
     Map { key: String },
     Array { index: usize },
+    ArrayFromEnd { index: usize },
+    Wildcard,
 }
 
 impl ValueGetter {
+    /// Returns the single value selected by this getter.
+    /// A Wildcard selects multiple values, so it is resolved by the AccessorExpression instead,
+    /// and this method always returns None for it.
     pub fn get<'o, I: ValueGet>(&self, value: &'o I) -> Option<&'o Value> {
         match self {
             ValueGetter::Map { key } => value.get_from_map(key),
             ValueGetter::Array { index } => value.get_from_array(*index),
+            ValueGetter::ArrayFromEnd { index } => value.get_from_array_end(*index),
+            ValueGetter::Wildcard => None,
         }
     }
 }
@@ -127,6 +140,12 @@ mod test {
         assert!(!template.is_accessor());
     }
 
+    #[test]
+    fn accessor_template_with_braces_within_quotes_is_an_accessor() {
+        let template = Template::from(r#"${sadf ?? "}"}"#);
+        assert!(template.is_accessor());
+    }
+
     #[test]
     fn accessor_template_is_not_an_accessor() {
         let template = Template::from("${sadf}");
@@ -14,7 +14,10 @@ const PAYLOAD_KEY_PARSE_REGEX: &str = r#"("[^"]+"|[^\.^\[]+|\[[^\]]+\])"#;
 const PAYLOAD_MAP_KEY_PARSE_TRAILING_DELIMITER: &str = "\"";
 const PAYLOAD_ARRAY_KEY_START_DELIMITER: &str = "[";
 const PAYLOAD_ARRAY_KEY_END_DELIMITER: &str = "]";
+const PAYLOAD_ARRAY_FROM_END_PREFIX: &str = "-";
+const WILDCARD_KEY: &str = "*";
 pub const EXTRACTED_VARIABLES_KEY: &str = "_variables";
+pub const FALLBACK_OPERATOR: &str = "??";
 
 lazy_static! {
     static ref RE: Regex = Regex::new(PAYLOAD_KEY_PARSE_REGEX).expect("Parser regex must be valid");
@@ -79,20 +82,14 @@ impl ParserBuilder {
             )
             .add_ignored_expression(FOREACH_ITEM_KEY.to_owned());
 
-        match parser_builder.build_parser(input) {
-            Ok(Parser::Exp(AccessorExpression { keys })) if is_valid_matcher_root(&keys) => {
-                Ok(Parser::Exp(AccessorExpression { keys }))
-            }
-            Ok(Parser::Exp(AccessorExpression { mut keys })) => match keys.first_mut() {
-                Some(ValueGetter::Array { index }) => {
-                    Err(ParserError::UnknownKeyError { key: format!("{}", index) })
-                }
-                Some(ValueGetter::Map { key }) => {
-                    Err(ParserError::UnknownKeyError { key: std::mem::take(key) })
-                }
-                None => Err(ParserError::EmptyAccessorError),
-            },
-            res => res,
+        match parser_builder.build_parser(input)? {
+            Parser::Fallback { parsers } => Ok(Parser::Fallback {
+                parsers: parsers
+                    .into_iter()
+                    .map(validate_matcher_root)
+                    .collect::<Result<Vec<_>, ParserError>>()?,
+            }),
+            parser => validate_matcher_root(parser),
         }
     }
 
@@ -111,15 +108,41 @@ impl ParserBuilder {
     fn parse_expression(&self, keys: &str) -> Result<Parser, ParserError> {
         let expression = &keys[2..keys.len() - 1];
 
+        let alternatives = split_fallback_alternatives(expression);
+        if alternatives.len() > 1 {
+            let parsers = alternatives
+                .into_iter()
+                .map(|alternative| self.parse_fallback_alternative(alternative))
+                .collect::<Result<Vec<_>, ParserError>>()?;
+            return Ok(Parser::Fallback { parsers });
+        }
+
+        self.parse_accessor(expression)
+    }
+
+    /// Parses a single alternative of a fallback expression.
+    /// An alternative is either a scalar JSON literal (e.g. `"unknown"`, `0`, `true`, `null`)
+    /// or an accessor without the enclosing delimiters (e.g. `event.payload.severity`).
+    fn parse_fallback_alternative(&self, alternative: &str) -> Result<Parser, ParserError> {
+        let alternative = alternative.trim();
+        if alternative.is_empty() {
+            return Err(ParserError::EmptyAccessorError);
+        }
+        match serde_json::from_str::<Value>(alternative) {
+            Ok(Value::Array(_)) | Ok(Value::Object(_)) | Err(_) => self.parse_accessor(alternative),
+            Ok(literal) => Ok(Parser::Val(literal)),
+        }
+    }
+
+    fn parse_accessor(&self, expression: &str) -> Result<Parser, ParserError> {
         let getters = Parser::parse_keys(expression)?;
         let (head, tail) = match getters.as_slice() {
             [] => return Err(ParserError::EmptyAccessorError), // "${}"
-            [ValueGetter::Map { .. }] // "${event}"
-            | [ValueGetter::Array { .. }, ..] // "${[123]event}"
-            | [ValueGetter::Map { .. }, ValueGetter::Array { .. }, ..] => { // "${event[123]}"
+            [ValueGetter::Map { key }, tail @ ..] // "${event.timestamp}"
+                if matches!(tail.first(), Some(ValueGetter::Map { .. })) => (key, tail),
+            _ => { // "${event}", "${[123]event}", "${event[123]}", "${event.*}"
                 return Ok(Parser::Exp(AccessorExpression { keys: getters }))
             }
-            [ValueGetter::Map { key }, tail @ ..] => (key, tail), // "${event.timestamp}"
         };
 
         for (key, factory) in &self.custom_parser_factories {
@@ -141,6 +164,7 @@ pub enum Parser {
     Interpolator { interpolator: StringInterpolator },
     Val(Value),
     Custom { key: ValueGetter, parser: Box<dyn CustomParser> },
+    Fallback { parsers: Vec<Parser> },
 }
 
 #[derive(Debug)]
@@ -150,13 +174,34 @@ pub struct AccessorExpression {
 
 impl AccessorExpression {
     pub fn parse_value<'o, I: ValueGet>(&'o self, value: &'o I) -> Option<Cow<'o, Value>> {
-        let mut iter = self.keys.iter();
-        let mut tmp = iter.next()?.get(value)?;
-        for key in iter {
-            tmp = key.get(tmp)?;
+        let (first, rest) = self.keys.split_first()?;
+        resolve_keys(first.get(value)?, rest)
+    }
+}
+
+/// Resolves the keys against the value.
+/// When a Wildcard is found, the remaining keys are resolved against every element of the
+/// array (or every value of the map) and the results are collected in a new array.
+/// The elements for which the remaining keys cannot be resolved are skipped.
+fn resolve_keys<'o>(value: &'o Value, keys: &[ValueGetter]) -> Option<Cow<'o, Value>> {
+    let mut tmp = value;
+    for (position, key) in keys.iter().enumerate() {
+        if let ValueGetter::Wildcard = key {
+            let remaining_keys = &keys[position + 1..];
+            let projection = match tmp {
+                Value::Array(array) => project(array.iter(), remaining_keys),
+                Value::Object(map) => project(map.values(), remaining_keys),
+                _ => return None,
+            };
+            return Some(Cow::Owned(Value::Array(projection)));
         }
-        Some(Cow::Borrowed(tmp))
+        tmp = key.get(tmp)?;
     }
+    Some(Cow::Borrowed(tmp))
+}
+
+fn project<'o>(values: impl Iterator<Item = &'o Value>, keys: &[ValueGetter]) -> Vec<Value> {
+    values.filter_map(|value| resolve_keys(value, keys)).map(Cow::into_owned).collect()
 }
 
 impl Parser {
@@ -164,6 +209,9 @@ impl Parser {
         RE.find_iter(expression)
             .map(|next_match| {
                 let next_match_string = next_match.as_str();
+                if next_match_string == WILDCARD_KEY {
+                    return Ok(ValueGetter::Wildcard);
+                }
                 let mut result = next_match_string.to_string();
 
                 {
@@ -179,10 +227,7 @@ impl Parser {
                         PAYLOAD_ARRAY_KEY_START_DELIMITER,
                         PAYLOAD_ARRAY_KEY_END_DELIMITER,
                     ) {
-                        return match key.parse() {
-                            Ok(index) => Ok(ValueGetter::Array { index }),
-                            Err(_) => Err(ParserError::NotANumberError { key: key.to_owned() }),
-                        };
+                        return parse_array_key(key);
                     }
                     if result.contains(PAYLOAD_MAP_KEY_PARSE_TRAILING_DELIMITER) {
                         return Err(ParserError::InvalidCharacterError {
@@ -210,8 +255,80 @@ impl Parser {
             Parser::Custom { key, parser } => {
                 key.get(value).and_then(|val| parser.parse_value(val, context))
             }
+            // Resolves to the first alternative that returns a value which is not `null`
+            Parser::Fallback { parsers } => {
+                let mut null_value = None;
+                for parser in parsers {
+                    match parser.parse_value(value, context) {
+                        Some(result) if !result.is_null() => return Some(result),
+                        Some(result) => null_value = Some(result),
+                        None => {}
+                    }
+                }
+                null_value
+            }
+        }
+    }
+}
+
+fn validate_matcher_root(parser: Parser) -> Result<Parser, ParserError> {
+    match parser {
+        Parser::Exp(AccessorExpression { keys }) if is_valid_matcher_root(&keys) => {
+            Ok(Parser::Exp(AccessorExpression { keys }))
+        }
+        Parser::Exp(AccessorExpression { mut keys }) => match keys.first_mut() {
+            Some(ValueGetter::Array { index }) => {
+                Err(ParserError::UnknownKeyError { key: format!("{}", index) })
+            }
+            Some(ValueGetter::ArrayFromEnd { index }) => Err(ParserError::UnknownKeyError {
+                key: format!("{}{}", PAYLOAD_ARRAY_FROM_END_PREFIX, index),
+            }),
+            Some(ValueGetter::Wildcard) => {
+                Err(ParserError::UnknownKeyError { key: WILDCARD_KEY.to_owned() })
+            }
+            Some(ValueGetter::Map { key }) => {
+                Err(ParserError::UnknownKeyError { key: std::mem::take(key) })
+            }
+            None => Err(ParserError::EmptyAccessorError),
+        },
+        parser => Ok(parser),
+    }
+}
+
+/// Splits an expression on the fallback operator `??`.
+/// Occurrences of the operator within double quotes are not considered.
+fn split_fallback_alternatives(expression: &str) -> Vec<&str> {
+    let mut alternatives = vec![];
+    let mut in_quotes = false;
+    let mut start = 0;
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '"' => in_quotes = !in_quotes,
+            '?' if !in_quotes && matches!(chars.peek(), Some((_, '?'))) => {
+                alternatives.push(&expression[start..index]);
+                chars.next();
+                start = index + FALLBACK_OPERATOR.len();
+            }
+            _ => {}
         }
     }
+    alternatives.push(&expression[start..]);
+    alternatives
+}
+
+fn parse_array_key(key: &str) -> Result<ValueGetter, ParserError> {
+    if key == WILDCARD_KEY {
+        return Ok(ValueGetter::Wildcard);
+    }
+    if let Ok(index) = key.parse() {
+        return Ok(ValueGetter::Array { index });
+    }
+    match key.strip_prefix(PAYLOAD_ARRAY_FROM_END_PREFIX).map(str::parse::<usize>) {
+        Some(Ok(index)) if index > 0 => Ok(ValueGetter::ArrayFromEnd { index }),
+        _ => Err(ParserError::NotANumberError { key: key.to_owned() }),
+    }
 }
 
 fn get_key_between_delimiters<'input_string>(
@@ -526,6 +643,114 @@ mod test {
         );
     }
 
+    #[test]
+    fn builder_should_parse_wildcards_and_negative_indexes() {
+        let expected: Vec<ValueGetter> = vec!["items".into(), ValueGetter::Wildcard, "name".into()];
+        assert_eq!(expected, Parser::parse_keys("items[*].name").unwrap());
+
+        let expected: Vec<ValueGetter> = vec!["labels".into(), ValueGetter::Wildcard];
+        assert_eq!(expected, Parser::parse_keys("labels.*").unwrap());
+
+        let expected: Vec<ValueGetter> = vec!["labels".into(), "*".into()];
+        assert_eq!(expected, Parser::parse_keys(r#"labels."*""#).unwrap());
+
+        let expected: Vec<ValueGetter> =
+            vec!["items".into(), ValueGetter::ArrayFromEnd { index: 1 }, "name".into()];
+        assert_eq!(expected, Parser::parse_keys("items[-1].name").unwrap());
+
+        assert!(Parser::parse_keys("items[-0]").is_err());
+        assert!(Parser::parse_keys("items[-a]").is_err());
+    }
+
+    #[test]
+    fn parser_expression_should_return_elements_from_the_end_of_an_array() {
+        // Arrange
+        let value = json!({
+            "items": ["one", "two", "three"]
+        });
+
+        // Assert
+        let parser = ParserBuilder::default().build_parser("${items[-1]}").unwrap();
+        assert_eq!(&json!("three"), parser.parse_value(&value, "").unwrap().as_ref());
+
+        let parser = ParserBuilder::default().build_parser("${items[-3]}").unwrap();
+        assert_eq!(&json!("one"), parser.parse_value(&value, "").unwrap().as_ref());
+
+        let parser = ParserBuilder::default().build_parser("${items[-4]}").unwrap();
+        assert!(parser.parse_value(&value, "").is_none());
+    }
+
+    #[test]
+    fn parser_expression_should_project_a_field_over_an_array() {
+        // Arrange
+        let parser = ParserBuilder::default().build_parser("${items[*].name}").unwrap();
+        let value = json!({
+            "items": [
+                { "name": "one" },
+                { "other": "two" },
+                { "name": { "inner": "three" } }
+            ]
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!(["one", { "inner": "three" }]), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_expression_should_project_the_values_of_a_map() {
+        // Arrange
+        let parser = ParserBuilder::default().build_parser("${labels.*}").unwrap();
+        let value = json!({
+            "labels": {
+                "first": "one",
+                "second": 2
+            }
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!(["one", 2]), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_expression_should_support_nested_projections() {
+        // Arrange
+        let parser = ParserBuilder::default().build_parser("${hosts[*].services.*.state}").unwrap();
+        let value = json!({
+            "hosts": [
+                { "services": { "ping": { "state": 0 }, "http": { "state": 2 } } },
+                { "services": {} },
+                { "other": {} }
+            ]
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!([[2, 0], []]), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_expression_should_return_none_if_projecting_a_scalar() {
+        // Arrange
+        let parser = ParserBuilder::default().build_parser("${items[*].name}").unwrap();
+        let value = json!({
+            "items": "one"
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert!(result.is_none());
+    }
+
     #[test]
     fn payload_key_parser_should_fail_if_key_contains_double_quotes() {
         // Act
@@ -720,6 +945,160 @@ mod test {
         assert_eq!(&json!(1), result.as_ref());
     }
 
+    #[test]
+    fn parser_expression_should_return_the_fallback_literal_if_not_present() {
+        // Arrange
+        let parser = ParserBuilder::default()
+            .build_parser(r#"${level_one.level_three ?? "unknown"}"#)
+            .unwrap();
+        let value = json!({
+            "level_one": {
+                "level_two": "level_two_value"
+            }
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!("unknown"), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_expression_should_not_use_the_fallback_if_present() {
+        // Arrange
+        let parser = ParserBuilder::default()
+            .build_parser(r#"${level_one.level_two ?? "unknown"}"#)
+            .unwrap();
+        let value = json!({
+            "level_one": {
+                "level_two": "level_two_value"
+            }
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!("level_two_value"), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_expression_should_chain_fallbacks() {
+        // Arrange
+        let parser = ParserBuilder::default()
+            .build_parser("${one.missing ?? one.null_value ?? one.two ?? 12}")
+            .unwrap();
+        let value = json!({
+            "one": {
+                "null_value": null,
+                "two": 2
+            }
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!(2), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_expression_should_support_non_string_fallback_literals() {
+        let value = json!({});
+
+        for (expression, expected) in [
+            ("${missing ?? 12.5}", json!(12.5)),
+            ("${missing ?? -1}", json!(-1)),
+            ("${missing ?? false}", json!(false)),
+            ("${missing ?? null}", json!(null)),
+        ] {
+            let parser = ParserBuilder::default().build_parser(expression).unwrap();
+            let result = parser.parse_value(&value, "");
+            assert_eq!(&expected, result.unwrap().as_ref());
+        }
+    }
+
+    #[test]
+    fn parser_expression_should_return_none_if_no_fallback_resolves() {
+        // Arrange
+        let parser =
+            ParserBuilder::default().build_parser("${one.missing ?? two.missing}").unwrap();
+        let value = json!({});
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn parser_expression_should_ignore_fallback_operator_within_double_quotes() {
+        // Arrange
+        let parser = ParserBuilder::default().build_parser(r#"${one."what??"}"#).unwrap();
+        let value = json!({
+            "one": {
+                "what??": "found"
+            }
+        });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!("found"), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_expression_should_use_fallbacks_in_interpolators() {
+        // Arrange
+        let parser = ParserBuilder::default()
+            .build_parser(r#"severity: ${one.severity ?? "unknown"}"#)
+            .unwrap();
+        let value = json!({ "one": {} });
+
+        // Act
+        let result = parser.parse_value(&value, "");
+
+        // Assert
+        assert_eq!(&json!("severity: unknown"), result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn parser_builder_should_fail_if_fallback_alternative_is_empty() {
+        assert!(matches!(
+            ParserBuilder::default().build_parser("${one.two ?? }"),
+            Err(ParserError::EmptyAccessorError)
+        ));
+    }
+
+    #[test]
+    fn engine_matcher_should_validate_the_root_of_each_fallback_alternative() {
+        assert!(ParserBuilder::engine_matcher(r#"${event.payload.one ?? "default"}"#).is_ok());
+        assert!(
+            ParserBuilder::engine_matcher("${event.payload.one ?? _variables.rule.two}").is_ok()
+        );
+        assert!(matches!(
+            ParserBuilder::engine_matcher("${event.payload.one ?? events.two}"),
+            Err(ParserError::UnknownKeyError { key }) if key == "events"
+        ));
+    }
+
+    #[test]
+    fn should_split_fallback_alternatives() {
+        assert_eq!(vec!["one.two"], split_fallback_alternatives("one.two"));
+        assert_eq!(
+            vec!["one ", r#" "default""#],
+            split_fallback_alternatives(r#"one ?? "default""#)
+        );
+        assert_eq!(vec!["one", "two", "three"], split_fallback_alternatives("one??two??three"));
+        assert_eq!(
+            vec![r#"one."a??b" "#, r#" "c??d""#],
+            split_fallback_alternatives(r#"one."a??b" ?? "c??d""#)
+        );
+    }
+
     #[derive(Debug)]
     pub struct MyParser {
         pub expression: Vec<ValueGetter>,
@@ -6,6 +6,9 @@ pub type Payload = Map<String, Value>;
 pub trait ValueGet {
     fn get_from_map(&self, key: &str) -> Option<&Value>;
     fn get_from_array(&self, index: usize) -> Option<&Value>;
+    /// Returns the element at the given position counting from the end of the array,
+    /// where 1 is the last element.
+    fn get_from_array_end(&self, index: usize) -> Option<&Value>;
 }
 
 impl<'o> ValueGet for HashMap<&'o str, &'o Value> {
@@ -15,6 +18,9 @@ impl<'o> ValueGet for HashMap<&'o str, &'o Value> {
     fn get_from_array(&self, _index: usize) -> Option<&Value> {
         None
     }
+    fn get_from_array_end(&self, _index: usize) -> Option<&Value> {
+        None
+    }
 }
 
 impl ValueGet for Map<String, Value> {
@@ -24,6 +30,9 @@ impl ValueGet for Map<String, Value> {
     fn get_from_array(&self, _index: usize) -> Option<&Value> {
         None
     }
+    fn get_from_array_end(&self, _index: usize) -> Option<&Value> {
+        None
+    }
 }
 
 impl ValueGet for HashMap<String, Value> {
@@ -33,6 +42,9 @@ impl ValueGet for HashMap<String, Value> {
     fn get_from_array(&self, _index: usize) -> Option<&Value> {
         None
     }
+    fn get_from_array_end(&self, _index: usize) -> Option<&Value> {
+        None
+    }
 }
 
 impl ValueGet for Value {
@@ -48,6 +60,14 @@ impl ValueGet for Value {
             _ => None,
         }
     }
+    fn get_from_array_end(&self, index: usize) -> Option<&Value> {
+        match self {
+            Value::Array(array) => {
+                array.len().checked_sub(index).filter(|_| index > 0).and_then(|i| array.get(i))
+            }
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -84,6 +104,22 @@ mod test {
         assert_eq!("second_value", result.unwrap());
     }
 
+    #[test]
+    fn value_array_should_return_child_from_the_end() {
+        // Arrange
+        let value = Value::Array(vec![
+            Value::String("first".to_owned()),
+            Value::String("second".to_owned()),
+            Value::String("third".to_owned()),
+        ]);
+
+        // Assert
+        assert_eq!(Some(&Value::String("third".to_owned())), value.get_from_array_end(1));
+        assert_eq!(Some(&Value::String("first".to_owned())), value.get_from_array_end(3));
+        assert_eq!(None, value.get_from_array_end(4));
+        assert_eq!(None, value.get_from_array_end(0));
+    }
+
     #[test]
     fn value_map_should_return_no_child_if_absent() {
         // Arrange
@@ -33,7 +33,7 @@ pub fn bench(c: &mut Criterion) {
         );
 
         // Add action
-        let mut action = ConfigAction { id: "log".to_owned(), payload: Map::new() };
+        let mut action = ConfigAction { id: "log".to_owned(), payload: Map::new(), when: None };
 
         action
             .payload
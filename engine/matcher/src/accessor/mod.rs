@@ -694,6 +694,45 @@ mod test {
         assert!(&accessor.is_ok());
     }
 
+    #[test]
+    fn should_return_the_fallback_value_if_accessor_is_missing() {
+        // Arrange
+        let builder = AccessorBuilder::new();
+        let accessor = builder
+            .build("rule1", r#"${event.payload.severity ?? _variables.severity ?? "unknown"}"#)
+            .unwrap();
+
+        let event = json!(Event::new("event_type_string"));
+
+        let mut extracted_vars = json!({
+            "rule1": {
+                "severity": "CRITICAL"
+            }
+        });
+        let mut empty_extracted_vars = Value::Null;
+
+        // Act
+        let result_with_var =
+            accessor.get(&(&event, &mut extracted_vars).into()).map(|value| value.into_owned());
+        let result_without_var = accessor
+            .get(&(&event, &mut empty_extracted_vars).into())
+            .map(|value| value.into_owned());
+
+        // Assert
+        assert_eq!(Some(json!("CRITICAL")), result_with_var);
+        assert_eq!(Some(json!("unknown")), result_without_var);
+        assert!(accessor.dynamic_value());
+    }
+
+    #[test]
+    fn builder_should_return_error_if_unknown_accessor_in_fallback() {
+        let builder = AccessorBuilder::new();
+
+        let accessor = builder.build("", r#"${event.payload.severity ?? events.type}"#);
+
+        assert!(accessor.is_err());
+    }
+
     #[test]
     fn should_return_nested_values_from_extracted_var() {
         // Arrange
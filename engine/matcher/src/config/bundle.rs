@@ -0,0 +1,223 @@
+use crate::config::MatcherConfig;
+use crate::error::MatcherError;
+use crate::validator::MatcherConfigValidator;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The version of the bundle format written by this release
+pub const BUNDLE_FORMAT_VERSION: &str = "1.0";
+
+/// A single-file export of the processing tree, or of one of its subtrees, that can be
+/// imported into another Tornado instance.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MatcherConfigBundle {
+    pub manifest: BundleManifest,
+    pub config: MatcherConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BundleManifest {
+    pub format_version: String,
+    pub created_ts_ms: i64,
+    /// The path of the exported node, starting from the root node.
+    /// The path of a bundle of the whole processing tree is `["root"]`.
+    pub node_path: Vec<String>,
+    pub rules_count: usize,
+    /// The hex encoded SHA-256 checksum of the exported configuration
+    pub checksum: String,
+}
+
+impl MatcherConfigBundle {
+    /// Exports the node at the given path of the configuration
+    pub fn export(config: &MatcherConfig, node_path: &[&str]) -> Result<Self, MatcherError> {
+        let node =
+            config.get_node_by_path(node_path).ok_or_else(|| MatcherError::ConfigurationError {
+                message: format!("Node for path {:?} not found", node_path),
+            })?;
+
+        Ok(MatcherConfigBundle {
+            manifest: BundleManifest {
+                format_version: BUNDLE_FORMAT_VERSION.to_owned(),
+                created_ts_ms: Local::now().timestamp_millis(),
+                node_path: node_path.iter().map(|name| name.to_string()).collect(),
+                rules_count: node.get_all_rules_count(),
+                checksum: checksum(node)?,
+            },
+            config: node.clone(),
+        })
+    }
+
+    /// Checks that the bundle has a supported format version and that the configuration
+    /// matches the manifest.
+    pub fn verify(&self) -> Result<(), MatcherError> {
+        if self.manifest.format_version != BUNDLE_FORMAT_VERSION {
+            return Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "Unsupported bundle format version [{}]. Expected [{}]",
+                    self.manifest.format_version, BUNDLE_FORMAT_VERSION
+                ),
+            });
+        }
+
+        let node_path = &self.manifest.node_path;
+        match (node_path.first(), node_path.last()) {
+            (Some(root), Some(name)) if root == "root" && name == self.config.get_name() => {}
+            _ => {
+                return Err(MatcherError::ConfigurationError {
+                    message: format!(
+                        "The bundle node path {:?} does not lead from the root to the bundled node [{}]",
+                        self.manifest.node_path,
+                        self.config.get_name()
+                    ),
+                })
+            }
+        }
+
+        let checksum = checksum(&self.config)?;
+        if checksum != self.manifest.checksum {
+            return Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "The bundle checksum [{}] does not match the one of its content [{}]",
+                    self.manifest.checksum, checksum
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the configuration with the bundled node at the path of the manifest.
+    /// An existing node at that path is replaced. The resulting configuration is validated.
+    pub fn import_into(&self, mut config: MatcherConfig) -> Result<MatcherConfig, MatcherError> {
+        self.verify()?;
+        let node_path = self.manifest.node_path.iter().map(String::as_str).collect::<Vec<_>>();
+        match node_path.as_slice() {
+            [_root] => config = self.config.clone(),
+            [parent @ .., _name] => {
+                if config.get_node_by_path(&node_path).is_some() {
+                    config.replace_node(&node_path, self.config.clone())?;
+                } else {
+                    config.create_node_in_path(parent, self.config.clone())?;
+                }
+            }
+            [] => unreachable!("The node path of a verified bundle is never empty"),
+        }
+
+        MatcherConfigValidator::new().validate(&config)?;
+        Ok(config)
+    }
+}
+
+fn checksum(config: &MatcherConfig) -> Result<String, MatcherError> {
+    // serde_json::Value keeps the keys of the objects sorted, so that the checksum does not
+    // depend on the order of the entries of the hash maps.
+    let value = serde_json::to_value(config).and_then(|value| serde_json::to_vec(&value)).map_err(
+        |err| MatcherError::InternalSystemError {
+            message: format!("Cannot serialize the configuration. Err: {:?}", err),
+        },
+    )?;
+    Ok(format!("{:x}", Sha256::digest(value)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::Rule;
+    use crate::config::Defaultable;
+
+    fn filter(name: &str, nodes: Vec<MatcherConfig>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: name.to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes,
+        }
+    }
+
+    fn ruleset(name: &str, rules: &[&str]) -> MatcherConfig {
+        MatcherConfig::Ruleset {
+            name: name.to_owned(),
+            rules: rules
+                .iter()
+                .map(|rule| Rule { name: rule.to_string(), active: true, ..Default::default() })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn should_export_and_import_a_subtree() {
+        // Arrange
+        let source = filter("root", vec![filter("master", vec![ruleset("emails", &["one"])])]);
+        let target = filter(
+            "root",
+            vec![filter("master", vec![ruleset("emails", &["old"])]), ruleset("other", &[])],
+        );
+
+        // Act
+        let bundle = MatcherConfigBundle::export(&source, &["root", "master", "emails"]).unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+        let bundle: MatcherConfigBundle = serde_json::from_str(&json).unwrap();
+        let imported = bundle.import_into(target).unwrap();
+        let created = bundle.import_into(filter("root", vec![filter("master", vec![])])).unwrap();
+
+        // Assert
+        assert_eq!(1, bundle.manifest.rules_count);
+        assert_eq!(
+            filter(
+                "root",
+                vec![filter("master", vec![ruleset("emails", &["one"])]), ruleset("other", &[])]
+            ),
+            imported
+        );
+        assert_eq!(source, created);
+    }
+
+    #[test]
+    fn should_import_the_whole_tree() {
+        // Arrange
+        let source = filter("root", vec![ruleset("emails", &["one", "two"])]);
+        let bundle = MatcherConfigBundle::export(&source, &["root"]).unwrap();
+
+        // Act
+        let imported = bundle.import_into(filter("root", vec![])).unwrap();
+
+        // Assert
+        assert_eq!(source, imported);
+    }
+
+    #[test]
+    fn should_reject_a_tampered_bundle() {
+        // Arrange
+        let source = filter("root", vec![ruleset("emails", &["one"])]);
+        let mut bundle = MatcherConfigBundle::export(&source, &["root", "emails"]).unwrap();
+        let mut wrong_version = bundle.clone();
+        wrong_version.manifest.format_version = "0.1".to_owned();
+
+        // Act
+        bundle.config = ruleset("emails", &["two"]);
+
+        // Assert
+        assert!(bundle.verify().is_err());
+        assert!(bundle.import_into(source.clone()).is_err());
+        assert!(wrong_version.verify().is_err());
+    }
+
+    #[test]
+    fn should_reject_a_bundle_without_the_parent_node() {
+        // Arrange
+        let source = filter("root", vec![filter("master", vec![ruleset("emails", &["one"])])]);
+        let bundle = MatcherConfigBundle::export(&source, &["root", "master", "emails"]).unwrap();
+
+        // Act
+        let result = bundle.import_into(filter("root", vec![]));
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
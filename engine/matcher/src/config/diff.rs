@@ -0,0 +1,670 @@
+//! The diff module compares two processing trees and lists the nodes and the rules
+//! that were added, removed, moved or changed, with the changed fields.
+
+use crate::config::rule::Rule;
+use crate::config::MatcherConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The kind of difference between the two processing trees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigChangeKind {
+    /// Present only in the second tree
+    Added,
+    /// Present only in the first tree
+    Removed,
+    /// Present in both trees, at the same position, with a different definition
+    Changed,
+    /// A node moved to a different parent, or a rule moved to a different position
+    /// in its ruleset. Its definition can be changed too.
+    Moved,
+}
+
+/// A node, or a rule of the ruleset at `node_path` when `rule` is set,
+/// that differs between the two processing trees.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// The path of the node in the second tree, or in the first one if the node was removed
+    pub node_path: Vec<String>,
+    pub rule: Option<String>,
+    pub change: ConfigChangeKind,
+    /// The path of a moved node in the first tree
+    pub moved_from: Option<Vec<String>>,
+    /// The position of the rule in the ruleset of the first tree
+    pub from_position: Option<usize>,
+    /// The position of the rule in the ruleset of the second tree
+    pub to_position: Option<usize>,
+    /// The changed fields of the node, without its children, or of the rule
+    pub fields: Vec<FieldChange>,
+}
+
+/// A field that differs between the two versions of a node or of a rule.
+/// The `field` is the path of the value in the JSON configuration, separated by dots;
+/// the array items are identified by their index, e.g. `actions.0.payload.message`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Option<Value>,
+    pub to: Option<Value>,
+}
+
+/// Returns the differences between the two processing trees, sorted by path.
+///
+/// Only the topmost node of an added or removed subtree is listed.
+/// A removed node and an added node with the same name and type are considered the same node
+/// moved to a different parent, when the name and type identify a single node on both sides;
+/// the children of a moved node are then compared with their previous version.
+/// A rule is moved when its position changed relative to the other rules of the ruleset,
+/// so the rules shifted by an insertion or a removal are not listed.
+pub fn diff_configs(from: &MatcherConfig, to: &MatcherConfig) -> Vec<ConfigChange> {
+    let mut from_nodes = BTreeMap::new();
+    collect_nodes(from, &[], &mut from_nodes);
+    let mut to_nodes = BTreeMap::new();
+    collect_nodes(to, &[], &mut to_nodes);
+    let moves = find_moved_nodes(&from_nodes, &to_nodes);
+
+    let mut changes = vec![];
+    let mut matched_from_paths = BTreeSet::new();
+    let mut added_paths = BTreeSet::new();
+    for (to_path, to_node) in &to_nodes {
+        let from_path = counterpart(to_path, &moves);
+        let from_node = match from_nodes.get(&from_path) {
+            Some(from_node) => from_node,
+            None => {
+                if !has_parent_in(to_path, &added_paths) {
+                    changes.push(node_change(to_path, ConfigChangeKind::Added, None, vec![]));
+                }
+                added_paths.insert(to_path.clone());
+                continue;
+            }
+        };
+
+        let mut fields = vec![];
+        diff_values("", &node_definition(from_node), &node_definition(to_node), &mut fields);
+        if moves.contains_key(to_path) {
+            changes.push(node_change(to_path, ConfigChangeKind::Moved, Some(&from_path), fields));
+        } else if !fields.is_empty() {
+            changes.push(node_change(to_path, ConfigChangeKind::Changed, None, fields));
+        }
+        diff_rules(to_path, node_rules(from_node), node_rules(to_node), &mut changes);
+        matched_from_paths.insert(from_path);
+    }
+
+    let removed_paths = from_nodes
+        .keys()
+        .filter(|from_path| !matched_from_paths.contains(*from_path))
+        .collect::<BTreeSet<_>>();
+    for from_path in &removed_paths {
+        let parent_removed = from_path.len() > 1
+            && removed_paths.contains(&from_path[..from_path.len() - 1].to_vec());
+        if !parent_removed {
+            changes.push(node_change(from_path, ConfigChangeKind::Removed, None, vec![]));
+        }
+    }
+
+    changes.sort_by(|first, second| {
+        (&first.node_path, &first.rule).cmp(&(&second.node_path, &second.rule))
+    });
+    changes
+}
+
+fn collect_nodes<'a>(
+    node: &'a MatcherConfig,
+    parent_path: &[String],
+    nodes: &mut BTreeMap<Vec<String>, &'a MatcherConfig>,
+) {
+    let mut path = parent_path.to_vec();
+    path.push(node.get_name().to_owned());
+    match node {
+        MatcherConfig::Filter { nodes: children, .. }
+        | MatcherConfig::Iterator { nodes: children, .. } => {
+            children.iter().for_each(|child| collect_nodes(child, &path, nodes));
+        }
+        MatcherConfig::Ruleset { .. } => {}
+    }
+    nodes.insert(path, node);
+}
+
+/// Pairs the removed and the added nodes that are the same node moved to a different parent.
+/// Returns the path of each moved node in the second tree mapped to its path in the first one.
+fn find_moved_nodes(
+    from_nodes: &BTreeMap<Vec<String>, &MatcherConfig>,
+    to_nodes: &BTreeMap<Vec<String>, &MatcherConfig>,
+) -> BTreeMap<Vec<String>, Vec<String>> {
+    let mut moves = BTreeMap::new();
+    loop {
+        let matched_from_paths = to_nodes
+            .keys()
+            .map(|to_path| counterpart(to_path, &moves))
+            .filter(|from_path| from_nodes.contains_key(from_path))
+            .collect::<BTreeSet<_>>();
+        let removed = from_nodes
+            .iter()
+            .filter(|(from_path, _)| !matched_from_paths.contains(*from_path))
+            .collect::<Vec<_>>();
+        let added = to_nodes
+            .iter()
+            .filter(|(to_path, _)| !from_nodes.contains_key(&counterpart(to_path, &moves)))
+            .collect::<Vec<_>>();
+
+        let identity = |node: &MatcherConfig| (node.get_name().to_owned(), node_type(node));
+        let count = |nodes: &[(&Vec<String>, &&MatcherConfig)]| {
+            let mut count = HashMap::new();
+            for (_, node) in nodes {
+                *count.entry(identity(node)).or_insert(0) += 1;
+            }
+            count
+        };
+        let removed_count = count(&removed);
+        let added_count = count(&added);
+
+        // The shallowest nodes first, so that a moved subtree is paired by its root
+        let moved = removed
+            .iter()
+            .filter(|(_, node)| {
+                let identity = identity(node);
+                removed_count.get(&identity) == Some(&1) && added_count.get(&identity) == Some(&1)
+            })
+            .min_by_key(|(from_path, _)| (from_path.len(), from_path.to_vec()))
+            .and_then(|(from_path, from_node)| {
+                added
+                    .iter()
+                    .find(|(_, to_node)| identity(to_node) == identity(from_node))
+                    .map(|(to_path, _)| ((*to_path).clone(), (*from_path).clone()))
+            });
+        match moved {
+            Some((to_path, from_path)) => {
+                moves.insert(to_path, from_path);
+            }
+            None => return moves,
+        }
+    }
+}
+
+/// Returns the path in the first tree of the node at `to_path` in the second tree.
+fn counterpart(to_path: &[String], moves: &BTreeMap<Vec<String>, Vec<String>>) -> Vec<String> {
+    for len in (1..=to_path.len()).rev() {
+        if let Some(from_path) = moves.get(&to_path[..len]) {
+            let mut path = from_path.clone();
+            path.extend_from_slice(&to_path[len..]);
+            return path;
+        }
+    }
+    to_path.to_vec()
+}
+
+fn has_parent_in(path: &[String], paths: &BTreeSet<Vec<String>>) -> bool {
+    path.len() > 1 && paths.contains(&path[..path.len() - 1])
+}
+
+fn node_type(node: &MatcherConfig) -> &'static str {
+    match node {
+        MatcherConfig::Filter { .. } => "Filter",
+        MatcherConfig::Iterator { .. } => "Iterator",
+        MatcherConfig::Ruleset { .. } => "Ruleset",
+    }
+}
+
+/// The definition of a node, without its name and its children.
+fn node_definition(node: &MatcherConfig) -> Value {
+    let mut definition = match node {
+        MatcherConfig::Filter { filter, .. } => serde_json::to_value(filter),
+        MatcherConfig::Iterator { iterator, .. } => serde_json::to_value(iterator),
+        MatcherConfig::Ruleset { .. } => Ok(Value::Object(Map::new())),
+    }
+    .unwrap_or(Value::Null);
+    if let Value::Object(map) = &mut definition {
+        map.insert("type".to_owned(), Value::String(node_type(node).to_owned()));
+    }
+    definition
+}
+
+fn node_rules(node: &MatcherConfig) -> &[Rule] {
+    match node {
+        MatcherConfig::Ruleset { rules, .. } => rules,
+        MatcherConfig::Filter { .. } | MatcherConfig::Iterator { .. } => &[],
+    }
+}
+
+fn node_change(
+    node_path: &[String],
+    change: ConfigChangeKind,
+    moved_from: Option<&[String]>,
+    fields: Vec<FieldChange>,
+) -> ConfigChange {
+    ConfigChange {
+        node_path: node_path.to_vec(),
+        rule: None,
+        change,
+        moved_from: moved_from.map(|path| path.to_vec()),
+        from_position: None,
+        to_position: None,
+        fields,
+    }
+}
+
+fn diff_rules(
+    node_path: &[String],
+    from_rules: &[Rule],
+    to_rules: &[Rule],
+    changes: &mut Vec<ConfigChange>,
+) {
+    let from_positions = from_rules
+        .iter()
+        .enumerate()
+        .map(|(position, rule)| (rule.name.as_str(), position))
+        .collect::<HashMap<_, _>>();
+    let to_names = to_rules.iter().map(|rule| rule.name.as_str()).collect::<BTreeSet<_>>();
+    let kept_in_order = longest_common_subsequence(
+        &from_rules.iter().map(|rule| rule.name.as_str()).collect::<Vec<_>>(),
+        &to_rules
+            .iter()
+            .map(|rule| rule.name.as_str())
+            .filter(|name| from_positions.contains_key(name))
+            .collect::<Vec<_>>(),
+    );
+
+    let rule_change = |rule: &Rule, change, from_position, to_position, fields| ConfigChange {
+        node_path: node_path.to_vec(),
+        rule: Some(rule.name.clone()),
+        change,
+        moved_from: None,
+        from_position,
+        to_position,
+        fields,
+    };
+
+    for (to_position, to_rule) in to_rules.iter().enumerate() {
+        match from_positions.get(to_rule.name.as_str()) {
+            None => changes.push(rule_change(
+                to_rule,
+                ConfigChangeKind::Added,
+                None,
+                Some(to_position),
+                vec![],
+            )),
+            Some(from_position) => {
+                let mut fields = vec![];
+                diff_values(
+                    "",
+                    &rule_definition(&from_rules[*from_position]),
+                    &rule_definition(to_rule),
+                    &mut fields,
+                );
+                let change = if !kept_in_order.contains(to_rule.name.as_str()) {
+                    ConfigChangeKind::Moved
+                } else if !fields.is_empty() {
+                    ConfigChangeKind::Changed
+                } else {
+                    continue;
+                };
+                changes.push(rule_change(
+                    to_rule,
+                    change,
+                    Some(*from_position),
+                    Some(to_position),
+                    fields,
+                ));
+            }
+        }
+    }
+
+    for (from_position, from_rule) in from_rules.iter().enumerate() {
+        if !to_names.contains(from_rule.name.as_str()) {
+            changes.push(rule_change(
+                from_rule,
+                ConfigChangeKind::Removed,
+                Some(from_position),
+                None,
+                vec![],
+            ));
+        }
+    }
+}
+
+/// The definition of a rule, without its name.
+fn rule_definition(rule: &Rule) -> Value {
+    let mut definition = serde_json::to_value(rule).unwrap_or(Value::Null);
+    if let Value::Object(map) = &mut definition {
+        map.remove("name");
+    }
+    definition
+}
+
+/// Returns the items of the longest sequence that appears, in the same order, in both slices.
+fn longest_common_subsequence<'a>(first: &[&'a str], second: &[&'a str]) -> BTreeSet<&'a str> {
+    let mut lengths = vec![vec![0usize; second.len() + 1]; first.len() + 1];
+    for i in (0..first.len()).rev() {
+        for j in (0..second.len()).rev() {
+            lengths[i][j] = if first[i] == second[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut common = BTreeSet::new();
+    let (mut i, mut j) = (0, 0);
+    while i < first.len() && j < second.len() {
+        if first[i] == second[j] {
+            common.insert(first[i]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+/// Compares two JSON values and collects the paths of the leaves that differ.
+fn diff_values(path: &str, from: &Value, to: &Value, fields: &mut Vec<FieldChange>) {
+    let child_path = |key: &str| {
+        if path.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (from, to) {
+        (Value::Object(from_map), Value::Object(to_map)) => {
+            let keys = from_map.keys().chain(to_map.keys()).collect::<BTreeSet<_>>();
+            for key in keys {
+                diff_optional_values(&child_path(key), from_map.get(key), to_map.get(key), fields);
+            }
+        }
+        (Value::Array(from_items), Value::Array(to_items)) => {
+            for index in 0..from_items.len().max(to_items.len()) {
+                diff_optional_values(
+                    &child_path(&index.to_string()),
+                    from_items.get(index),
+                    to_items.get(index),
+                    fields,
+                );
+            }
+        }
+        _ => {
+            if from != to {
+                fields.push(FieldChange {
+                    field: path.to_owned(),
+                    from: Some(from.clone()),
+                    to: Some(to.clone()),
+                });
+            }
+        }
+    }
+}
+
+fn diff_optional_values(
+    path: &str,
+    from: Option<&Value>,
+    to: Option<&Value>,
+    fields: &mut Vec<FieldChange>,
+) {
+    match (from, to) {
+        (Some(from), Some(to)) => diff_values(path, from, to, fields),
+        (None, None) => {}
+        _ => fields.push(FieldChange {
+            field: path.to_owned(),
+            from: from.cloned(),
+            to: to.cloned(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::{ConfigAction, Constraint};
+    use crate::config::Defaultable;
+    use serde_json::json;
+
+    #[test]
+    fn should_list_the_added_removed_and_changed_nodes_and_rules() {
+        // Arrange
+        let from = filter(
+            "root",
+            true,
+            vec![
+                ruleset("ruleset_1", vec![rule("rule_1", true), rule("rule_2", true)]),
+                filter("filter_1", true, vec![ruleset("ruleset_3", vec![])]),
+                filter("filter_2", true, vec![ruleset("ruleset_4", vec![])]),
+            ],
+        );
+        let to = filter(
+            "root",
+            true,
+            vec![
+                ruleset("ruleset_1", vec![rule("rule_1", false), rule("rule_3", true)]),
+                filter("filter_1", false, vec![ruleset("ruleset_3", vec![])]),
+                ruleset("ruleset_2", vec![rule("rule_1", true)]),
+            ],
+        );
+
+        // Act
+        let changes = diff_configs(&from, &to);
+
+        // Assert
+        assert_eq!(
+            vec![
+                ConfigChange {
+                    fields: vec![FieldChange {
+                        field: "active".to_owned(),
+                        from: Some(json!(true)),
+                        to: Some(json!(false)),
+                    }],
+                    ..node_change(&path(&["root", "filter_1"]), ConfigChangeKind::Changed)
+                },
+                node_change(&path(&["root", "filter_2"]), ConfigChangeKind::Removed),
+                ConfigChange {
+                    from_position: Some(0),
+                    to_position: Some(0),
+                    fields: vec![FieldChange {
+                        field: "active".to_owned(),
+                        from: Some(json!(true)),
+                        to: Some(json!(false)),
+                    }],
+                    ..rule_change(&["root", "ruleset_1"], "rule_1", ConfigChangeKind::Changed)
+                },
+                ConfigChange {
+                    from_position: Some(1),
+                    ..rule_change(&["root", "ruleset_1"], "rule_2", ConfigChangeKind::Removed)
+                },
+                ConfigChange {
+                    to_position: Some(1),
+                    ..rule_change(&["root", "ruleset_1"], "rule_3", ConfigChangeKind::Added)
+                },
+                node_change(&path(&["root", "ruleset_2"]), ConfigChangeKind::Added),
+            ],
+            changes
+        );
+        assert!(diff_configs(&from, &from).is_empty());
+    }
+
+    #[test]
+    fn should_detect_the_moved_nodes_with_their_children() {
+        // Arrange
+        let from = filter(
+            "root",
+            true,
+            vec![
+                filter(
+                    "filter_1",
+                    true,
+                    vec![ruleset("ruleset_1", vec![rule("rule_1", true), rule("rule_2", true)])],
+                ),
+                filter("filter_2", true, vec![]),
+            ],
+        );
+        let to = filter(
+            "root",
+            true,
+            vec![
+                filter("filter_1", true, vec![]),
+                filter(
+                    "filter_2",
+                    true,
+                    vec![filter(
+                        "filter_3",
+                        true,
+                        vec![ruleset(
+                            "ruleset_1",
+                            vec![rule("rule_1", true), rule("rule_2", false)],
+                        )],
+                    )],
+                ),
+            ],
+        );
+
+        // Act
+        let changes = diff_configs(&from, &to);
+
+        // Assert
+        assert_eq!(
+            vec![
+                node_change(&path(&["root", "filter_2", "filter_3"]), ConfigChangeKind::Added),
+                ConfigChange {
+                    moved_from: Some(path(&["root", "filter_1", "ruleset_1"])),
+                    ..node_change(
+                        &path(&["root", "filter_2", "filter_3", "ruleset_1"]),
+                        ConfigChangeKind::Moved
+                    )
+                },
+                ConfigChange {
+                    from_position: Some(1),
+                    to_position: Some(1),
+                    fields: vec![FieldChange {
+                        field: "active".to_owned(),
+                        from: Some(json!(true)),
+                        to: Some(json!(false)),
+                    }],
+                    ..rule_change(
+                        &["root", "filter_2", "filter_3", "ruleset_1"],
+                        "rule_2",
+                        ConfigChangeKind::Changed
+                    )
+                },
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn should_detect_the_reordered_rules_and_the_changed_fields() {
+        // Arrange
+        let mut changed_rule = rule("rule_3", true);
+        changed_rule.actions = vec![ConfigAction {
+            id: "logger".to_owned(),
+            payload: serde_json::from_value(json!({ "message": "changed", "level": "info" }))
+                .unwrap(),
+            when: None,
+        }];
+        let from = ruleset(
+            "ruleset",
+            vec![rule("rule_1", true), rule("rule_2", true), rule("rule_3", true)],
+        );
+        let to = ruleset(
+            "ruleset",
+            vec![rule("rule_new", true), rule("rule_2", true), rule("rule_1", true), changed_rule],
+        );
+
+        // Act
+        let changes = diff_configs(&from, &to);
+
+        // Assert
+        assert_eq!(
+            vec![
+                ConfigChange {
+                    from_position: Some(0),
+                    to_position: Some(2),
+                    ..rule_change(&["ruleset"], "rule_1", ConfigChangeKind::Moved)
+                },
+                ConfigChange {
+                    from_position: Some(2),
+                    to_position: Some(3),
+                    fields: vec![FieldChange {
+                        field: "actions.0".to_owned(),
+                        from: None,
+                        to: Some(json!({
+                            "id": "logger",
+                            "payload": { "message": "changed", "level": "info" }
+                        })),
+                    }],
+                    ..rule_change(&["ruleset"], "rule_3", ConfigChangeKind::Changed)
+                },
+                ConfigChange {
+                    to_position: Some(0),
+                    ..rule_change(&["ruleset"], "rule_new", ConfigChangeKind::Added)
+                },
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn should_list_the_nested_changed_fields() {
+        // Arrange
+        let from = json!({ "description": "", "payload": { "a": 1, "b": [1, 2] } });
+        let to = json!({ "description": "new", "payload": { "b": [1, 3, 4] } });
+        let mut fields = vec![];
+
+        // Act
+        diff_values("", &from, &to, &mut fields);
+
+        // Assert
+        let field = |field: &str, from: Option<Value>, to: Option<Value>| FieldChange {
+            field: field.to_owned(),
+            from,
+            to,
+        };
+        assert_eq!(
+            vec![
+                field("description", Some(json!("")), Some(json!("new"))),
+                field("payload.a", Some(json!(1)), None),
+                field("payload.b.1", Some(json!(2)), Some(json!(3))),
+                field("payload.b.2", None, Some(json!(4))),
+            ],
+            fields
+        );
+    }
+
+    fn path(nodes: &[&str]) -> Vec<String> {
+        nodes.iter().map(|node| node.to_string()).collect()
+    }
+
+    fn node_change(node_path: &[String], change: ConfigChangeKind) -> ConfigChange {
+        super::node_change(node_path, change, None, vec![])
+    }
+
+    fn rule_change(node_path: &[&str], rule: &str, change: ConfigChangeKind) -> ConfigChange {
+        ConfigChange { rule: Some(rule.to_owned()), ..node_change(&path(node_path), change) }
+    }
+
+    fn filter(name: &str, active: bool, nodes: Vec<MatcherConfig>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: name.to_owned(),
+            filter: Filter { description: "".to_owned(), active, filter: Defaultable::Default {} },
+            nodes,
+        }
+    }
+
+    fn ruleset(name: &str, rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Ruleset { name: name.to_owned(), rules }
+    }
+
+    fn rule(name: &str, active: bool) -> Rule {
+        Rule {
+            name: name.to_owned(),
+            description: "".to_owned(),
+            do_continue: true,
+            active,
+            constraint: Constraint { where_operator: None, with: Default::default() },
+            actions: vec![],
+        }
+    }
+}
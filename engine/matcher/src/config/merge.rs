@@ -0,0 +1,397 @@
+//! The merge module applies to a draft the changes made to the active configuration
+//! after the draft was created, with a three-way merge of the processing trees.
+
+use crate::config::rule::Rule;
+use crate::config::MatcherConfig;
+use serde::{Deserialize, Serialize};
+
+/// Why a node or a rule could not be merged automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeConflictKind {
+    /// Changed both in the active configuration and in the draft
+    BothChanged,
+    /// Added both to the active configuration and to the draft, with a different definition
+    BothAdded,
+    /// Removed from the active configuration and changed in the draft
+    RemovedInActive,
+    /// Changed in the active configuration and removed from the draft
+    RemovedInDraft,
+}
+
+/// A node, or a rule of the ruleset at `node_path` when `rule` is set,
+/// whose changes conflict. The version of the draft is kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    pub node_path: Vec<String>,
+    pub rule: Option<String>,
+    pub kind: MergeConflictKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeResult {
+    pub config: MatcherConfig,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Merges the changes made to the active configuration since the `base` one into the draft.
+///
+/// A node or a rule changed only on one side takes the changed version; when it is changed
+/// on both sides, the draft version is kept and a conflict is reported. A node is compared by its
+/// own definition, and its children are merged one by one, so that changes to different
+/// children of the same node do not conflict. The children and the rules keep the order of the
+/// draft; those added to the active configuration are placed after the same sibling they follow
+/// in the active configuration.
+pub fn merge_configs(
+    base: &MatcherConfig,
+    active: &MatcherConfig,
+    draft: &MatcherConfig,
+) -> MergeResult {
+    let mut conflicts = vec![];
+    let config = merge_node(&[], Some(base), Some(active), Some(draft), &mut conflicts)
+        .unwrap_or_else(|| draft.clone());
+    MergeResult { config, conflicts }
+}
+
+fn merge_node(
+    parent_path: &[String],
+    base: Option<&MatcherConfig>,
+    active: Option<&MatcherConfig>,
+    draft: Option<&MatcherConfig>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Option<MatcherConfig> {
+    let (active, draft) = match merge_item(base, active, draft) {
+        Merge::Resolved(node) => return node.cloned(),
+        Merge::Conflict(kind, node) => {
+            if let Some(node) = base.or(active).or(draft) {
+                conflicts.push(conflict(parent_path, node.get_name(), kind));
+            }
+            return node.cloned();
+        }
+        Merge::ChangedOnBothSides(active, draft) => (active, draft),
+    };
+
+    let mut path = parent_path.to_vec();
+    path.push(draft.get_name().to_owned());
+    let both_changed_kind =
+        if base.is_some() { MergeConflictKind::BothChanged } else { MergeConflictKind::BothAdded };
+
+    if std::mem::discriminant(active) != std::mem::discriminant(draft) {
+        // The type of the node changed: its children cannot be merged
+        conflicts.push(MergeConflict { node_path: path, rule: None, kind: both_changed_kind });
+        return Some(draft.clone());
+    }
+
+    let base_definition = base.map(definition);
+    let (active_definition, draft_definition) = (definition(active), definition(draft));
+    let mut merged = match merge_item(
+        base_definition.as_ref(),
+        Some(&active_definition),
+        Some(&draft_definition),
+    ) {
+        Merge::Resolved(Some(definition)) => definition.clone(),
+        _ => {
+            conflicts.push(MergeConflict {
+                node_path: path.clone(),
+                rule: None,
+                kind: both_changed_kind,
+            });
+            draft_definition.clone()
+        }
+    };
+
+    match &mut merged {
+        MatcherConfig::Filter { nodes, .. } | MatcherConfig::Iterator { nodes, .. } => {
+            *nodes = merge_named(
+                base.map(children).unwrap_or_default(),
+                children(active),
+                children(draft),
+                |node| node.get_name(),
+                |base, active, draft| merge_node(&path, base, active, draft, conflicts),
+            );
+        }
+        MatcherConfig::Ruleset { rules, .. } => {
+            *rules = merge_named(
+                base.map(node_rules).unwrap_or_default(),
+                node_rules(active),
+                node_rules(draft),
+                |rule| rule.name.as_str(),
+                |base, active, draft| merge_rule(&path, base, active, draft, conflicts),
+            );
+        }
+    }
+    Some(merged)
+}
+
+fn merge_rule(
+    ruleset_path: &[String],
+    base: Option<&Rule>,
+    active: Option<&Rule>,
+    draft: Option<&Rule>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Option<Rule> {
+    let kind = match merge_item(base, active, draft) {
+        Merge::Resolved(rule) => return rule.cloned(),
+        Merge::Conflict(kind, _) => kind,
+        Merge::ChangedOnBothSides(..) if base.is_some() => MergeConflictKind::BothChanged,
+        Merge::ChangedOnBothSides(..) => MergeConflictKind::BothAdded,
+    };
+    if let Some(rule) = base.or(active).or(draft) {
+        conflicts.push(MergeConflict {
+            node_path: ruleset_path.to_vec(),
+            rule: Some(rule.name.clone()),
+            kind,
+        });
+    }
+    draft.cloned()
+}
+
+/// The outcome of the three-way merge of a single item.
+enum Merge<'a, T> {
+    /// The merged item, or None if it is removed
+    Resolved(Option<&'a T>),
+    /// The changes conflict; the draft version is kept
+    Conflict(MergeConflictKind, Option<&'a T>),
+    /// The item is present on both sides, with different changes
+    ChangedOnBothSides(&'a T, &'a T),
+}
+
+fn merge_item<'a, T: PartialEq>(
+    base: Option<&'a T>,
+    active: Option<&'a T>,
+    draft: Option<&'a T>,
+) -> Merge<'a, T> {
+    match (base, active, draft) {
+        (_, None, None) => Merge::Resolved(None),
+        (None, Some(active), None) => Merge::Resolved(Some(active)),
+        (None, None, Some(draft)) => Merge::Resolved(Some(draft)),
+        (Some(base), None, Some(draft)) if base == draft => Merge::Resolved(None),
+        (Some(_), None, Some(draft)) => {
+            Merge::Conflict(MergeConflictKind::RemovedInActive, Some(draft))
+        }
+        (Some(base), Some(active), None) if base == active => Merge::Resolved(None),
+        (Some(_), Some(_), None) => Merge::Conflict(MergeConflictKind::RemovedInDraft, None),
+        (base, Some(active), Some(draft)) => {
+            if active == draft || base == Some(active) {
+                Merge::Resolved(Some(draft))
+            } else if base == Some(draft) {
+                Merge::Resolved(Some(active))
+            } else {
+                Merge::ChangedOnBothSides(active, draft)
+            }
+        }
+    }
+}
+
+/// Merges two lists of named items, with the order of the draft.
+fn merge_named<T, N, M>(base: &[T], active: &[T], draft: &[T], name: N, mut merge: M) -> Vec<T>
+where
+    N: Fn(&T) -> &str,
+    M: FnMut(Option<&T>, Option<&T>, Option<&T>) -> Option<T>,
+{
+    let mut names: Vec<&str> = draft.iter().map(&name).collect();
+    for (index, item) in active.iter().enumerate() {
+        if names.contains(&name(item)) {
+            continue;
+        }
+        let position = active[..index]
+            .iter()
+            .rev()
+            .find_map(|previous| names.iter().position(|item_name| *item_name == name(previous)))
+            .map(|position| position + 1)
+            .unwrap_or(0);
+        names.insert(position, name(item));
+    }
+
+    let find = |items: &[T], item_name: &str| items.iter().position(|item| name(item) == item_name);
+    names
+        .iter()
+        .filter_map(|item_name| {
+            merge(
+                find(base, item_name).map(|index| &base[index]),
+                find(active, item_name).map(|index| &active[index]),
+                find(draft, item_name).map(|index| &draft[index]),
+            )
+        })
+        .collect()
+}
+
+/// The node without its children.
+fn definition(node: &MatcherConfig) -> MatcherConfig {
+    match node {
+        MatcherConfig::Filter { name, filter, .. } => {
+            MatcherConfig::Filter { name: name.clone(), filter: filter.clone(), nodes: vec![] }
+        }
+        MatcherConfig::Iterator { name, iterator, .. } => MatcherConfig::Iterator {
+            name: name.clone(),
+            iterator: iterator.clone(),
+            nodes: vec![],
+        },
+        MatcherConfig::Ruleset { name, .. } => {
+            MatcherConfig::Ruleset { name: name.clone(), rules: vec![] }
+        }
+    }
+}
+
+fn children(node: &MatcherConfig) -> &[MatcherConfig] {
+    match node {
+        MatcherConfig::Filter { nodes, .. } | MatcherConfig::Iterator { nodes, .. } => nodes,
+        MatcherConfig::Ruleset { .. } => &[],
+    }
+}
+
+fn node_rules(node: &MatcherConfig) -> &[Rule] {
+    match node {
+        MatcherConfig::Ruleset { rules, .. } => rules,
+        MatcherConfig::Filter { .. } | MatcherConfig::Iterator { .. } => &[],
+    }
+}
+
+fn conflict(parent_path: &[String], name: &str, kind: MergeConflictKind) -> MergeConflict {
+    let mut node_path = parent_path.to_vec();
+    node_path.push(name.to_owned());
+    MergeConflict { node_path, rule: None, kind }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::Constraint;
+    use crate::config::Defaultable;
+
+    #[test]
+    fn should_apply_the_changes_that_do_not_conflict() {
+        // Arrange
+        let base = filter(
+            "root",
+            vec![
+                ruleset("ruleset_1", vec![rule("rule_1", ""), rule("rule_2", "")]),
+                filter("filter_1", vec![]),
+            ],
+        );
+        let active = filter(
+            "root",
+            vec![
+                ruleset(
+                    "ruleset_1",
+                    vec![rule("rule_1", "active"), rule("rule_3", ""), rule("rule_2", "")],
+                ),
+                filter("filter_1", vec![]),
+                filter("filter_2", vec![]),
+            ],
+        );
+        let draft = filter(
+            "root",
+            vec![ruleset(
+                "ruleset_1",
+                vec![rule("rule_1", ""), rule("rule_2", "draft"), rule("rule_4", "")],
+            )],
+        );
+
+        // Act
+        let result = merge_configs(&base, &active, &draft);
+
+        // Assert
+        assert_eq!(
+            MergeResult {
+                config: filter(
+                    "root",
+                    vec![
+                        ruleset(
+                            "ruleset_1",
+                            vec![
+                                rule("rule_1", "active"),
+                                rule("rule_3", ""),
+                                rule("rule_2", "draft"),
+                                rule("rule_4", "")
+                            ]
+                        ),
+                        filter("filter_2", vec![]),
+                    ]
+                ),
+                conflicts: vec![],
+            },
+            result
+        );
+        assert_eq!(draft, merge_configs(&base, &base, &draft).config);
+        assert_eq!(active, merge_configs(&base, &active, &base).config);
+    }
+
+    #[test]
+    fn should_keep_the_draft_version_of_the_conflicting_changes() {
+        // Arrange
+        let base = filter(
+            "root",
+            vec![
+                ruleset("ruleset_1", vec![rule("rule_1", ""), rule("rule_2", "")]),
+                filter("filter_1", vec![ruleset("ruleset_2", vec![])]),
+            ],
+        );
+        let active = filter(
+            "root",
+            vec![
+                ruleset("ruleset_1", vec![rule("rule_1", "active")]),
+                filter("filter_1", vec![ruleset("ruleset_2", vec![rule("rule_1", "")])]),
+                ruleset("node_3", vec![]),
+            ],
+        );
+        let draft = filter(
+            "root",
+            vec![
+                ruleset("ruleset_1", vec![rule("rule_1", "draft"), rule("rule_2", "draft")]),
+                filter("node_3", vec![]),
+            ],
+        );
+
+        // Act
+        let result = merge_configs(&base, &active, &draft);
+
+        // Assert
+        let conflict = |node_path: &[&str], rule: Option<&str>, kind| MergeConflict {
+            node_path: node_path.iter().map(|node| node.to_string()).collect(),
+            rule: rule.map(|rule| rule.to_owned()),
+            kind,
+        };
+        assert_eq!(draft, result.config);
+        assert_eq!(
+            vec![
+                conflict(&["root", "ruleset_1"], Some("rule_1"), MergeConflictKind::BothChanged),
+                conflict(
+                    &["root", "ruleset_1"],
+                    Some("rule_2"),
+                    MergeConflictKind::RemovedInActive
+                ),
+                conflict(&["root", "filter_1"], None, MergeConflictKind::RemovedInDraft),
+                conflict(&["root", "node_3"], None, MergeConflictKind::BothAdded),
+            ],
+            result.conflicts
+        );
+    }
+
+    fn filter(name: &str, nodes: Vec<MatcherConfig>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: name.to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes,
+        }
+    }
+
+    fn ruleset(name: &str, rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Ruleset { name: name.to_owned(), rules }
+    }
+
+    fn rule(name: &str, description: &str) -> Rule {
+        Rule {
+            name: name.to_owned(),
+            description: description.to_owned(),
+            do_continue: true,
+            active: true,
+            constraint: Constraint { where_operator: None, with: Default::default() },
+            actions: vec![],
+        }
+    }
+}
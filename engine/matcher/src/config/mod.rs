@@ -1,14 +1,21 @@
+use crate::config::merge::MergeConflict;
 use crate::config::nodes::{Filter, MatcherIterator};
 use crate::config::rule::Rule;
 use crate::config::v2::{ConfigNodeDir, ConfigType};
 use crate::error::MatcherError;
 use crate::matcher;
 use crate::matcher::Matcher;
+use crate::test_case::RulesetTests;
 use serde::{de::Deserializer, Deserialize, Serialize};
 use std::borrow::Cow;
+use typescript_definitions::TypeScriptify;
 
+pub mod bundle;
+pub mod diff;
+pub mod merge;
 pub mod nodes;
 pub mod operation;
+pub mod render;
 pub mod rule;
 pub mod v1;
 pub mod v2;
@@ -27,6 +34,9 @@ pub struct MatcherConfigDraftData {
     pub updated_ts_ms: i64,
     pub user: String,
     pub draft_id: String,
+    /// The version of the deploy history the draft is based on, if the history is enabled
+    #[serde(default)]
+    pub base_version: Option<String>,
 }
 
 impl ConfigNodeDir for MatcherConfigDraftData {
@@ -35,6 +45,41 @@ impl ConfigNodeDir for MatcherConfigDraftData {
     }
 }
 
+/// The changes of the active configuration since the draft was created, or last rebased.
+/// `outdated` is true if the active configuration changed; the `conflicts` are the changes
+/// that cannot be merged automatically into the draft.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DraftRebase {
+    pub outdated: bool,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// A configuration kept in the deploy history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeployedConfig {
+    pub data: DeployedConfigData,
+    pub config: MatcherConfig,
+}
+
+/// The details of a deployment. `user` and `draft_id` are not set when the configuration
+/// was not deployed from a draft; `rollback_of` is set when it was restored from a previous version.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypeScriptify)]
+#[serde(deny_unknown_fields)]
+pub struct DeployedConfigData {
+    pub version_id: String,
+    pub deployed_ts_ms: i64,
+    pub user: Option<String>,
+    pub draft_id: Option<String>,
+    pub rollback_of: Option<String>,
+}
+
+impl ConfigNodeDir for DeployedConfigData {
+    fn config_type() -> ConfigType {
+        ConfigType::Deployment
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub enum MatcherConfig {
@@ -496,6 +541,12 @@ impl<T: Serialize + Clone> From<Option<T>> for Defaultable<T> {
 #[async_trait::async_trait(? Send)]
 pub trait MatcherConfigReader: Sync + Send {
     async fn get_config(&self) -> Result<MatcherConfig, MatcherError>;
+
+    /// Returns the test cases stored next to the rulesets of the configuration.
+    /// Configuration sources that do not support test cases return an empty list.
+    async fn get_config_tests(&self) -> Result<Vec<RulesetTests>, MatcherError> {
+        Ok(vec![])
+    }
 }
 
 /// A MatcherConfigEditor permits to edit Tornado Configuration drafts
@@ -529,6 +580,44 @@ pub trait MatcherConfigEditor: MatcherConfigReader + Sync + Send {
 
     /// Deploys a new configuration overriding the current one
     async fn deploy_config(&self, config: &MatcherConfig) -> Result<MatcherConfig, MatcherError>;
+
+    /// Returns the deployments kept in the history, the most recent first.
+    /// Configuration sources that do not keep a history return an empty list.
+    async fn get_deployed_configs(&self) -> Result<Vec<DeployedConfigData>, MatcherError> {
+        Ok(vec![])
+    }
+
+    /// Returns a configuration of the deploy history by version
+    async fn get_deployed_config(&self, version_id: &str) -> Result<DeployedConfig, MatcherError> {
+        Err(MatcherError::DeployedConfigNotFoundError { version_id: version_id.to_owned() })
+    }
+
+    /// Deploys again a configuration of the deploy history, replacing the current one
+    async fn rollback_config(
+        &self,
+        version_id: &str,
+        _user: String,
+    ) -> Result<MatcherConfig, MatcherError> {
+        Err(MatcherError::DeployedConfigNotFoundError { version_id: version_id.to_owned() })
+    }
+
+    /// Compares the active configuration with the one the draft is based on and returns
+    /// the conflicts that a rebase would report, without changing the draft.
+    /// Configuration sources that do not track the base of the drafts never report changes.
+    async fn get_draft_rebase(&self, _draft_id: &str) -> Result<DraftRebase, MatcherError> {
+        Ok(DraftRebase::default())
+    }
+
+    /// Merges into the draft the changes of the active configuration since the draft was created,
+    /// and bases the draft on the active configuration. The conflicting changes are not
+    /// applied; they are returned, and the draft version is kept.
+    async fn rebase_draft(
+        &self,
+        _draft_id: &str,
+        _user: String,
+    ) -> Result<DraftRebase, MatcherError> {
+        Ok(DraftRebase::default())
+    }
 }
 
 pub fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -0,0 +1,378 @@
+//! The render module draws the processing tree as a Graphviz DOT graph, a Mermaid flowchart
+//! or a static HTML report, to give an overview of large trees.
+
+use crate::config::nodes::{Filter, MatcherIterator};
+use crate::config::rule::Rule;
+use crate::config::{Defaultable, MatcherConfig};
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TreeRenderFormat {
+    Dot,
+    Mermaid,
+    Html,
+}
+
+impl TreeRenderFormat {
+    pub const ALL: [TreeRenderFormat; 3] =
+        [TreeRenderFormat::Dot, TreeRenderFormat::Mermaid, TreeRenderFormat::Html];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TreeRenderFormat::Dot => "dot",
+            TreeRenderFormat::Mermaid => "mermaid",
+            TreeRenderFormat::Html => "html",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TreeRenderFormat::Dot => "text/vnd.graphviz",
+            TreeRenderFormat::Mermaid => "text/plain",
+            TreeRenderFormat::Html => "text/html",
+        }
+    }
+
+    /// Renders the processing tree starting from the given node
+    pub fn render(&self, config: &MatcherConfig) -> String {
+        match self {
+            TreeRenderFormat::Dot => render_dot(config),
+            TreeRenderFormat::Mermaid => render_mermaid(config),
+            TreeRenderFormat::Html => render_html(config),
+        }
+    }
+}
+
+impl Display for TreeRenderFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TreeRenderFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.to_lowercase();
+        TreeRenderFormat::ALL.iter().copied().find(|format| format.name() == value).ok_or_else(
+            || format!("Unknown format [{}]. Expected one of dot, mermaid or html.", value),
+        )
+    }
+}
+
+/// The text lines describing a node, shared by the graph formats
+fn node_lines(node: &MatcherConfig) -> Vec<String> {
+    match node {
+        MatcherConfig::Filter { name, filter, .. } => {
+            vec![format!("Filter: {}", name), format!("condition: {}", filter_condition(filter))]
+        }
+        MatcherConfig::Iterator { name, iterator, .. } => {
+            vec![format!("Iterator: {}", name), format!("target: {}", iterator.target())]
+        }
+        MatcherConfig::Ruleset { name, rules } => {
+            let mut lines = vec![format!("Ruleset: {}", name)];
+            lines.extend(rules.iter().map(rule_line));
+            lines
+        }
+    }
+}
+
+fn rule_line(rule: &Rule) -> String {
+    format!(
+        "{} {} -> [{}]",
+        if rule.active { "+" } else { "-" },
+        rule.name,
+        action_ids(rule).join(", ")
+    )
+}
+
+fn action_ids(rule: &Rule) -> Vec<&str> {
+    rule.actions.iter().map(|action| action.id.as_str()).collect()
+}
+
+fn filter_condition(filter: &Filter) -> String {
+    match &filter.filter {
+        Defaultable::Value(operator) => {
+            serde_json::to_string(operator).unwrap_or_else(|err| format!("<{}>", err))
+        }
+        Defaultable::Default {} => "all events".to_owned(),
+    }
+}
+
+fn is_active(node: &MatcherConfig) -> bool {
+    match node {
+        MatcherConfig::Filter { filter, .. } => filter.active,
+        MatcherConfig::Iterator { iterator, .. } => iterator.is_active(),
+        MatcherConfig::Ruleset { .. } => true,
+    }
+}
+
+fn children(node: &MatcherConfig) -> &[MatcherConfig] {
+    match node {
+        MatcherConfig::Filter { nodes, .. } | MatcherConfig::Iterator { nodes, .. } => nodes,
+        MatcherConfig::Ruleset { .. } => &[],
+    }
+}
+
+/// Visits the nodes depth-first, passing to the callback the id of each node, the id of its
+/// parent and the node itself. The ids are assigned in visiting order.
+fn visit_nodes<F: FnMut(usize, Option<usize>, &MatcherConfig)>(
+    node: &MatcherConfig,
+    parent: Option<usize>,
+    next_id: &mut usize,
+    callback: &mut F,
+) {
+    let id = *next_id;
+    *next_id += 1;
+    callback(id, parent, node);
+    for child in children(node) {
+        visit_nodes(child, Some(id), next_id, callback);
+    }
+}
+
+fn render_dot(config: &MatcherConfig) -> String {
+    let mut out = String::from("digraph processing_tree {\n    node [fontname=\"monospace\"];\n");
+    visit_nodes(config, None, &mut 0, &mut |id, parent, node| {
+        let shape = match node {
+            MatcherConfig::Filter { .. } => "box",
+            MatcherConfig::Iterator { .. } => "hexagon",
+            MatcherConfig::Ruleset { .. } => "note",
+        };
+        let label =
+            node_lines(node).iter().map(|line| escape_dot(line) + "\\l").collect::<String>();
+        let style = if is_active(node) { "" } else { ", style=dashed, fontcolor=gray" };
+        let _ = writeln!(out, "    n{} [shape={}, label=\"{}\"{}];", id, shape, label, style);
+        if let Some(parent) = parent {
+            let _ = writeln!(out, "    n{} -> n{};", parent, id);
+        }
+    });
+    out.push_str("}\n");
+    out
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn render_mermaid(config: &MatcherConfig) -> String {
+    let mut out = String::from("flowchart TD\n");
+    let mut inactive = vec![];
+    visit_nodes(config, None, &mut 0, &mut |id, parent, node| {
+        let label = node_lines(node)
+            .iter()
+            .map(|line| escape_mermaid(line))
+            .collect::<Vec<_>>()
+            .join("<br/>");
+        let (open, close) = match node {
+            MatcherConfig::Filter { .. } => ("[\"", "\"]"),
+            MatcherConfig::Iterator { .. } => ("{{\"", "\"}}"),
+            MatcherConfig::Ruleset { .. } => ("[/\"", "\"/]"),
+        };
+        let _ = writeln!(out, "    n{}{}{}{}", id, open, label, close);
+        if let Some(parent) = parent {
+            let _ = writeln!(out, "    n{} --> n{}", parent, id);
+        }
+        if !is_active(node) {
+            inactive.push(format!("n{}", id));
+        }
+    });
+    if !inactive.is_empty() {
+        out.push_str("    classDef inactive stroke-dasharray: 5 5,color:#999\n");
+        let _ = writeln!(out, "    class {} inactive", inactive.join(","));
+    }
+    out
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('&', "#amp;").replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
+
+fn render_html(config: &MatcherConfig) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Processing tree {}</title>\n\
+         <style>\n\
+         body {{ font-family: sans-serif; }}\n\
+         ul {{ list-style: none; border-left: 1px solid #ccc; padding-left: 1.5em; }}\n\
+         code {{ background: #f4f4f4; word-break: break-all; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         td, th {{ border: 1px solid #ccc; padding: 0.2em 0.5em; text-align: left; }}\n\
+         .inactive {{ color: #999; }}\n\
+         </style>\n</head>\n<body>\n<h1>Processing tree {}</h1>\n<ul>\n",
+        escape_html(config.get_name()),
+        escape_html(config.get_name())
+    );
+    write_html_node(&mut out, config);
+    out.push_str("</ul>\n</body>\n</html>\n");
+    out
+}
+
+fn write_html_node(out: &mut String, node: &MatcherConfig) {
+    let class = if is_active(node) { "" } else { " class=\"inactive\"" };
+    let _ = write!(out, "<li{}>\n<details open>\n<summary>", class);
+    match node {
+        MatcherConfig::Filter { name, filter, .. } => {
+            let _ = write!(
+                out,
+                "Filter <b>{}</b>{}</summary>\n<p>{}</p>\n<p>Condition: <code>{}</code></p>\n",
+                escape_html(name),
+                if filter.active { "" } else { " (inactive)" },
+                escape_html(&filter.description),
+                escape_html(&filter_condition(filter))
+            );
+        }
+        MatcherConfig::Iterator { name, iterator, .. } => {
+            write_html_iterator(out, name, iterator);
+        }
+        MatcherConfig::Ruleset { name, rules } => {
+            let _ = writeln!(
+                out,
+                "Ruleset <b>{}</b> ({} rules)</summary>\n<table>\n\
+                 <tr><th>Rule</th><th>Active</th><th>Continue</th><th>Actions</th></tr>",
+                escape_html(name),
+                rules.len()
+            );
+            for rule in rules {
+                let _ = writeln!(
+                    out,
+                    "<tr{}><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    if rule.active { "" } else { " class=\"inactive\"" },
+                    escape_html(&rule.name),
+                    rule.active,
+                    rule.do_continue,
+                    escape_html(&action_ids(rule).join(", "))
+                );
+            }
+            out.push_str("</table>\n");
+        }
+    }
+    let children = children(node);
+    if !children.is_empty() {
+        out.push_str("<ul>\n");
+        for child in children {
+            write_html_node(out, child);
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</details>\n</li>\n");
+}
+
+fn write_html_iterator(out: &mut String, name: &str, iterator: &MatcherIterator) {
+    let _ = write!(
+        out,
+        "Iterator <b>{}</b>{}</summary>\n<p>{}</p>\n<p>Target: <code>{}</code></p>\n",
+        escape_html(name),
+        if iterator.is_active() { "" } else { " (inactive)" },
+        escape_html(iterator.description()),
+        escape_html(iterator.target())
+    );
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::rule::{ConfigAction, Operator};
+    use serde_json::json;
+
+    fn config() -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Value(Operator::Equals {
+                    first: json!("${event.type}"),
+                    second: json!("\"email\" <a>"),
+                }),
+            },
+            nodes: vec![
+                MatcherConfig::Iterator {
+                    name: "items".to_owned(),
+                    iterator: MatcherIterator::new(
+                        "".to_owned(),
+                        false,
+                        "${event.payload.items}".to_owned(),
+                    ),
+                    nodes: vec![],
+                },
+                MatcherConfig::Ruleset {
+                    name: "emails".to_owned(),
+                    rules: vec![
+                        Rule {
+                            name: "archive".to_owned(),
+                            active: true,
+                            actions: vec![ConfigAction {
+                                id: "logger".to_owned(),
+                                payload: Default::default(),
+                                when: None,
+                            }],
+                            ..Default::default()
+                        },
+                        Rule { name: "old".to_owned(), active: false, ..Default::default() },
+                    ],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn should_render_the_tree_as_dot() {
+        // Act
+        let dot = TreeRenderFormat::Dot.render(&config());
+
+        // Assert
+        assert!(dot.starts_with("digraph processing_tree {\n"));
+        assert!(dot.contains(
+            r#"n0 [shape=box, label="Filter: root\lcondition: {\"type\":\"equals\",\"first\":\"${event.type}\",\"second\":\"\\\"email\\\" <a>\"}\l"];"#
+        ));
+        assert!(dot.contains(
+            r#"n1 [shape=hexagon, label="Iterator: items\ltarget: ${event.payload.items}\l", style=dashed, fontcolor=gray];"#
+        ));
+        assert!(dot.contains(r#"label="Ruleset: emails\l+ archive -> [logger]\l- old -> []\l""#));
+        assert!(dot.contains("n0 -> n1;"));
+        assert!(dot.contains("n0 -> n2;"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn should_render_the_tree_as_mermaid() {
+        // Act
+        let mermaid = TreeRenderFormat::Mermaid.render(&config());
+
+        // Assert
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("#quot;\\#quot;email\\#quot; #lt;a#gt;#quot;"));
+        assert!(mermaid.contains(r#"n1{{"Iterator: items<br/>target: ${event.payload.items}"}}"#));
+        assert!(mermaid.contains(
+            r#"n2[/"Ruleset: emails<br/>+ archive -#gt; [logger]<br/>- old -#gt; []"/]"#
+        ));
+        assert!(mermaid.contains("n0 --> n2"));
+        assert!(mermaid.contains("class n1 inactive"));
+    }
+
+    #[test]
+    fn should_render_the_tree_as_html() {
+        // Act
+        let html = TreeRenderFormat::Html.render(&config());
+
+        // Assert
+        assert!(html.contains("<title>Processing tree root</title>"));
+        assert!(html.contains("&quot;email\\&quot; &lt;a&gt;"));
+        assert!(html.contains(
+            "<li class=\"inactive\">\n<details open>\n<summary>Iterator <b>items</b> (inactive)"
+        ));
+        assert!(
+            html.contains("<tr><td>archive</td><td>true</td><td>false</td><td>logger</td></tr>")
+        );
+        assert!(html.contains("<tr class=\"inactive\"><td>old</td>"));
+        assert_eq!(Ok(TreeRenderFormat::Html), "HTML".parse());
+    }
+}
@@ -127,6 +127,10 @@ pub enum Operator {
 pub struct ConfigAction {
     pub id: String,
     pub payload: Payload,
+    /// An optional condition evaluated after the rule's variables are extracted.
+    /// If present, the action is produced only when the condition is satisfied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Operator>,
 }
 
 impl Rule {
@@ -261,4 +265,46 @@ mod test {
         let rule = Rule::from_json(&json);
         assert!(rule.is_ok());
     }
+
+    #[test]
+    fn should_deserialize_rule_from_json_with_action_when_condition() {
+        let json = r#"{
+            "description": "",
+            "continue": true,
+            "active": true,
+            "constraint": {
+                "WHERE": null,
+                "WITH": {}
+            },
+            "actions": [
+                {
+                    "id": "archive",
+                    "payload": {}
+                },
+                {
+                    "id": "icinga2",
+                    "payload": {},
+                    "when": {
+                        "type": "equals",
+                        "first": "${event.payload.severity}",
+                        "second": "CRITICAL"
+                    }
+                }
+            ]
+        }"#;
+        let rule = Rule::from_json(json).unwrap();
+
+        assert_eq!(None, rule.actions[0].when);
+        assert_eq!(
+            Some(Operator::Equals {
+                first: Value::String("${event.payload.severity}".to_owned()),
+                second: Value::String("CRITICAL".to_owned()),
+            }),
+            rule.actions[1].when
+        );
+
+        let serialized = serde_json::to_value(&rule.actions).unwrap();
+        assert!(serialized[0].get("when").is_none());
+        assert!(serialized[1].get("when").is_some());
+    }
 }
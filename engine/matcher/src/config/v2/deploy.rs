@@ -0,0 +1,202 @@
+//! The checks and the operations on drafts shared by the storage backends.
+
+use crate::config::merge::{merge_configs, MergeConflict};
+use crate::config::{DraftRebase, MatcherConfig, MatcherConfigDraftData};
+use crate::error::MatcherError;
+use crate::matcher::Matcher;
+use crate::test_case::{check_tests, RulesetTests};
+use crate::validator::MatcherConfigValidator;
+use log::info;
+
+/// Performs the checks of a save. The references to the extracted variables are checked
+/// only on deploy, so that incomplete drafts can be saved.
+pub(super) fn validate_save(config: &MatcherConfig) -> Result<(), MatcherError> {
+    // Validate also regex and accessor, which the MatcherConfigValidator does not do.
+    let _ = Matcher::build(config)?;
+    Ok(())
+}
+
+/// Performs the checks of a deploy and returns the Matcher built from the configuration,
+/// so that the test cases can be run on it.
+/// Contrary to the saves of a draft, which only require the configuration to build,
+/// a deploy also requires the references to the extracted variables to be defined.
+pub(super) fn validate_deploy(config: &MatcherConfig) -> Result<Matcher, MatcherError> {
+    let matcher = Matcher::build(config)?;
+    MatcherConfigValidator::new().validate_variable_references(config)?;
+    Ok(matcher)
+}
+
+/// Performs the checks of a deploy and runs the test cases of the configuration,
+/// as required to deploy a draft or to roll back to a previous version.
+pub(super) fn validate_deploy_with_tests(
+    config: &MatcherConfig,
+    tests: &[RulesetTests],
+) -> Result<(), MatcherError> {
+    check_tests(&validate_deploy(config)?, tests)
+}
+
+/// Fails if the draft is based on a configuration that is not the active one anymore.
+/// The drafts without a base configuration can always be deployed.
+pub(super) fn check_draft_up_to_date(
+    draft_id: &str,
+    base_config: Option<&MatcherConfig>,
+    active_config: &MatcherConfig,
+) -> Result<(), MatcherError> {
+    match base_config {
+        Some(base_config) if base_config != active_config => {
+            Err(MatcherError::DraftOutdatedError { draft_id: draft_id.to_owned() })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Bases a deployed draft on the version it was deployed as, so that it can be edited
+/// and deployed again.
+pub(super) fn base_on_deployed_version(data: &mut MatcherConfigDraftData, version_id: String) {
+    data.base_version = Some(version_id);
+}
+
+/// Returns whether the draft, based on `base_config`, is outdated and the conflicts
+/// a rebase on the active configuration would have, without changing the draft.
+pub(super) fn preview_rebase(
+    base_config: &MatcherConfig,
+    active_config: &MatcherConfig,
+    draft_config: &MatcherConfig,
+) -> DraftRebase {
+    if base_config == active_config {
+        return DraftRebase::default();
+    }
+    let merged = merge_configs(base_config, active_config, draft_config);
+    DraftRebase { outdated: true, conflicts: merged.conflicts }
+}
+
+/// A draft rebased on the active configuration
+pub(super) struct RebasedDraft {
+    pub config: MatcherConfig,
+    pub tests: Vec<RulesetTests>,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl From<RebasedDraft> for DraftRebase {
+    fn from(rebased: RebasedDraft) -> Self {
+        DraftRebase { outdated: true, conflicts: rebased.conflicts }
+    }
+}
+
+/// Rebases a draft, based on `base_config`, on the active configuration.
+/// The changes made to the active configuration are merged into the draft, and the tests of
+/// the rulesets added to the active configuration are added to the ones of the draft.
+/// The merged configuration is validated as for a save.
+/// Returns None if the draft is already based on the active configuration.
+pub(super) fn rebase_onto(
+    base_config: &MatcherConfig,
+    active_config: &MatcherConfig,
+    active_tests: Vec<RulesetTests>,
+    draft_config: &MatcherConfig,
+    draft_tests: Vec<RulesetTests>,
+) -> Result<Option<RebasedDraft>, MatcherError> {
+    if base_config == active_config {
+        return Ok(None);
+    }
+    let merged = merge_configs(base_config, active_config, draft_config);
+    validate_save(&merged.config)?;
+
+    let mut tests = draft_tests;
+    for active_tests in active_tests {
+        if !tests.iter().any(|draft_tests| draft_tests.ruleset_path == active_tests.ruleset_path) {
+            tests.push(active_tests);
+        }
+    }
+    let tests = retain_tests_for(&merged.config, tests);
+    Ok(Some(RebasedDraft { config: merged.config, tests, conflicts: merged.conflicts }))
+}
+
+/// Drops the test cases of the rulesets that are not part of the configuration anymore
+pub(super) fn retain_tests_for(
+    config: &MatcherConfig,
+    tests: Vec<RulesetTests>,
+) -> Vec<RulesetTests> {
+    tests
+        .into_iter()
+        .filter(|ruleset_tests| {
+            let path: Vec<&str> = ruleset_tests.ruleset_path.iter().map(String::as_str).collect();
+            let is_ruleset =
+                matches!(config.get_node_by_path(&path), Some(MatcherConfig::Ruleset { .. }));
+            if !is_ruleset {
+                info!(
+                    "Dropping the tests of the ruleset [{}] as it is not part of the configuration anymore.",
+                    ruleset_tests.ruleset_path.join(",")
+                );
+            }
+            is_ruleset
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::Defaultable;
+
+    fn filter(name: &str, nodes: Vec<MatcherConfig>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: name.to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes,
+        }
+    }
+
+    fn ruleset(name: &str) -> MatcherConfig {
+        MatcherConfig::Ruleset { name: name.to_owned(), rules: vec![] }
+    }
+
+    fn ruleset_tests(name: &str) -> RulesetTests {
+        RulesetTests { ruleset_path: vec!["root".to_owned(), name.to_owned()], tests: vec![] }
+    }
+
+    #[test]
+    fn rebase_onto_should_keep_the_tests_of_the_rulesets_of_the_rebased_draft() {
+        // Arrange
+        let base = filter("root", vec![ruleset("a")]);
+        let active = filter("root", vec![ruleset("a"), ruleset("b")]);
+        let draft = filter("root", vec![ruleset("c")]);
+
+        // Act
+        let rebased = rebase_onto(
+            &base,
+            &active,
+            vec![ruleset_tests("a"), ruleset_tests("b")],
+            &draft,
+            vec![ruleset_tests("c"), ruleset_tests("a")],
+        )
+        .unwrap()
+        .unwrap();
+        let up_to_date = rebase_onto(&active, &active, vec![], &draft, vec![]).unwrap();
+
+        // Assert
+        assert_eq!(filter("root", vec![ruleset("b"), ruleset("c")]), rebased.config);
+        assert_eq!(vec![ruleset_tests("c"), ruleset_tests("b")], rebased.tests);
+        assert!(rebased.conflicts.is_empty());
+        assert!(up_to_date.is_none());
+    }
+
+    #[test]
+    fn check_draft_up_to_date_should_fail_if_the_base_is_not_the_active_configuration() {
+        // Arrange
+        let base = filter("root", vec![ruleset("a")]);
+        let active = filter("root", vec![ruleset("b")]);
+
+        // Act & Assert
+        assert!(check_draft_up_to_date("draft", None, &active).is_ok());
+        assert!(check_draft_up_to_date("draft", Some(&active), &active).is_ok());
+        assert_eq!(
+            Err(MatcherError::DraftOutdatedError { draft_id: "draft".to_owned() }),
+            check_draft_up_to_date("draft", Some(&base), &active)
+        );
+    }
+}
@@ -1,26 +1,41 @@
 use crate::config::nodes::{Filter, MatcherIterator};
 use crate::config::rule::Rule;
+use crate::config::v2::deploy::{
+    check_draft_up_to_date, preview_rebase, rebase_onto, validate_deploy,
+    validate_deploy_with_tests, validate_save,
+};
 use crate::config::v2::error::DeploymentError;
 use crate::config::v2::{
-    gather_dir_entries, parse_node_config_from_file, read_config_from_root_dir, ConfigNodeDir,
-    FsMatcherConfigManagerV2, MatcherConfigError, MatcherConfigFilter, MatcherConfigIterator,
-    MatcherConfigRuleset, Version,
+    find_node_config_file, gather_dir_entries, parse_from_file, parse_node_config_from_file,
+    read_config_from_root_dir, read_tests_from_root_dir, ConfigFileFormat, ConfigNodeDir,
+    ConfigType, FsMatcherConfigManagerV2, MatcherConfigError, MatcherConfigFilter,
+    MatcherConfigIterator, MatcherConfigRuleset, Version, TESTS_DIR,
 };
 use crate::config::{
-    v1, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData, MatcherConfigEditor,
+    v1, DeployedConfig, DeployedConfigData, DraftRebase, MatcherConfig, MatcherConfigDraft,
+    MatcherConfigDraftData, MatcherConfigEditor, MatcherConfigReader,
 };
 use crate::error::MatcherError;
-use crate::matcher::Matcher;
+use crate::test_case::RulesetTests;
 use chrono::Local;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use log::{debug, error, info, warn};
-use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use tokio::io::AsyncWriteExt;
 
 const DRAFT_ID: &str = "draft_001";
 
+/// The directory of a version of the deploy history that contains the deployed configuration.
+const DEPLOYED_CONFIG_DIR: &str = "config";
+
+/// The directory of a draft that contains the active configuration the draft is based on.
+const DRAFT_BASE_DIR: &str = "base";
+
 #[async_trait::async_trait(?Send)]
 impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
     async fn get_drafts(&self) -> Result<Vec<String>, MatcherError> {
@@ -49,7 +64,8 @@ impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
             path
         };
 
-        create_draft(&self.root_path, &draft_path, &user, DRAFT_ID).await?;
+        let base_version = self.get_last_deployed_version().await;
+        create_draft(&self.root_path, &draft_path, &user, DRAFT_ID, base_version).await?;
         Ok(DRAFT_ID.to_string())
     }
 
@@ -91,7 +107,8 @@ impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
             path.push("config");
             path
         };
-        atomic_deploy_config(&draft_config_dir, config).await?;
+        let tests = read_tests_from_root_dir(&draft_config_dir).await?;
+        atomic_deploy_config(&draft_config_dir, config, &tests).await?;
         Ok(())
     }
 
@@ -102,7 +119,31 @@ impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
         }
 
         let draft = self.get_draft(draft_id).await?;
-        atomic_deploy_config(&self.root_path, &draft.config).await?;
+        let draft_dir = {
+            let mut path = self.drafts_path.to_path_buf();
+            path.push(draft_id);
+            path
+        };
+        let base_config = get_draft_base(&draft_dir).await?;
+        check_draft_up_to_date(draft_id, base_config.as_ref(), &self.get_config().await?)?;
+
+        let draft_config_dir = {
+            let mut path = draft_dir.clone();
+            path.push("config");
+            path
+        };
+        let tests = read_tests_from_root_dir(&draft_config_dir).await?;
+        validate_deploy_with_tests(&draft.config, &tests)?;
+        self.deploy_and_record(
+            &draft.config,
+            &tests,
+            Some(draft.data.user.clone()),
+            Some(draft_id.to_owned()),
+            None,
+        )
+        .await?;
+        // The deployed configuration becomes the base of the draft
+        self.set_draft_base(&draft_dir, draft.data, &draft.config).await?;
         Ok(draft.config)
     }
 
@@ -148,15 +189,382 @@ impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
         Ok(())
     }
 
+    async fn get_draft_rebase(&self, draft_id: &str) -> Result<DraftRebase, MatcherError> {
+        let draft = self.get_draft(draft_id).await?;
+        let draft_dir = {
+            let mut path = self.drafts_path.to_path_buf();
+            path.push(draft_id);
+            path
+        };
+        let Some(base_config) = get_draft_base(&draft_dir).await? else {
+            return Ok(DraftRebase::default());
+        };
+        Ok(preview_rebase(&base_config, &self.get_config().await?, &draft.config))
+    }
+
+    async fn rebase_draft(
+        &self,
+        draft_id: &str,
+        user: String,
+    ) -> Result<DraftRebase, MatcherError> {
+        let draft = self.get_draft(draft_id).await?;
+        if draft.data.user != user {
+            warn!("User {user} tried rebasing a draft that is owned by {}.", draft.data.user);
+            return Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "User [{}] cannot rebase draft owned by [{}]",
+                    user, draft.data.user
+                ),
+            });
+        }
+
+        let draft_dir = {
+            let mut path = self.drafts_path.to_path_buf();
+            path.push(draft_id);
+            path
+        };
+        let Some(base_config) = get_draft_base(&draft_dir).await? else {
+            return Ok(DraftRebase::default());
+        };
+        let active_config = self.get_config().await?;
+        let draft_config_dir = {
+            let mut path = draft_dir.clone();
+            path.push("config");
+            path
+        };
+        let Some(rebased) = rebase_onto(
+            &base_config,
+            &active_config,
+            self.get_config_tests().await?,
+            &draft.config,
+            read_tests_from_root_dir(&draft_config_dir).await?,
+        )?
+        else {
+            return Ok(DraftRebase::default());
+        };
+
+        info!("User {} is rebasing draft {} on the active configuration", user, draft_id);
+        atomic_deploy_config(&draft_config_dir, &rebased.config, &rebased.tests).await?;
+
+        let mut draft_data = draft.data;
+        draft_data.updated_ts_ms = Local::now().timestamp_millis();
+        self.set_draft_base(&draft_dir, draft_data, &active_config).await?;
+        Ok(rebased.into())
+    }
+
     async fn deploy_config(&self, config: &MatcherConfig) -> Result<MatcherConfig, MatcherError> {
-        atomic_deploy_config(&self.root_path, config).await?;
+        validate_deploy(config)?;
+        let tests = read_tests_from_root_dir(&self.root_path).await?;
+        self.deploy_and_record(config, &tests, None, None, None).await?;
         Ok(config.clone())
     }
+
+    async fn get_deployed_configs(&self) -> Result<Vec<DeployedConfigData>, MatcherError> {
+        match &self.history {
+            Some(history) => history.get_all().await,
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn get_deployed_config(&self, version_id: &str) -> Result<DeployedConfig, MatcherError> {
+        match &self.history {
+            Some(history) => history.get(version_id).await,
+            None => {
+                Err(MatcherError::DeployedConfigNotFoundError { version_id: version_id.to_owned() })
+            }
+        }
+    }
+
+    async fn rollback_config(
+        &self,
+        version_id: &str,
+        user: String,
+    ) -> Result<MatcherConfig, MatcherError> {
+        let Some(history) = &self.history else {
+            return Err(MatcherError::DeployedConfigNotFoundError {
+                version_id: version_id.to_owned(),
+            });
+        };
+        let config_dir = history.version_dir(version_id)?.join(DEPLOYED_CONFIG_DIR);
+        let config = read_config_from_root_dir(&config_dir).await?;
+        let tests = read_tests_from_root_dir(&config_dir).await?;
+        validate_deploy_with_tests(&config, &tests)?;
+
+        info!("User {} is rolling back the configuration to version {}", user, version_id);
+        self.deploy_and_record(&config, &tests, Some(user), None, Some(version_id.to_owned()))
+            .await?;
+        Ok(config)
+    }
+}
+
+impl FsMatcherConfigManagerV2 {
+    /// Returns the test cases of a draft
+    pub(super) async fn get_draft_tests(
+        &self,
+        draft_id: &str,
+    ) -> Result<Vec<RulesetTests>, MatcherError> {
+        if draft_id != DRAFT_ID {
+            return Err(MatcherError::DraftNotFoundError { draft_id: draft_id.to_string() });
+        }
+        let draft_config_dir = self.drafts_path.join(draft_id).join("config");
+        Ok(read_tests_from_root_dir(&draft_config_dir).await?)
+    }
+
+    /// Returns the configuration a draft is based on, if it was recorded
+    pub(super) async fn get_draft_base_config(
+        &self,
+        draft_id: &str,
+    ) -> Result<Option<MatcherConfig>, MatcherError> {
+        if draft_id != DRAFT_ID {
+            return Err(MatcherError::DraftNotFoundError { draft_id: draft_id.to_string() });
+        }
+        get_draft_base(&self.drafts_path.join(draft_id)).await
+    }
+
+    /// Returns the test cases of a configuration of the deploy history
+    pub(super) async fn get_deployed_config_tests(
+        &self,
+        version_id: &str,
+    ) -> Result<Vec<RulesetTests>, MatcherError> {
+        let Some(history) = &self.history else {
+            return Err(MatcherError::DeployedConfigNotFoundError {
+                version_id: version_id.to_owned(),
+            });
+        };
+        let config_dir = history.version_dir(version_id)?.join(DEPLOYED_CONFIG_DIR);
+        Ok(read_tests_from_root_dir(&config_dir).await?)
+    }
+
+    /// Returns the last version of the deploy history, if the history is enabled
+    async fn get_last_deployed_version(&self) -> Option<String> {
+        let history = self.history.as_ref()?;
+        match history.versions().await {
+            Ok(versions) => versions.last().map(|version| format_version(*version)),
+            Err(error) => {
+                error!("Cannot read the versions of the deploy history. {}", error);
+                None
+            }
+        }
+    }
+
+    /// Bases the draft on the given configuration, that must be the active one.
+    async fn set_draft_base(
+        &self,
+        draft_dir: &Path,
+        mut draft_data: MatcherConfigDraftData,
+        config: &MatcherConfig,
+    ) -> Result<(), MatcherError> {
+        let draft_base_dir = draft_dir.join(DRAFT_BASE_DIR);
+        if let Err(error) = tokio::fs::create_dir_all(&draft_base_dir).await {
+            return Err(MatcherError::InternalSystemError {
+                message: format!("Cannot create draft base directory: {:?}", error),
+            });
+        }
+        atomic_deploy_config(&draft_base_dir, config, &[]).await?;
+        draft_data.base_version = self.get_last_deployed_version().await;
+        serialize_config_node_to_file(draft_dir, &draft_data).await?;
+        Ok(())
+    }
+
+    /// Replaces the current configuration and, if the history is enabled, records the deployment.
+    /// A failure while updating the history is logged, but does not fail the deploy.
+    async fn deploy_and_record(
+        &self,
+        config: &MatcherConfig,
+        tests: &[RulesetTests],
+        user: Option<String>,
+        draft_id: Option<String>,
+        rollback_of: Option<String>,
+    ) -> Result<(), MatcherError> {
+        let _deploy_guard = self.deploy_lock.lock().await;
+        if let Some(history) = &self.history {
+            // The configuration deployed before the history was enabled is kept as first version
+            let is_empty = history.versions().await.map(|versions| versions.is_empty());
+            if let Ok(true) = is_empty {
+                if let Err(error) = history.record(&self.root_path, None, None, None).await {
+                    error!("Cannot add the current configuration to the deploy history. {}", error);
+                }
+            }
+        }
+
+        atomic_deploy_config(&self.root_path, config, tests).await?;
+
+        if let Some(history) = &self.history {
+            if let Err(error) = history.record(&self.root_path, user, draft_id, rollback_of).await {
+                error!("Cannot add the deployed configuration to the deploy history. {}", error);
+            }
+        }
+        Ok(())
+    }
 }
 
-async fn atomic_deploy_config(dir: &Path, config: &MatcherConfig) -> Result<(), MatcherError> {
-    // Validate also regex and accessor, which the MatcherConfigValidator does not do.
-    let _ = Matcher::build(config)?;
+/// The deploy history keeps the last deployed configurations. Each of them is stored in a
+/// sub directory named after its version, a progressive number:
+///
+/// ```text
+/// history/
+/// ├── 0000000001/
+/// │   ├── deployment.json   <- the DeployedConfigData
+/// │   └── config/           <- the processing tree, with the layout of the rules directory
+/// └── 0000000002/
+///     └── ...
+/// ```
+///
+/// A version is written to a temporary directory and then renamed, so that it is never
+/// read partially written.
+pub(crate) struct DeployHistory {
+    path: PathBuf,
+    max_versions: usize,
+}
+
+impl DeployHistory {
+    pub(crate) fn new(path: PathBuf, max_versions: usize) -> Self {
+        Self { path, max_versions: max_versions.max(1) }
+    }
+
+    /// Returns the versions in the history, the oldest first.
+    async fn versions(&self) -> Result<Vec<u64>, MatcherError> {
+        if !tokio::fs::try_exists(&self.path).await.unwrap_or(false) {
+            return Ok(vec![]);
+        }
+        let mut versions: Vec<u64> = gather_dir_entries(&self.path)
+            .await?
+            .iter()
+            .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.parse().ok()))
+            .collect();
+        versions.sort_unstable();
+        Ok(versions)
+    }
+
+    /// Returns the directory of an existing version. Only well formed version ids are
+    /// accepted, to avoid path traversals.
+    fn version_dir(&self, version_id: &str) -> Result<PathBuf, MatcherError> {
+        let well_formed =
+            version_id.parse::<u64>().map(|version| format_version(version) == version_id);
+        let dir = self.path.join(version_id);
+        if well_formed.unwrap_or(false) && dir.is_dir() {
+            Ok(dir)
+        } else {
+            Err(MatcherError::DeployedConfigNotFoundError { version_id: version_id.to_owned() })
+        }
+    }
+
+    async fn get_all(&self) -> Result<Vec<DeployedConfigData>, MatcherError> {
+        let mut deployments = vec![];
+        for version in self.versions().await?.into_iter().rev() {
+            let dir = self.path.join(format_version(version));
+            deployments.push(parse_node_config_from_file::<DeployedConfigData>(&dir).await?);
+        }
+        Ok(deployments)
+    }
+
+    async fn get(&self, version_id: &str) -> Result<DeployedConfig, MatcherError> {
+        let dir = self.version_dir(version_id)?;
+        let data = parse_node_config_from_file::<DeployedConfigData>(&dir).await?;
+        let config = read_config_from_root_dir(&dir.join(DEPLOYED_CONFIG_DIR)).await?;
+        Ok(DeployedConfig { data, config })
+    }
+
+    /// Adds a copy of the configuration in `config_dir` as a new version, then removes
+    /// the oldest versions exceeding `max_versions`.
+    async fn record(
+        &self,
+        config_dir: &Path,
+        user: Option<String>,
+        draft_id: Option<String>,
+        rollback_of: Option<String>,
+    ) -> Result<(), MatcherError> {
+        if let Err(error) = tokio::fs::create_dir_all(&self.path).await {
+            return Err(MatcherConfigError::DirIoError { path: self.path.clone(), error }.into());
+        }
+        let versions = self.versions().await?;
+        let data = DeployedConfigData {
+            version_id: format_version(versions.last().map(|version| version + 1).unwrap_or(1)),
+            deployed_ts_ms: Local::now().timestamp_millis(),
+            user,
+            draft_id,
+            rollback_of,
+        };
+
+        let tempdir =
+            tempfile::tempdir_in(&self.path).map_err(|err| MatcherError::InternalSystemError {
+                message: format!("Cannot create temporary directory. Err: {:?}", err),
+            })?;
+        v1::fs::copy_recursive(config_dir.to_path_buf(), tempdir.path().join(DEPLOYED_CONFIG_DIR))
+            .await?;
+        serialize_config_node_to_file(tempdir.path(), &data).await?;
+        let version_dir = self.path.join(&data.version_id);
+        if let Err(error) = tokio::fs::rename(tempdir.path(), &version_dir).await {
+            return Err(DeploymentError::DirIo { error, path: version_dir }.into());
+        }
+        info!("Added version {} to the deploy history", data.version_id);
+
+        let versions = self.versions().await?;
+        let exceeding = versions.len().saturating_sub(self.max_versions);
+        for version in &versions[..exceeding] {
+            let dir = self.path.join(format_version(*version));
+            if let Err(error) = tokio::fs::remove_dir_all(&dir).await {
+                warn!("Cannot remove version {} from the deploy history. {}", version, error);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn format_version(version: u64) -> String {
+    format!("{:010}", version)
+}
+
+/// Replaces the configuration in the directory. The test cases are written to the rulesets
+/// that are still part of the new configuration.
+///
+/// The node and rule files keep the format they had in the replaced configuration. Unchanged
+/// files are copied as they are, so that their comments are preserved.
+pub(super) async fn atomic_deploy_config(
+    dir: &Path,
+    config: &MatcherConfig,
+    tests: &[RulesetTests],
+) -> Result<(), MatcherError> {
+    validate_save(config)?;
+    let (dir_canonical, tempdir) = write_config_to_tempdir(dir, config, tests, None).await?;
+    replace_dir(&dir_canonical, tempdir).await
+}
+
+/// Rewrites all the node and rule files of the configurations in the directories in the given
+/// format. Comments are not preserved.
+///
+/// All the configurations are checked and written to temporary directories before replacing
+/// any of the directories, so that a configuration that cannot be converted, for example
+/// because toml cannot represent one of its values, leaves all of them unchanged.
+pub async fn convert_config_format(
+    dirs: &[PathBuf],
+    format: ConfigFileFormat,
+) -> Result<(), MatcherError> {
+    let mut converted = vec![];
+    for dir in dirs {
+        let config = read_config_from_root_dir(dir).await?;
+        let tests = read_tests_from_root_dir(dir).await?;
+        format.check_config(&config).map_err(|message| MatcherError::ConfigurationError {
+            message: format!("Cannot convert the configuration in {}. {}", dir.display(), message),
+        })?;
+        validate_save(&config)?;
+        converted.push(write_config_to_tempdir(dir, &config, &tests, Some(format)).await?);
+    }
+    for (dir_canonical, tempdir) in converted {
+        replace_dir(&dir_canonical, tempdir).await?;
+    }
+    Ok(())
+}
+
+/// Writes the configuration to a temporary directory next to `dir`, that can then replace it.
+/// If a format is given, all the node and rule files are written in it, otherwise they keep
+/// the format they have in `dir`.
+async fn write_config_to_tempdir(
+    dir: &Path,
+    config: &MatcherConfig,
+    tests: &[RulesetTests],
+    format: Option<ConfigFileFormat>,
+) -> Result<(PathBuf, tempfile::TempDir), MatcherError> {
     let dir_canonical = match dir.canonicalize() {
         Ok(parent) => parent,
         Err(error) => {
@@ -169,19 +577,30 @@ async fn atomic_deploy_config(dir: &Path, config: &MatcherConfig) -> Result<(),
             message: format!("Cannot create temporary directory. Err: {:?}", err),
         })?;
 
+    let (previous_dir, format) = match format {
+        Some(format) => (None, format),
+        None => (Some(dir_canonical.as_path()), ConfigFileFormat::Json),
+    };
+
     serialize_config_node_to_file(tempdir.path(), &Version::default()).await?;
     match config {
         MatcherConfig::Filter { name, nodes, .. } if name == "root" => {
-            deploy_child_nodes_to_dir(tempdir.path(), nodes).await?;
+            deploy_child_nodes_to_dir(tempdir.path(), nodes, previous_dir, format).await?;
         }
         config => {
             // This branch should never be taken. If we read a config without root node by accident,
             // however, this will be the fallback.
-            deploy_child_nodes_to_dir(tempdir.path(), &[config.clone()]).await?;
+            deploy_child_nodes_to_dir(tempdir.path(), &[config.clone()], previous_dir, format)
+                .await?;
         }
     };
+    deploy_tests(tempdir.path(), tests).await?;
+    Ok((dir_canonical, tempdir))
+}
 
-    if let Err(error) = tokio::fs::remove_dir_all(&dir_canonical).await {
+/// Replaces the directory with the temporary directory the configuration was written to.
+async fn replace_dir(dir_canonical: &Path, tempdir: tempfile::TempDir) -> Result<(), MatcherError> {
+    if let Err(error) = tokio::fs::remove_dir_all(dir_canonical).await {
         // todo: improve in NEPROD-1658
         return Err(MatcherError::InternalSystemError {
             message: format!(
@@ -195,21 +614,65 @@ async fn atomic_deploy_config(dir: &Path, config: &MatcherConfig) -> Result<(),
     // todo: If the machine looses power here, or the kernel panics, we can loose the whole or parts of the configuration.
 
     // Replace the directory inode. This is an atomic operation to overwrite the directory.
-    if let Err(error) = tokio::fs::rename(tempdir.path(), &dir_canonical).await {
+    if let Err(error) = tokio::fs::rename(tempdir.path(), dir_canonical).await {
         return Err(DeploymentError::DirIo { error, path: dir_canonical.to_path_buf() }.into());
     }
 
     Ok(())
 }
 
+/// The directories of the child nodes in the previous configuration, by node name.
+async fn previous_child_dirs(previous_dir: Option<&Path>) -> HashMap<String, PathBuf> {
+    #[derive(Deserialize)]
+    struct NodeName {
+        name: String,
+    }
+
+    let mut dirs = HashMap::new();
+    let Some(previous_dir) = previous_dir else { return dirs };
+    let Ok(entries) = gather_dir_entries(previous_dir).await else { return dirs };
+    for entry in entries {
+        let child_dir = entry.path();
+        if !child_dir.is_dir() {
+            continue;
+        }
+        for config_type in [ConfigType::Filter, ConfigType::Iterator, ConfigType::Ruleset] {
+            if let Ok(Some(file)) = find_node_config_file(&child_dir, config_type).await {
+                if let Ok(node) = parse_from_file::<NodeName>(&file).await {
+                    dirs.insert(node.name, child_dir.clone());
+                }
+                break;
+            }
+        }
+    }
+    dirs
+}
+
+/// The rule files of a ruleset in the previous configuration, by rule name.
+async fn previous_rule_files(previous_dir: Option<&Path>) -> HashMap<String, (PathBuf, Rule)> {
+    let mut files = HashMap::new();
+    let Some(previous_dir) = previous_dir else { return files };
+    let Ok(entries) = gather_dir_entries(&previous_dir.join("rules")).await else { return files };
+    for entry in entries {
+        if let Ok(rule) = parse_from_file::<Rule>(&entry.path()).await {
+            files.insert(rule.name.clone(), (entry.path(), rule));
+        }
+    }
+    files
+}
+
 #[async_recursion::async_recursion]
 async fn deploy_child_nodes_to_dir(
     path: &Path,
     nodes: &[MatcherConfig],
+    previous_dir: Option<&'async_recursion Path>,
+    format: ConfigFileFormat,
 ) -> Result<(), DeploymentError> {
+    let previous_dirs = previous_child_dirs(previous_dir).await;
     let mut futures_unordered = FuturesUnordered::new();
     for node in nodes {
-        futures_unordered.push(deploy_child_node(path, node))
+        let previous_dir = previous_dirs.get(node.get_name()).map(PathBuf::as_path);
+        futures_unordered.push(deploy_child_node(path, node, previous_dir, format))
     }
 
     // Await the deployment concurrently to lessen the impact of all the syncs.
@@ -220,17 +683,22 @@ async fn deploy_child_nodes_to_dir(
     Ok(())
 }
 
-async fn deploy_child_node(path: &Path, node: &MatcherConfig) -> Result<(), DeploymentError> {
+async fn deploy_child_node(
+    path: &Path,
+    node: &MatcherConfig,
+    previous_dir: Option<&Path>,
+    format: ConfigFileFormat,
+) -> Result<(), DeploymentError> {
     let parent = create_sub_directory(path, node.get_name()).await?;
     match node {
         MatcherConfig::Filter { name, filter, nodes } => {
-            deploy_filter_node(&parent, name, filter, nodes).await?;
+            deploy_filter_node(&parent, name, filter, nodes, previous_dir, format).await?;
         }
         MatcherConfig::Ruleset { name, rules } => {
-            deploy_ruleset_node(&parent, name, rules).await?;
+            deploy_ruleset_node(&parent, name, rules, previous_dir, format).await?;
         }
         MatcherConfig::Iterator { name, iterator, nodes } => {
-            deploy_iterator_node(&parent, name, iterator, nodes).await?
+            deploy_iterator_node(&parent, name, iterator, nodes, previous_dir, format).await?
         }
     }
 
@@ -242,6 +710,8 @@ async fn deploy_filter_node(
     name: &str,
     filter: &Filter,
     nodes: &[MatcherConfig],
+    previous_dir: Option<&Path>,
+    format: ConfigFileFormat,
 ) -> Result<(), DeploymentError> {
     let config = MatcherConfigFilter {
         node_type: Default::default(),
@@ -249,8 +719,8 @@ async fn deploy_filter_node(
         filter: filter.clone(),
     };
 
-    serialize_config_node_to_file(dir, &config).await?;
-    deploy_child_nodes_to_dir(dir, nodes).await?;
+    let format = write_node_file(dir, &config, previous_dir, format).await?;
+    deploy_child_nodes_to_dir(dir, nodes, previous_dir, format).await?;
 
     Ok(())
 }
@@ -260,6 +730,8 @@ async fn deploy_iterator_node(
     name: &str,
     iterator: &MatcherIterator,
     nodes: &[MatcherConfig],
+    previous_dir: Option<&Path>,
+    format: ConfigFileFormat,
 ) -> Result<(), DeploymentError> {
     let config = MatcherConfigIterator {
         node_type: Default::default(),
@@ -267,8 +739,8 @@ async fn deploy_iterator_node(
         iterator: iterator.to_owned(),
     };
 
-    serialize_config_node_to_file(dir, &config).await?;
-    deploy_child_nodes_to_dir(dir, nodes).await?;
+    let format = write_node_file(dir, &config, previous_dir, format).await?;
+    deploy_child_nodes_to_dir(dir, nodes, previous_dir, format).await?;
 
     Ok(())
 }
@@ -277,24 +749,135 @@ async fn deploy_ruleset_node(
     dir: &Path,
     name: &str,
     rules: &[Rule],
+    previous_dir: Option<&Path>,
+    format: ConfigFileFormat,
 ) -> Result<(), DeploymentError> {
     let config = MatcherConfigRuleset { node_type: Default::default(), name: name.to_string() };
-    serialize_config_node_to_file(dir, &config).await?;
-    deploy_rules(dir, rules).await?;
+    let format = write_node_file(dir, &config, previous_dir, format).await?;
+    deploy_rules(dir, rules, previous_dir, format).await?;
 
     Ok(())
 }
 
-async fn deploy_rules(dir: &Path, rules: &[Rule]) -> Result<(), DeploymentError> {
+/// Writes the config file of a node and returns the format it was written in.
+async fn write_node_file<T: Serialize + DeserializeOwned + ConfigNodeDir>(
+    dir: &Path,
+    data: &T,
+    previous_dir: Option<&Path>,
+    format: ConfigFileFormat,
+) -> Result<ConfigFileFormat, DeploymentError> {
+    let mut previous = None;
+    if let Some(previous_dir) = previous_dir {
+        if let Ok(Some(file)) = find_node_config_file(previous_dir, T::config_type()).await {
+            if let Ok(content) = parse_from_file::<T>(&file).await {
+                previous = Some((file, content));
+            }
+        }
+    }
+
+    write_config_file(dir, T::config_type().file_stem(), data, previous, format).await
+}
+
+async fn deploy_rules(
+    dir: &Path,
+    rules: &[Rule],
+    previous_dir: Option<&Path>,
+    format: ConfigFileFormat,
+) -> Result<(), DeploymentError> {
+    let mut previous_files = previous_rule_files(previous_dir).await;
     let rules_dir = create_sub_directory(dir, "rules").await?;
     for (index, rule) in rules.iter().enumerate() {
-        let filename = format!("{:09}0_{}.json", index, rule.name);
-        let config_file_path = {
-            let mut path = rules_dir.clone();
-            path.push(&filename);
+        let file_stem = format!("{:09}0_{}", index, rule.name);
+        let previous = previous_files.remove(&rule.name);
+        write_config_file(&rules_dir, &file_stem, rule, previous, format).await?;
+    }
+
+    Ok(())
+}
+
+/// Writes a node or a rule file. If the file was part of the previous configuration, it keeps
+/// its format, and it is copied as it is when its content did not change.
+/// Returns the format the file was written in.
+async fn write_config_file<T: Serialize>(
+    dir: &Path,
+    file_stem: &str,
+    data: &T,
+    previous: Option<(PathBuf, T)>,
+    format: ConfigFileFormat,
+) -> Result<ConfigFileFormat, DeploymentError> {
+    // The previous file keeps also its extension, e.g. `.yml` instead of `.yaml`.
+    let (format, extension) = match previous {
+        Some((previous_file, previous_data)) => {
+            let format = ConfigFileFormat::from_path(&previous_file).unwrap_or_default();
+            let extension = previous_file
+                .extension()
+                .and_then(OsStr::to_str)
+                .unwrap_or_else(|| format.extension())
+                .to_owned();
+            if serde_json::to_value(&previous_data).ok() == serde_json::to_value(data).ok() {
+                let path = dir.join(format!("{}.{}", file_stem, extension));
+                if let Err(error) = tokio::fs::copy(&previous_file, &path).await {
+                    return Err(DeploymentError::FileIo { path, error });
+                }
+                return Ok(format);
+            }
+            (format, extension)
+        }
+        None => (format, format.extension().to_owned()),
+    };
+
+    let (format, extension, bytes) = match format.serialize(data) {
+        Ok(bytes) => (format, extension, bytes),
+        Err(message) => {
+            warn!(
+                "Cannot write the file {} in {} format, falling back to json. {}",
+                file_stem, format, message
+            );
+            match serde_json::to_vec_pretty(data) {
+                Ok(bytes) => {
+                    (ConfigFileFormat::Json, ConfigFileFormat::Json.extension().to_owned(), bytes)
+                }
+                Err(error) => {
+                    return Err(DeploymentError::Serialization {
+                        error,
+                        data_type: std::any::type_name::<T>(),
+                    })
+                }
+            }
+        }
+    };
+
+    let path = dir.join(format!("{}.{}", file_stem, extension));
+    write_to_file(&path, &bytes).await?;
+    Ok(format)
+}
+
+async fn deploy_tests(dir: &Path, tests: &[RulesetTests]) -> Result<(), DeploymentError> {
+    for ruleset_tests in tests {
+        let ruleset_dir = {
+            let mut path = dir.to_path_buf();
+            // The first element of the path is the implicit root node
+            ruleset_tests.ruleset_path.iter().skip(1).for_each(|name| path.push(name));
             path
         };
-        serialize_to_file(&config_file_path, rule).await?;
+        if !matches!(find_node_config_file(&ruleset_dir, ConfigType::Ruleset).await, Ok(Some(_))) {
+            info!(
+                "Dropping the tests of the ruleset [{}] as it is not part of the configuration anymore.",
+                ruleset_tests.ruleset_path.join(",")
+            );
+            continue;
+        }
+
+        let tests_dir = create_sub_directory(&ruleset_dir, TESTS_DIR).await?;
+        for (index, test) in ruleset_tests.tests.iter().enumerate() {
+            let name: String = test
+                .name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+                .collect();
+            let filename = format!("{:09}0_{}.json", index, name);
+            serialize_to_file(&tests_dir.join(filename), test).await?;
+        }
     }
 
     Ok(())
@@ -324,13 +907,17 @@ async fn serialize_to_file<T: Serialize>(path: &Path, data: &T) -> Result<(), De
         }
     };
 
+    write_to_file(path, &bytes).await
+}
+
+async fn write_to_file(path: &Path, bytes: &[u8]) -> Result<(), DeploymentError> {
     let mut file =
         match tokio::fs::File::options().write(true).create(true).truncate(true).open(path).await {
             Ok(file) => file,
             Err(error) => return Err(DeploymentError::DirIo { path: path.to_path_buf(), error }),
         };
 
-    if let Err(error) = file.write_all(&bytes).await {
+    if let Err(error) = file.write_all(bytes).await {
         return Err(DeploymentError::FileIo { path: path.to_path_buf(), error });
     }
 
@@ -405,11 +992,22 @@ async fn get_draft_from_dir(draft_dir: &Path) -> Result<MatcherConfigDraft, Matc
     Ok(MatcherConfigDraft { data: draft_data, config: draft_config })
 }
 
+/// Returns the configuration the draft is based on. The drafts created before the base
+/// was recorded do not have it.
+async fn get_draft_base(draft_dir: &Path) -> Result<Option<MatcherConfig>, MatcherError> {
+    let draft_base_dir = draft_dir.join(DRAFT_BASE_DIR);
+    if !tokio::fs::try_exists(&draft_base_dir).await.unwrap_or(false) {
+        return Ok(None);
+    }
+    Ok(Some(read_config_from_root_dir(&draft_base_dir).await?))
+}
+
 async fn create_draft(
     processing_tree_dir: &Path,
     draft_dir: &Path,
     user: &str,
     draft_id: &str,
+    base_version: Option<String>,
 ) -> Result<(), MatcherError> {
     info!("Creating a new draft {draft_id} for user {user}");
 
@@ -425,6 +1023,7 @@ async fn create_draft(
         updated_ts_ms: now,
         user: user.to_string(),
         draft_id: draft_id.to_string(),
+        base_version,
     };
 
     if let Err(error) = tokio::fs::create_dir_all(draft_dir).await {
@@ -433,20 +1032,27 @@ async fn create_draft(
         });
     };
     serialize_config_node_to_file(draft_dir, &draft_data).await?;
-    v1::fs::copy_and_override(processing_tree_dir, &draft_config_dir).await
+    v1::fs::copy_and_override(processing_tree_dir, &draft_config_dir).await?;
+    v1::fs::copy_and_override(processing_tree_dir, draft_dir.join(DRAFT_BASE_DIR)).await
 }
 
 #[cfg(test)]
 mod tests {
     use crate::config::nodes::MatcherIterator;
+    use crate::config::rule::Rule;
     use crate::config::v1::fs::copy_recursive;
-    use crate::config::v2::editor::{deploy_iterator_node, get_draft_from_dir, DRAFT_ID};
+    use crate::config::v2::editor::{
+        convert_config_format, deploy_iterator_node, get_draft_from_dir, DRAFT_ID,
+    };
     use crate::config::v2::{
-        parse_node_config_from_file, ConfigType, FsMatcherConfigManagerV2, MatcherConfigIterator,
+        parse_node_config_from_file, ConfigFileFormat, ConfigType, FsMatcherConfigManagerV2,
+        MatcherConfigIterator,
     };
     use crate::config::{
-        MatcherConfig, MatcherConfigDraftData, MatcherConfigEditor, MatcherConfigReader,
+        DeployedConfigData, MatcherConfig, MatcherConfigDraftData, MatcherConfigEditor,
+        MatcherConfigReader,
     };
+    use crate::error::MatcherError;
     use std::path::{Path, PathBuf};
     use tempfile::TempDir;
 
@@ -625,6 +1231,246 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn matcher_config_editor_should_keep_the_tests_when_deploying_a_draft() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path());
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_DRAFT_DIR), draft_temp_dir.clone()).await.unwrap();
+        let draft = config_manager.get_draft(DRAFT_ID).await.unwrap();
+
+        // Act
+        config_manager.update_draft(DRAFT_ID, "root".to_owned(), &draft.config).await.unwrap();
+        config_manager.deploy_draft(DRAFT_ID).await.unwrap();
+
+        // Assert
+        let tests = config_manager.get_config_tests().await.unwrap();
+        assert_eq!(1, tests.len());
+        assert_eq!(vec!["root", "master", "archive_emails"], tests[0].ruleset_path);
+        assert_eq!("email", tests[0].tests[0].name);
+        assert!(config_temp_dir
+            .join("master/archive_emails/tests/0000000000_email.json")
+            .is_file());
+    }
+
+    #[tokio::test]
+    async fn matcher_config_editor_should_not_deploy_a_draft_with_failing_tests() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path());
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_DRAFT_DIR), draft_temp_dir.clone()).await.unwrap();
+        let test_file = draft_temp_dir
+            .join(DRAFT_ID)
+            .join("config/master/archive_emails/tests/0000000000_email.json");
+        let test = tokio::fs::read_to_string(&test_file).await.unwrap();
+        tokio::fs::write(
+            &test_file,
+            test.replace(r#""archive_type": "one""#, r#""archive_type": "two""#),
+        )
+        .await
+        .unwrap();
+
+        // Act
+        let result = config_manager.deploy_draft(DRAFT_ID).await;
+
+        // Assert
+        match result {
+            Err(MatcherError::RuleTestsFailedError { message }) => {
+                assert!(message.starts_with(
+                    "Test [email] of ruleset [root,master,archive_emails] failed: The payload of action 0 [archive]"
+                ));
+            }
+            result => panic!("{:?}", result),
+        }
+        match config_manager.get_config().await.unwrap() {
+            MatcherConfig::Filter { nodes, .. } => assert_eq!(3, nodes.len()),
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn matcher_config_editor_should_save_but_not_deploy_a_draft_with_undefined_variables() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path());
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_DRAFT_DIR), draft_temp_dir.clone()).await.unwrap();
+        let mut config = config_manager.get_draft(DRAFT_ID).await.unwrap().config;
+        let rule: Rule = serde_json::from_value(serde_json::json!({
+            "name": "incomplete",
+            "description": "",
+            "continue": true,
+            "active": true,
+            "constraint": { "WHERE": null, "WITH": {} },
+            "actions": [{ "id": "logger", "payload": { "value": "${_variables.missing}" } }]
+        }))
+        .unwrap();
+        config
+            .create_node_in_path(
+                &["root"],
+                MatcherConfig::Ruleset { name: "incomplete".to_owned(), rules: vec![rule] },
+            )
+            .unwrap();
+
+        // Act
+        let update = config_manager.update_draft(DRAFT_ID, "root".to_owned(), &config).await;
+        let deploy = config_manager.deploy_draft(DRAFT_ID).await;
+
+        // Assert
+        assert!(update.is_ok());
+        let saved = config_manager.get_draft(DRAFT_ID).await.unwrap().config;
+        assert_eq!(
+            config.get_node_by_path(&["root", "incomplete"]),
+            saved.get_node_by_path(&["root", "incomplete"])
+        );
+        match deploy {
+            Err(MatcherError::ConfigurationError { message }) => {
+                assert!(message.contains("[${_variables.missing}]"));
+            }
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn matcher_config_editor_should_keep_the_deploy_history_and_roll_back() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        let history_temp_dir = temp_dir.path().join("history");
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path())
+                .with_history(history_temp_dir.as_path(), 2);
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_DRAFT_DIR), draft_temp_dir.clone()).await.unwrap();
+        let initial_config = config_manager.get_config().await.unwrap();
+
+        // Act
+        config_manager.deploy_draft(DRAFT_ID).await.unwrap();
+        let deployed_draft = config_manager.get_config().await.unwrap();
+        let history_after_deploy = config_manager.get_deployed_configs().await.unwrap();
+        config_manager.rollback_config("0000000001", "admin".to_owned()).await.unwrap();
+        let history_after_rollback = config_manager.get_deployed_configs().await.unwrap();
+
+        // Assert
+        let versions = |history: &[DeployedConfigData]| {
+            history.iter().map(|data| data.version_id.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["0000000002", "0000000001"], versions(&history_after_deploy));
+        assert_eq!(Some("root".to_owned()), history_after_deploy[0].user);
+        assert_eq!(Some(DRAFT_ID.to_owned()), history_after_deploy[0].draft_id);
+        assert_eq!(None, history_after_deploy[1].user);
+
+        // Only the last two versions are kept
+        assert_eq!(vec!["0000000003", "0000000002"], versions(&history_after_rollback));
+        assert_eq!(Some("admin".to_owned()), history_after_rollback[0].user);
+        assert_eq!(Some("0000000001".to_owned()), history_after_rollback[0].rollback_of);
+        assert_eq!(initial_config, config_manager.get_config().await.unwrap());
+        assert_eq!(
+            deployed_draft,
+            config_manager.get_deployed_config("0000000002").await.unwrap().config
+        );
+        assert_eq!(
+            Err(MatcherError::DeployedConfigNotFoundError { version_id: "0000000001".to_owned() }),
+            config_manager.get_deployed_config("0000000001").await
+        );
+        assert!(config_manager.rollback_config("../rules.d", "admin".to_owned()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn matcher_config_editor_should_record_concurrent_deploys_as_different_versions() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        let config_manager = FsMatcherConfigManagerV2::new(
+            config_temp_dir.as_path(),
+            temp_dir.path().join("drafts"),
+        )
+        .with_history(temp_dir.path().join("history"), 10);
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        let config = config_manager.get_config().await.unwrap();
+
+        // Act
+        let deploys = futures::future::join_all(
+            (0..3).map(|_| config_manager.deploy_config(&config)).collect::<Vec<_>>(),
+        )
+        .await;
+
+        // Assert
+        assert!(deploys.iter().all(|deploy| deploy.is_ok()));
+        let versions = config_manager
+            .get_deployed_configs()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|data| data.version_id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["0000000004", "0000000003", "0000000002", "0000000001"], versions);
+    }
+
+    #[tokio::test]
+    async fn matcher_config_editor_should_rebase_an_outdated_draft_before_deploying_it() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path())
+                .with_history(temp_dir.path().join("history"), 10);
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        config_manager.deploy_config(&config_manager.get_config().await.unwrap()).await.unwrap();
+
+        let draft_id = config_manager.create_draft("user".to_owned()).await.unwrap();
+        let mut draft_config = config_manager.get_draft(&draft_id).await.unwrap().config;
+        draft_config.delete_node_in_path(&["root", "empty_filter"]).unwrap();
+        config_manager.update_draft(&draft_id, "user".to_owned(), &draft_config).await.unwrap();
+
+        let mut active_config = config_manager.get_config().await.unwrap();
+        active_config.delete_node_in_path(&["root", "tenant_a"]).unwrap();
+        config_manager.deploy_config(&active_config).await.unwrap();
+
+        // Act
+        let outdated_deploy = config_manager.deploy_draft(&draft_id).await;
+        let rebase_preview = config_manager.get_draft_rebase(&draft_id).await.unwrap();
+        let not_owner_rebase = config_manager.rebase_draft(&draft_id, "another".to_owned()).await;
+        let rebase = config_manager.rebase_draft(&draft_id, "user".to_owned()).await.unwrap();
+        let rebased_draft = config_manager.get_draft(&draft_id).await.unwrap();
+        let deployed = config_manager.deploy_draft(&draft_id).await.unwrap();
+
+        // Assert
+        assert_eq!(
+            Err(MatcherError::DraftOutdatedError { draft_id: draft_id.clone() }),
+            outdated_deploy
+        );
+        assert!(rebase_preview.outdated);
+        assert!(rebase_preview.conflicts.is_empty());
+        assert!(not_owner_rebase.is_err());
+        assert_eq!(rebase_preview, rebase);
+        assert_eq!(Some("0000000003".to_owned()), rebased_draft.data.base_version);
+        match &deployed {
+            MatcherConfig::Filter { nodes, .. } => {
+                assert_eq!(
+                    vec!["master"],
+                    nodes.iter().map(|node| node.get_name()).collect::<Vec<_>>()
+                );
+            }
+            result => panic!("{:?}", result),
+        }
+        assert_eq!(deployed, config_manager.get_config().await.unwrap());
+        assert!(!config_manager.get_draft_rebase(&draft_id).await.unwrap().outdated);
+    }
+
     #[tokio::test]
     async fn matcher_config_editor_should_take_over_draft() {
         // Arrange
@@ -660,6 +1506,8 @@ mod tests {
                 target: "${event.payload.alerts}.to_string()".to_string(),
             },
             &[config],
+            None,
+            ConfigFileFormat::Json,
         )
         .await
         .unwrap();
@@ -669,4 +1517,125 @@ mod tests {
 
         assert_eq!("master_iterator", loaded.name);
     }
+
+    fn write_file(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_keep_the_format_and_the_comments_of_the_files_on_deploy() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let filter_yaml = "# Accepts all the events\ntype: filter\nname: master\ndescription: ''\nactive: true\nfilter: {}\n";
+        let ruleset_toml = "# The emails\ntype = \"ruleset\"\nname = \"emails\"\n";
+        let rule_yaml = |name: &str, description: &str| {
+            format!(
+                "# A commented rule\nname: {}\ndescription: {}\ncontinue: true\nactive: true\nconstraint:\n  WITH: {{}}\nactions: []\n",
+                name, description
+            )
+        };
+        write_file(&root.join("version.json"), r#"{"version": "2.0"}"#);
+        write_file(&root.join("master/filter.yaml"), filter_yaml);
+        write_file(&root.join("master/emails/ruleset.toml"), ruleset_toml);
+        write_file(
+            &root.join("master/emails/rules/0000000000_first.yaml"),
+            &rule_yaml("first", "one"),
+        );
+        write_file(
+            &root.join("master/emails/rules/0000000010_second.yml"),
+            &rule_yaml("second", "two"),
+        );
+        let config_manager = FsMatcherConfigManagerV2::new(root, root.join("drafts"));
+
+        let mut config = config_manager.get_config().await.unwrap();
+        let MatcherConfig::Filter { nodes, .. } = &mut config else { unreachable!() };
+        let MatcherConfig::Filter { nodes, .. } = &mut nodes[0] else { unreachable!() };
+        let MatcherConfig::Ruleset { rules, .. } = &mut nodes[0] else { unreachable!() };
+        rules[1].description = "changed".to_owned();
+        rules.push(Rule { name: "third".to_owned(), ..rules[0].clone() });
+
+        // Act
+        config_manager.deploy_config(&config).await.unwrap();
+
+        // Assert
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).unwrap();
+        assert_eq!(filter_yaml, read("master/filter.yaml"));
+        assert_eq!(ruleset_toml, read("master/emails/ruleset.toml"));
+        assert_eq!(rule_yaml("first", "one"), read("master/emails/rules/0000000000_first.yaml"));
+        let second = read("master/emails/rules/0000000010_second.yml");
+        assert!(!second.contains("# A commented rule"));
+        assert!(second.contains("description: changed"));
+        // New rules are written in the format of their ruleset
+        assert!(root.join("master/emails/rules/0000000020_third.toml").is_file());
+        assert_eq!(config, config_manager.get_config().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_convert_the_configuration_to_another_format() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("rules.d");
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), root.clone()).await.unwrap();
+        let config_manager = FsMatcherConfigManagerV2::new(&root, temp_dir.path());
+        let config = config_manager.get_config().await.unwrap();
+
+        for format in ConfigFileFormat::ALL {
+            // Act
+            convert_config_format(std::slice::from_ref(&root), format).await.unwrap();
+
+            // Assert
+            assert!(root.join("master").join(format!("filter.{}", format)).is_file());
+            assert!(root.join("version.json").is_file());
+            assert_eq!(config, config_manager.get_config().await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn should_not_convert_any_configuration_if_one_contains_nulls_in_toml() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("rules.d");
+        let draft_root = temp_dir.path().join("draft");
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), root.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), draft_root.clone()).await.unwrap();
+        let draft_manager = FsMatcherConfigManagerV2::new(&draft_root, temp_dir.path());
+        let mut draft_config = draft_manager.get_config().await.unwrap();
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "name": "with_null",
+                "description": "",
+                "continue": true,
+                "active": true,
+                "constraint": { "WHERE": null, "WITH": {} },
+                "actions": [ { "id": "logger", "payload": { "list": [1, null] } } ]
+            }"#,
+        )
+        .unwrap();
+        draft_config
+            .create_node_in_path(
+                &["root"],
+                MatcherConfig::Ruleset { name: "nulls".to_owned(), rules: vec![rule] },
+            )
+            .unwrap();
+        draft_manager.deploy_config(&draft_config).await.unwrap();
+
+        // Act
+        let result =
+            convert_config_format(&[root.clone(), draft_root.clone()], ConfigFileFormat::Toml)
+                .await;
+
+        // Assert
+        match result {
+            Err(MatcherError::ConfigurationError { message }) => {
+                assert!(message.contains("The rule [with_null] of the node [/root/nulls]"));
+                assert!(message.contains("[logger] contains a null value at [/list/1]"));
+            }
+            result => panic!("{:?}", result),
+        }
+        assert!(root.join("master/filter.json").is_file());
+        assert!(draft_root.join("master/filter.json").is_file());
+        assert!(draft_root.join("nulls/ruleset.json").is_file());
+    }
 }
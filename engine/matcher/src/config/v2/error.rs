@@ -1,4 +1,4 @@
-use crate::config::v2::{ConfigType, Version};
+use crate::config::v2::{ConfigFileFormat, ConfigType, Version};
 use crate::error::MatcherError;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
@@ -14,6 +14,7 @@ pub enum MatcherConfigError {
     FileIoError { path: PathBuf, error: io::Error },
     DeserializationError { file: PathBuf, object_path: String, error: serde_json::Error },
     FormatError { file: PathBuf, error: serde_json::Error },
+    ParseError { file: PathBuf, format: ConfigFileFormat, message: String },
     FileNameError { path: PathBuf },
     DuplicateName { name: String, previous: PathBuf, next: PathBuf },
     OldVersion { found_version: Version },
@@ -66,6 +67,12 @@ impl Display for MatcherConfigError {
                 file.display(),
                 error
             )),
+            MatcherConfigError::ParseError { file, format, message } => f.write_fmt(format_args!(
+                "Could not deserialize config file {}, as it is not a valid {} file: {}",
+                file.display(),
+                format,
+                message
+            )),
             MatcherConfigError::OldVersion { found_version } => f.write_fmt(format_args!(
                 "Found old version {}, but the current supported version is {}",
                 serde_json::to_string(found_version).unwrap(),
@@ -82,6 +89,7 @@ impl Error for MatcherConfigError {
             MatcherConfigError::FileIoError { error, .. } => Some(error as &dyn Error),
             MatcherConfigError::DeserializationError { error, .. } => Some(error as &dyn Error),
             MatcherConfigError::FormatError { error, .. } => Some(error as &dyn Error),
+            MatcherConfigError::ParseError { .. } => None,
             MatcherConfigError::UnexpectedFile { .. } => None,
             MatcherConfigError::UnknownNodeDir { .. } => None,
             MatcherConfigError::FileNotFound { .. } => None,
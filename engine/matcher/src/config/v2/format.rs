@@ -0,0 +1,203 @@
+use crate::config::rule::Rule;
+use crate::config::MatcherConfig;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The formats in which the node and the rule files of the processing tree can be written.
+/// The format of a file is determined by its extension.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ConfigFileFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFileFormat {
+    pub const ALL: [ConfigFileFormat; 3] =
+        [ConfigFileFormat::Json, ConfigFileFormat::Yaml, ConfigFileFormat::Toml];
+
+    /// The file extensions of the format. The first one is used when writing new files.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            ConfigFileFormat::Json => &["json"],
+            ConfigFileFormat::Yaml => &["yaml", "yml"],
+            ConfigFileFormat::Toml => &["toml"],
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        self.extensions()[0]
+    }
+
+    pub fn from_path(path: &Path) -> Option<ConfigFileFormat> {
+        let extension = path.extension()?.to_str()?;
+        ConfigFileFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.extensions().contains(&extension))
+    }
+
+    /// Parses the content of a yaml or toml file into its json representation, so that it can
+    /// be deserialized like the content of a json file.
+    pub(super) fn parse_to_json(&self, content: &str) -> Result<serde_json::Value, String> {
+        match self {
+            ConfigFileFormat::Json => serde_json::from_str(content).map_err(|err| err.to_string()),
+            ConfigFileFormat::Yaml => serde_yaml::from_str(content).map_err(|err| err.to_string()),
+            ConfigFileFormat::Toml => {
+                let value: toml::Value = toml::from_str(content).map_err(|err| err.to_string())?;
+                serde_json::to_value(value).map_err(|err| err.to_string())
+            }
+        }
+    }
+
+    /// Serializes the data in this format. Fails, for example, when writing toml files
+    /// containing null values, which toml cannot represent.
+    pub(super) fn serialize<T: Serialize>(&self, data: &T) -> Result<Vec<u8>, String> {
+        match self {
+            ConfigFileFormat::Json => {
+                serde_json::to_vec_pretty(data).map_err(|err| err.to_string())
+            }
+            ConfigFileFormat::Yaml => {
+                serde_yaml::to_string(data).map(String::into_bytes).map_err(|err| err.to_string())
+            }
+            ConfigFileFormat::Toml => {
+                // Going through toml::Value puts the tables after the plain values,
+                // as required by the toml format.
+                let value = toml::Value::try_from(data).map_err(|err| err.to_string())?;
+                toml::to_string_pretty(&value)
+                    .map(String::into_bytes)
+                    .map_err(|err| err.to_string())
+            }
+        }
+    }
+
+    /// Checks that all the nodes and rules of the configuration can be written in this format,
+    /// so that a conversion fails before any file is written. The error names the first node
+    /// or rule that cannot be written, for example a rule with a null in an action payload,
+    /// which toml cannot represent.
+    pub(super) fn check_config(&self, config: &MatcherConfig) -> Result<(), String> {
+        self.check_node(config, "")
+    }
+
+    fn check_node(&self, node: &MatcherConfig, parent_path: &str) -> Result<(), String> {
+        let node_path = format!("{}/{}", parent_path, node.get_name());
+        let (serialized, nodes) = match node {
+            MatcherConfig::Filter { filter, nodes, .. } => (self.serialize(filter), nodes),
+            MatcherConfig::Iterator { iterator, nodes, .. } => (self.serialize(iterator), nodes),
+            MatcherConfig::Ruleset { rules, .. } => {
+                return rules.iter().try_for_each(|rule| self.check_rule(rule, &node_path));
+            }
+        };
+        if let Err(err) = serialized {
+            return Err(format!("The node [{}] cannot be written in {}: {}", node_path, self, err));
+        }
+        nodes.iter().try_for_each(|child| self.check_node(child, &node_path))
+    }
+
+    fn check_rule(&self, rule: &Rule, node_path: &str) -> Result<(), String> {
+        self.serialize(rule).map(|_| ()).map_err(|err| {
+            let null_in_payload = rule.actions.iter().find_map(|action| {
+                find_null(&Value::Object(action.payload.clone()), "").map(|pointer| {
+                    format!(
+                        "the payload of the action [{}] contains a null value at [{}], \
+                        which {} cannot represent",
+                        action.id, pointer, self
+                    )
+                })
+            });
+            format!(
+                "The rule [{}] of the node [{}] cannot be written in {}: {}",
+                rule.name,
+                node_path,
+                self,
+                null_in_payload.unwrap_or(err)
+            )
+        })
+    }
+}
+
+/// Returns the JSON pointer of the first null in the value
+fn find_null(value: &Value, pointer: &str) -> Option<String> {
+    match value {
+        Value::Null => Some(pointer.to_owned()),
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .find_map(|(index, value)| find_null(value, &format!("{}/{}", pointer, index))),
+        Value::Object(map) => {
+            map.iter().find_map(|(key, value)| find_null(value, &format!("{}/{}", pointer, key)))
+        }
+        _ => None,
+    }
+}
+
+impl Display for ConfigFileFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for ConfigFileFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.to_lowercase();
+        ConfigFileFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.extensions().contains(&value.as_str()))
+            .ok_or_else(|| {
+                format!("Unknown format [{}]. Expected one of json, yaml or toml.", value)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::rule::Rule;
+    use std::path::Path;
+
+    #[test]
+    fn should_detect_the_format_from_the_extension() {
+        assert_eq!(Some(ConfigFileFormat::Json), ConfigFileFormat::from_path(Path::new("a.json")));
+        assert_eq!(Some(ConfigFileFormat::Yaml), ConfigFileFormat::from_path(Path::new("a.yml")));
+        assert_eq!(Some(ConfigFileFormat::Yaml), ConfigFileFormat::from_path(Path::new("a.yaml")));
+        assert_eq!(Some(ConfigFileFormat::Toml), ConfigFileFormat::from_path(Path::new("a.toml")));
+        assert_eq!(None, ConfigFileFormat::from_path(Path::new("a.txt")));
+        assert_eq!(Ok(ConfigFileFormat::Yaml), "YAML".parse());
+        assert!("xml".parse::<ConfigFileFormat>().is_err());
+    }
+
+    #[test]
+    fn should_round_trip_a_rule_in_every_format() {
+        // Arrange
+        let rule: Rule = serde_json::from_str(
+            r#"{
+                "name": "rule",
+                "description": "",
+                "continue": true,
+                "active": true,
+                "constraint": {
+                    "WHERE": { "type": "equals", "first": "${event.type}", "second": "email" },
+                    "WITH": {}
+                },
+                "actions": [ { "id": "logger", "payload": { "nested": { "list": [1, "two"] } } } ]
+            }"#,
+        )
+        .unwrap();
+
+        for format in ConfigFileFormat::ALL {
+            // Act
+            let bytes = format.serialize(&rule).unwrap();
+            let json = format.parse_to_json(std::str::from_utf8(&bytes).unwrap()).unwrap();
+
+            // Assert
+            assert_eq!(serde_json::to_value(&rule).unwrap(), json, "format {}", format);
+        }
+    }
+}
@@ -0,0 +1,934 @@
+use crate::config::v2::deploy::{
+    base_on_deployed_version, check_draft_up_to_date, preview_rebase, rebase_onto, validate_deploy,
+    validate_deploy_with_tests,
+};
+use crate::config::v2::editor::{atomic_deploy_config, serialize_config_node_to_file};
+use crate::config::v2::error::DeploymentError;
+use crate::config::v2::{
+    read_config_from_root_dir, read_tests_from_root_dir, ConfigType, MatcherConfigError, Version,
+};
+use crate::config::{
+    DeployedConfig, DeployedConfigData, DraftRebase, MatcherConfig, MatcherConfigDraft,
+    MatcherConfigDraftData, MatcherConfigEditor, MatcherConfigReader,
+};
+use crate::error::MatcherError;
+use crate::test_case::RulesetTests;
+use chrono::Local;
+use git2::{Commit, ErrorCode, ObjectType, Oid, Repository, Signature, Tree};
+use log::{info, warn};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The branch containing the active configuration
+const DEPLOY_BRANCH: &str = "refs/heads/main";
+/// The prefix of the branches of the drafts
+const DRAFT_BRANCH_PREFIX: &str = "refs/heads/drafts/";
+/// The directory of a draft tree that contains the draft configuration
+const DRAFT_CONFIG_DIR: &str = "config";
+/// The author of the commits not made on behalf of a user
+const SYSTEM_USER: &str = "tornado";
+
+const DRAFT_ID_TRAILER: &str = "Draft-Id:";
+const ROLLBACK_OF_TRAILER: &str = "Rollback-Of:";
+
+const FILE_MODE_BLOB: i32 = 0o100644;
+const FILE_MODE_TREE: i32 = 0o040000;
+
+/// The number of configuration trees kept parsed in memory
+const PARSED_TREES_CACHE_SIZE: usize = 8;
+
+/// Keeps the processing tree in a bare git repository, that is created on first use:
+///
+/// ```text
+/// main                   <- the active configuration, with the layout of the rules directory
+/// drafts/draft_001       <- a draft
+/// ├── data.json          <- the MatcherConfigDraftData
+/// └── config/            <- the draft configuration, with the layout of the rules directory
+/// ```
+///
+/// Each deploy is a commit on `main` whose author is the user who deployed it, so the deploy
+/// history is the log of `main`. The id of a deployed version is the id of its commit.
+/// The `base_version` of a draft is the commit of `main` the draft is based on.
+///
+/// When `main` does not exist yet, the configuration in `initial_config_dir`, if set,
+/// is committed as its first version; otherwise the first version is an empty configuration.
+///
+/// The repository is accessed in blocking tasks. The configurations read from the repository
+/// are cached by tree id: a tree id identifies its content, so the cached entries never
+/// become stale.
+pub struct GitMatcherConfigManagerV2 {
+    repository_path: PathBuf,
+    initial_config_dir: Option<PathBuf>,
+    parsed_trees: Mutex<VecDeque<ParsedTree>>,
+}
+
+/// A configuration and its tests read from a tree of the repository
+struct ParsedTree {
+    tree_id: Oid,
+    config: MatcherConfig,
+    tests: Vec<RulesetTests>,
+}
+
+/// The ids of a commit and of its tree
+#[derive(Clone, Copy)]
+struct CommitIds {
+    commit_id: Oid,
+    tree_id: Oid,
+}
+
+impl CommitIds {
+    fn of(commit: &Commit) -> Self {
+        CommitIds { commit_id: commit.id(), tree_id: commit.tree_id() }
+    }
+}
+
+impl GitMatcherConfigManagerV2 {
+    pub fn new<P: Into<PathBuf>>(repository_path: P) -> GitMatcherConfigManagerV2 {
+        GitMatcherConfigManagerV2 {
+            repository_path: repository_path.into(),
+            initial_config_dir: None,
+            parsed_trees: Mutex::new(VecDeque::with_capacity(PARSED_TREES_CACHE_SIZE)),
+        }
+    }
+
+    /// Imports the configuration in `config_dir`, with the layout of the rules directory,
+    /// when the repository does not contain a configuration yet.
+    pub fn with_initial_config_dir<P: Into<PathBuf>>(mut self, config_dir: P) -> Self {
+        self.initial_config_dir = Some(config_dir.into());
+        self
+    }
+
+    /// Runs a function on the repository in a blocking task, as libgit2 performs blocking I/O
+    async fn with_repository<T, F>(&self, function: F) -> Result<T, MatcherError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Repository) -> Result<T, MatcherError> + Send + 'static,
+    {
+        let repository_path = self.repository_path.clone();
+        tokio::task::spawn_blocking(move || function(&open_repository(&repository_path)?))
+            .await
+            .map_err(|err| MatcherError::InternalSystemError {
+                message: format!(
+                    "GitMatcherConfigManagerV2 - Cannot execute Tokio internal task. Err: {:?}",
+                    err
+                ),
+            })?
+    }
+
+    /// Returns the commit of the active configuration
+    async fn deployed_commit(&self) -> Result<CommitIds, MatcherError> {
+        let deployed = self
+            .with_repository(|repository| match repository.find_reference(DEPLOY_BRANCH) {
+                Ok(reference) => {
+                    Ok(Some(CommitIds::of(&reference.peel_to_commit().map_err(git_error)?)))
+                }
+                Err(error) if error.code() == ErrorCode::NotFound => Ok(None),
+                Err(error) => Err(git_error(error)),
+            })
+            .await?;
+        if let Some(deployed) = deployed {
+            return Ok(deployed);
+        }
+
+        let (config_dir, tempdir) = match &self.initial_config_dir {
+            Some(config_dir) => {
+                info!(
+                    "Importing the configuration in {} into the git repository",
+                    config_dir.display()
+                );
+                (config_dir.clone(), None)
+            }
+            None => {
+                let tempdir = create_tempdir()?;
+                serialize_config_node_to_file(tempdir.path(), &Version::default()).await?;
+                (tempdir.path().to_path_buf(), Some(tempdir))
+            }
+        };
+        self.with_repository(move |repository| {
+            let tree_id = write_dir_to_tree(repository, &config_dir)?;
+            drop(tempdir);
+            let tree = repository.find_tree(tree_id).map_err(git_error)?;
+            let signature = signature(SYSTEM_USER)?;
+            let commit_id = repository
+                .commit(
+                    Some(DEPLOY_BRANCH),
+                    &signature,
+                    &signature,
+                    "Initial configuration",
+                    &tree,
+                    &[],
+                )
+                .map_err(git_error)?;
+            Ok(CommitIds { commit_id, tree_id })
+        })
+        .await
+    }
+
+    /// Returns a deployed commit by id with its details. Only the commits of the deploy
+    /// branch are accepted.
+    async fn find_deployed_commit(
+        &self,
+        version_id: &str,
+    ) -> Result<(CommitIds, DeployedConfigData), MatcherError> {
+        let deployed_id = self.deployed_commit().await?.commit_id;
+        let version_id = version_id.to_owned();
+        self.with_repository(move |repository| {
+            let not_found =
+                || MatcherError::DeployedConfigNotFoundError { version_id: version_id.clone() };
+            let commit_id = Oid::from_str(&version_id).map_err(|_| not_found())?;
+            let is_deployed = commit_id == deployed_id
+                || repository.graph_descendant_of(deployed_id, commit_id).unwrap_or(false);
+            if version_id.len() != commit_id.to_string().len() || !is_deployed {
+                return Err(not_found());
+            }
+            let commit = repository.find_commit(commit_id).map_err(|_| not_found())?;
+            Ok((CommitIds::of(&commit), deployed_config_data(&commit)))
+        })
+        .await
+    }
+
+    /// Returns the configuration the draft is based on. The drafts without a base version
+    /// do not have it.
+    async fn get_draft_base(
+        &self,
+        draft_data: &MatcherConfigDraftData,
+    ) -> Result<Option<MatcherConfig>, MatcherError> {
+        let Some(base_version) = &draft_data.base_version else {
+            return Ok(None);
+        };
+        let (base_commit, _) = self.find_deployed_commit(base_version).await?;
+        Ok(Some(self.read_config_from_tree(base_commit.tree_id).await?.0))
+    }
+
+    async fn read_draft(&self, draft_id: &str) -> Result<GitDraft, MatcherError> {
+        let draft_id = draft_id.to_owned();
+        let (commit_id, data, config_tree_id) = self
+            .with_repository(move |repository| read_draft_commit(repository, &draft_id))
+            .await?;
+        let (config, tests) = self.read_config_from_tree(config_tree_id).await?;
+        Ok(GitDraft { commit_id, data, config, tests, config_tree_id })
+    }
+
+    /// Reads a configuration and its tests from a tree with the layout of the rules directory
+    async fn read_config_from_tree(
+        &self,
+        tree_id: Oid,
+    ) -> Result<(MatcherConfig, Vec<RulesetTests>), MatcherError> {
+        if let Some(parsed) =
+            self.lock_parsed_trees().iter().find(|parsed| parsed.tree_id == tree_id)
+        {
+            return Ok((parsed.config.clone(), parsed.tests.clone()));
+        }
+
+        let tempdir = self
+            .with_repository(move |repository| {
+                let tempdir = create_tempdir()?;
+                let tree = repository.find_tree(tree_id).map_err(git_error)?;
+                write_tree_to_dir(repository, &tree, tempdir.path())?;
+                Ok(tempdir)
+            })
+            .await?;
+        let config = read_config_from_root_dir(tempdir.path()).await?;
+        let tests = read_tests_from_root_dir(tempdir.path()).await?;
+
+        let mut parsed_trees = self.lock_parsed_trees();
+        if !parsed_trees.iter().any(|parsed| parsed.tree_id == tree_id) {
+            if parsed_trees.len() >= PARSED_TREES_CACHE_SIZE {
+                parsed_trees.pop_front();
+            }
+            parsed_trees.push_back(ParsedTree {
+                tree_id,
+                config: config.clone(),
+                tests: tests.clone(),
+            });
+        }
+        Ok((config, tests))
+    }
+
+    fn lock_parsed_trees(&self) -> std::sync::MutexGuard<'_, VecDeque<ParsedTree>> {
+        // The cache is never left inconsistent, so it is still usable after a panic
+        self.parsed_trees.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Writes a configuration and its tests to a tree with the layout of the rules directory.
+    /// The configuration is validated as for a save to the rules directory.
+    async fn write_config_to_tree(
+        &self,
+        config: &MatcherConfig,
+        tests: &[RulesetTests],
+    ) -> Result<Oid, MatcherError> {
+        let tempdir = create_tempdir()?;
+        let config_dir = tempdir.path().join(DRAFT_CONFIG_DIR);
+        if let Err(error) = std::fs::create_dir(&config_dir) {
+            return Err(DeploymentError::DirIo { path: config_dir, error }.into());
+        }
+        atomic_deploy_config(&config_dir, config, tests).await?;
+        self.with_repository(move |repository| {
+            let tree_id = write_dir_to_tree(repository, &config_dir);
+            drop(tempdir);
+            tree_id
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MatcherConfigReader for GitMatcherConfigManagerV2 {
+    async fn get_config(&self) -> Result<MatcherConfig, MatcherError> {
+        let deployed = self.deployed_commit().await?;
+        Ok(self.read_config_from_tree(deployed.tree_id).await?.0)
+    }
+
+    async fn get_config_tests(&self) -> Result<Vec<RulesetTests>, MatcherError> {
+        let deployed = self.deployed_commit().await?;
+        Ok(self.read_config_from_tree(deployed.tree_id).await?.1)
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MatcherConfigEditor for GitMatcherConfigManagerV2 {
+    async fn get_drafts(&self) -> Result<Vec<String>, MatcherError> {
+        self.with_repository(|repository| {
+            let references = repository
+                .references_glob(&format!("{}*", DRAFT_BRANCH_PREFIX))
+                .map_err(git_error)?;
+            let mut drafts = vec![];
+            for reference in references {
+                let reference = reference.map_err(git_error)?;
+                if let Some(draft_id) =
+                    reference.name().and_then(|name| name.strip_prefix(DRAFT_BRANCH_PREFIX))
+                {
+                    drafts.push(draft_id.to_owned());
+                }
+            }
+            Ok(drafts)
+        })
+        .await
+    }
+
+    async fn get_draft(&self, draft_id: &str) -> Result<MatcherConfigDraft, MatcherError> {
+        let draft = self.read_draft(draft_id).await?;
+        Ok(MatcherConfigDraft { data: draft.data, config: draft.config })
+    }
+
+    async fn create_draft(&self, user: String) -> Result<String, MatcherError> {
+        let drafts = self.get_drafts().await?;
+        let draft_id = (1..)
+            .map(|index| format!("draft_{:03}", index))
+            .find(|draft_id| !drafts.contains(draft_id))
+            .expect("The draft ids are unbounded");
+        info!("Creating a new draft {draft_id} for user {user}");
+
+        let deployed = self.deployed_commit().await?;
+        let now = Local::now().timestamp_millis();
+        let draft_data = MatcherConfigDraftData {
+            created_ts_ms: now,
+            updated_ts_ms: now,
+            user: user.clone(),
+            draft_id: draft_id.clone(),
+            base_version: Some(deployed.commit_id.to_string()),
+        };
+        self.with_repository(move |repository| {
+            let tree_id = write_draft_tree(repository, &draft_data, deployed.tree_id)?;
+            commit_draft(
+                repository,
+                &draft_id,
+                tree_id,
+                &user,
+                &[deployed.commit_id],
+                &format!("Create draft {}", draft_id),
+            )?;
+            Ok(draft_id)
+        })
+        .await
+    }
+
+    async fn update_draft(
+        &self,
+        draft_id: &str,
+        user: String,
+        config: &MatcherConfig,
+    ) -> Result<(), MatcherError> {
+        let mut draft = self.read_draft(draft_id).await?;
+
+        if draft.data.user != user {
+            warn!("User {user} tried overwriting a draft that is owned by {}.", draft.data.user);
+            return Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "User [{}] cannot overwrite draft owned by [{}]",
+                    user, draft.data.user
+                ),
+            });
+        }
+
+        let config_tree_id = self.write_config_to_tree(config, &draft.tests).await?;
+        draft.data.updated_ts_ms = Local::now().timestamp_millis();
+        let draft_id = draft_id.to_owned();
+        self.with_repository(move |repository| {
+            let tree_id = write_draft_tree(repository, &draft.data, config_tree_id)?;
+            commit_draft(
+                repository,
+                &draft_id,
+                tree_id,
+                &user,
+                &[draft.commit_id],
+                &format!("Update draft {}", draft_id),
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn deploy_draft(&self, draft_id: &str) -> Result<MatcherConfig, MatcherError> {
+        let draft = self.read_draft(draft_id).await?;
+        let deployed = self.deployed_commit().await?;
+        let base_config = self.get_draft_base(&draft.data).await?;
+        let active_config = self.read_config_from_tree(deployed.tree_id).await?.0;
+        check_draft_up_to_date(draft_id, base_config.as_ref(), &active_config)?;
+
+        validate_deploy_with_tests(&draft.config, &draft.tests)?;
+        let draft_id = draft_id.to_owned();
+        let GitDraft { commit_id: draft_commit_id, mut data, config, config_tree_id, .. } = draft;
+        self.with_repository(move |repository| {
+            let commit_id = commit_deploy(
+                repository,
+                deployed.commit_id,
+                config_tree_id,
+                Some(&data.user),
+                &format!("Deploy draft {}\n\n{} {}", draft_id, DRAFT_ID_TRAILER, draft_id),
+            )?;
+
+            base_on_deployed_version(&mut data, commit_id.to_string());
+            let tree_id = write_draft_tree(repository, &data, config_tree_id)?;
+            commit_draft(
+                repository,
+                &draft_id,
+                tree_id,
+                &data.user,
+                &[draft_commit_id],
+                &format!("Base draft {} on version {}", draft_id, commit_id),
+            )?;
+            Ok(())
+        })
+        .await?;
+        Ok(config)
+    }
+
+    async fn delete_draft(&self, draft_id: &str) -> Result<(), MatcherError> {
+        info!("Deleting draft {}", draft_id);
+        let draft_id = draft_id.to_owned();
+        self.with_repository(move |repository| {
+            let mut reference = find_draft_reference(repository, &draft_id)?;
+            reference.delete().map_err(|error| MatcherError::InternalSystemError {
+                message: format!("Cannot delete draft [{}]: {}", draft_id, error),
+            })
+        })
+        .await
+    }
+
+    async fn draft_take_over(&self, draft_id: &str, user: String) -> Result<(), MatcherError> {
+        let mut draft = self.read_draft(draft_id).await?;
+        info!("User {} is taking over draft {} from user {}", user, draft_id, draft.data.user);
+        draft.data.user = user.clone();
+        let draft_id = draft_id.to_owned();
+        self.with_repository(move |repository| {
+            let tree_id = write_draft_tree(repository, &draft.data, draft.config_tree_id)?;
+            commit_draft(
+                repository,
+                &draft_id,
+                tree_id,
+                &user,
+                &[draft.commit_id],
+                &format!("Take over draft {}", draft_id),
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_draft_rebase(&self, draft_id: &str) -> Result<DraftRebase, MatcherError> {
+        let draft = self.read_draft(draft_id).await?;
+        let Some(base_config) = self.get_draft_base(&draft.data).await? else {
+            return Ok(DraftRebase::default());
+        };
+        Ok(preview_rebase(&base_config, &self.get_config().await?, &draft.config))
+    }
+
+    async fn rebase_draft(
+        &self,
+        draft_id: &str,
+        user: String,
+    ) -> Result<DraftRebase, MatcherError> {
+        let mut draft = self.read_draft(draft_id).await?;
+        if draft.data.user != user {
+            warn!("User {user} tried rebasing a draft that is owned by {}.", draft.data.user);
+            return Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "User [{}] cannot rebase draft owned by [{}]",
+                    user, draft.data.user
+                ),
+            });
+        }
+
+        let Some(base_config) = self.get_draft_base(&draft.data).await? else {
+            return Ok(DraftRebase::default());
+        };
+        let deployed = self.deployed_commit().await?;
+        let (active_config, active_tests) = self.read_config_from_tree(deployed.tree_id).await?;
+        let Some(rebased) =
+            rebase_onto(&base_config, &active_config, active_tests, &draft.config, draft.tests)?
+        else {
+            return Ok(DraftRebase::default());
+        };
+
+        info!("User {} is rebasing draft {} on the active configuration", user, draft_id);
+        let config_tree_id = self.write_config_to_tree(&rebased.config, &rebased.tests).await?;
+
+        draft.data.updated_ts_ms = Local::now().timestamp_millis();
+        draft.data.base_version = Some(deployed.commit_id.to_string());
+        let draft_id = draft_id.to_owned();
+        let (draft_commit_id, draft_data) = (draft.commit_id, draft.data);
+        self.with_repository(move |repository| {
+            let tree_id = write_draft_tree(repository, &draft_data, config_tree_id)?;
+            // The rebase is a merge of the deploy branch into the draft branch
+            commit_draft(
+                repository,
+                &draft_id,
+                tree_id,
+                &user,
+                &[draft_commit_id, deployed.commit_id],
+                &format!("Rebase draft {} on version {}", draft_id, deployed.commit_id),
+            )?;
+            Ok(())
+        })
+        .await?;
+        Ok(rebased.into())
+    }
+
+    async fn deploy_config(&self, config: &MatcherConfig) -> Result<MatcherConfig, MatcherError> {
+        validate_deploy(config)?;
+        let deployed = self.deployed_commit().await?;
+        let tests = self.read_config_from_tree(deployed.tree_id).await?.1;
+        let tree_id = self.write_config_to_tree(config, &tests).await?;
+        self.with_repository(move |repository| {
+            commit_deploy(repository, deployed.commit_id, tree_id, None, "Deploy configuration")
+        })
+        .await?;
+        Ok(config.clone())
+    }
+
+    async fn get_deployed_configs(&self) -> Result<Vec<DeployedConfigData>, MatcherError> {
+        let deployed_id = self.deployed_commit().await?.commit_id;
+        self.with_repository(move |repository| {
+            let mut revwalk = repository.revwalk().map_err(git_error)?;
+            revwalk.push(deployed_id).map_err(git_error)?;
+            revwalk.simplify_first_parent().map_err(git_error)?;
+
+            let mut deployments = vec![];
+            for commit_id in revwalk {
+                let commit =
+                    repository.find_commit(commit_id.map_err(git_error)?).map_err(git_error)?;
+                deployments.push(deployed_config_data(&commit));
+            }
+            Ok(deployments)
+        })
+        .await
+    }
+
+    async fn get_deployed_config(&self, version_id: &str) -> Result<DeployedConfig, MatcherError> {
+        let (commit, data) = self.find_deployed_commit(version_id).await?;
+        let config = self.read_config_from_tree(commit.tree_id).await?.0;
+        Ok(DeployedConfig { data, config })
+    }
+
+    async fn rollback_config(
+        &self,
+        version_id: &str,
+        user: String,
+    ) -> Result<MatcherConfig, MatcherError> {
+        let (commit, _) = self.find_deployed_commit(version_id).await?;
+        let (config, tests) = self.read_config_from_tree(commit.tree_id).await?;
+        validate_deploy_with_tests(&config, &tests)?;
+
+        info!("User {} is rolling back the configuration to version {}", user, version_id);
+        let deployed = self.deployed_commit().await?;
+        let message = format!(
+            "Roll back to version {}\n\n{} {}",
+            version_id, ROLLBACK_OF_TRAILER, version_id
+        );
+        self.with_repository(move |repository| {
+            commit_deploy(repository, deployed.commit_id, commit.tree_id, Some(&user), &message)
+        })
+        .await?;
+        Ok(config)
+    }
+}
+
+/// A draft read from its branch
+struct GitDraft {
+    commit_id: Oid,
+    data: MatcherConfigDraftData,
+    config: MatcherConfig,
+    tests: Vec<RulesetTests>,
+    config_tree_id: Oid,
+}
+
+fn open_repository(repository_path: &Path) -> Result<Repository, MatcherError> {
+    match Repository::open_bare(repository_path) {
+        Ok(repository) => Ok(repository),
+        Err(error) if error.code() == ErrorCode::NotFound => {
+            info!(
+                "Creating the git repository of the configuration in {}",
+                repository_path.display()
+            );
+            Repository::init_bare(repository_path).map_err(git_error)
+        }
+        Err(error) => Err(git_error(error)),
+    }
+}
+
+/// Returns the branch of a draft. Only the draft ids that are valid branch names
+/// are accepted.
+fn find_draft_reference<'r>(
+    repository: &'r Repository,
+    draft_id: &str,
+) -> Result<git2::Reference<'r>, MatcherError> {
+    let not_found = || MatcherError::DraftNotFoundError { draft_id: draft_id.to_owned() };
+    let well_formed = !draft_id.is_empty()
+        && draft_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !well_formed {
+        return Err(not_found());
+    }
+    match repository.find_reference(&format!("{}{}", DRAFT_BRANCH_PREFIX, draft_id)) {
+        Ok(reference) => Ok(reference),
+        Err(error) if error.code() == ErrorCode::NotFound => Err(not_found()),
+        Err(error) => Err(git_error(error)),
+    }
+}
+
+/// Returns the head commit of a draft branch, the data of the draft and the id of the tree
+/// of its configuration
+fn read_draft_commit(
+    repository: &Repository,
+    draft_id: &str,
+) -> Result<(Oid, MatcherConfigDraftData, Oid), MatcherError> {
+    let commit = find_draft_reference(repository, draft_id)?.peel_to_commit().map_err(git_error)?;
+    let tree = commit.tree().map_err(git_error)?;
+
+    let data_entry = tree.get_name(ConfigType::Draft.filename()).ok_or_else(|| {
+        MatcherError::InternalSystemError {
+            message: format!("The draft [{}] does not contain its data", draft_id),
+        }
+    })?;
+    let data_blob = repository.find_blob(data_entry.id()).map_err(git_error)?;
+    let data: MatcherConfigDraftData =
+        serde_json::from_slice(data_blob.content()).map_err(|error| {
+            MatcherError::InternalSystemError {
+                message: format!("Cannot read the data of draft [{}]: {}", draft_id, error),
+            }
+        })?;
+
+    let config_tree_id =
+        tree.get_name(DRAFT_CONFIG_DIR).map(|entry| entry.id()).ok_or_else(|| {
+            MatcherError::InternalSystemError {
+                message: format!("The draft [{}] does not contain its configuration", draft_id),
+            }
+        })?;
+    Ok((commit.id(), data, config_tree_id))
+}
+
+/// Commits a new version of the active configuration
+fn commit_deploy(
+    repository: &Repository,
+    parent_id: Oid,
+    tree_id: Oid,
+    user: Option<&str>,
+    message: &str,
+) -> Result<Oid, MatcherError> {
+    let tree = repository.find_tree(tree_id).map_err(git_error)?;
+    let parent = repository.find_commit(parent_id).map_err(git_error)?;
+    let signature = signature(user.unwrap_or(SYSTEM_USER))?;
+    let commit_id = repository
+        .commit(Some(DEPLOY_BRANCH), &signature, &signature, message, &tree, &[&parent])
+        .map_err(git_error)?;
+    info!("Deployed the configuration version {}", commit_id);
+    Ok(commit_id)
+}
+
+fn commit_draft(
+    repository: &Repository,
+    draft_id: &str,
+    tree_id: Oid,
+    user: &str,
+    parent_ids: &[Oid],
+    message: &str,
+) -> Result<Oid, MatcherError> {
+    let tree = repository.find_tree(tree_id).map_err(git_error)?;
+    let parents = parent_ids
+        .iter()
+        .map(|parent_id| repository.find_commit(*parent_id).map_err(git_error))
+        .collect::<Result<Vec<_>, _>>()?;
+    let parents = parents.iter().collect::<Vec<_>>();
+    let signature = signature(user)?;
+    let branch = format!("{}{}", DRAFT_BRANCH_PREFIX, draft_id);
+    // The branch is moved explicitly, as the first parent of a rebase is not the deploy branch
+    let commit_id = repository
+        .commit(None, &signature, &signature, message, &tree, &parents)
+        .map_err(git_error)?;
+    repository.reference(&branch, commit_id, true, message).map_err(git_error)?;
+    Ok(commit_id)
+}
+
+fn write_draft_tree(
+    repository: &Repository,
+    draft_data: &MatcherConfigDraftData,
+    config_tree_id: Oid,
+) -> Result<Oid, MatcherError> {
+    let data =
+        serde_json::to_vec_pretty(draft_data).map_err(|error| DeploymentError::Serialization {
+            error,
+            data_type: std::any::type_name::<MatcherConfigDraftData>(),
+        })?;
+    let data_id = repository.blob(&data).map_err(git_error)?;
+    let mut builder = repository.treebuilder(None).map_err(git_error)?;
+    builder.insert(ConfigType::Draft.filename(), data_id, FILE_MODE_BLOB).map_err(git_error)?;
+    builder.insert(DRAFT_CONFIG_DIR, config_tree_id, FILE_MODE_TREE).map_err(git_error)?;
+    builder.write().map_err(git_error)
+}
+
+fn write_tree_to_dir(repository: &Repository, tree: &Tree, dir: &Path) -> Result<(), MatcherError> {
+    for entry in tree.iter() {
+        let Some(name) = entry.name() else {
+            return Err(MatcherConfigError::FileNameError { path: dir.to_path_buf() }.into());
+        };
+        let path = dir.join(name);
+        match entry.kind() {
+            Some(ObjectType::Tree) => {
+                if let Err(error) = std::fs::create_dir(&path) {
+                    return Err(DeploymentError::DirIo { path, error }.into());
+                }
+                let subtree = repository.find_tree(entry.id()).map_err(git_error)?;
+                write_tree_to_dir(repository, &subtree, &path)?;
+            }
+            Some(ObjectType::Blob) => {
+                let blob = repository.find_blob(entry.id()).map_err(git_error)?;
+                if let Err(error) = std::fs::write(&path, blob.content()) {
+                    return Err(DeploymentError::FileIo { path, error }.into());
+                }
+            }
+            kind => warn!("Skipping the git entry {} of kind {:?}", path.display(), kind),
+        }
+    }
+    Ok(())
+}
+
+fn write_dir_to_tree(repository: &Repository, dir: &Path) -> Result<Oid, MatcherError> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|error| MatcherConfigError::DirIoError { path: dir.to_path_buf(), error })?;
+    let mut builder = repository.treebuilder(None).map_err(git_error)?;
+    for entry in entries {
+        let entry = entry
+            .map_err(|error| MatcherConfigError::DirIoError { path: dir.to_path_buf(), error })?;
+        let path = entry.path();
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            return Err(MatcherConfigError::FileNameError { path }.into());
+        };
+        if path.is_dir() {
+            let tree_id = write_dir_to_tree(repository, &path)?;
+            builder.insert(&name, tree_id, FILE_MODE_TREE).map_err(git_error)?;
+        } else {
+            let blob_id = repository.blob_path(&path).map_err(git_error)?;
+            builder.insert(&name, blob_id, FILE_MODE_BLOB).map_err(git_error)?;
+        }
+    }
+    builder.write().map_err(git_error)
+}
+
+/// Returns the details of a deployment from its commit. The deploys made without a user
+/// are authored by the system user.
+fn deployed_config_data(commit: &Commit) -> DeployedConfigData {
+    let message = commit.message().unwrap_or_default();
+    let trailer = |key: &str| {
+        message.lines().find_map(|line| line.strip_prefix(key)).map(|value| value.trim().to_owned())
+    };
+    let author = commit.author();
+    DeployedConfigData {
+        version_id: commit.id().to_string(),
+        deployed_ts_ms: commit.time().seconds() * 1000,
+        user: author.name().filter(|name| *name != SYSTEM_USER).map(str::to_owned),
+        draft_id: trailer(DRAFT_ID_TRAILER),
+        rollback_of: trailer(ROLLBACK_OF_TRAILER),
+    }
+}
+
+/// The signature of the commits made on behalf of a user. The users do not have an email,
+/// that git requires, so a local one is derived from the user name.
+fn signature(user: &str) -> Result<Signature<'static>, MatcherError> {
+    Signature::now(user, &format!("{}@{}", user, SYSTEM_USER)).map_err(git_error)
+}
+
+fn create_tempdir() -> Result<tempfile::TempDir, MatcherError> {
+    tempfile::tempdir().map_err(|err| MatcherError::InternalSystemError {
+        message: format!("Cannot create temporary directory. Err: {:?}", err),
+    })
+}
+
+fn git_error(error: git2::Error) -> MatcherError {
+    MatcherError::InternalSystemError {
+        message: format!("Error while accessing the git repository: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::v2::FsMatcherConfigManagerV2;
+    use tempfile::TempDir;
+
+    const TEST_CONFIG_DIR: &str = "./test_resources/v2/test_config/";
+
+    #[tokio::test]
+    async fn should_import_the_initial_configuration_and_deploy_drafts_as_commits() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let config_manager = GitMatcherConfigManagerV2::new(temp_dir.path().join("rules.git"))
+            .with_initial_config_dir(TEST_CONFIG_DIR);
+        let fs_config_manager = FsMatcherConfigManagerV2::new(TEST_CONFIG_DIR, temp_dir.path());
+        let initial_config = fs_config_manager.get_config().await.unwrap();
+
+        // Act
+        let imported_config = config_manager.get_config().await.unwrap();
+        let imported_tests = config_manager.get_config_tests().await.unwrap();
+        let draft_id = config_manager.create_draft("user".to_owned()).await.unwrap();
+        let mut draft_config = config_manager.get_draft(&draft_id).await.unwrap().config;
+        draft_config.delete_node_in_path(&["root", "empty_filter"]).unwrap();
+        let not_owner_update =
+            config_manager.update_draft(&draft_id, "another".to_owned(), &draft_config).await;
+        config_manager.update_draft(&draft_id, "user".to_owned(), &draft_config).await.unwrap();
+        let deployed = config_manager.deploy_draft(&draft_id).await.unwrap();
+        let history_after_deploy = config_manager.get_deployed_configs().await.unwrap();
+        let initial_version = history_after_deploy[1].version_id.clone();
+        config_manager.rollback_config(&initial_version, "admin".to_owned()).await.unwrap();
+        let history_after_rollback = config_manager.get_deployed_configs().await.unwrap();
+
+        // Assert
+        assert_eq!(initial_config, imported_config);
+        assert_eq!(fs_config_manager.get_config_tests().await.unwrap(), imported_tests);
+        assert_eq!("draft_001", draft_id);
+        assert!(not_owner_update.is_err());
+        assert_eq!(draft_config, deployed);
+
+        assert_eq!(2, history_after_deploy.len());
+        assert_eq!(Some("user".to_owned()), history_after_deploy[0].user);
+        assert_eq!(Some(draft_id.clone()), history_after_deploy[0].draft_id);
+        assert_eq!(None, history_after_deploy[1].user);
+        assert_eq!(
+            deployed,
+            config_manager
+                .get_deployed_config(&history_after_deploy[0].version_id)
+                .await
+                .unwrap()
+                .config
+        );
+
+        assert_eq!(3, history_after_rollback.len());
+        assert_eq!(Some("admin".to_owned()), history_after_rollback[0].user);
+        assert_eq!(Some(initial_version), history_after_rollback[0].rollback_of);
+        assert_eq!(initial_config, config_manager.get_config().await.unwrap());
+
+        // The draft commits are not deployed versions
+        let draft_commit = {
+            let repository = open_repository(&config_manager.repository_path).unwrap();
+            let reference = find_draft_reference(&repository, &draft_id).unwrap();
+            reference.target().unwrap().to_string()
+        };
+        assert_eq!(
+            Err(MatcherError::DeployedConfigNotFoundError { version_id: draft_commit.clone() }),
+            config_manager.get_deployed_config(&draft_commit).await
+        );
+        assert!(config_manager.get_draft("../main").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_rebase_an_outdated_draft_with_a_merge_commit() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let config_manager = GitMatcherConfigManagerV2::new(temp_dir.path().join("rules.git"))
+            .with_initial_config_dir(TEST_CONFIG_DIR);
+
+        let draft_id = config_manager.create_draft("user".to_owned()).await.unwrap();
+        let mut draft_config = config_manager.get_draft(&draft_id).await.unwrap().config;
+        draft_config.delete_node_in_path(&["root", "empty_filter"]).unwrap();
+        config_manager.update_draft(&draft_id, "user".to_owned(), &draft_config).await.unwrap();
+
+        let other_draft_id = config_manager.create_draft("another".to_owned()).await.unwrap();
+        let mut other_draft_config =
+            config_manager.get_draft(&other_draft_id).await.unwrap().config;
+        other_draft_config.delete_node_in_path(&["root", "tenant_a"]).unwrap();
+        config_manager
+            .update_draft(&other_draft_id, "another".to_owned(), &other_draft_config)
+            .await
+            .unwrap();
+        config_manager.deploy_draft(&other_draft_id).await.unwrap();
+
+        // Act
+        let outdated_deploy = config_manager.deploy_draft(&draft_id).await;
+        let rebase = config_manager.rebase_draft(&draft_id, "user".to_owned()).await.unwrap();
+        let deployed = config_manager.deploy_draft(&draft_id).await.unwrap();
+        config_manager.delete_draft(&other_draft_id).await.unwrap();
+
+        // Assert
+        assert_eq!("draft_002", other_draft_id);
+        assert_eq!(
+            Err(MatcherError::DraftOutdatedError { draft_id: draft_id.clone() }),
+            outdated_deploy
+        );
+        assert!(rebase.outdated);
+        assert!(rebase.conflicts.is_empty());
+        match &deployed {
+            MatcherConfig::Filter { nodes, .. } => {
+                assert_eq!(
+                    vec!["master"],
+                    nodes.iter().map(|node| node.get_name()).collect::<Vec<_>>()
+                );
+            }
+            result => panic!("{:?}", result),
+        }
+        assert_eq!(deployed, config_manager.get_config().await.unwrap());
+        assert_eq!(vec![draft_id.clone()], config_manager.get_drafts().await.unwrap());
+
+        let repository = open_repository(&config_manager.repository_path).unwrap();
+        let draft_head =
+            find_draft_reference(&repository, &draft_id).unwrap().peel_to_commit().unwrap();
+        // The deploy after the rebase only updates the base of the draft
+        let rebase_commit = draft_head.parent(0).unwrap();
+        assert_eq!(2, rebase_commit.parent_count());
+    }
+
+    #[tokio::test]
+    async fn should_read_each_tree_of_the_repository_once() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let config_manager = GitMatcherConfigManagerV2::new(temp_dir.path().join("rules.git"))
+            .with_initial_config_dir(TEST_CONFIG_DIR);
+        let config = config_manager.get_config().await.unwrap();
+        let deployed = config_manager.deployed_commit().await.unwrap();
+        let cached_config = MatcherConfig::Ruleset { name: "cached".to_owned(), rules: vec![] };
+        config_manager.lock_parsed_trees()[0].config = cached_config.clone();
+
+        // Act
+        let draft_id = config_manager.create_draft("user".to_owned()).await.unwrap();
+        let draft_config = config_manager.get_draft(&draft_id).await.unwrap().config;
+        let config_after_draft = config_manager.get_config().await.unwrap();
+
+        // Assert
+        assert_ne!(cached_config, config);
+        // The draft is created from the deployed tree, so both are read from the cache
+        assert_eq!(cached_config, draft_config);
+        assert_eq!(cached_config, config_after_draft);
+        let parsed_trees = config_manager.lock_parsed_trees();
+        assert_eq!(1, parsed_trees.len());
+        assert_eq!(deployed.tree_id, parsed_trees[0].tree_id);
+    }
+}
@@ -1,11 +1,21 @@
+mod deploy;
 mod editor;
 mod error;
+mod format;
+mod git;
+mod sqlite;
 
 use crate::config::nodes::{Filter, MatcherIterator};
 use crate::config::rule::Rule;
+pub use crate::config::v2::editor::convert_config_format;
+use crate::config::v2::editor::DeployHistory;
 pub use crate::config::v2::error::MatcherConfigError;
+pub use crate::config::v2::format::ConfigFileFormat;
+pub use crate::config::v2::git::GitMatcherConfigManagerV2;
+pub use crate::config::v2::sqlite::SqliteMatcherConfigManagerV2;
 use crate::config::{Defaultable, MatcherConfig, MatcherConfigReader};
 use crate::error::MatcherError;
+use crate::test_case::{RulesetTestCase, RulesetTests};
 use futures::stream::FuturesOrdered;
 use futures::StreamExt;
 use log::{debug, error, info, trace, warn};
@@ -19,9 +29,15 @@ use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use tokio::fs::DirEntry;
 
+/// The name of the directory of a ruleset containing its test cases.
+pub const TESTS_DIR: &str = "tests";
+
 pub struct FsMatcherConfigManagerV2 {
     root_path: PathBuf,
     drafts_path: PathBuf,
+    history: Option<DeployHistory>,
+    /// Serializes the deploys, that would otherwise record the same version in the history
+    deploy_lock: tokio::sync::Mutex<()>,
 }
 
 impl FsMatcherConfigManagerV2 {
@@ -29,13 +45,26 @@ impl FsMatcherConfigManagerV2 {
         root_path: P1,
         drafts_path: P2,
     ) -> FsMatcherConfigManagerV2 {
-        FsMatcherConfigManagerV2 { root_path: root_path.into(), drafts_path: drafts_path.into() }
+        FsMatcherConfigManagerV2 {
+            root_path: root_path.into(),
+            drafts_path: drafts_path.into(),
+            history: None,
+            deploy_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Keeps the last `max_versions` deployed configurations in the `history_path` directory,
+    /// so that they can be restored later.
+    pub fn with_history<P: Into<PathBuf>>(mut self, history_path: P, max_versions: usize) -> Self {
+        self.history = Some(DeployHistory::new(history_path.into(), max_versions));
+        self
     }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ConfigType {
     Draft,
+    Deployment,
     Root,
     Filter,
     Iterator,
@@ -46,6 +75,7 @@ impl Display for ConfigType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ConfigType::Draft => f.write_str("draft"),
+            ConfigType::Deployment => f.write_str("deployment"),
             ConfigType::Root => f.write_str("root"),
             ConfigType::Filter => f.write_str("filter"),
             ConfigType::Ruleset => f.write_str("ruleset"),
@@ -65,9 +95,28 @@ impl ConfigType {
             ConfigType::Filter => "filter.json",
             ConfigType::Ruleset => "ruleset.json",
             ConfigType::Draft => "data.json",
+            ConfigType::Deployment => "deployment.json",
             ConfigType::Iterator => "iterator.json",
         }
     }
+
+    /// The name of the config file without its extension. Next to the default json, the file
+    /// can also be written in any other [`ConfigFileFormat`].
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            ConfigType::Root => "version",
+            ConfigType::Filter => "filter",
+            ConfigType::Ruleset => "ruleset",
+            ConfigType::Draft => "data",
+            ConfigType::Deployment => "deployment",
+            ConfigType::Iterator => "iterator",
+        }
+    }
+
+    fn is_config_file(&self, path: &Path) -> bool {
+        path.file_stem() == Some(OsStr::new(self.file_stem()))
+            && ConfigFileFormat::from_path(path).is_some()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -153,6 +202,10 @@ impl MatcherConfigReader for FsMatcherConfigManagerV2 {
     async fn get_config(&self) -> Result<MatcherConfig, MatcherError> {
         Ok(read_config_from_root_dir(&self.root_path).await?)
     }
+
+    async fn get_config_tests(&self) -> Result<Vec<RulesetTests>, MatcherError> {
+        Ok(read_tests_from_root_dir(&self.root_path).await?)
+    }
 }
 
 pub async fn get_config_version(path: &Path) -> Result<Version, MatcherConfigError> {
@@ -218,15 +271,15 @@ async fn read_child_nodes_from_dir(
         };
 
         if file_type.is_file() {
-            if dir_entry.file_name() == config_type.filename() {
+            if config_type.is_config_file(&dir_entry.path()) {
                 debug!(
                     "Skipping file {} because it is a node configuration.",
-                    config_type.filename()
+                    dir_entry.path().display()
                 );
                 continue;
             }
 
-            if dir_entry.path().extension() != Some(OsStr::new("json")) {
+            if ConfigFileFormat::from_path(&dir_entry.path()).is_none() {
                 info!("Ignoring file [{}] as it is not a config file.", dir_entry.path().display());
                 continue;
             }
@@ -363,13 +416,122 @@ async fn read_rules_from_dir(dir: &Path) -> Result<Vec<Rule>, MatcherConfigError
     Ok(rules.into_iter().map(FileEntry::into_inner).collect())
 }
 
+/// Reads the test cases stored in the `tests` directory of each ruleset of the processing tree.
+pub async fn read_tests_from_root_dir(
+    root_dir: &Path,
+) -> Result<Vec<RulesetTests>, MatcherConfigError> {
+    let mut tests = vec![];
+    match parse_node_config_from_file::<Version>(root_dir).await {
+        Ok(_) => read_tests_from_dir(root_dir, vec!["root".to_owned()], &mut tests).await?,
+        Err(MatcherConfigError::FileNotFound { .. }) => {}
+        Err(error) => return Err(error),
+    }
+    Ok(tests)
+}
+
+#[async_recursion::async_recursion]
+async fn read_tests_from_dir(
+    dir: &Path,
+    path: Vec<String>,
+    tests: &mut Vec<RulesetTests>,
+) -> Result<(), MatcherConfigError> {
+    for dir_entry in gather_dir_entries(dir).await? {
+        let child_dir = dir_entry.path();
+        if !child_dir.is_dir() {
+            continue;
+        }
+
+        let mut child_path = path.clone();
+        if let Some(filter) = parse_optional_node_config::<MatcherConfigFilter>(&child_dir).await? {
+            child_path.push(filter.name);
+            read_tests_from_dir(&child_dir, child_path, tests).await?;
+        } else if let Some(iterator) =
+            parse_optional_node_config::<MatcherConfigIterator>(&child_dir).await?
+        {
+            child_path.push(iterator.name);
+            read_tests_from_dir(&child_dir, child_path, tests).await?;
+        } else if let Some(ruleset) =
+            parse_optional_node_config::<MatcherConfigRuleset>(&child_dir).await?
+        {
+            child_path.push(ruleset.name);
+            let ruleset_tests = read_ruleset_tests_from_dir(&child_dir.join(TESTS_DIR)).await?;
+            if !ruleset_tests.is_empty() {
+                tests.push(RulesetTests { ruleset_path: child_path, tests: ruleset_tests });
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn read_ruleset_tests_from_dir(
+    dir: &Path,
+) -> Result<Vec<RulesetTestCase>, MatcherConfigError> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut tests: Vec<FileEntry<RulesetTestCase>> = vec![];
+    for dir_entry in gather_dir_entries(dir).await? {
+        if dir_entry.path().extension() != Some(OsStr::new("json")) {
+            info!("Ignoring file [{}] as it is not a test file.", dir_entry.path().display());
+            continue;
+        }
+
+        let test: RulesetTestCase = parse_from_file(&dir_entry.path()).await?;
+        if let Some(duplicate) = tests.iter().find(|entry| entry.content.name == test.name) {
+            return Err(MatcherConfigError::DuplicateName {
+                name: test.name,
+                previous: duplicate.path.clone(),
+                next: dir_entry.path(),
+            });
+        }
+
+        tests.push(FileEntry { path: dir_entry.path(), content: test })
+    }
+
+    Ok(tests.into_iter().map(FileEntry::into_inner).collect())
+}
+
+async fn parse_optional_node_config<Data: DeserializeOwned + ConfigNodeDir>(
+    dir: &Path,
+) -> Result<Option<Data>, MatcherConfigError> {
+    match parse_node_config_from_file(dir).await {
+        Ok(data) => Ok(Some(data)),
+        Err(MatcherConfigError::FileNotFound { .. }) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Returns the config file of the given type in the directory, whatever its format.
+pub(super) async fn find_node_config_file(
+    dir: &Path,
+    config_type: ConfigType,
+) -> Result<Option<PathBuf>, MatcherConfigError> {
+    let mut found: Option<PathBuf> = None;
+    for format in ConfigFileFormat::ALL {
+        for extension in format.extensions() {
+            let path = dir.join(format!("{}.{}", config_type.file_stem(), extension));
+            if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+                continue;
+            }
+
+            if found.is_some() {
+                error!("Found more than one {} config file in {}", config_type, dir.display());
+                return Err(MatcherConfigError::UnexpectedFile { path, config_type });
+            }
+            found = Some(path);
+        }
+    }
+
+    Ok(found)
+}
+
 async fn parse_node_config_from_file<Data: DeserializeOwned + ConfigNodeDir>(
     dir: &Path,
 ) -> Result<Data, MatcherConfigError> {
-    let config_file_path = {
-        let mut path = PathBuf::from(dir);
-        path.push(Data::config_type().filename());
-        path
+    let config_file_path = match find_node_config_file(dir, Data::config_type()).await? {
+        Some(path) => path,
+        None => dir.join(Data::config_type().filename()),
     };
 
     trace!(
@@ -395,6 +557,11 @@ pub(crate) async fn parse_from_file<Data: DeserializeOwned>(
         }
     };
 
+    let format = ConfigFileFormat::from_path(path).unwrap_or_default();
+    if format != ConfigFileFormat::Json {
+        return parse_from_value(path, format, &content);
+    }
+
     let json = content.trim();
     let jd = &mut serde_json::Deserializer::from_str(json);
     match serde_path_to_error::deserialize(jd) {
@@ -418,6 +585,36 @@ pub(crate) async fn parse_from_file<Data: DeserializeOwned>(
     }
 }
 
+fn parse_from_value<Data: DeserializeOwned>(
+    path: &Path,
+    format: ConfigFileFormat,
+    content: &str,
+) -> Result<Data, MatcherConfigError> {
+    let value = match format.parse_to_json(content) {
+        Ok(value) => value,
+        Err(message) => {
+            error!("Could not parse config from file {}. {}", path.display(), message);
+            return Err(MatcherConfigError::ParseError {
+                file: path.to_path_buf(),
+                format,
+                message,
+            });
+        }
+    };
+
+    match serde_path_to_error::deserialize(value) {
+        Ok(result) => Ok(result),
+        Err(error) => {
+            error!("Could not parse config from file {}. {}", path.display(), error);
+            Err(MatcherConfigError::DeserializationError {
+                file: path.to_path_buf(),
+                object_path: error.path().to_string(),
+                error: error.into_inner(),
+            })
+        }
+    }
+}
+
 pub async fn gather_dir_entries(dir: &Path) -> Result<Vec<DirEntry>, MatcherConfigError> {
     let mut root_dir_iter = match tokio::fs::read_dir(dir).await {
         Ok(root_dir_iter) => root_dir_iter,
@@ -464,8 +661,9 @@ mod tests {
     use crate::config::rule::{ConfigAction, Constraint, Operator, Rule};
     use crate::config::v2::{
         parse_from_file, read_config_from_root_dir, read_filter_from_dir, read_iterator_from_dir,
-        read_node_from_dir, read_rules_from_dir, read_ruleset_from_dir, ConfigType,
-        MatcherConfigError, MatcherConfigFilter, MatcherConfigIterator, MatcherConfigRuleset,
+        read_node_from_dir, read_rules_from_dir, read_ruleset_from_dir, read_tests_from_root_dir,
+        ConfigFileFormat, ConfigType, MatcherConfigError, MatcherConfigFilter,
+        MatcherConfigIterator, MatcherConfigRuleset,
     };
     use crate::config::{Defaultable, MatcherConfig};
     use monostate::MustBe;
@@ -474,6 +672,22 @@ mod tests {
 
     const TEST_CONFIG_DIR: &str = "./test_resources/v2/test_config/";
     const TEST_BROKEN_CONFIG_DIR: &str = "./test_resources/v2/erroneous_configs/";
+    const TEST_DRAFT_CONFIG_DIR: &str = "./test_resources/v2/test_drafts/draft_001/config/";
+
+    #[tokio::test]
+    async fn should_read_the_tests_of_the_rulesets() {
+        // Act
+        let tests = read_tests_from_root_dir(Path::new(TEST_DRAFT_CONFIG_DIR)).await.unwrap();
+        let no_tests = read_tests_from_root_dir(Path::new(TEST_CONFIG_DIR)).await.unwrap();
+
+        // Assert
+        assert_eq!(1, tests.len());
+        assert_eq!(vec!["root", "master", "archive_emails"], tests[0].ruleset_path);
+        assert_eq!(1, tests[0].tests.len());
+        assert_eq!("email", tests[0].tests[0].name);
+        assert_eq!(vec!["archive_emails"], tests[0].tests[0].expected.matched_rules);
+        assert!(no_tests.is_empty());
+    }
 
     #[tokio::test]
     async fn should_parse_filter_from_file() {
@@ -544,7 +758,7 @@ mod tests {
         };
 
         match actions.as_slice() {
-            [ConfigAction { id, payload }] => {
+            [ConfigAction { id, payload, .. }] => {
                 assert_eq!("logger", id);
                 assert!(payload.contains_key("event"))
             }
@@ -824,4 +1038,21 @@ mod tests {
             result => unreachable!("{:?}", result),
         }
     }
+
+    #[tokio::test]
+    async fn should_fail_on_invalid_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filter.yaml");
+        std::fs::write(&path, "type: [filter").unwrap();
+
+        let error = parse_from_file::<MatcherConfigFilter>(&path).await.unwrap_err();
+
+        match error {
+            MatcherConfigError::ParseError { file, format, .. } => {
+                assert_eq!(path, file);
+                assert_eq!(ConfigFileFormat::Yaml, format);
+            }
+            result => panic!("{:#?}", result),
+        }
+    }
 }
@@ -0,0 +1,830 @@
+use crate::config::v2::deploy::{
+    base_on_deployed_version, check_draft_up_to_date, preview_rebase, rebase_onto,
+    retain_tests_for, validate_deploy, validate_deploy_with_tests, validate_save,
+};
+use crate::config::v2::editor::format_version;
+use crate::config::v2::FsMatcherConfigManagerV2;
+use crate::config::{
+    DeployedConfig, DeployedConfigData, DraftRebase, MatcherConfig, MatcherConfigDraft,
+    MatcherConfigDraftData, MatcherConfigEditor, MatcherConfigReader,
+};
+use crate::error::MatcherError;
+use crate::test_case::RulesetTests;
+use chrono::Local;
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long a connection waits for the lock held by another connection
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS deployment (
+    version INTEGER PRIMARY KEY,
+    deployed_ts_ms INTEGER NOT NULL,
+    user TEXT,
+    draft_id TEXT,
+    rollback_of TEXT,
+    config TEXT NOT NULL,
+    tests TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS draft (
+    draft_id TEXT PRIMARY KEY,
+    data TEXT NOT NULL,
+    config TEXT NOT NULL,
+    tests TEXT NOT NULL,
+    base_config TEXT
+);
+";
+
+/// Keeps the processing tree, the drafts and the deploy history in an embedded SQLite database:
+/// - the `deployment` table contains the deployed configurations; the active one is the
+///   configuration with the highest version;
+/// - the `draft` table contains the drafts, with the configuration each draft is based on.
+///
+/// The configurations and their test cases are stored as JSON documents. Each change,
+/// a deploy included, is a single transaction, so a configuration is never read partially written.
+/// The database is created on first use, empty; a configuration in the v2 filesystem layout
+/// can be imported with `import_from_fs`.
+///
+/// The database is accessed in blocking tasks, as a connection can wait up to BUSY_TIMEOUT
+/// for the lock held by another connection.
+pub struct SqliteMatcherConfigManagerV2 {
+    database: Database,
+}
+
+/// The location and the settings of the database, that are moved to the blocking tasks
+#[derive(Clone)]
+struct Database {
+    path: PathBuf,
+    max_versions: Option<usize>,
+}
+
+impl SqliteMatcherConfigManagerV2 {
+    pub fn new<P: Into<PathBuf>>(database_path: P) -> SqliteMatcherConfigManagerV2 {
+        SqliteMatcherConfigManagerV2 {
+            database: Database { path: database_path.into(), max_versions: None },
+        }
+    }
+
+    /// Keeps only the last `max_versions` deployed configurations. By default, all of them are kept.
+    pub fn with_history_size(mut self, max_versions: usize) -> Self {
+        self.database.max_versions = Some(max_versions.max(1));
+        self
+    }
+
+    /// Imports the active configuration, the deploy history and the drafts of a configuration
+    /// stored in the v2 filesystem layout. The database must not contain a configuration yet.
+    pub async fn import_from_fs(
+        &self,
+        source: &FsMatcherConfigManagerV2,
+    ) -> Result<(), MatcherError> {
+        let mut deployments = vec![];
+        for data in source.get_deployed_configs().await?.into_iter().rev() {
+            let config = source.get_deployed_config(&data.version_id).await?.config;
+            let tests = source.get_deployed_config_tests(&data.version_id).await?;
+            deployments.push((data, config, tests));
+        }
+        let config = source.get_config().await?;
+        let tests = source.get_config_tests().await?;
+        let mut drafts = vec![];
+        for draft_id in source.get_drafts().await? {
+            let draft = source.get_draft(&draft_id).await?;
+            let draft_tests = source.get_draft_tests(&draft_id).await?;
+            let base_config = source.get_draft_base_config(&draft_id).await?;
+            drafts.push((draft, draft_tests, base_config));
+        }
+
+        self.with_connection(move |database, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            if get_active_deployment(&transaction)?.is_some() {
+                return Err(MatcherError::ConfigurationError {
+                    message: format!(
+                        "The database [{}] already contains a configuration",
+                        database.path.display()
+                    ),
+                });
+            }
+
+            let active_in_history = deployments
+                .last()
+                .map(|(_, last_config, last_tests)| last_config == &config && last_tests == &tests);
+            for (data, config, tests) in &deployments {
+                insert_deployment(&transaction, data, config, tests)?;
+            }
+            if active_in_history != Some(true) {
+                let data = DeployedConfigData {
+                    version_id: next_version_id(&transaction)?,
+                    deployed_ts_ms: Local::now().timestamp_millis(),
+                    user: None,
+                    draft_id: None,
+                    rollback_of: None,
+                };
+                insert_deployment(&transaction, &data, &config, &tests)?;
+            }
+            for (draft, draft_tests, base_config) in &drafts {
+                insert_draft(
+                    &transaction,
+                    &draft.data,
+                    &draft.config,
+                    draft_tests,
+                    base_config.as_ref(),
+                )?;
+            }
+            database.prune_history(&transaction)?;
+            transaction.commit().map_err(sqlite_error)?;
+
+            info!(
+                "Imported {} deployed configurations and {} drafts into the database [{}]",
+                deployments.len(),
+                drafts.len(),
+                database.path.display()
+            );
+            Ok(())
+        })
+        .await
+    }
+
+    /// Runs a function on a new connection in a blocking task, as SQLite performs blocking I/O
+    async fn with_connection<T, F>(&self, function: F) -> Result<T, MatcherError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Database, &mut Connection) -> Result<T, MatcherError> + Send + 'static,
+    {
+        let database = self.database.clone();
+        tokio::task::spawn_blocking(move || function(&database, &mut database.connection()?))
+            .await
+            .map_err(|err| MatcherError::InternalSystemError {
+                message: format!(
+                    "SqliteMatcherConfigManagerV2 - Cannot execute Tokio internal task. Err: {:?}",
+                    err
+                ),
+            })?
+    }
+}
+
+impl Database {
+    fn connection(&self) -> Result<Connection, MatcherError> {
+        let connection = Connection::open(&self.path).map_err(sqlite_error)?;
+        connection.busy_timeout(BUSY_TIMEOUT).map_err(sqlite_error)?;
+        connection.execute_batch(SCHEMA).map_err(sqlite_error)?;
+        Ok(connection)
+    }
+
+    fn active_deployment(&self, connection: &Connection) -> Result<Deployment, MatcherError> {
+        get_active_deployment(connection)?.ok_or_else(|| MatcherError::ConfigurationError {
+            message: format!(
+                "The database [{}] does not contain a configuration",
+                self.path.display()
+            ),
+        })
+    }
+
+    /// Adds a new version to the deploy history, making it the active configuration
+    fn deploy(
+        &self,
+        connection: &Connection,
+        config: &MatcherConfig,
+        tests: &[RulesetTests],
+        user: Option<String>,
+        draft_id: Option<String>,
+        rollback_of: Option<String>,
+    ) -> Result<String, MatcherError> {
+        let data = DeployedConfigData {
+            version_id: next_version_id(connection)?,
+            deployed_ts_ms: Local::now().timestamp_millis(),
+            user,
+            draft_id,
+            rollback_of,
+        };
+        insert_deployment(connection, &data, config, tests)?;
+        self.prune_history(connection)?;
+        info!("Deployed the configuration version {}", data.version_id);
+        Ok(data.version_id)
+    }
+
+    fn prune_history(&self, connection: &Connection) -> Result<(), MatcherError> {
+        if let Some(max_versions) = self.max_versions {
+            connection
+                .execute(
+                    "DELETE FROM deployment WHERE version NOT IN
+                        (SELECT version FROM deployment ORDER BY version DESC LIMIT ?1)",
+                    params![max_versions as i64],
+                )
+                .map_err(sqlite_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MatcherConfigReader for SqliteMatcherConfigManagerV2 {
+    async fn get_config(&self) -> Result<MatcherConfig, MatcherError> {
+        self.with_connection(|database, connection| {
+            Ok(database.active_deployment(connection)?.config)
+        })
+        .await
+    }
+
+    async fn get_config_tests(&self) -> Result<Vec<RulesetTests>, MatcherError> {
+        self.with_connection(|database, connection| {
+            Ok(database.active_deployment(connection)?.tests)
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MatcherConfigEditor for SqliteMatcherConfigManagerV2 {
+    async fn get_drafts(&self) -> Result<Vec<String>, MatcherError> {
+        self.with_connection(|_, connection| get_draft_ids(connection)).await
+    }
+
+    async fn get_draft(&self, draft_id: &str) -> Result<MatcherConfigDraft, MatcherError> {
+        let draft_id = draft_id.to_owned();
+        self.with_connection(move |_, connection| {
+            let draft = get_draft(connection, &draft_id)?;
+            Ok(MatcherConfigDraft { data: draft.data, config: draft.config })
+        })
+        .await
+    }
+
+    async fn create_draft(&self, user: String) -> Result<String, MatcherError> {
+        self.with_connection(move |database, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            let active = database.active_deployment(&transaction)?;
+            let draft_ids = get_draft_ids(&transaction)?;
+            let draft_id = (1..)
+                .map(|index| format!("draft_{:03}", index))
+                .find(|draft_id| !draft_ids.contains(draft_id))
+                .expect("The draft ids are unbounded");
+            info!("Creating a new draft {draft_id} for user {user}");
+
+            let now = Local::now().timestamp_millis();
+            let draft_data = MatcherConfigDraftData {
+                created_ts_ms: now,
+                updated_ts_ms: now,
+                user,
+                draft_id: draft_id.clone(),
+                base_version: Some(active.data.version_id),
+            };
+            insert_draft(
+                &transaction,
+                &draft_data,
+                &active.config,
+                &active.tests,
+                Some(&active.config),
+            )?;
+            transaction.commit().map_err(sqlite_error)?;
+            Ok(draft_id)
+        })
+        .await
+    }
+
+    async fn update_draft(
+        &self,
+        draft_id: &str,
+        user: String,
+        config: &MatcherConfig,
+    ) -> Result<(), MatcherError> {
+        let draft_id = draft_id.to_owned();
+        let config = config.clone();
+        self.with_connection(move |_, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            let mut draft = get_draft(&transaction, &draft_id)?;
+
+            if draft.data.user != user {
+                warn!(
+                    "User {user} tried overwriting a draft that is owned by {}.",
+                    draft.data.user
+                );
+                return Err(MatcherError::ConfigurationError {
+                    message: format!(
+                        "User [{}] cannot overwrite draft owned by [{}]",
+                        user, draft.data.user
+                    ),
+                });
+            }
+
+            validate_save(&config)?;
+            let tests = retain_tests_for(&config, draft.tests);
+            draft.data.updated_ts_ms = Local::now().timestamp_millis();
+            insert_draft(&transaction, &draft.data, &config, &tests, draft.base_config.as_ref())?;
+            transaction.commit().map_err(sqlite_error)
+        })
+        .await
+    }
+
+    async fn deploy_draft(&self, draft_id: &str) -> Result<MatcherConfig, MatcherError> {
+        let draft_id = draft_id.to_owned();
+        self.with_connection(move |database, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            let mut draft = get_draft(&transaction, &draft_id)?;
+            let active = database.active_deployment(&transaction)?;
+            check_draft_up_to_date(&draft_id, draft.base_config.as_ref(), &active.config)?;
+
+            validate_deploy_with_tests(&draft.config, &draft.tests)?;
+            let version_id = database.deploy(
+                &transaction,
+                &draft.config,
+                &draft.tests,
+                Some(draft.data.user.clone()),
+                Some(draft_id),
+                None,
+            )?;
+
+            base_on_deployed_version(&mut draft.data, version_id);
+            insert_draft(
+                &transaction,
+                &draft.data,
+                &draft.config,
+                &draft.tests,
+                Some(&draft.config),
+            )?;
+            transaction.commit().map_err(sqlite_error)?;
+            Ok(draft.config)
+        })
+        .await
+    }
+
+    async fn delete_draft(&self, draft_id: &str) -> Result<(), MatcherError> {
+        info!("Deleting draft {}", draft_id);
+        let draft_id = draft_id.to_owned();
+        self.with_connection(move |_, connection| {
+            let deleted = connection
+                .execute("DELETE FROM draft WHERE draft_id = ?1", params![draft_id])
+                .map_err(sqlite_error)?;
+            if deleted == 0 {
+                return Err(MatcherError::DraftNotFoundError { draft_id });
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn draft_take_over(&self, draft_id: &str, user: String) -> Result<(), MatcherError> {
+        let draft_id = draft_id.to_owned();
+        self.with_connection(move |_, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            let mut draft = get_draft(&transaction, &draft_id)?;
+            info!("User {} is taking over draft {} from user {}", user, draft_id, draft.data.user);
+            draft.data.user = user;
+            insert_draft(
+                &transaction,
+                &draft.data,
+                &draft.config,
+                &draft.tests,
+                draft.base_config.as_ref(),
+            )?;
+            transaction.commit().map_err(sqlite_error)
+        })
+        .await
+    }
+
+    async fn get_draft_rebase(&self, draft_id: &str) -> Result<DraftRebase, MatcherError> {
+        let draft_id = draft_id.to_owned();
+        self.with_connection(move |database, connection| {
+            let draft = get_draft(connection, &draft_id)?;
+            let Some(base_config) = &draft.base_config else {
+                return Ok(DraftRebase::default());
+            };
+            let active = database.active_deployment(connection)?;
+            Ok(preview_rebase(base_config, &active.config, &draft.config))
+        })
+        .await
+    }
+
+    async fn rebase_draft(
+        &self,
+        draft_id: &str,
+        user: String,
+    ) -> Result<DraftRebase, MatcherError> {
+        let draft_id = draft_id.to_owned();
+        self.with_connection(move |database, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            let mut draft = get_draft(&transaction, &draft_id)?;
+            if draft.data.user != user {
+                warn!("User {user} tried rebasing a draft that is owned by {}.", draft.data.user);
+                return Err(MatcherError::ConfigurationError {
+                    message: format!(
+                        "User [{}] cannot rebase draft owned by [{}]",
+                        user, draft.data.user
+                    ),
+                });
+            }
+
+            let Some(base_config) = &draft.base_config else {
+                return Ok(DraftRebase::default());
+            };
+            let active = database.active_deployment(&transaction)?;
+            let Some(rebased) =
+                rebase_onto(base_config, &active.config, active.tests, &draft.config, draft.tests)?
+            else {
+                return Ok(DraftRebase::default());
+            };
+
+            info!("User {} is rebasing draft {} on the active configuration", user, draft_id);
+            draft.data.updated_ts_ms = Local::now().timestamp_millis();
+            draft.data.base_version = Some(active.data.version_id);
+            insert_draft(
+                &transaction,
+                &draft.data,
+                &rebased.config,
+                &rebased.tests,
+                Some(&active.config),
+            )?;
+            transaction.commit().map_err(sqlite_error)?;
+            Ok(rebased.into())
+        })
+        .await
+    }
+
+    async fn deploy_config(&self, config: &MatcherConfig) -> Result<MatcherConfig, MatcherError> {
+        let config = config.clone();
+        self.with_connection(move |database, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            validate_deploy(&config)?;
+            let tests = match get_active_deployment(&transaction)? {
+                Some(active) => retain_tests_for(&config, active.tests),
+                None => vec![],
+            };
+            database.deploy(&transaction, &config, &tests, None, None, None)?;
+            transaction.commit().map_err(sqlite_error)?;
+            Ok(config)
+        })
+        .await
+    }
+
+    async fn get_deployed_configs(&self) -> Result<Vec<DeployedConfigData>, MatcherError> {
+        self.with_connection(|_, connection| {
+            let mut statement = connection
+                .prepare(
+                    "SELECT version, deployed_ts_ms, user, draft_id, rollback_of
+                        FROM deployment ORDER BY version DESC",
+                )
+                .map_err(sqlite_error)?;
+            let deployments = statement
+                .query_map([], deployed_config_data_from_row)
+                .map_err(sqlite_error)?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(sqlite_error)?;
+            Ok(deployments)
+        })
+        .await
+    }
+
+    async fn get_deployed_config(&self, version_id: &str) -> Result<DeployedConfig, MatcherError> {
+        let version_id = version_id.to_owned();
+        self.with_connection(move |_, connection| {
+            let deployment = get_deployment(connection, &version_id)?;
+            Ok(DeployedConfig { data: deployment.data, config: deployment.config })
+        })
+        .await
+    }
+
+    async fn rollback_config(
+        &self,
+        version_id: &str,
+        user: String,
+    ) -> Result<MatcherConfig, MatcherError> {
+        let version_id = version_id.to_owned();
+        self.with_connection(move |database, connection| {
+            let transaction = connection
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(sqlite_error)?;
+            let deployment = get_deployment(&transaction, &version_id)?;
+            validate_deploy_with_tests(&deployment.config, &deployment.tests)?;
+
+            info!("User {} is rolling back the configuration to version {}", user, version_id);
+            database.deploy(
+                &transaction,
+                &deployment.config,
+                &deployment.tests,
+                Some(user),
+                None,
+                Some(version_id),
+            )?;
+            transaction.commit().map_err(sqlite_error)?;
+            Ok(deployment.config)
+        })
+        .await
+    }
+}
+
+/// A row of the `deployment` table
+struct Deployment {
+    data: DeployedConfigData,
+    config: MatcherConfig,
+    tests: Vec<RulesetTests>,
+}
+
+/// A row of the `draft` table
+struct Draft {
+    data: MatcherConfigDraftData,
+    config: MatcherConfig,
+    tests: Vec<RulesetTests>,
+    base_config: Option<MatcherConfig>,
+}
+
+fn get_active_deployment(connection: &Connection) -> Result<Option<Deployment>, MatcherError> {
+    let row = connection
+        .query_row(
+            "SELECT version, deployed_ts_ms, user, draft_id, rollback_of, config, tests
+                FROM deployment ORDER BY version DESC LIMIT 1",
+            [],
+            |row| Ok((deployed_config_data_from_row(row)?, row.get(5)?, row.get(6)?)),
+        )
+        .optional()
+        .map_err(sqlite_error)?;
+    row.map(deployment_from_row).transpose()
+}
+
+/// Returns a deployment by version. Only well formed version ids are accepted.
+fn get_deployment(connection: &Connection, version_id: &str) -> Result<Deployment, MatcherError> {
+    let not_found =
+        || MatcherError::DeployedConfigNotFoundError { version_id: version_id.to_owned() };
+    let version = version_id
+        .parse::<u64>()
+        .ok()
+        .filter(|version| format_version(*version) == version_id)
+        .ok_or_else(not_found)?;
+    let row = connection
+        .query_row(
+            "SELECT version, deployed_ts_ms, user, draft_id, rollback_of, config, tests
+                FROM deployment WHERE version = ?1",
+            params![version as i64],
+            |row| Ok((deployed_config_data_from_row(row)?, row.get(5)?, row.get(6)?)),
+        )
+        .optional()
+        .map_err(sqlite_error)?;
+    row.map(deployment_from_row).transpose()?.ok_or_else(not_found)
+}
+
+fn deployment_from_row(
+    (data, config, tests): (DeployedConfigData, String, String),
+) -> Result<Deployment, MatcherError> {
+    Ok(Deployment { data, config: from_json(&config)?, tests: from_json(&tests)? })
+}
+
+fn deployed_config_data_from_row(row: &rusqlite::Row) -> rusqlite::Result<DeployedConfigData> {
+    Ok(DeployedConfigData {
+        version_id: format_version(row.get::<_, i64>(0)? as u64),
+        deployed_ts_ms: row.get(1)?,
+        user: row.get(2)?,
+        draft_id: row.get(3)?,
+        rollback_of: row.get(4)?,
+    })
+}
+
+fn next_version_id(connection: &Connection) -> Result<String, MatcherError> {
+    let last_version: i64 = connection
+        .query_row("SELECT COALESCE(MAX(version), 0) FROM deployment", [], |row| row.get(0))
+        .map_err(sqlite_error)?;
+    Ok(format_version(last_version as u64 + 1))
+}
+
+fn insert_deployment(
+    connection: &Connection,
+    data: &DeployedConfigData,
+    config: &MatcherConfig,
+    tests: &[RulesetTests],
+) -> Result<(), MatcherError> {
+    let version = data.version_id.parse::<u64>().map_err(|_| MatcherError::ConfigurationError {
+        message: format!("Version [{}] is not a valid version number", data.version_id),
+    })?;
+    connection
+        .execute(
+            "INSERT INTO deployment (version, deployed_ts_ms, user, draft_id, rollback_of, config, tests)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                version as i64,
+                data.deployed_ts_ms,
+                data.user,
+                data.draft_id,
+                data.rollback_of,
+                to_json(config)?,
+                to_json(tests)?
+            ],
+        )
+        .map_err(sqlite_error)?;
+    Ok(())
+}
+
+fn get_draft_ids(connection: &Connection) -> Result<Vec<String>, MatcherError> {
+    let mut statement =
+        connection.prepare("SELECT draft_id FROM draft ORDER BY draft_id").map_err(sqlite_error)?;
+    let draft_ids = statement
+        .query_map([], |row| row.get(0))
+        .map_err(sqlite_error)?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(sqlite_error)?;
+    Ok(draft_ids)
+}
+
+fn get_draft(connection: &Connection, draft_id: &str) -> Result<Draft, MatcherError> {
+    let row = connection
+        .query_row(
+            "SELECT data, config, tests, base_config FROM draft WHERE draft_id = ?1",
+            params![draft_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(sqlite_error)?;
+    let Some((data, config, tests, base_config)) = row else {
+        return Err(MatcherError::DraftNotFoundError { draft_id: draft_id.to_owned() });
+    };
+    Ok(Draft {
+        data: from_json(&data)?,
+        config: from_json(&config)?,
+        tests: from_json(&tests)?,
+        base_config: base_config.as_deref().map(from_json).transpose()?,
+    })
+}
+
+/// Inserts a draft, or replaces it if it exists
+fn insert_draft(
+    connection: &Connection,
+    data: &MatcherConfigDraftData,
+    config: &MatcherConfig,
+    tests: &[RulesetTests],
+    base_config: Option<&MatcherConfig>,
+) -> Result<(), MatcherError> {
+    connection
+        .execute(
+            "INSERT OR REPLACE INTO draft (draft_id, data, config, tests, base_config)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                data.draft_id,
+                to_json(data)?,
+                to_json(config)?,
+                to_json(tests)?,
+                base_config.map(to_json).transpose()?
+            ],
+        )
+        .map_err(sqlite_error)?;
+    Ok(())
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, MatcherError> {
+    serde_json::to_string(value).map_err(|error| MatcherError::InternalSystemError {
+        message: format!("Cannot serialize {}: {}", std::any::type_name::<T>(), error),
+    })
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, MatcherError> {
+    serde_json::from_str(json).map_err(|error| MatcherError::InternalSystemError {
+        message: format!("Cannot deserialize {}: {}", std::any::type_name::<T>(), error),
+    })
+}
+
+fn sqlite_error(error: rusqlite::Error) -> MatcherError {
+    MatcherError::InternalSystemError {
+        message: format!("Error while accessing the configuration database: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::v1::fs::copy_recursive;
+    use tempfile::TempDir;
+
+    const TEST_CONFIG_DIR: &str = "./test_resources/v2/test_config/";
+    const TEST_DRAFT_DIR: &str = "./test_resources/v2/test_drafts/";
+
+    #[tokio::test]
+    async fn should_import_the_configuration_from_the_filesystem_layout() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_DRAFT_DIR), draft_temp_dir.clone()).await.unwrap();
+        let fs_config_manager = FsMatcherConfigManagerV2::new(&config_temp_dir, &draft_temp_dir)
+            .with_history(temp_dir.path().join("history"), 10);
+        let mut config = fs_config_manager.get_config().await.unwrap();
+        config.delete_node_in_path(&["root", "empty_filter"]).unwrap();
+        fs_config_manager.deploy_config(&config).await.unwrap();
+
+        let config_manager = SqliteMatcherConfigManagerV2::new(temp_dir.path().join("rules.db"));
+
+        // Act
+        config_manager.import_from_fs(&fs_config_manager).await.unwrap();
+        let second_import = config_manager.import_from_fs(&fs_config_manager).await;
+
+        // Assert
+        assert_eq!(
+            fs_config_manager.get_deployed_configs().await.unwrap(),
+            config_manager.get_deployed_configs().await.unwrap()
+        );
+        assert_eq!(
+            fs_config_manager.get_deployed_config("0000000001").await.unwrap(),
+            config_manager.get_deployed_config("0000000001").await.unwrap()
+        );
+        assert_eq!(config, config_manager.get_config().await.unwrap());
+        assert_eq!(
+            fs_config_manager.get_config_tests().await.unwrap(),
+            config_manager.get_config_tests().await.unwrap()
+        );
+        assert_eq!(vec!["draft_001"], config_manager.get_drafts().await.unwrap());
+        assert_eq!(
+            fs_config_manager.get_draft("draft_001").await.unwrap(),
+            config_manager.get_draft("draft_001").await.unwrap()
+        );
+        assert!(second_import.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_deploy_rebase_and_roll_back_drafts() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let fs_config_manager = FsMatcherConfigManagerV2::new(TEST_CONFIG_DIR, temp_dir.path());
+        let config_manager = SqliteMatcherConfigManagerV2::new(temp_dir.path().join("rules.db"))
+            .with_history_size(3);
+        config_manager.import_from_fs(&fs_config_manager).await.unwrap();
+        let initial_config = config_manager.get_config().await.unwrap();
+
+        let draft_id = config_manager.create_draft("user".to_owned()).await.unwrap();
+        let mut draft_config = config_manager.get_draft(&draft_id).await.unwrap().config;
+        draft_config.delete_node_in_path(&["root", "empty_filter"]).unwrap();
+        config_manager.update_draft(&draft_id, "user".to_owned(), &draft_config).await.unwrap();
+
+        let other_draft_id = config_manager.create_draft("another".to_owned()).await.unwrap();
+        let mut other_draft_config =
+            config_manager.get_draft(&other_draft_id).await.unwrap().config;
+        other_draft_config.delete_node_in_path(&["root", "tenant_a"]).unwrap();
+        config_manager
+            .update_draft(&other_draft_id, "another".to_owned(), &other_draft_config)
+            .await
+            .unwrap();
+        config_manager.deploy_draft(&other_draft_id).await.unwrap();
+
+        // Act
+        let not_owner_update =
+            config_manager.update_draft(&draft_id, "another".to_owned(), &draft_config).await;
+        let outdated_deploy = config_manager.deploy_draft(&draft_id).await;
+        let rebase = config_manager.rebase_draft(&draft_id, "user".to_owned()).await.unwrap();
+        let deployed = config_manager.deploy_draft(&draft_id).await.unwrap();
+        config_manager.rollback_config("0000000001", "admin".to_owned()).await.unwrap();
+        let history = config_manager.get_deployed_configs().await.unwrap();
+
+        // Assert
+        assert_eq!("draft_002", other_draft_id);
+        assert!(not_owner_update.is_err());
+        assert_eq!(
+            Err(MatcherError::DraftOutdatedError { draft_id: draft_id.clone() }),
+            outdated_deploy
+        );
+        assert!(rebase.outdated);
+        assert!(rebase.conflicts.is_empty());
+        match &deployed {
+            MatcherConfig::Filter { nodes, .. } => {
+                assert_eq!(
+                    vec!["master"],
+                    nodes.iter().map(|node| node.get_name()).collect::<Vec<_>>()
+                );
+            }
+            result => panic!("{:?}", result),
+        }
+        assert_eq!(
+            Some("0000000003".to_owned()),
+            config_manager.get_draft(&draft_id).await.unwrap().data.base_version
+        );
+
+        // Only the last three versions are kept
+        let versions = history.iter().map(|data| data.version_id.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["0000000004", "0000000003", "0000000002"], versions);
+        assert_eq!(Some("admin".to_owned()), history[0].user);
+        assert_eq!(Some("0000000001".to_owned()), history[0].rollback_of);
+        assert_eq!(Some(draft_id.clone()), history[1].draft_id);
+        assert_eq!(initial_config, config_manager.get_config().await.unwrap());
+        assert_eq!(
+            Err(MatcherError::DeployedConfigNotFoundError { version_id: "1".to_owned() }),
+            config_manager.get_deployed_config("1").await
+        );
+    }
+}
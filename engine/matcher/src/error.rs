@@ -59,8 +59,17 @@ pub enum MatcherError {
     #[error("DraftNotFoundError: Could not find draft with id [{draft_id}]")]
     DraftNotFoundError { draft_id: String },
 
+    #[error("DeployedConfigNotFoundError: Could not find deployed configuration with version [{version_id}]")]
+    DeployedConfigNotFoundError { version_id: String },
+
+    #[error("DraftOutdatedError: The active configuration changed after the draft [{draft_id}] was created. Rebase the draft before deploying it")]
+    DraftOutdatedError { draft_id: String },
+
     #[error("NestedIteratorError")]
     NestedIteratorError,
+
+    #[error("RuleTestsFailedError: {message}")]
+    RuleTestsFailedError { message: String },
 }
 
 impl From<ParserError> for MatcherError {
@@ -7,6 +7,10 @@ pub mod error;
 pub mod matcher;
 pub mod model;
 pub mod regex;
+pub mod replay;
+pub mod shadow;
+pub mod stats;
+pub mod test_case;
 pub mod validator;
 
 #[cfg(test)]
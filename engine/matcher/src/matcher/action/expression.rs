@@ -0,0 +1,760 @@
+//! The expression module contains a small language to compute values in the action payloads.
+//!
+//! An expression is declared in the action payload as a string starting with the `$=` prefix,
+//! for example:
+//! - `$= ${event.payload.used} / ${event.payload.total} * 100`
+//! - `$= ${event.payload.host} + "/" + ${event.payload.service}`
+//! - `$= if ${event.payload.severity} > 3 then "CRITICAL" else "WARNING"`
+//!
+//! A plain string that has to start with `$=` is declared with the `$$=` escape prefix,
+//! whose first `$` is removed; for example, `$$= 1 + 2` is the string `$= 1 + 2`,
+//! and `$$$= 1 + 2` is the string `$$= 1 + 2`.
+//!
+//! The grammar is intentionally minimal: it has no variables, loops or function calls,
+//! so the evaluation of an expression always terminates and cannot have side effects.
+//!
+//! ```text
+//! expression := "if" expression "then" expression "else" expression | or
+//! or         := and ("or" and)*
+//! and        := not ("and" not)*
+//! not        := "not" not | comparison
+//! comparison := sum (("==" | "!=" | "<" | "<=" | ">" | ">=") sum)?
+//! sum        := product (("+" | "-") product)*
+//! product    := unary (("*" | "/" | "%") unary)*
+//! unary      := "-" unary | primary
+//! primary    := number | string | "true" | "false" | "null" | accessor | "(" expression ")"
+//! ```
+
+use crate::accessor::{Accessor, AccessorBuilder};
+use crate::error::MatcherError;
+use crate::model::InternalEvent;
+use serde_json::{Number, Value};
+use std::cmp::Ordering;
+
+pub const EXPRESSION_PREFIX: &str = "$=";
+
+const MAX_EXPRESSION_DEPTH: usize = 64;
+/// The maximum number of nodes of an expression. As the depth of an expression cannot exceed
+/// its number of nodes, this also bounds the recursion of the evaluation, for example
+/// of a long chain of additions.
+const MAX_EXPRESSION_NODES: usize = 256;
+
+/// Returns the expression text if the value is an expression declaration, None otherwise.
+pub fn expression_text(value: &str) -> Option<&str> {
+    value.strip_prefix(EXPRESSION_PREFIX)
+}
+
+/// Removes the first `$` of a value starting with the `$$=` escape prefix, or with more `$`
+/// followed by `=`, so that a string starting with `$$=` can be declared as `$$$=`.
+/// Any other value is returned unchanged.
+pub fn unescape_expression_prefix(value: &str) -> &str {
+    match value.strip_prefix('$') {
+        Some(unescaped)
+            if unescaped.starts_with('$') && unescaped.trim_start_matches('$').starts_with('=') =>
+        {
+            unescaped
+        }
+        _ => value,
+    }
+}
+
+#[derive(Debug)]
+pub enum Expression {
+    Constant(Value),
+    Accessor(Accessor),
+    Negate(Box<Expression>),
+    Not(Box<Expression>),
+    Binary { operator: BinaryOperator, left: Box<Expression>, right: Box<Expression> },
+    If { condition: Box<Expression>, then: Box<Expression>, otherwise: Box<Expression> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equals,
+    NotEquals,
+    LessThan,
+    LessEquals,
+    GreaterThan,
+    GreaterEquals,
+    And,
+    Or,
+}
+
+impl BinaryOperator {
+    fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Remainder => "%",
+            BinaryOperator::Equals => "==",
+            BinaryOperator::NotEquals => "!=",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::LessEquals => "<=",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::GreaterEquals => ">=",
+            BinaryOperator::And => "and",
+            BinaryOperator::Or => "or",
+        }
+    }
+}
+
+impl Expression {
+    /// Parses the expression text and builds the accessors it contains.
+    pub fn build(
+        rule_name: &str,
+        accessor_builder: &AccessorBuilder,
+        text: &str,
+    ) -> Result<Expression, MatcherError> {
+        let tokens = tokenize(text).map_err(|message| build_error(text, message))?;
+        let mut parser =
+            ExpressionParser { rule_name, accessor_builder, tokens, position: 0, nodes: 0 };
+        let expression =
+            parser.parse_expression(0).map_err(|message| build_error(text, message))?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(expression),
+            Some(token) => {
+                Err(build_error(text, format!("unexpected token [{}]", token.description())))
+            }
+        }
+    }
+
+    /// Returns true if the result of this expression depends on the event content.
+    pub fn dynamic_value(&self) -> bool {
+        match self {
+            Expression::Constant(_) => false,
+            Expression::Accessor(accessor) => accessor.dynamic_value(),
+            Expression::Negate(inner) | Expression::Not(inner) => inner.dynamic_value(),
+            Expression::Binary { left, right, .. } => left.dynamic_value() || right.dynamic_value(),
+            Expression::If { condition, then, otherwise } => {
+                condition.dynamic_value() || then.dynamic_value() || otherwise.dynamic_value()
+            }
+        }
+    }
+
+    pub fn evaluate(&self, event: &InternalEvent) -> Result<Value, String> {
+        match self {
+            Expression::Constant(value) => Ok(value.clone()),
+            Expression::Accessor(accessor) => accessor
+                .get(event)
+                .map(|value| value.into_owned())
+                .ok_or_else(|| format!("Accessor [{:?}] returned empty value.", accessor)),
+            Expression::Negate(inner) => match inner.evaluate(event)? {
+                Value::Number(number) => match number.as_i64().and_then(i64::checked_neg) {
+                    Some(negated) => Ok(Value::Number(negated.into())),
+                    None => float_to_value(-as_f64(&number)),
+                },
+                value => Err(format!("Cannot negate non numeric value [{}]", value)),
+            },
+            Expression::Not(inner) => Ok(Value::Bool(!as_bool(&inner.evaluate(event)?)?)),
+            Expression::Binary { operator: BinaryOperator::And, left, right } => Ok(Value::Bool(
+                as_bool(&left.evaluate(event)?)? && as_bool(&right.evaluate(event)?)?,
+            )),
+            Expression::Binary { operator: BinaryOperator::Or, left, right } => Ok(Value::Bool(
+                as_bool(&left.evaluate(event)?)? || as_bool(&right.evaluate(event)?)?,
+            )),
+            Expression::Binary { operator, left, right } => {
+                evaluate_binary(*operator, left.evaluate(event)?, right.evaluate(event)?)
+            }
+            Expression::If { condition, then, otherwise } => {
+                if as_bool(&condition.evaluate(event)?)? {
+                    then.evaluate(event)
+                } else {
+                    otherwise.evaluate(event)
+                }
+            }
+        }
+    }
+}
+
+fn build_error(text: &str, message: String) -> MatcherError {
+    MatcherError::ConfigurationError {
+        message: format!("Invalid expression [{}]: {}", text.trim(), message),
+    }
+}
+
+fn evaluate_binary(operator: BinaryOperator, left: Value, right: Value) -> Result<Value, String> {
+    match (operator, left, right) {
+        (BinaryOperator::Equals, left, right) => Ok(Value::Bool(values_equal(&left, &right))),
+        (BinaryOperator::NotEquals, left, right) => Ok(Value::Bool(!values_equal(&left, &right))),
+        (
+            BinaryOperator::LessThan
+            | BinaryOperator::LessEquals
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterEquals,
+            left,
+            right,
+        ) => {
+            let ordering = match (&left, &right) {
+                (Value::Number(left), Value::Number(right)) => {
+                    as_f64(left).partial_cmp(&as_f64(right))
+                }
+                (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                format!("Cannot compare [{}] and [{}] with [{}]", left, right, operator.symbol())
+            })?;
+            Ok(Value::Bool(match operator {
+                BinaryOperator::LessThan => ordering == Ordering::Less,
+                BinaryOperator::LessEquals => ordering != Ordering::Greater,
+                BinaryOperator::GreaterThan => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        (BinaryOperator::Add, left @ Value::String(_), right)
+        | (BinaryOperator::Add, left, right @ Value::String(_)) => {
+            Ok(Value::String(format!("{}{}", as_text(&left)?, as_text(&right)?)))
+        }
+        (operator, Value::Number(left), Value::Number(right)) => {
+            evaluate_arithmetic(operator, &left, &right)
+        }
+        (operator, left, right) => {
+            Err(format!("Cannot apply [{}] to [{}] and [{}]", operator.symbol(), left, right))
+        }
+    }
+}
+
+fn evaluate_arithmetic(
+    operator: BinaryOperator,
+    left: &Number,
+    right: &Number,
+) -> Result<Value, String> {
+    if let (Some(left), Some(right)) = (left.as_i64(), right.as_i64()) {
+        let result = match operator {
+            BinaryOperator::Add => left.checked_add(right),
+            BinaryOperator::Subtract => left.checked_sub(right),
+            BinaryOperator::Multiply => left.checked_mul(right),
+            BinaryOperator::Remainder if right == 0 => return Err("Division by zero".to_owned()),
+            BinaryOperator::Remainder => left.checked_rem(right),
+            _ => None,
+        };
+        if let Some(result) = result {
+            return Ok(Value::Number(result.into()));
+        }
+    }
+
+    let (left, right) = (as_f64(left), as_f64(right));
+    match operator {
+        BinaryOperator::Add => float_to_value(left + right),
+        BinaryOperator::Subtract => float_to_value(left - right),
+        BinaryOperator::Multiply => float_to_value(left * right),
+        BinaryOperator::Divide | BinaryOperator::Remainder if right == 0.0 => {
+            Err("Division by zero".to_owned())
+        }
+        BinaryOperator::Divide => float_to_value(left / right),
+        BinaryOperator::Remainder => float_to_value(left % right),
+        _ => Err(format!("[{}] is not an arithmetic operator", operator.symbol())),
+    }
+}
+
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => as_f64(left) == as_f64(right),
+        _ => left == right,
+    }
+}
+
+fn as_f64(number: &Number) -> f64 {
+    number.as_f64().unwrap_or(f64::NAN)
+}
+
+fn as_bool(value: &Value) -> Result<bool, String> {
+    match value {
+        Value::Bool(boolean) => Ok(*boolean),
+        _ => Err(format!("Expected a boolean value but found [{}]", value)),
+    }
+}
+
+fn as_text(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(text) => Ok(text.to_owned()),
+        Value::Bool(boolean) => Ok(boolean.to_string()),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Null => Ok("null".to_owned()),
+        Value::Array(_) | Value::Object(_) => {
+            Err(format!("Cannot concatenate non scalar value [{}]", value))
+        }
+    }
+}
+
+fn float_to_value(value: f64) -> Result<Value, String> {
+    Number::from_f64(value)
+        .map(Value::Number)
+        .ok_or_else(|| format!("The result [{}] is not a valid number", value))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Number(Number),
+    Text(String),
+    Accessor(String),
+    Word(String),
+    Symbol(&'static str),
+}
+
+impl Token {
+    fn description(&self) -> String {
+        match self {
+            Token::Number(number) => number.to_string(),
+            Token::Text(text) => format!("\"{}\"", text),
+            Token::Accessor(accessor) => accessor.to_owned(),
+            Token::Word(word) => word.to_owned(),
+            Token::Symbol(symbol) => (*symbol).to_owned(),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 13] = ["==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "(", ")"];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut rest = text.trim_start();
+
+    while !rest.is_empty() {
+        let next = rest.chars().next().unwrap_or_default();
+        let consumed = if rest.starts_with("${") {
+            let end =
+                accessor_end(rest).ok_or_else(|| format!("accessor [{}] is not closed", rest))?;
+            tokens.push(Token::Accessor(rest[..=end].to_owned()));
+            end + 1
+        } else if next == '"' {
+            let (text, length) = tokenize_string(rest)?;
+            tokens.push(Token::Text(text));
+            length
+        } else if next.is_ascii_digit() {
+            let length =
+                rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+            let number = &rest[..length];
+            let number = if number.contains('.') {
+                number.parse::<f64>().ok().and_then(Number::from_f64)
+            } else {
+                number.parse::<i64>().ok().map(Number::from)
+            }
+            .ok_or_else(|| format!("[{}] is not a valid number", number))?;
+            tokens.push(Token::Number(number));
+            length
+        } else if next.is_alphabetic() || next == '_' {
+            let length =
+                rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..length].to_owned()));
+            length
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        } else {
+            return Err(format!("unexpected character [{}]", next));
+        };
+        rest = rest[consumed..].trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// Returns the position of the brace that closes the accessor at the start of the text,
+/// ignoring the braces within double quoted strings.
+fn accessor_end(text: &str) -> Option<usize> {
+    let mut in_quotes = false;
+    let mut chars = text.char_indices();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '"' => in_quotes = !in_quotes,
+            '\\' if in_quotes => {
+                chars.next();
+            }
+            '}' if !in_quotes => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Reads a double quoted string. The `\"` and `\\` escape sequences are supported.
+/// Returns the unescaped string and the number of bytes consumed.
+fn tokenize_string(text: &str) -> Result<(String, usize), String> {
+    let mut result = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '"' => return Ok((result, index + 1)),
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('"' | '\\'))) => result.push(escaped),
+                Some((_, 'n')) => result.push('\n'),
+                Some((_, 't')) => result.push('\t'),
+                _ => return Err(format!("invalid escape sequence in string [{}]", text)),
+            },
+            _ => result.push(char),
+        }
+    }
+    Err(format!("string [{}] is not closed", text))
+}
+
+struct ExpressionParser<'a> {
+    rule_name: &'a str,
+    accessor_builder: &'a AccessorBuilder,
+    tokens: Vec<Token>,
+    position: usize,
+    nodes: usize,
+}
+
+impl ExpressionParser<'_> {
+    /// Counts a new node of the expression and fails if there are too many.
+    fn add_node(&mut self) -> Result<(), String> {
+        self.nodes += 1;
+        if self.nodes > MAX_EXPRESSION_NODES {
+            return Err(format!(
+                "the maximum number of {} terms is exceeded",
+                MAX_EXPRESSION_NODES
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_expression(&mut self, depth: usize) -> Result<Expression, String> {
+        if depth > MAX_EXPRESSION_DEPTH {
+            return Err(format!(
+                "the maximum nesting depth of {} is exceeded",
+                MAX_EXPRESSION_DEPTH
+            ));
+        }
+        if self.next_is_word("if") {
+            self.position += 1;
+            let condition = self.parse_expression(depth + 1)?;
+            self.expect_word("then")?;
+            let then = self.parse_expression(depth + 1)?;
+            self.expect_word("else")?;
+            let otherwise = self.parse_expression(depth + 1)?;
+            self.add_node()?;
+            return Ok(Expression::If {
+                condition: Box::new(condition),
+                then: Box::new(then),
+                otherwise: Box::new(otherwise),
+            });
+        }
+        self.parse_or(depth)
+    }
+
+    fn parse_or(&mut self, depth: usize) -> Result<Expression, String> {
+        let mut left = self.parse_and(depth)?;
+        while self.next_is_word("or") {
+            self.position += 1;
+            let right = self.parse_and(depth)?;
+            self.add_node()?;
+            left = binary(BinaryOperator::Or, left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self, depth: usize) -> Result<Expression, String> {
+        let mut left = self.parse_not(depth)?;
+        while self.next_is_word("and") {
+            self.position += 1;
+            let right = self.parse_not(depth)?;
+            self.add_node()?;
+            left = binary(BinaryOperator::And, left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self, depth: usize) -> Result<Expression, String> {
+        if self.next_is_word("not") {
+            self.position += 1;
+            let inner = self.parse_not(depth + 1)?;
+            self.add_node()?;
+            return Ok(Expression::Not(Box::new(inner)));
+        }
+        self.parse_comparison(depth)
+    }
+
+    fn parse_comparison(&mut self, depth: usize) -> Result<Expression, String> {
+        let left = self.parse_sum(depth)?;
+        let operator = match self.tokens.get(self.position) {
+            Some(Token::Symbol("==")) => BinaryOperator::Equals,
+            Some(Token::Symbol("!=")) => BinaryOperator::NotEquals,
+            Some(Token::Symbol("<")) => BinaryOperator::LessThan,
+            Some(Token::Symbol("<=")) => BinaryOperator::LessEquals,
+            Some(Token::Symbol(">")) => BinaryOperator::GreaterThan,
+            Some(Token::Symbol(">=")) => BinaryOperator::GreaterEquals,
+            _ => return Ok(left),
+        };
+        self.position += 1;
+        let right = self.parse_sum(depth)?;
+        self.add_node()?;
+        Ok(binary(operator, left, right))
+    }
+
+    fn parse_sum(&mut self, depth: usize) -> Result<Expression, String> {
+        let mut left = self.parse_product(depth)?;
+        loop {
+            let operator = match self.tokens.get(self.position) {
+                Some(Token::Symbol("+")) => BinaryOperator::Add,
+                Some(Token::Symbol("-")) => BinaryOperator::Subtract,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            let right = self.parse_product(depth)?;
+            self.add_node()?;
+            left = binary(operator, left, right);
+        }
+    }
+
+    fn parse_product(&mut self, depth: usize) -> Result<Expression, String> {
+        let mut left = self.parse_unary(depth)?;
+        loop {
+            let operator = match self.tokens.get(self.position) {
+                Some(Token::Symbol("*")) => BinaryOperator::Multiply,
+                Some(Token::Symbol("/")) => BinaryOperator::Divide,
+                Some(Token::Symbol("%")) => BinaryOperator::Remainder,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            let right = self.parse_unary(depth)?;
+            self.add_node()?;
+            left = binary(operator, left, right);
+        }
+    }
+
+    fn parse_unary(&mut self, depth: usize) -> Result<Expression, String> {
+        if let Some(Token::Symbol("-")) = self.tokens.get(self.position) {
+            self.position += 1;
+            let inner = self.parse_unary(depth + 1)?;
+            self.add_node()?;
+            return Ok(Expression::Negate(Box::new(inner)));
+        }
+        self.parse_primary(depth)
+    }
+
+    fn parse_primary(&mut self, depth: usize) -> Result<Expression, String> {
+        self.add_node()?;
+        let token = self.tokens.get(self.position).ok_or("unexpected end of expression")?;
+        self.position += 1;
+        match token {
+            Token::Number(number) => Ok(Expression::Constant(Value::Number(number.clone()))),
+            Token::Text(text) => Ok(Expression::Constant(Value::String(text.to_owned()))),
+            Token::Word(word) if word == "true" => Ok(Expression::Constant(Value::Bool(true))),
+            Token::Word(word) if word == "false" => Ok(Expression::Constant(Value::Bool(false))),
+            Token::Word(word) if word == "null" => Ok(Expression::Constant(Value::Null)),
+            Token::Accessor(accessor) => self
+                .accessor_builder
+                .build(self.rule_name, accessor)
+                .map(Expression::Accessor)
+                .map_err(|err| format!("invalid accessor [{}]: {}", accessor, err)),
+            Token::Symbol("(") => {
+                let expression = self.parse_expression(depth + 1)?;
+                match self.tokens.get(self.position) {
+                    Some(Token::Symbol(")")) => {
+                        self.position += 1;
+                        Ok(expression)
+                    }
+                    _ => Err("missing closing parenthesis".to_owned()),
+                }
+            }
+            token => Err(format!("unexpected token [{}]", token.description())),
+        }
+    }
+
+    fn next_is_word(&self, expected: &str) -> bool {
+        matches!(self.tokens.get(self.position), Some(Token::Word(word)) if word == expected)
+    }
+
+    fn expect_word(&mut self, expected: &str) -> Result<(), String> {
+        if self.next_is_word(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(format!("expected [{}]", expected))
+        }
+    }
+}
+
+fn binary(operator: BinaryOperator, left: Expression, right: Expression) -> Expression {
+    Expression::Binary { operator, left: Box::new(left), right: Box::new(right) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use tornado_common_api::Event;
+
+    fn evaluate(text: &str, payload: Value) -> Result<Value, String> {
+        let expression = Expression::build("rule", &AccessorBuilder::new(), text).unwrap();
+        let mut event = Event::new("test_type");
+        event.payload = payload.as_object().unwrap().clone();
+        let event = json!(event);
+        let mut extracted_vars = json!({ "rule": { "var": "extracted" } });
+        expression.evaluate(&(&event, &mut extracted_vars).into())
+    }
+
+    #[test]
+    fn should_evaluate_constants() {
+        assert_eq!(Ok(json!(12)), evaluate("12", json!({})));
+        assert_eq!(Ok(json!(12.5)), evaluate(" 12.5 ", json!({})));
+        assert_eq!(Ok(json!("text")), evaluate(r#""text""#, json!({})));
+        assert_eq!(
+            Ok(json!(r#"a "quoted" \ text"#)),
+            evaluate(r#""a \"quoted\" \\ text""#, json!({}))
+        );
+        assert_eq!(Ok(json!(true)), evaluate("true", json!({})));
+        assert_eq!(Ok(json!(null)), evaluate("null", json!({})));
+    }
+
+    #[test]
+    fn should_evaluate_arithmetic_with_precedence() {
+        assert_eq!(Ok(json!(7)), evaluate("1 + 2 * 3", json!({})));
+        assert_eq!(Ok(json!(9)), evaluate("(1 + 2) * 3", json!({})));
+        assert_eq!(Ok(json!(-1)), evaluate("2 - 3", json!({})));
+        assert_eq!(Ok(json!(-6)), evaluate("-(2 * 3)", json!({})));
+        assert_eq!(Ok(json!(1)), evaluate("7 % 3", json!({})));
+        assert_eq!(Ok(json!(2.5)), evaluate("5 / 2", json!({})));
+        assert_eq!(Ok(json!(3.0)), evaluate("1.5 * 2", json!({})));
+    }
+
+    #[test]
+    fn should_evaluate_accessors() {
+        let payload = json!({ "used": 25, "total": 200 });
+        assert_eq!(
+            Ok(json!(12.5)),
+            evaluate("${event.payload.used} / ${event.payload.total} * 100", payload)
+        );
+        assert_eq!(Ok(json!("extracted!")), evaluate(r#"${_variables.var} + "!""#, json!({})));
+    }
+
+    #[test]
+    fn should_concatenate_strings() {
+        let payload = json!({ "host": "server", "port": 8080, "secure": false });
+        assert_eq!(
+            Ok(json!("server:8080 secure=false")),
+            evaluate(
+                r#"${event.payload.host} + ":" + ${event.payload.port} + " secure=" + ${event.payload.secure}"#,
+                payload
+            )
+        );
+    }
+
+    #[test]
+    fn should_evaluate_conditionals() {
+        let expression = r#"if ${event.payload.severity} > 3 then "CRITICAL" else "WARNING""#;
+        assert_eq!(Ok(json!("CRITICAL")), evaluate(expression, json!({ "severity": 4 })));
+        assert_eq!(Ok(json!("WARNING")), evaluate(expression, json!({ "severity": 3 })));
+
+        let expression = r#"if ${event.payload.state} == "DOWN" or not (${event.payload.up} and true) then 2 else if ${event.payload.load} >= 0.8 then 1 else 0"#;
+        assert_eq!(Ok(json!(2)), evaluate(expression, json!({ "state": "DOWN", "up": true })));
+        assert_eq!(Ok(json!(2)), evaluate(expression, json!({ "state": "UP", "up": false })));
+        assert_eq!(
+            Ok(json!(1)),
+            evaluate(expression, json!({ "state": "UP", "up": true, "load": 0.8 }))
+        );
+        assert_eq!(
+            Ok(json!(0)),
+            evaluate(expression, json!({ "state": "UP", "up": true, "load": 0.1 }))
+        );
+    }
+
+    #[test]
+    fn should_compare_values() {
+        assert_eq!(Ok(json!(true)), evaluate("1 == 1.0", json!({})));
+        assert_eq!(Ok(json!(true)), evaluate(r#""a" != "b""#, json!({})));
+        assert_eq!(Ok(json!(true)), evaluate(r#""a" < "b""#, json!({})));
+        assert_eq!(Ok(json!(false)), evaluate("2 <= 1", json!({})));
+        assert_eq!(Ok(json!(true)), evaluate("null == null", json!({})));
+    }
+
+    #[test]
+    fn should_short_circuit_boolean_operators() {
+        assert_eq!(Ok(json!(true)), evaluate("true or ${event.payload.missing}", json!({})));
+        assert_eq!(Ok(json!(false)), evaluate("false and ${event.payload.missing}", json!({})));
+    }
+
+    #[test]
+    fn should_return_evaluation_errors() {
+        assert!(evaluate("${event.payload.missing} + 1", json!({})).is_err());
+        assert!(evaluate("1 / 0", json!({})).is_err());
+        assert!(evaluate("1 % 0", json!({})).is_err());
+        assert!(evaluate(r#""a" * 2"#, json!({})).is_err());
+        assert!(evaluate(r#"1 < "a""#, json!({})).is_err());
+        assert!(evaluate("if 1 then 2 else 3", json!({})).is_err());
+        assert!(evaluate("not 1", json!({})).is_err());
+        assert!(evaluate("${event.payload.list} + \"\"", json!({ "list": [1] })).is_err());
+    }
+
+    #[test]
+    fn should_fail_to_build_invalid_expressions() {
+        for text in [
+            "",
+            "1 +",
+            "(1 + 2",
+            "1 2",
+            "if true then 1",
+            "unknown_word",
+            r#""not closed"#,
+            "${event.payload",
+            "${events.payload}",
+            "1 & 2",
+            "99999999999999999999",
+        ] {
+            assert!(
+                Expression::build("rule", &AccessorBuilder::new(), text).is_err(),
+                "expression [{}] should not be valid",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn should_fail_to_build_too_deeply_nested_expressions() {
+        let text = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert!(Expression::build("rule", &AccessorBuilder::new(), &text).is_err());
+    }
+
+    #[test]
+    fn should_fail_to_build_too_long_expressions() {
+        let text = vec!["${event.payload.value}"; 10_000].join(" + ");
+        let error = Expression::build("rule", &AccessorBuilder::new(), &text).unwrap_err();
+        assert!(format!("{:?}", error).contains("maximum number of"));
+
+        let text = vec!["${event.payload.value}"; 100].join(" + ");
+        assert_eq!(Ok(json!(100)), evaluate(&text, json!({ "value": 1 })));
+    }
+
+    #[test]
+    fn should_ignore_the_braces_within_the_strings_of_accessors() {
+        assert_eq!(Ok(json!("}")), evaluate(r#"${event.payload.missing ?? "}"}"#, json!({})));
+        assert_eq!(
+            Ok(json!("value}")),
+            evaluate(r#"${event.payload.value ?? "}"} + "}""#, json!({ "value": "value" }))
+        );
+    }
+
+    #[test]
+    fn should_return_whether_the_expression_is_dynamic() {
+        let builder = AccessorBuilder::new();
+        assert!(!Expression::build("rule", &builder, "1 + 2").unwrap().dynamic_value());
+        assert!(Expression::build("rule", &builder, "1 + ${event.payload.value}")
+            .unwrap()
+            .dynamic_value());
+    }
+
+    #[test]
+    fn should_return_the_expression_text() {
+        assert_eq!(Some(" 1 + 2"), expression_text("$= 1 + 2"));
+        assert_eq!(None, expression_text("1 + 2"));
+        assert_eq!(None, expression_text("${event.payload}"));
+        assert_eq!(None, expression_text("$$= 1 + 2"));
+    }
+
+    #[test]
+    fn should_unescape_the_expression_prefix() {
+        assert_eq!("$= 1 + 2", unescape_expression_prefix("$$= 1 + 2"));
+        assert_eq!("$$$= 1 + 2", unescape_expression_prefix("$$$$= 1 + 2"));
+        assert_eq!("$= 1 + 2", unescape_expression_prefix("$= 1 + 2"));
+        assert_eq!("$$ 1 + 2", unescape_expression_prefix("$$ 1 + 2"));
+        assert_eq!("${event.payload}", unescape_expression_prefix("${event.payload}"));
+    }
+}
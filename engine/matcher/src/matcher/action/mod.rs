@@ -7,6 +7,8 @@
 use crate::accessor::{Accessor, AccessorBuilder};
 use crate::config::rule::ConfigAction;
 use crate::error::MatcherError;
+use crate::matcher::action::expression::{expression_text, unescape_expression_prefix, Expression};
+use crate::matcher::operator::{Operator, OperatorBuilder};
 use crate::model::{
     ActionMetaData, EnrichedValue, EnrichedValueContent, InternalEvent, ValueMetaData,
 };
@@ -15,15 +17,18 @@ use serde_json::{Map, Number, Value};
 use std::collections::HashMap;
 use tornado_common_api::{Action, WithEventData};
 
+pub mod expression;
+
 #[derive(Default)]
 pub struct ActionResolverBuilder {
     accessor: AccessorBuilder,
+    operator: OperatorBuilder,
 }
 
 /// The ActionResolver builder
 impl ActionResolverBuilder {
     pub fn new() -> ActionResolverBuilder {
-        ActionResolverBuilder { accessor: AccessorBuilder::new() }
+        ActionResolverBuilder { accessor: AccessorBuilder::new(), operator: OperatorBuilder::new() }
     }
 
     /// Receives an array of Actions as defined in a Rule and returns an array of ActionResolver elements.
@@ -52,6 +57,11 @@ impl ActionResolverBuilder {
             rule_name: rule_name.to_owned(),
             id: action.id.to_owned(),
             payload: HashMap::new(),
+            when: action
+                .when
+                .as_ref()
+                .map(|operator| self.operator.build(rule_name, operator))
+                .transpose()?,
         };
 
         for (payload_key, payload_value) in &action.payload {
@@ -98,9 +108,14 @@ impl ActionResolverBuilder {
                 }
                 Ok(ActionValueProcessor::Array(processor_values))
             }
-            Value::String(text) => {
-                Ok(ActionValueProcessor::Accessor(accessor.build(rule_name, text)?))
-            }
+            Value::String(text) => match expression_text(text) {
+                Some(expression) => Ok(ActionValueProcessor::Expression(Expression::build(
+                    rule_name, accessor, expression,
+                )?)),
+                None => Ok(ActionValueProcessor::Accessor(
+                    accessor.build(rule_name, unescape_expression_prefix(text))?,
+                )),
+            },
             Value::Bool(boolean) => Ok(ActionValueProcessor::Bool(*boolean)),
             Value::Number(number) => Ok(ActionValueProcessor::Number(number.clone())),
             Value::Null => Ok(ActionValueProcessor::Null),
@@ -113,9 +128,21 @@ pub struct ActionResolver {
     rule_name: String,
     pub id: String,
     payload: HashMap<String, ActionValueProcessor>,
+    when: Option<Box<dyn Operator>>,
 }
 
 impl ActionResolver {
+    /// Returns whether this Action has a `when` condition.
+    pub fn has_condition(&self) -> bool {
+        self.when.is_some()
+    }
+
+    /// Evaluates the `when` condition of this Action.
+    /// Returns true if the Action has no condition.
+    pub fn condition_matches(&self, data: &InternalEvent) -> bool {
+        self.when.as_ref().map(|operator| operator.evaluate(data)).unwrap_or(true)
+    }
+
     /// Builds an Action by extracting the required data from the InternalEvent.
     /// The outcome is a fully resolved Action ready to be processed by the executors.
     pub fn resolve(&self, data: &InternalEvent) -> Result<Action, MatcherError> {
@@ -167,6 +194,7 @@ impl ActionResolver {
 #[derive(Debug)]
 enum ActionValueProcessor {
     Accessor(Accessor),
+    Expression(Expression),
     Null,
     Bool(bool),
     Number(Number),
@@ -190,6 +218,13 @@ impl ActionValueProcessor {
                     cause: format!("Accessor [{:?}] returned empty value.", accessor),
                 })?
                 .into_owned()),
+            ActionValueProcessor::Expression(expression) => {
+                expression.evaluate(data).map_err(|cause| MatcherError::CreateActionError {
+                    action_id: action_id.to_owned(),
+                    rule_name: rule_name.to_owned(),
+                    cause,
+                })
+            }
             ActionValueProcessor::Null => Ok(Value::Null),
             ActionValueProcessor::Number(number) => Ok(Value::Number(number.clone())),
             ActionValueProcessor::Bool(boolean) => Ok(Value::Bool(*boolean)),
@@ -235,6 +270,21 @@ impl ActionValueProcessor {
                     },
                 ))
             }
+            ActionValueProcessor::Expression(expression) => {
+                let value =
+                    expression.evaluate(data).map_err(|cause| MatcherError::CreateActionError {
+                        action_id: action_id.to_owned(),
+                        rule_name: rule_name.to_owned(),
+                        cause,
+                    })?;
+                Ok((
+                    value.clone(),
+                    EnrichedValue {
+                        content: EnrichedValueContent::Single { content: value },
+                        meta: ValueMetaData { is_leaf: true, modified: expression.dynamic_value() },
+                    },
+                ))
+            }
             ActionValueProcessor::Null => {
                 let value = Value::Null;
                 Ok((
@@ -324,7 +374,8 @@ mod test {
     #[test]
     fn should_build_a_matcher_action() {
         // Arrange
-        let mut action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         let value = "constant value".to_owned();
         action.payload.insert("key".to_owned(), Value::String(value));
 
@@ -345,7 +396,8 @@ mod test {
     #[test]
     fn should_build_an_action() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert("type".to_owned(), Value::String("${event.type}".to_owned()));
         config_action
             .payload
@@ -405,7 +457,8 @@ mod test {
     #[test]
     fn should_build_an_action_with_text_to_be_interpolated_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert(
             "type".to_owned(),
             Value::String("The event type is: ${event.type}".to_owned()),
@@ -438,7 +491,8 @@ mod test {
     #[test]
     fn should_build_an_action_with_bool_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert("type".to_owned(), Value::Bool(true));
 
         let rule_name = "rule_for_test";
@@ -462,7 +516,8 @@ mod test {
     #[test]
     fn should_build_an_action_with_null_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert("type".to_owned(), Value::Null);
 
         let rule_name = "rule_for_test";
@@ -486,7 +541,8 @@ mod test {
     #[test]
     fn should_build_an_action_with_number_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert("type".to_owned(), json!(123456));
 
         let rule_name = "rule_for_test";
@@ -510,7 +566,8 @@ mod test {
     #[test]
     fn should_build_an_action_with_array_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert(
             "type".to_owned(),
             Value::Array(vec![
@@ -550,7 +607,8 @@ mod test {
     #[test]
     fn should_build_an_action_with_map_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert("type".to_owned(),
                                      json!(hashmap![
                                          "one".to_owned() => json!(123456.0),
@@ -584,7 +642,8 @@ mod test {
     #[test]
     fn should_build_an_action_with_maps_in_payload() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action
             .payload
             .insert("payload_body".to_owned(), Value::String("${event.payload.body}".to_owned()));
@@ -618,7 +677,8 @@ mod test {
     #[test]
     fn should_put_the_whole_event_in_the_payload() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action.payload.insert("event".to_owned(), Value::String("${event}".to_owned()));
 
         let rule_name = "rule_for_test";
@@ -645,7 +705,8 @@ mod test {
     #[test]
     fn should_put_the_whole_event_payload_in_the_action_payload() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action
             .payload
             .insert("event_payload".to_owned(), Value::String("${event.payload}".to_owned()));
@@ -668,10 +729,119 @@ mod test {
         assert_eq!(&Value::Object(payload), result.payload.get("event_payload").unwrap());
     }
 
+    #[test]
+    fn should_build_an_action_with_computed_values() {
+        // Arrange
+        let config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: json!({
+                "usage": "$= ${event.payload.used} / ${event.payload.total} * 100",
+                "state": r#"$= if ${event.payload.severity} > 3 then "CRITICAL" else "WARNING""#,
+                "nested": ["$= 1 + 2"]
+            })
+            .as_object()
+            .unwrap()
+            .clone(),
+            when: None,
+        };
+
+        let matcher_action = ActionResolverBuilder::new().build("rule", &config_action).unwrap();
+
+        let mut payload = Payload::new();
+        payload.insert("used".to_owned(), json!(30));
+        payload.insert("total".to_owned(), json!(120));
+        payload.insert("severity".to_owned(), json!(5));
+        let event = json!(Event::new_with_payload("event_type_value".to_owned(), payload));
+
+        // Act
+        let (result, meta) =
+            matcher_action.resolve_with_meta(&(&event, &mut Value::Null).into()).unwrap();
+
+        // Assert
+        assert_eq!(&json!(25.0), result.payload.get("usage").unwrap());
+        assert_eq!(&json!("CRITICAL"), result.payload.get("state").unwrap());
+        assert_eq!(&json!([3]), result.payload.get("nested").unwrap());
+        assert!(meta.payload.get("usage").unwrap().meta.modified);
+        assert!(!meta.payload.get("nested").unwrap().meta.modified);
+    }
+
+    #[test]
+    fn should_build_an_action_with_escaped_expression_prefixes() {
+        // Arrange
+        let config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: json!({
+                "literal": "$$= 1 + 2",
+                "interpolated": "$$= ${event.payload.used}",
+                "nested": ["$$$= 1 + 2"]
+            })
+            .as_object()
+            .unwrap()
+            .clone(),
+            when: None,
+        };
+
+        let matcher_action = ActionResolverBuilder::new().build("rule", &config_action).unwrap();
+
+        let mut payload = Payload::new();
+        payload.insert("used".to_owned(), json!(30));
+        let event = json!(Event::new_with_payload("event_type_value".to_owned(), payload));
+
+        // Act
+        let result = matcher_action.resolve(&(&event, &mut Value::Null).into()).unwrap();
+
+        // Assert
+        assert_eq!(&json!("$= 1 + 2"), result.payload.get("literal").unwrap());
+        assert_eq!(&json!("$= 30"), result.payload.get("interpolated").unwrap());
+        assert_eq!(&json!(["$$= 1 + 2"]), result.payload.get("nested").unwrap());
+    }
+
+    #[test]
+    fn should_fail_to_resolve_an_action_if_the_expression_cannot_be_evaluated() {
+        // Arrange
+        let config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: json!({ "usage": "$= ${event.payload.used} / 0" })
+                .as_object()
+                .unwrap()
+                .clone(),
+            when: None,
+        };
+
+        let matcher_action = ActionResolverBuilder::new().build("rule", &config_action).unwrap();
+
+        let mut payload = Payload::new();
+        payload.insert("used".to_owned(), json!(30));
+        let event = json!(Event::new_with_payload("event_type_value".to_owned(), payload));
+
+        // Act
+        let result = matcher_action.resolve(&(&event, &mut Value::Null).into());
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::CreateActionError { .. })));
+    }
+
+    #[test]
+    fn should_fail_to_build_an_action_with_an_invalid_expression() {
+        // Arrange
+        let config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: json!({ "usage": "$= ${event.payload.used} / " }).as_object().unwrap().clone(),
+            when: None,
+        };
+
+        // Act
+        let result = ActionResolverBuilder::new().build("rule", &config_action);
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ConfigurationError { .. })));
+    }
+
     #[test]
     fn should_return_action_metadata_for_simple_action() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action =
+            ConfigAction { id: "an_action_id".to_owned(), payload: Map::new(), when: None };
         config_action
             .payload
             .insert("event_payload".to_owned(), Value::String("${event.payload}".to_owned()));
@@ -745,7 +915,7 @@ mod test {
             }),
         );
 
-        let config_action = ConfigAction { id: "an_action_id".to_owned(), payload };
+        let config_action = ConfigAction { id: "an_action_id".to_owned(), payload, when: None };
 
         let rule_name = "rule_for_test";
         let action_resolver =
@@ -830,7 +1000,7 @@ mod test {
             })]),
         );
 
-        let config_action = ConfigAction { id: "an_action_id".to_owned(), payload };
+        let config_action = ConfigAction { id: "an_action_id".to_owned(), payload, when: None };
 
         let rule_name = "rule_for_test";
         let action_resolver =
@@ -8,7 +8,7 @@ use crate::accessor::{Accessor, AccessorBuilder};
 use crate::config::rule::{Extractor, ExtractorRegex};
 use crate::error::MatcherError;
 use crate::matcher::modifier::ValueModifier;
-use crate::model::InternalEvent;
+use crate::model::{ExtractorExplanation, InternalEvent, ModifierExplanation};
 use crate::regex::RegexWrapper;
 use log::*;
 use regex::{Captures, Regex as RustRegex};
@@ -133,6 +133,15 @@ impl MatcherExtractor {
         }
         Ok(())
     }
+
+    /// Returns the evaluation trace of each extractor of the rule, sorted by variable name.
+    /// Contrary to `process_all`, the extracted variables are not added to the event.
+    pub fn explain(&self, event: &InternalEvent) -> Vec<ExtractorExplanation> {
+        let mut explanations: Vec<_> =
+            self.extractors.iter().map(|(key, extractor)| extractor.explain(key, event)).collect();
+        explanations.sort_by(|first, second| first.variable.cmp(&second.variable));
+        explanations
+    }
 }
 
 #[derive(Debug)]
@@ -167,6 +176,45 @@ impl ValueExtractor {
         }
         Ok(extracted_value)
     }
+
+    pub fn explain(&self, variable_name: &str, event: &InternalEvent) -> ExtractorExplanation {
+        let mut explanation = ExtractorExplanation {
+            variable: variable_name.to_owned(),
+            input: self
+                .regex_extractor
+                .target()
+                .get(event)
+                .map(|value| value.into_owned())
+                .unwrap_or(Value::Null),
+            matched: false,
+            value: None,
+            modifiers: vec![],
+            message: None,
+        };
+
+        let mut extracted_value = match self.regex_extractor.extract(variable_name, event) {
+            Ok(value) => value,
+            Err(err) => {
+                explanation.message = Some(format!("{}", err));
+                return explanation;
+            }
+        };
+
+        for modifier in &self.modifiers_post {
+            if let Err(err) = modifier.apply(variable_name, &mut extracted_value, event) {
+                explanation.message = Some(format!("{}", err));
+                return explanation;
+            }
+            explanation.modifiers.push(ModifierExplanation {
+                modifier: modifier.name().to_owned(),
+                output: extracted_value.clone(),
+            });
+        }
+
+        explanation.matched = true;
+        explanation.value = Some(extracted_value);
+        explanation
+    }
 }
 
 #[derive(Debug)]
@@ -181,6 +229,18 @@ enum RegexValueExtractor {
 }
 
 impl RegexValueExtractor {
+    fn target(&self) -> &Accessor {
+        match self {
+            RegexValueExtractor::SingleMatchSingleGroup { target, .. }
+            | RegexValueExtractor::AllMatchesSingleGroup { target, .. }
+            | RegexValueExtractor::SingleMatchAllGroups { target, .. }
+            | RegexValueExtractor::AllMatchesAllGroups { target, .. }
+            | RegexValueExtractor::SingleMatchNamedGroups { target, .. }
+            | RegexValueExtractor::AllMatchesNamedGroups { target, .. }
+            | RegexValueExtractor::SingleKeyMatch { target, .. } => target,
+        }
+    }
+
     pub fn build(
         rule_name: &str,
         extractor: &Extractor,
@@ -1592,6 +1652,73 @@ mod test {
         assert_eq!(Value::String("hello to be trimmed replaced_and lowercased".to_owned()), result);
     }
 
+    #[test]
+    fn explain_should_return_the_output_of_each_modifier() {
+        // Arrange
+        let mut extractor_config = HashMap::new();
+        extractor_config.insert(
+            String::from("var"),
+            Extractor {
+                from: String::from("${event.type}"),
+                regex: ExtractorRegex::Regex {
+                    regex: String::from(r"[A-Z ]+"),
+                    group_match_idx: Some(0),
+                    all_matches: false,
+                },
+                modifiers_post: vec![Modifier::Trim {}, Modifier::Lowercase {}],
+            },
+        );
+        extractor_config.insert(
+            String::from("missing"),
+            Extractor {
+                from: String::from("${event.type}"),
+                regex: ExtractorRegex::Regex {
+                    regex: String::from(r"[0-9]+"),
+                    group_match_idx: Some(0),
+                    all_matches: false,
+                },
+                modifiers_post: vec![Modifier::Trim {}],
+            },
+        );
+        let extractor = MatcherExtractorBuilder::new().build("rule", &extractor_config).unwrap();
+        let event = new_event("event TYPE ");
+
+        // Act
+        let result = extractor.explain(&(&event, &mut Value::Null).into());
+
+        // Assert
+        assert_eq!(
+            vec![
+                ExtractorExplanation {
+                    variable: "missing".to_owned(),
+                    input: json!("event TYPE "),
+                    matched: false,
+                    value: None,
+                    modifiers: vec![],
+                    message: Some(
+                        "MissingExtractedVariableError: Cannot extract variable [missing]."
+                            .to_owned()
+                    ),
+                },
+                ExtractorExplanation {
+                    variable: "var".to_owned(),
+                    input: json!("event TYPE "),
+                    matched: true,
+                    value: Some(json!("type")),
+                    modifiers: vec![
+                        ModifierExplanation { modifier: "Trim".to_owned(), output: json!("TYPE") },
+                        ModifierExplanation {
+                            modifier: "Lowercase".to_owned(),
+                            output: json!("type"),
+                        },
+                    ],
+                    message: None,
+                },
+            ],
+            result
+        );
+    }
+
     fn new_event(event_type: &str) -> Value {
         json!(Event::new(event_type))
     }
@@ -11,9 +11,9 @@ use crate::config::MatcherConfig;
 use crate::error::MatcherError;
 use crate::matcher::extractor::{MatcherExtractor, MatcherExtractorBuilder};
 use crate::model::{
-    InternalEvent, ProcessedEvent, ProcessedFilter, ProcessedFilterStatus, ProcessedIteration,
-    ProcessedIterator, ProcessedNode, ProcessedRule, ProcessedRuleMetaData, ProcessedRuleStatus,
-    ProcessedRules,
+    ActionConditionMetaData, InternalEvent, ProcessedEvent, ProcessedFilter, ProcessedFilterStatus,
+    ProcessedIteration, ProcessedIterator, ProcessedNode, ProcessedRule, ProcessedRuleMetaData,
+    ProcessedRuleStatus, ProcessedRules, RuleExplanation,
 };
 use crate::validator::MatcherConfigValidator;
 use log::*;
@@ -139,7 +139,16 @@ impl Matcher {
             &event,
             include_metadata
         );
-        let result = Matcher::process_node(&self.node, &event, include_metadata);
+        let result = Matcher::process_node(&self.node, &event, include_metadata, false);
+        ProcessedEvent { event, result }
+    }
+
+    /// Processes an incoming Event like `process` with the metadata included; in addition,
+    /// the metadata of each processed rule contains the evaluation trace of its operator and extractors.
+    /// This is meant to debug the rules and is slower than `process`.
+    pub fn explain(&self, event: Value) -> ProcessedEvent {
+        trace!("Matcher explain - processing event: [{:?}]", &event);
+        let result = Matcher::process_node(&self.node, &event, true, true);
         ProcessedEvent { event, result }
     }
 
@@ -147,17 +156,28 @@ impl Matcher {
         node: &ProcessingNode,
         internal_event: &Value,
         include_metadata: bool,
+        explain: bool,
     ) -> ProcessedNode {
         match node {
-            ProcessingNode::Filter { name, filter, nodes } => {
-                Matcher::process_filter(name, filter, nodes, internal_event, include_metadata)
-            }
+            ProcessingNode::Filter { name, filter, nodes } => Matcher::process_filter(
+                name,
+                filter,
+                nodes,
+                internal_event,
+                include_metadata,
+                explain,
+            ),
             ProcessingNode::Ruleset { name, rules } => {
-                Matcher::process_rules(name, rules, internal_event, include_metadata)
-            }
-            ProcessingNode::Iterator { name, target, nodes } => {
-                Matcher::process_iterator(name, target, nodes, internal_event, include_metadata)
+                Matcher::process_rules(name, rules, internal_event, include_metadata, explain)
             }
+            ProcessingNode::Iterator { name, target, nodes } => Matcher::process_iterator(
+                name,
+                target,
+                nodes,
+                internal_event,
+                include_metadata,
+                explain,
+            ),
         }
     }
 
@@ -168,6 +188,7 @@ impl Matcher {
         nodes: &[ProcessingNode],
         event: &Value,
         include_metadata: bool,
+        explain: bool,
     ) -> ProcessedNode {
         trace!("Matcher process - check matching of filter: [{}]", filter_name);
 
@@ -181,7 +202,8 @@ impl Matcher {
                         filter_name
                     );
                 nodes.iter().for_each(|node| {
-                    let processed_node = Matcher::process_node(node, event, include_metadata);
+                    let processed_node =
+                        Matcher::process_node(node, event, include_metadata, explain);
                     result_nodes.push(processed_node);
                 });
                 ProcessedFilterStatus::Matched
@@ -206,6 +228,7 @@ impl Matcher {
         nodes: &[ProcessingNode],
         event: &Value,
         include_metadata: bool,
+        explain: bool,
     ) -> ProcessedNode {
         trace!("Matcher process - check matching of iterator: [{}]", name);
         let internal_event = InternalEvent { event, extracted_variables: &mut Default::default() };
@@ -221,12 +244,12 @@ impl Matcher {
         match target.as_ref() {
             Value::Array(slice) => {
                 let iterator = slice.iter().enumerate();
-                Matcher::iterate_over(name, iterator, event, nodes, include_metadata)
+                Matcher::iterate_over(name, iterator, event, nodes, include_metadata, explain)
             }
             Value::Object(map) => {
                 let iterator =
                     map.keys().flat_map(|key| map.get(key).map(|value| (key.as_str(), value)));
-                Matcher::iterate_over(name, iterator, event, nodes, include_metadata)
+                Matcher::iterate_over(name, iterator, event, nodes, include_metadata, explain)
             }
             _ => {
                 return ProcessedNode::Iterator {
@@ -244,6 +267,7 @@ impl Matcher {
         event: &Value,
         nodes: &[ProcessingNode],
         include_metadata: bool,
+        explain: bool,
     ) -> ProcessedNode
     where
         Key: Into<Value> + Copy,
@@ -267,7 +291,8 @@ impl Matcher {
 
             let mut processed_nodes = vec![];
             for node in nodes {
-                let processed_node = Matcher::process_node(node, &iterator_event, include_metadata);
+                let processed_node =
+                    Matcher::process_node(node, &iterator_event, include_metadata, explain);
                 processed_nodes.push(processed_node)
             }
 
@@ -294,6 +319,7 @@ impl Matcher {
         rules: &[MatcherRule],
         event: &Value,
         include_metadata: bool,
+        explain: bool,
     ) -> ProcessedNode {
         trace!("Matcher process - check matching of ruleset: [{}]", ruleset_name);
         let mut extracted_vars = Value::Object(Map::new());
@@ -319,10 +345,27 @@ impl Matcher {
             };
 
             if include_metadata {
-                processed_rule.meta = Some(ProcessedRuleMetaData { actions: vec![] })
+                processed_rule.meta = Some(ProcessedRuleMetaData {
+                    actions: vec![],
+                    action_conditions: vec![],
+                    explain: None,
+                })
             }
 
-            if rule.operator.evaluate(&internal_event) {
+            let matched = if explain {
+                let operator = rule.operator.explain(&internal_event);
+                let matched = operator.result;
+                let extractors =
+                    if matched { rule.extractor.explain(&internal_event) } else { vec![] };
+                if let Some(metadata) = &mut processed_rule.meta {
+                    metadata.explain = Some(RuleExplanation { operator, extractors });
+                }
+                matched
+            } else {
+                rule.operator.evaluate(&internal_event)
+            };
+
+            if matched {
                 trace!(
                     "Matcher process - event matches rule: [{}]. Checking extracted variables.",
                     &rule.name
@@ -377,13 +420,33 @@ impl Matcher {
         processed_rule: &mut ProcessedRule,
         actions: &[action::ActionResolver],
     ) -> Result<(), MatcherError> {
-        for action in actions {
+        for (index, action) in actions.iter().enumerate() {
             let _action_span = tracing::debug_span!(
                 "process_action",
                 otel.name = format!("Process Action: {}", action.id).as_str()
             )
             .entered();
 
+            if action.has_condition() {
+                let matched = action.condition_matches(processed_event);
+                trace!(
+                    "Matcher process - condition of action [{}] of rule [{}] evaluated to [{}]",
+                    action.id,
+                    processed_rule.name,
+                    matched
+                );
+                if let Some(metadata) = &mut processed_rule.meta {
+                    metadata.action_conditions.push(ActionConditionMetaData {
+                        index,
+                        id: action.id.to_owned(),
+                        matched,
+                    });
+                }
+                if !matched {
+                    continue;
+                }
+            }
+
             if let Some(metadata) = &mut processed_rule.meta {
                 let (action, action_metadata) = action.resolve_with_meta(processed_event)?;
                 processed_rule.actions.push(action);
@@ -848,7 +911,8 @@ mod test {
             },
         );
 
-        let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+        let mut action =
+            ConfigAction { id: String::from("action_id"), payload: Map::new(), when: None };
 
         action
             .payload
@@ -999,7 +1063,8 @@ mod test {
             },
         );
 
-        let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+        let mut action =
+            ConfigAction { id: String::from("action_id"), payload: Map::new(), when: None };
 
         action
             .payload
@@ -2629,7 +2694,8 @@ mod test {
                 },
             );
 
-            let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+            let mut action =
+                ConfigAction { id: String::from("action_id"), payload: Map::new(), when: None };
             action
                 .payload
                 .insert("value".to_owned(), Value::String("${_variables.VALUE}".to_owned()));
@@ -2651,7 +2717,8 @@ mod test {
                 },
             );
 
-            let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+            let mut action =
+                ConfigAction { id: String::from("action_id"), payload: Map::new(), when: None };
             action.payload.insert(
                 "value".to_owned(),
                 Value::String("${_variables.collision_name.VALUE}".to_owned()),
@@ -2667,7 +2734,8 @@ mod test {
         let rule_3 = {
             let mut rule = new_rule("rule3", None);
 
-            let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+            let mut action =
+                ConfigAction { id: String::from("action_id"), payload: Map::new(), when: None };
             action.payload.insert(
                 "value".to_owned(),
                 Value::String("${_variables.collision_name.VALUE}".to_owned()),
@@ -2737,9 +2805,21 @@ mod test {
     fn should_return_processed_rule_metadata() {
         // Arrange
         let mut rule = new_rule("rule_name", None);
-        rule.actions.push(ConfigAction { id: String::from("action_1"), payload: Map::new() });
-        rule.actions.push(ConfigAction { id: String::from("action_2"), payload: Map::new() });
-        rule.actions.push(ConfigAction { id: String::from("action_3"), payload: Map::new() });
+        rule.actions.push(ConfigAction {
+            id: String::from("action_1"),
+            payload: Map::new(),
+            when: None,
+        });
+        rule.actions.push(ConfigAction {
+            id: String::from("action_2"),
+            payload: Map::new(),
+            when: None,
+        });
+        rule.actions.push(ConfigAction {
+            id: String::from("action_3"),
+            payload: Map::new(),
+            when: None,
+        });
 
         let matcher =
             new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
@@ -2778,6 +2858,216 @@ mod test {
         };
     }
 
+    #[test]
+    fn should_emit_only_the_actions_whose_when_condition_matches() {
+        // Arrange
+        let mut rule = new_rule("rule_name", None);
+        rule.constraint.with.insert(
+            String::from("severity"),
+            Extractor {
+                from: String::from("${event.payload.severity}"),
+                regex: ExtractorRegex::Regex {
+                    regex: String::from(r"[A-Z]+"),
+                    group_match_idx: Some(0),
+                    all_matches: false,
+                },
+                modifiers_post: vec![],
+            },
+        );
+        rule.actions.push(ConfigAction {
+            id: String::from("archive"),
+            payload: Map::new(),
+            when: None,
+        });
+        rule.actions.push(ConfigAction {
+            id: String::from("icinga2"),
+            payload: Map::new(),
+            when: Some(Operator::Equals {
+                first: Value::String("${_variables.severity}".to_owned()),
+                second: Value::String("CRITICAL".to_owned()),
+            }),
+        });
+
+        let matcher =
+            new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
+                .expect("should create a matcher");
+
+        let mut critical_event = Event::new("email");
+        critical_event.payload.insert("severity".to_owned(), json!("CRITICAL"));
+        let mut warning_event = Event::new("email");
+        warning_event.payload.insert("severity".to_owned(), json!("WARNING"));
+
+        // Act
+        let critical_result = matcher.process(json!(critical_event), true);
+        let warning_result = matcher.process(json!(warning_event), false);
+
+        // Assert
+        match critical_result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let rule_processed = rules.rules.first().expect("should contain rule");
+                assert_eq!(ProcessedRuleStatus::Matched, rule_processed.status);
+                assert_eq!(2, rule_processed.actions.len());
+                assert_eq!("archive", rule_processed.actions[0].id);
+                assert_eq!("icinga2", rule_processed.actions[1].id);
+
+                let metadata = rule_processed.meta.as_ref().expect("should contain metadata");
+                assert_eq!(2, metadata.actions.len());
+                assert_eq!(
+                    vec![ActionConditionMetaData {
+                        index: 1,
+                        id: "icinga2".to_owned(),
+                        matched: true
+                    }],
+                    metadata.action_conditions
+                );
+            }
+            _ => unreachable!(),
+        };
+
+        match warning_result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let rule_processed = rules.rules.first().expect("should contain rule");
+                assert_eq!(ProcessedRuleStatus::Matched, rule_processed.status);
+                assert_eq!(1, rule_processed.actions.len());
+                assert_eq!("archive", rule_processed.actions[0].id);
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_report_skipped_actions_in_processed_rule_metadata() {
+        // Arrange
+        let mut rule = new_rule("rule_name", None);
+        rule.actions.push(ConfigAction {
+            id: String::from("action_1"),
+            payload: Map::new(),
+            when: Some(Operator::Equals {
+                first: Value::String("${event.type}".to_owned()),
+                second: Value::String("trap".to_owned()),
+            }),
+        });
+
+        let matcher =
+            new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
+                .expect("should create a matcher");
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), true);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let rule_processed = rules.rules.first().expect("should contain rule");
+                assert_eq!(ProcessedRuleStatus::Matched, rule_processed.status);
+                assert!(rule_processed.actions.is_empty());
+
+                let metadata = rule_processed.meta.as_ref().expect("should contain metadata");
+                assert!(metadata.actions.is_empty());
+                assert_eq!(
+                    vec![ActionConditionMetaData {
+                        index: 0,
+                        id: "action_1".to_owned(),
+                        matched: false
+                    }],
+                    metadata.action_conditions
+                );
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_fail_to_build_a_rule_with_an_invalid_when_condition() {
+        // Arrange
+        let mut rule = new_rule("rule_name", None);
+        rule.actions.push(ConfigAction {
+            id: String::from("action_1"),
+            payload: Map::new(),
+            when: Some(Operator::Equals {
+                first: Value::String("${events.type}".to_owned()),
+                second: Value::String("trap".to_owned()),
+            }),
+        });
+
+        // Act
+        let matcher =
+            new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] });
+
+        // Assert
+        assert!(matcher.is_err());
+    }
+
+    #[test]
+    fn should_return_the_evaluation_trace_in_explain_mode() {
+        // Arrange
+        let mut matching_rule = new_rule(
+            "matching",
+            Operator::Equals {
+                first: Value::String("${event.type}".to_owned()),
+                second: Value::String("email".to_owned()),
+            },
+        );
+        matching_rule.constraint.with.insert(
+            String::from("first_char"),
+            Extractor {
+                from: String::from("${event.type}"),
+                regex: ExtractorRegex::Regex {
+                    regex: String::from(r"^."),
+                    group_match_idx: Some(0),
+                    all_matches: false,
+                },
+                modifiers_post: vec![],
+            },
+        );
+        let not_matching_rule = new_rule(
+            "not_matching",
+            Operator::Not {
+                operator: Box::new(Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String("email".to_owned()),
+                }),
+            },
+        );
+
+        let matcher = new_matcher(&MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![matching_rule, not_matching_rule],
+        })
+        .expect("should create a matcher");
+
+        // Act
+        let result = matcher.explain(json!(Event::new("email")));
+        let result_without_explain = matcher.process(json!(Event::new("email")), true);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                assert_eq!(ProcessedRuleStatus::Matched, rules.rules[0].status);
+                let explanation = rules.rules[0].meta.as_ref().unwrap().explain.as_ref().unwrap();
+                assert!(explanation.operator.result);
+                assert_eq!(vec![json!("email"), json!("email")], explanation.operator.operands);
+                assert_eq!(1, explanation.extractors.len());
+                assert_eq!(Some(json!("e")), explanation.extractors[0].value);
+
+                assert_eq!(ProcessedRuleStatus::NotMatched, rules.rules[1].status);
+                let explanation = rules.rules[1].meta.as_ref().unwrap().explain.as_ref().unwrap();
+                assert_eq!("not", explanation.operator.operator);
+                assert!(!explanation.operator.result);
+                assert!(explanation.operator.operators[0].result);
+                assert!(explanation.extractors.is_empty());
+            }
+            _ => unreachable!(),
+        };
+
+        match result_without_explain.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                assert!(rules.rules[0].meta.as_ref().unwrap().explain.is_none());
+            }
+            _ => unreachable!(),
+        };
+    }
+
     fn new_matcher(config: &MatcherConfig) -> Result<Matcher, MatcherError> {
         //crate::test_root::start_context();
         Matcher::build(config)
@@ -88,6 +88,20 @@ impl ValueModifier {
         Ok(value_modifiers)
     }
 
+    /// Returns the modifier name, as used in the `type` field of the configuration.
+    pub fn name(&self) -> &str {
+        match self {
+            ValueModifier::Lowercase => "Lowercase",
+            ValueModifier::Map { .. } => "Map",
+            ValueModifier::ReplaceAll { .. } | ValueModifier::ReplaceAllRegex { .. } => {
+                "ReplaceAll"
+            }
+            ValueModifier::ToNumber => "ToNumber",
+            ValueModifier::Trim => "Trim",
+            ValueModifier::DateAndTime { .. } => "DateAndTime",
+        }
+    }
+
     pub fn apply(
         &self,
         variable_name: &str,
@@ -1,7 +1,7 @@
 use crate::config;
 use crate::error::MatcherError;
 use crate::matcher::operator::{Operator, OperatorBuilder};
-use crate::model::InternalEvent;
+use crate::model::{InternalEvent, OperatorExplanation};
 
 const OPERATOR_NAME: &str = "and";
 
@@ -34,6 +34,18 @@ impl Operator for And {
     fn evaluate(&self, event: &InternalEvent) -> bool {
         self.operators.iter().all(|op| op.evaluate(event))
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        // All the nested operators are explained, even those not evaluated because of short-circuiting
+        let operators: Vec<_> = self.operators.iter().map(|op| op.explain(event)).collect();
+        let result = operators.iter().all(|op| op.result);
+        OperatorExplanation {
+            operator: OPERATOR_NAME.to_owned(),
+            result,
+            operands: vec![],
+            operators,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -336,4 +348,50 @@ mod test {
 
         assert!(!operator.evaluate(&(&json!(event), &mut Value::Null).into()));
     }
+
+    #[test]
+    fn explain_should_return_the_trace_of_all_nested_operators() {
+        let operator = And::build(
+            "",
+            &[
+                config::rule::Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String("type1".to_owned()),
+                },
+                config::rule::Operator::Equals {
+                    first: Value::String("${event.payload.missing}".to_owned()),
+                    second: Value::String("value".to_owned()),
+                },
+            ],
+            &OperatorBuilder::new(),
+        )
+        .unwrap();
+
+        let event = Event::new("type");
+
+        let explanation = operator.explain(&(&json!(event), &mut Value::Null).into());
+
+        assert_eq!(
+            OperatorExplanation {
+                operator: "and".to_owned(),
+                result: false,
+                operands: vec![],
+                operators: vec![
+                    OperatorExplanation {
+                        operator: "equals".to_owned(),
+                        result: false,
+                        operands: vec![json!("type"), json!("type1")],
+                        operators: vec![],
+                    },
+                    OperatorExplanation {
+                        operator: "equals".to_owned(),
+                        result: false,
+                        operands: vec![Value::Null, json!("value")],
+                        operators: vec![],
+                    },
+                ],
+            },
+            explanation
+        );
+    }
 }
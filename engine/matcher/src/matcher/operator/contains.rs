@@ -1,6 +1,9 @@
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::{accessor::Accessor, model::InternalEvent};
+use crate::matcher::operator::{explain_with_operands, Operator};
+use crate::{
+    accessor::Accessor,
+    model::{InternalEvent, OperatorExplanation},
+};
 use tornado_common_api::{cow_to_str, Value};
 
 const OPERATOR_NAME: &str = "contains";
@@ -52,6 +55,10 @@ impl Operator for Contains {
             None => false,
         }
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_operands(self, event, &[&self.first, &self.second])
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +248,25 @@ mod test {
         assert!(operator.evaluate(&(&json!(event), &mut Value::Null).into()));
     }
 
+    #[test]
+    fn should_evaluate_to_true_if_projected_array_contains_a_value() {
+        let operator = Contains::build(
+            AccessorBuilder::new()
+                .build_from_value("", &Value::String("${event.payload.items[*].name}".to_owned()))
+                .unwrap(),
+            AccessorBuilder::new().build_from_value("", &json!("disk")).unwrap(),
+        )
+        .unwrap();
+
+        let mut event = Event::new("test_type");
+        event.payload.insert(
+            "items".to_owned(),
+            json!([{"name": "cpu"}, {"name": "disk"}, {"other": "memory"}]),
+        );
+
+        assert!(operator.evaluate(&(&json!(event), &mut Value::Null).into()));
+    }
+
     #[test]
     fn should_evaluate_to_false_if_array_does_not_contain_a_value() {
         let operator = Contains::build(
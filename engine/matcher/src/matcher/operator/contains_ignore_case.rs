@@ -1,7 +1,7 @@
 use crate::accessor::Accessor;
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::model::InternalEvent;
+use crate::matcher::operator::{explain_with_operands, Operator};
+use crate::model::{InternalEvent, OperatorExplanation};
 use log::*;
 use serde_json::Value;
 use std::borrow::Borrow;
@@ -66,6 +66,10 @@ impl Operator for ContainsIgnoreCase {
             None => false,
         }
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_operands(self, event, &[&self.first, &self.second])
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,9 @@
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::{accessor::Accessor, model::InternalEvent};
+use crate::matcher::operator::{explain_with_operands, Operator};
+use crate::{
+    accessor::Accessor,
+    model::{InternalEvent, OperatorExplanation},
+};
 use log::*;
 use tornado_common_api::{cow_to_str, ValueExt};
 
@@ -47,6 +50,10 @@ impl Operator for EqualsIgnoreCase {
             None => false,
         }
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_operands(self, event, &[&self.first, &self.second])
+    }
 }
 
 #[cfg(test)]
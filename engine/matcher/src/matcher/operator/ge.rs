@@ -1,6 +1,9 @@
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::{accessor::Accessor, model::InternalEvent};
+use crate::matcher::operator::{explain_with_operands, Operator};
+use crate::{
+    accessor::Accessor,
+    model::{InternalEvent, OperatorExplanation},
+};
 use std::cmp::Ordering;
 use tornado_common_api::partial_cmp_option_cow_value;
 
@@ -29,6 +32,10 @@ impl Operator for GreaterEqualThan {
         let cmp = partial_cmp_option_cow_value(&self.first.get(event), || self.second.get(event));
         cmp == Some(Ordering::Greater) || cmp == Some(Ordering::Equal)
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_operands(self, event, &[&self.first, &self.second])
+    }
 }
 
 #[cfg(test)]
@@ -4,11 +4,13 @@
 //! An *Operator* is linked to the "WHERE" clause of a Rule and determines whether the rule
 //! is matched by an Event.
 
+use crate::accessor::Accessor;
 use crate::accessor::AccessorBuilder;
 use crate::config::rule;
 use crate::error::MatcherError;
-use crate::model::InternalEvent;
+use crate::model::{InternalEvent, OperatorExplanation};
 use log::*;
+use serde_json::Value;
 use std::fmt;
 
 pub mod and;
@@ -33,6 +35,26 @@ pub trait Operator: fmt::Debug + Send + Sync {
 
     /// Executes the current matcher.operator on a target Event and returns whether the Event matches it.
     fn evaluate(&self, event: &InternalEvent) -> bool;
+
+    /// Executes the current matcher.operator on a target Event and returns a trace of the evaluation.
+    /// The result of the trace is always the same returned by `evaluate`.
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        OperatorExplanation::new(self.name(), self.evaluate(event))
+    }
+}
+
+/// Returns the trace of an operator whose result depends only on the values of its arguments.
+fn explain_with_operands(
+    operator: &dyn Operator,
+    event: &InternalEvent,
+    operands: &[&Accessor],
+) -> OperatorExplanation {
+    let mut explanation = OperatorExplanation::new(operator.name(), operator.evaluate(event));
+    explanation.operands = operands
+        .iter()
+        .map(|operand| operand.get(event).map(|value| value.into_owned()).unwrap_or(Value::Null))
+        .collect();
+    explanation
 }
 
 /// The Operator instance builder
@@ -1,7 +1,7 @@
 use crate::config;
 use crate::error::MatcherError;
 use crate::matcher::operator::{Operator, OperatorBuilder};
-use crate::model::InternalEvent;
+use crate::model::{InternalEvent, OperatorExplanation};
 
 const OPERATOR_NAME: &str = "not";
 
@@ -30,6 +30,16 @@ impl Operator for Not {
     fn evaluate(&self, event: &InternalEvent) -> bool {
         !self.operator.evaluate(event)
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        let operator = self.operator.explain(event);
+        OperatorExplanation {
+            operator: OPERATOR_NAME.to_owned(),
+            result: !operator.result,
+            operands: vec![],
+            operators: vec![operator],
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 use crate::config;
 use crate::error::MatcherError;
 use crate::matcher::operator::{Operator, OperatorBuilder};
-use crate::model::InternalEvent;
+use crate::model::{InternalEvent, OperatorExplanation};
 
 const OPERATOR_NAME: &str = "or";
 
@@ -34,6 +34,18 @@ impl Operator for Or {
     fn evaluate(&self, event: &InternalEvent) -> bool {
         self.operators.iter().any(|op| op.evaluate(event))
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        // All the nested operators are explained, even those not evaluated because of short-circuiting
+        let operators: Vec<_> = self.operators.iter().map(|op| op.explain(event)).collect();
+        let result = operators.iter().any(|op| op.result);
+        OperatorExplanation {
+            operator: OPERATOR_NAME.to_owned(),
+            result,
+            operands: vec![],
+            operators,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,11 @@
 use crate::error::MatcherError;
 use crate::matcher::operator::Operator;
-use crate::{accessor::Accessor, model::InternalEvent};
+use crate::{
+    accessor::Accessor,
+    model::{InternalEvent, OperatorExplanation},
+};
 use regex::Regex as RustRegex;
-use tornado_common_api::cow_to_str;
+use tornado_common_api::{cow_to_str, Value};
 
 const OPERATOR_NAME: &str = "regex";
 
@@ -33,6 +36,15 @@ impl Operator for Regex {
         let cow_value = self.target.get(event);
         cow_to_str(&cow_value).is_some_and(|text| self.regex.is_match(text))
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        let mut explanation = OperatorExplanation::new(self.name(), self.evaluate(event));
+        explanation.operands = vec![
+            Value::String(self.regex.as_str().to_owned()),
+            self.target.get(event).map(|value| value.into_owned()).unwrap_or(Value::Null),
+        ];
+        explanation
+    }
 }
 
 #[cfg(test)]
@@ -156,4 +168,18 @@ mod test {
 
         assert!(!operator.evaluate(&(&json!(event), &mut Value::Null).into()));
     }
+
+    #[test]
+    fn explain_should_return_the_regex_and_the_resolved_target() {
+        let operator =
+            Regex::build("[a-fA-F]", AccessorBuilder::new().build("", "${event.type}").unwrap())
+                .unwrap();
+
+        let event = Event::new("type");
+
+        let explanation = operator.explain(&(&json!(event), &mut Value::Null).into());
+
+        assert!(explanation.result);
+        assert_eq!(vec![json!("[a-fA-F]"), json!("type")], explanation.operands);
+    }
 }
@@ -41,6 +41,10 @@ impl ValueGet for InternalEvent<'_> {
     fn get_from_array(&self, _index: usize) -> Option<&tornado_common_api::Value> {
         None
     }
+
+    fn get_from_array_end(&self, _index: usize) -> Option<&tornado_common_api::Value> {
+        None
+    }
 }
 
 /// A ProcessedEvent is the result of the matcher process.
@@ -109,6 +113,69 @@ pub enum ProcessedRuleStatus {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
 pub struct ProcessedRuleMetaData {
     pub actions: Vec<ActionMetaData>,
+    #[serde(default)]
+    pub action_conditions: Vec<ActionConditionMetaData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<RuleExplanation>,
+}
+
+/// The evaluation trace of a rule, produced only when an event is processed in explain mode.
+/// The extractors are evaluated only if the rule's operator matches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct RuleExplanation {
+    pub operator: OperatorExplanation,
+    pub extractors: Vec<ExtractorExplanation>,
+}
+
+/// The evaluation trace of an operator.
+/// The operands are the values the operator arguments resolved to, `null` if they could not be resolved;
+/// the operators are the traces of the nested operators, if any.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct OperatorExplanation {
+    pub operator: String,
+    pub result: bool,
+    pub operands: Vec<Value>,
+    pub operators: Vec<OperatorExplanation>,
+}
+
+impl OperatorExplanation {
+    pub fn new(operator: &str, result: bool) -> Self {
+        OperatorExplanation {
+            operator: operator.to_owned(),
+            result,
+            operands: vec![],
+            operators: vec![],
+        }
+    }
+}
+
+/// The evaluation trace of the extractor of a variable.
+/// The input is the value read from the `from` field of the extractor, the modifiers contain the
+/// output of each modifier of the chain in order of application.
+/// If the variable cannot be extracted, `matched` is false and the message contains the reason.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ExtractorExplanation {
+    pub variable: String,
+    pub input: Value,
+    pub matched: bool,
+    pub value: Option<Value>,
+    pub modifiers: Vec<ModifierExplanation>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ModifierExplanation {
+    pub modifier: String,
+    pub output: Value,
+}
+
+/// The outcome of the `when` condition of a rule's action.
+/// The index is the position of the action in the rule's actions list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ActionConditionMetaData {
+    pub index: usize,
+    pub id: String,
+    pub matched: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
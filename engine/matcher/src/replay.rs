@@ -0,0 +1,433 @@
+//! The replay module processes events, for example the ones written by the archive executor,
+//! with both the active configuration and a draft, and reports the differences between the
+//! rules matched and the actions produced by the two configurations.
+//!
+//! The actions are only compared, never executed.
+
+use crate::matcher::Matcher;
+use crate::model::{ProcessedNode, ProcessedRuleStatus};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typescript_definitions::TypeScriptify;
+
+/// The maximum number of differences and of invalid lines kept in the report.
+/// The ones past the limit are only counted.
+pub const MAX_REPORTED_DIFFERENCES: usize = 100;
+
+/// The kind of difference between the outcome of the active configuration and the one of the draft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TypeScriptify)]
+pub enum ReplayChange {
+    /// Present only in the outcome of the draft
+    Added,
+    /// Present only in the outcome of the active configuration
+    Removed,
+    /// Present in both outcomes, but with a different payload
+    Changed,
+}
+
+/// A rule that is matched by the event with only one of the two configurations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct RuleReplayDiff {
+    pub ruleset_path: Vec<String>,
+    pub rule: String,
+    pub change: ReplayChange,
+}
+
+/// An action produced by only one of the two configurations, or with a different payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ActionReplayDiff {
+    pub ruleset_path: Vec<String>,
+    pub rule: String,
+    pub action_id: String,
+    pub change: ReplayChange,
+    pub active_payload: Option<Value>,
+    pub draft_payload: Option<Value>,
+}
+
+/// The differences produced by an event. The line is the position of the event in its source,
+/// starting from 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct EventReplayDiff {
+    pub line: u64,
+    pub event: Value,
+    pub rules: Vec<RuleReplayDiff>,
+    pub actions: Vec<ActionReplayDiff>,
+}
+
+/// A line that could not be replayed because it does not contain a valid event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ReplayLineError {
+    pub line: u64,
+    pub message: String,
+}
+
+/// The outcome of a replay. `changed_events` and `invalid_lines` count all the events with
+/// a different outcome and all the invalid lines, while only the first
+/// MAX_REPORTED_DIFFERENCES of them are reported in `differences` and `line_errors`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ReplayReport {
+    pub replayed_events: u64,
+    pub changed_events: u64,
+    pub invalid_lines: u64,
+    pub differences: Vec<EventReplayDiff>,
+    pub line_errors: Vec<ReplayLineError>,
+}
+
+/// Replays events, one JSON event per line, against the active configuration and a draft.
+pub struct Replay {
+    active: Matcher,
+    draft: Matcher,
+    line: u64,
+    report: ReplayReport,
+}
+
+impl Replay {
+    pub fn new(active: Matcher, draft: Matcher) -> Self {
+        Self { active, draft, line: 0, report: ReplayReport::default() }
+    }
+
+    /// Replays a line of an archive file. Empty lines are skipped.
+    pub fn replay_line(&mut self, line: &str) {
+        self.line += 1;
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(event @ Value::Object(_)) => self.replay_event(event),
+            Ok(_) => {
+                self.record_invalid_line("The line does not contain a JSON object.".to_owned())
+            }
+            Err(err) => self.record_invalid_line(err.to_string()),
+        }
+    }
+
+    fn record_invalid_line(&mut self, message: String) {
+        self.report.invalid_lines += 1;
+        if self.report.line_errors.len() < MAX_REPORTED_DIFFERENCES {
+            self.report.line_errors.push(ReplayLineError { line: self.line, message });
+        }
+    }
+
+    fn replay_event(&mut self, event: Value) {
+        self.report.replayed_events += 1;
+        let active = self.active.process(event.clone(), false);
+        let draft = self.draft.process(event.clone(), false);
+        let (rules, actions) = compare(&active.result, &draft.result);
+
+        if !rules.is_empty() || !actions.is_empty() {
+            self.report.changed_events += 1;
+            if self.report.differences.len() == MAX_REPORTED_DIFFERENCES {
+                return;
+            }
+            self.report.differences.push(EventReplayDiff {
+                line: self.line,
+                event,
+                rules,
+                actions,
+            });
+        }
+    }
+
+    /// Returns the report of the lines replayed so far and starts a new one.
+    pub fn take_report(&mut self) -> ReplayReport {
+        self.line = 0;
+        std::mem::take(&mut self.report)
+    }
+}
+
+/// Compares the outcomes of the processing of the same event by the active configuration and
+/// by the draft, and returns the rules and the actions that differ.
+pub fn compare(
+    active: &ProcessedNode,
+    draft: &ProcessedNode,
+) -> (Vec<RuleReplayDiff>, Vec<ActionReplayDiff>) {
+    let active = Outcome::of(active);
+    let draft = Outcome::of(draft);
+
+    let rules: Vec<_> = differences(active.rules, draft.rules)
+        .into_iter()
+        .map(|((ruleset_path, rule), active, _)| RuleReplayDiff {
+            ruleset_path,
+            rule,
+            change: if active.is_some() { ReplayChange::Removed } else { ReplayChange::Added },
+        })
+        .collect();
+    let actions: Vec<_> = differences(active.actions, draft.actions)
+        .into_iter()
+        .map(|((ruleset_path, rule, action_id), active_payload, draft_payload)| {
+            let change = match (&active_payload, &draft_payload) {
+                (Some(_), Some(_)) => ReplayChange::Changed,
+                (Some(_), None) => ReplayChange::Removed,
+                _ => ReplayChange::Added,
+            };
+            ActionReplayDiff {
+                ruleset_path,
+                rule,
+                action_id,
+                change,
+                active_payload,
+                draft_payload,
+            }
+        })
+        .collect();
+    (rules, actions)
+}
+
+type RuleKey = (Vec<String>, String);
+pub(crate) type ActionKey = (Vec<String>, String, String);
+
+/// The rules matched and the actions produced by the processing of an event.
+/// A ruleset below an iterator contributes once per iteration.
+#[derive(Default)]
+pub(crate) struct Outcome {
+    rules: Vec<(RuleKey, ())>,
+    pub(crate) actions: Vec<(ActionKey, Value)>,
+}
+
+impl Outcome {
+    pub(crate) fn of(node: &ProcessedNode) -> Self {
+        let mut outcome = Outcome::default();
+        outcome.collect(node, &mut vec![]);
+        outcome
+    }
+
+    fn collect(&mut self, node: &ProcessedNode, path: &mut Vec<String>) {
+        match node {
+            ProcessedNode::Filter { name, nodes, .. } => {
+                path.push(name.to_owned());
+                nodes.iter().for_each(|child| self.collect(child, path));
+                path.pop();
+            }
+            ProcessedNode::Iterator { name, events, .. } => {
+                path.push(name.to_owned());
+                events
+                    .iter()
+                    .flat_map(|iteration| iteration.result.iter())
+                    .for_each(|child| self.collect(child, path));
+                path.pop();
+            }
+            ProcessedNode::Ruleset { name, rules } => {
+                path.push(name.to_owned());
+                for rule in &rules.rules {
+                    if rule.status == ProcessedRuleStatus::Matched {
+                        self.rules.push(((path.clone(), rule.name.to_owned()), ()));
+                    }
+                    for action in &rule.actions {
+                        self.actions.push((
+                            (path.clone(), rule.name.to_owned(), action.id.to_owned()),
+                            Value::Object(action.payload.clone()),
+                        ));
+                    }
+                }
+                path.pop();
+            }
+        }
+    }
+}
+
+/// Pairs the entries with the same key, in order, and returns the ones that are missing
+/// on one side or that have a different value.
+fn differences<K: PartialEq, T: PartialEq>(
+    active: Vec<(K, T)>,
+    draft: Vec<(K, T)>,
+) -> Vec<(K, Option<T>, Option<T>)> {
+    let mut draft: Vec<Option<(K, T)>> = draft.into_iter().map(Some).collect();
+    let mut result = vec![];
+    for (key, value) in active {
+        let paired = draft
+            .iter_mut()
+            .find(|entry| matches!(entry, Some((draft_key, _)) if *draft_key == key))
+            .and_then(Option::take);
+        match paired {
+            Some((_, draft_value)) if draft_value == value => {}
+            Some((_, draft_value)) => result.push((key, Some(value), Some(draft_value))),
+            None => result.push((key, Some(value), None)),
+        }
+    }
+    result.extend(draft.into_iter().flatten().map(|(key, value)| (key, None, Some(value))));
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::{ConfigAction, Constraint, Operator, Rule};
+    use crate::config::{Defaultable, MatcherConfig};
+    use serde_json::{json, Map};
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_not_report_events_with_the_same_outcome() {
+        // Arrange
+        let config = config(vec![rule("email", "email", "archive", "${event.type}")]);
+        let mut replay =
+            Replay::new(Matcher::build(&config).unwrap(), Matcher::build(&config).unwrap());
+
+        // Act
+        replay.replay_line(r#"{"type": "email", "created_ms": 0, "payload": {}}"#);
+        replay.replay_line("");
+        replay.replay_line(r#"{"type": "trap", "created_ms": 0, "payload": {}}"#);
+        let report = replay.take_report();
+
+        // Assert
+        assert_eq!(2, report.replayed_events);
+        assert_eq!(0, report.changed_events);
+        assert!(report.differences.is_empty());
+        assert_eq!(0, report.invalid_lines);
+    }
+
+    #[test]
+    fn should_report_added_removed_and_changed_rules_and_actions() {
+        // Arrange
+        let active = config(vec![
+            rule("email", "email", "archive", "${event.type}"),
+            rule("email_log", "email", "log", "${event.type}"),
+        ]);
+        let draft = config(vec![
+            rule("email", "email", "archive", "${event.created_ms}"),
+            rule("email_monitor", "email", "monitor", "${event.type}"),
+        ]);
+        let mut replay =
+            Replay::new(Matcher::build(&active).unwrap(), Matcher::build(&draft).unwrap());
+
+        // Act
+        replay.replay_line(r#"{"type": "trap", "created_ms": 0, "payload": {}}"#);
+        replay.replay_line(r#"{"type": "email", "created_ms": 0, "payload": {}}"#);
+        let report = replay.take_report();
+
+        // Assert
+        assert_eq!(2, report.replayed_events);
+        assert_eq!(1, report.changed_events);
+        assert_eq!(1, report.differences.len());
+        let diff = &report.differences[0];
+        assert_eq!(2, diff.line);
+        let path = vec!["root".to_owned(), "ruleset".to_owned()];
+        assert_eq!(
+            vec![
+                RuleReplayDiff {
+                    ruleset_path: path.clone(),
+                    rule: "email_log".to_owned(),
+                    change: ReplayChange::Removed
+                },
+                RuleReplayDiff {
+                    ruleset_path: path.clone(),
+                    rule: "email_monitor".to_owned(),
+                    change: ReplayChange::Added
+                },
+            ],
+            diff.rules
+        );
+        assert_eq!(
+            vec![
+                ActionReplayDiff {
+                    ruleset_path: path.clone(),
+                    rule: "email".to_owned(),
+                    action_id: "archive".to_owned(),
+                    change: ReplayChange::Changed,
+                    active_payload: Some(json!({ "value": "email" })),
+                    draft_payload: Some(json!({ "value": 0 })),
+                },
+                ActionReplayDiff {
+                    ruleset_path: path.clone(),
+                    rule: "email_log".to_owned(),
+                    action_id: "log".to_owned(),
+                    change: ReplayChange::Removed,
+                    active_payload: Some(json!({ "value": "email" })),
+                    draft_payload: None,
+                },
+                ActionReplayDiff {
+                    ruleset_path: path,
+                    rule: "email_monitor".to_owned(),
+                    action_id: "monitor".to_owned(),
+                    change: ReplayChange::Added,
+                    active_payload: None,
+                    draft_payload: Some(json!({ "value": "email" })),
+                },
+            ],
+            diff.actions
+        );
+    }
+
+    #[test]
+    fn should_report_the_invalid_lines() {
+        // Arrange
+        let config = config(vec![]);
+        let mut replay =
+            Replay::new(Matcher::build(&config).unwrap(), Matcher::build(&config).unwrap());
+
+        // Act
+        replay.replay_line("not json");
+        replay.replay_line("[]");
+        let report = replay.take_report();
+        replay.replay_line("{}");
+        let next_report = replay.take_report();
+
+        // Assert
+        assert_eq!(0, report.replayed_events);
+        assert_eq!(2, report.invalid_lines);
+        assert_eq!(
+            vec![1, 2],
+            report.line_errors.iter().map(|error| error.line).collect::<Vec<_>>()
+        );
+        assert_eq!(1, next_report.replayed_events);
+        assert_eq!(0, next_report.invalid_lines);
+        assert!(next_report.line_errors.is_empty());
+    }
+
+    #[test]
+    fn should_count_but_not_report_the_differences_past_the_limit() {
+        // Arrange
+        let active = config(vec![rule("email", "email", "archive", "${event.type}")]);
+        let draft = config(vec![]);
+        let mut replay =
+            Replay::new(Matcher::build(&active).unwrap(), Matcher::build(&draft).unwrap());
+
+        // Act
+        for _ in 0..(MAX_REPORTED_DIFFERENCES + 5) {
+            replay.replay_line(r#"{"type": "email", "created_ms": 0, "payload": {}}"#);
+            replay.replay_line("not json");
+        }
+        let report = replay.take_report();
+
+        // Assert
+        assert_eq!((MAX_REPORTED_DIFFERENCES + 5) as u64, report.replayed_events);
+        assert_eq!((MAX_REPORTED_DIFFERENCES + 5) as u64, report.changed_events);
+        assert_eq!(MAX_REPORTED_DIFFERENCES, report.differences.len());
+        assert_eq!(1, report.differences[0].line);
+        assert_eq!((MAX_REPORTED_DIFFERENCES + 5) as u64, report.invalid_lines);
+        assert_eq!(MAX_REPORTED_DIFFERENCES, report.line_errors.len());
+    }
+
+    fn config(rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes: vec![MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules }],
+        }
+    }
+
+    fn rule(name: &str, event_type: &str, action_id: &str, value: &str) -> Rule {
+        let mut payload = Map::new();
+        payload.insert("value".to_owned(), Value::String(value.to_owned()));
+        Rule {
+            name: name.to_owned(),
+            description: "".to_owned(),
+            do_continue: true,
+            active: true,
+            constraint: Constraint {
+                where_operator: Some(Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String(event_type.to_owned()),
+                }),
+                with: HashMap::new(),
+            },
+            actions: vec![ConfigAction { id: action_id.to_owned(), payload, when: None }],
+        }
+    }
+}
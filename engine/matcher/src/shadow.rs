@@ -0,0 +1,320 @@
+//! The shadow module keeps track of how a draft behaves on live traffic, when each event
+//! is processed both by the active configuration and by the draft.
+//!
+//! Only the actions of the active configuration are executed; the ones of the draft are
+//! compared with them and recorded here. The memory used is bounded: the counters grow with
+//! the number of actions of the two configurations and only the most recent differences are kept.
+
+use crate::model::ProcessedNode;
+use crate::replay::{compare, ActionKey, ActionReplayDiff, Outcome, ReplayChange, RuleReplayDiff};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use typescript_definitions::TypeScriptify;
+
+/// The maximum number of differences kept in the report.
+pub const MAX_RECENT_DIFFERENCES: usize = 100;
+
+/// The counters of an action. `active` and `shadow` count the times the action was produced
+/// by the active configuration and by the draft; `changed` counts the times it was produced
+/// by both with a different payload.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypeScriptify)]
+pub struct ShadowActionStats {
+    pub ruleset_path: Vec<String>,
+    pub rule: String,
+    pub action_id: String,
+    pub active: u64,
+    pub shadow: u64,
+    pub changed: u64,
+}
+
+/// The differences produced by a live event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ShadowEventDiff {
+    pub received_epoch_ms: i64,
+    pub event: Value,
+    pub rules: Vec<RuleReplayDiff>,
+    pub actions: Vec<ActionReplayDiff>,
+}
+
+/// The comparison of the draft running in shadow with the active configuration since it was started.
+/// The skipped events are the ones not processed by the draft because it could not keep up
+/// with the live traffic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ShadowReport {
+    pub draft_id: String,
+    pub started_epoch_ms: i64,
+    pub processed_events: u64,
+    pub changed_events: u64,
+    pub skipped_events: u64,
+    pub actions: Vec<ShadowActionStats>,
+    pub recent_differences: Vec<ShadowEventDiff>,
+}
+
+#[derive(Default)]
+struct ShadowState {
+    processed_events: u64,
+    changed_events: u64,
+    skipped_events: u64,
+    actions: HashMap<ActionKey, ShadowActionStats>,
+    recent_differences: VecDeque<ShadowEventDiff>,
+}
+
+/// The statistics of a draft running in shadow. They are shared between the component that
+/// processes the events and the one that reads the report.
+pub struct ShadowStats {
+    draft_id: String,
+    started_epoch_ms: i64,
+    state: Mutex<ShadowState>,
+}
+
+impl ShadowStats {
+    pub fn new(draft_id: String, started_epoch_ms: i64) -> Self {
+        Self { draft_id, started_epoch_ms, state: Mutex::new(ShadowState::default()) }
+    }
+
+    pub fn draft_id(&self) -> &str {
+        &self.draft_id
+    }
+
+    /// Records the outcomes of the processing of an event by the active configuration
+    /// and by the draft.
+    pub fn record(
+        &self,
+        event: &Value,
+        active: &ProcessedNode,
+        shadow: &ProcessedNode,
+        received_epoch_ms: i64,
+    ) {
+        let (rules, actions) = compare(active, shadow);
+        let active = Outcome::of(active);
+        let shadow = Outcome::of(shadow);
+
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.processed_events += 1;
+        for (key, _) in active.actions {
+            action_stats(&mut state.actions, key).active += 1;
+        }
+        for (key, _) in shadow.actions {
+            action_stats(&mut state.actions, key).shadow += 1;
+        }
+        for action in actions.iter().filter(|action| action.change == ReplayChange::Changed) {
+            let key = (action.ruleset_path.clone(), action.rule.clone(), action.action_id.clone());
+            action_stats(&mut state.actions, key).changed += 1;
+        }
+
+        if !rules.is_empty() || !actions.is_empty() {
+            state.changed_events += 1;
+            if state.recent_differences.len() == MAX_RECENT_DIFFERENCES {
+                state.recent_differences.pop_front();
+            }
+            state.recent_differences.push_back(ShadowEventDiff {
+                received_epoch_ms,
+                event: event.clone(),
+                rules,
+                actions,
+            });
+        }
+    }
+
+    /// Records an event that was not processed by the draft.
+    pub fn record_skipped(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.skipped_events += 1;
+    }
+
+    /// Returns the report restricted to the rulesets below the given node path.
+    pub fn report(&self, node_path: &[String]) -> ShadowReport {
+        let state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut actions: Vec<_> = state
+            .actions
+            .values()
+            .filter(|stats| stats.ruleset_path.starts_with(node_path))
+            .cloned()
+            .collect();
+        actions.sort_by(|first, second| {
+            (&first.ruleset_path, &first.rule, &first.action_id).cmp(&(
+                &second.ruleset_path,
+                &second.rule,
+                &second.action_id,
+            ))
+        });
+        let recent_differences = state
+            .recent_differences
+            .iter()
+            .filter_map(|diff| {
+                let rules: Vec<_> = diff
+                    .rules
+                    .iter()
+                    .filter(|rule| rule.ruleset_path.starts_with(node_path))
+                    .cloned()
+                    .collect();
+                let actions: Vec<_> = diff
+                    .actions
+                    .iter()
+                    .filter(|action| action.ruleset_path.starts_with(node_path))
+                    .cloned()
+                    .collect();
+                if rules.is_empty() && actions.is_empty() {
+                    None
+                } else {
+                    Some(ShadowEventDiff {
+                        received_epoch_ms: diff.received_epoch_ms,
+                        event: diff.event.clone(),
+                        rules,
+                        actions,
+                    })
+                }
+            })
+            .collect();
+
+        ShadowReport {
+            draft_id: self.draft_id.clone(),
+            started_epoch_ms: self.started_epoch_ms,
+            processed_events: state.processed_events,
+            changed_events: state.changed_events,
+            skipped_events: state.skipped_events,
+            actions,
+            recent_differences,
+        }
+    }
+}
+
+fn action_stats(
+    actions: &mut HashMap<ActionKey, ShadowActionStats>,
+    key: ActionKey,
+) -> &mut ShadowActionStats {
+    actions.entry(key).or_insert_with_key(|(ruleset_path, rule, action_id)| ShadowActionStats {
+        ruleset_path: ruleset_path.clone(),
+        rule: rule.clone(),
+        action_id: action_id.clone(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::{ConfigAction, Constraint, Operator, Rule};
+    use crate::config::{Defaultable, MatcherConfig};
+    use crate::matcher::Matcher;
+    use serde_json::{json, Map};
+
+    #[test]
+    fn should_count_the_actions_and_keep_the_differences() {
+        // Arrange
+        let active = matcher(vec![rule("email", "archive", "${event.type}")]);
+        let shadow = matcher(vec![
+            rule("email", "archive", "${event.created_ms}"),
+            rule("email_log", "log", "${event.type}"),
+        ]);
+        let stats = ShadowStats::new("draft_001".to_owned(), 10);
+        let email = json!({ "type": "email", "created_ms": 0, "payload": {} });
+        let trap = json!({ "type": "trap", "created_ms": 0, "payload": {} });
+
+        // Act
+        for event in [&email, &trap, &email] {
+            stats.record(
+                event,
+                &active.process(event.clone(), false).result,
+                &shadow.process(event.clone(), false).result,
+                20,
+            );
+        }
+        stats.record_skipped();
+        let report = stats.report(&["root".to_owned()]);
+
+        // Assert
+        assert_eq!("draft_001", report.draft_id);
+        assert_eq!(10, report.started_epoch_ms);
+        assert_eq!(3, report.processed_events);
+        assert_eq!(2, report.changed_events);
+        assert_eq!(1, report.skipped_events);
+        let path = vec!["root".to_owned(), "ruleset".to_owned()];
+        assert_eq!(
+            vec![
+                ShadowActionStats {
+                    ruleset_path: path.clone(),
+                    rule: "email".to_owned(),
+                    action_id: "archive".to_owned(),
+                    active: 2,
+                    shadow: 2,
+                    changed: 2,
+                },
+                ShadowActionStats {
+                    ruleset_path: path,
+                    rule: "email_log".to_owned(),
+                    action_id: "log".to_owned(),
+                    active: 0,
+                    shadow: 2,
+                    changed: 0,
+                },
+            ],
+            report.actions
+        );
+        assert_eq!(2, report.recent_differences.len());
+        assert_eq!(email, report.recent_differences[0].event);
+        assert_eq!(2, report.recent_differences[0].actions.len());
+    }
+
+    #[test]
+    fn should_keep_only_the_most_recent_differences() {
+        // Arrange
+        let active = matcher(vec![]);
+        let shadow = matcher(vec![rule("email", "archive", "${event.created_ms}")]);
+        let stats = ShadowStats::new("draft_001".to_owned(), 0);
+
+        // Act
+        for created_ms in 0..(MAX_RECENT_DIFFERENCES + 5) {
+            let event = json!({ "type": "email", "created_ms": created_ms, "payload": {} });
+            stats.record(
+                &event,
+                &active.process(event.clone(), false).result,
+                &shadow.process(event.clone(), false).result,
+                created_ms as i64,
+            );
+        }
+        let report = stats.report(&["root".to_owned()]);
+        let other_node_report = stats.report(&["root".to_owned(), "other".to_owned()]);
+
+        // Assert
+        assert_eq!(MAX_RECENT_DIFFERENCES, report.recent_differences.len());
+        assert_eq!(5, report.recent_differences[0].received_epoch_ms);
+        assert!(other_node_report.actions.is_empty());
+        assert!(other_node_report.recent_differences.is_empty());
+    }
+
+    fn matcher(rules: Vec<Rule>) -> Matcher {
+        Matcher::build(&MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes: vec![MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules }],
+        })
+        .unwrap()
+    }
+
+    fn rule(name: &str, action_id: &str, value: &str) -> Rule {
+        let mut payload = Map::new();
+        payload.insert("value".to_owned(), Value::String(value.to_owned()));
+        Rule {
+            name: name.to_owned(),
+            description: "".to_owned(),
+            do_continue: true,
+            active: true,
+            constraint: Constraint {
+                where_operator: Some(Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String("email".to_owned()),
+                }),
+                with: Default::default(),
+            },
+            actions: vec![ConfigAction { id: action_id.to_owned(), payload, when: None }],
+        }
+    }
+}
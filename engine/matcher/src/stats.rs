@@ -0,0 +1,500 @@
+//! The stats module keeps track of how the nodes and the rules of the processing tree
+//! behave over time.
+//!
+//! The statistics are collected from the results of the Matcher (see `ProcessedNode`) and are
+//! identified by the path of the node in the processing tree, starting from the root node;
+//! the path of a rule is the path of its ruleset followed by the rule name.
+//! The paths are built once per configuration by the `StatsLayout`, which assigns an id
+//! to each node and rule.
+
+use crate::config::MatcherConfig;
+use crate::model::{ProcessedFilterStatus, ProcessedIterator, ProcessedNode, ProcessedRuleStatus};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The separator used to join the path of a node when it is used as a label.
+pub const STATS_PATH_SEPARATOR: &str = ",";
+
+/// The counters of a node of the processing tree.
+/// A Filter is matched when its filter matches the event, an Iterator when its target can be
+/// iterated, a Ruleset when at least one of its rules matches.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeStats {
+    pub evaluated: u64,
+    pub matched: u64,
+    pub last_matched_epoch_ms: Option<i64>,
+}
+
+/// The counters of a rule. A rule is evaluated only if its ruleset is reached and
+/// no previous rule stopped the processing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleStats {
+    pub evaluated: u64,
+    pub matched: u64,
+    pub partially_matched: u64,
+    pub actions_emitted: u64,
+    pub last_matched_epoch_ms: Option<i64>,
+}
+
+/// The path of a node or of a rule, together with its label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsPath {
+    pub path: Vec<String>,
+    /// The path joined by the `STATS_PATH_SEPARATOR`
+    pub label: String,
+}
+
+impl StatsPath {
+    fn new(path: Vec<String>) -> Self {
+        let label = path.join(STATS_PATH_SEPARATOR);
+        Self { path, label }
+    }
+}
+
+/// The nodes and the rules of a configuration. Each node and rule is identified by its
+/// position in the layout, so that the outcomes of an event can be collected without
+/// building their paths; the paths and their labels are built once per configuration.
+#[derive(Debug, Default)]
+pub struct StatsLayout {
+    root: Option<LayoutNode>,
+    nodes: Vec<StatsPath>,
+    rules: Vec<StatsPath>,
+}
+
+#[derive(Debug)]
+struct LayoutNode {
+    name: String,
+    id: usize,
+    children: HashMap<String, LayoutNode>,
+    rules: HashMap<String, usize>,
+}
+
+impl StatsLayout {
+    pub fn new(config: &MatcherConfig) -> Self {
+        let mut layout = Self::default();
+        layout.root = Some(layout.add_node(config, &mut vec![]));
+        layout
+    }
+
+    fn add_node(&mut self, config: &MatcherConfig, path: &mut Vec<String>) -> LayoutNode {
+        path.push(config.get_name().to_owned());
+        let mut node = LayoutNode {
+            name: config.get_name().to_owned(),
+            id: self.nodes.len(),
+            children: HashMap::new(),
+            rules: HashMap::new(),
+        };
+        self.nodes.push(StatsPath::new(path.clone()));
+        match config {
+            MatcherConfig::Filter { nodes, .. } | MatcherConfig::Iterator { nodes, .. } => {
+                for child in nodes {
+                    let child = self.add_node(child, path);
+                    node.children.insert(child.name.clone(), child);
+                }
+            }
+            MatcherConfig::Ruleset { rules, .. } => {
+                for rule in rules {
+                    let mut rule_path = path.clone();
+                    rule_path.push(rule.name.to_owned());
+                    node.rules.insert(rule.name.to_owned(), self.rules.len());
+                    self.rules.push(StatsPath::new(rule_path));
+                }
+            }
+        }
+        path.pop();
+        node
+    }
+
+    /// Returns the nodes, indexed by the id used in the StatsUpdates.
+    pub fn nodes(&self) -> &[StatsPath] {
+        &self.nodes
+    }
+
+    /// Returns the rules, indexed by the id used in the StatsUpdates.
+    pub fn rules(&self) -> &[StatsPath] {
+        &self.rules
+    }
+
+    /// Returns the id of the node at the given path.
+    pub fn node_id(&self, path: &[&str]) -> Option<usize> {
+        self.find_node(path).map(|node| node.id)
+    }
+
+    /// Returns the id of the rule with the given name in the ruleset at the given path.
+    pub fn rule_id(&self, ruleset_path: &[&str], rule_name: &str) -> Option<usize> {
+        self.find_node(ruleset_path).and_then(|node| node.rules.get(rule_name).copied())
+    }
+
+    fn find_node(&self, path: &[&str]) -> Option<&LayoutNode> {
+        let (root_name, path) = path.split_first()?;
+        let root = self.root.as_ref().filter(|root| root.name == *root_name)?;
+        path.iter().try_fold(root, |node, name| node.children.get(*name))
+    }
+
+    /// Returns the outcomes of the nodes and rules reached while processing an event.
+    /// The result must be produced by a Matcher built from the configuration of the layout,
+    /// or from a subset of it.
+    pub fn updates(&self, result: &ProcessedNode) -> Vec<StatsUpdate> {
+        let mut updates = vec![];
+        if let Some(root) = &self.root {
+            if root.name == result_name(result) {
+                collect_updates(result, root, &mut updates);
+            }
+        }
+        updates
+    }
+}
+
+fn result_name(node: &ProcessedNode) -> &str {
+    match node {
+        ProcessedNode::Filter { name, .. }
+        | ProcessedNode::Iterator { name, .. }
+        | ProcessedNode::Ruleset { name, .. } => name,
+    }
+}
+
+/// A single outcome of the processing of an event. The ids are the ones of the StatsLayout
+/// that produced the update.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatsUpdate {
+    Node { id: usize, matched: bool },
+    Rule { id: usize, status: ProcessedRuleStatus, actions: usize },
+}
+
+fn collect_updates(node: &ProcessedNode, layout: &LayoutNode, updates: &mut Vec<StatsUpdate>) {
+    match node {
+        ProcessedNode::Filter { filter, nodes, .. } => {
+            if filter.status == ProcessedFilterStatus::Inactive {
+                return;
+            }
+            updates.push(StatsUpdate::Node {
+                id: layout.id,
+                matched: filter.status == ProcessedFilterStatus::Matched,
+            });
+            for child in nodes {
+                collect_child_updates(child, layout, updates);
+            }
+        }
+        ProcessedNode::Iterator { iterator, events, .. } => {
+            updates.push(StatsUpdate::Node {
+                id: layout.id,
+                matched: *iterator == ProcessedIterator::Matched,
+            });
+            for child in events.iter().flat_map(|iteration| iteration.result.iter()) {
+                collect_child_updates(child, layout, updates);
+            }
+        }
+        ProcessedNode::Ruleset { rules, .. } => {
+            updates.push(StatsUpdate::Node {
+                id: layout.id,
+                matched: rules.rules.iter().any(|rule| rule.status == ProcessedRuleStatus::Matched),
+            });
+            for rule in &rules.rules {
+                if rule.status == ProcessedRuleStatus::NotProcessed {
+                    continue;
+                }
+                if let Some(id) = layout.rules.get(&rule.name) {
+                    updates.push(StatsUpdate::Rule {
+                        id: *id,
+                        status: rule.status.clone(),
+                        actions: rule.actions.len(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn collect_child_updates(
+    child: &ProcessedNode,
+    parent: &LayoutNode,
+    updates: &mut Vec<StatsUpdate>,
+) {
+    if let Some(layout) = parent.children.get(result_name(child)) {
+        collect_updates(child, layout, updates);
+    }
+}
+
+/// The statistics of the processing tree, shared among all the processed events.
+/// When the configuration changes, the statistics of the nodes and rules that are still
+/// present are kept, while the ones of the removed nodes and rules are dropped.
+#[derive(Debug, Default)]
+pub struct MatcherStats {
+    inner: Mutex<MatcherStatsInner>,
+}
+
+#[derive(Debug, Default)]
+struct MatcherStatsInner {
+    layout: Arc<StatsLayout>,
+    nodes: Vec<NodeStats>,
+    rules: Vec<RuleStats>,
+}
+
+impl MatcherStats {
+    pub fn new(layout: Arc<StatsLayout>) -> Self {
+        let stats = Self::default();
+        stats.reconfigure(layout);
+        stats
+    }
+
+    /// Replaces the layout of the statistics after a change of the configuration.
+    pub fn reconfigure(&self, layout: Arc<StatsLayout>) {
+        let mut inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let old = std::mem::take(&mut *inner);
+        let nodes = carry_over(&old.layout.nodes, old.nodes, &layout.nodes);
+        let rules = carry_over(&old.layout.rules, old.rules, &layout.rules);
+        *inner = MatcherStatsInner { layout, nodes, rules };
+    }
+
+    /// Adds the outcomes of a processed event to the statistics.
+    /// The updates must be produced by the current layout of the statistics.
+    /// The timestamp is used as last-matched time of the matched nodes and rules.
+    pub fn record(&self, updates: &[StatsUpdate], timestamp_epoch_ms: i64) {
+        let mut inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for update in updates {
+            match update {
+                StatsUpdate::Node { id, matched } => {
+                    let Some(stats) = inner.nodes.get_mut(*id) else { continue };
+                    stats.evaluated += 1;
+                    if *matched {
+                        stats.matched += 1;
+                        stats.last_matched_epoch_ms = Some(timestamp_epoch_ms);
+                    }
+                }
+                StatsUpdate::Rule { id, status, actions } => {
+                    let Some(stats) = inner.rules.get_mut(*id) else { continue };
+                    stats.evaluated += 1;
+                    match status {
+                        ProcessedRuleStatus::Matched => {
+                            stats.matched += 1;
+                            stats.actions_emitted += *actions as u64;
+                            stats.last_matched_epoch_ms = Some(timestamp_epoch_ms);
+                        }
+                        ProcessedRuleStatus::PartiallyMatched => stats.partially_matched += 1,
+                        ProcessedRuleStatus::NotMatched | ProcessedRuleStatus::NotProcessed => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the statistics of the node at the given path.
+    /// A node never reached by an event has all counters set to zero.
+    pub fn node_stats(&self, path: &[&str]) -> NodeStats {
+        let inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        inner.layout.node_id(path).and_then(|id| inner.nodes.get(id)).cloned().unwrap_or_default()
+    }
+
+    /// Returns the statistics of the rule with the given name in the ruleset at the given path.
+    /// A rule never evaluated has all counters set to zero.
+    pub fn rule_stats(&self, ruleset_path: &[&str], rule_name: &str) -> RuleStats {
+        let inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        inner
+            .layout
+            .rule_id(ruleset_path, rule_name)
+            .and_then(|id| inner.rules.get(id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the path label and the last-matched timestamp of the nodes that matched at least once.
+    pub fn nodes_last_matched(&self) -> Vec<(String, i64)> {
+        let inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        last_matched(&inner.layout.nodes, &inner.nodes, |stats| stats.last_matched_epoch_ms)
+    }
+
+    /// Returns the path label and the last-matched timestamp of the rules that matched at least once.
+    pub fn rules_last_matched(&self) -> Vec<(String, i64)> {
+        let inner = self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        last_matched(&inner.layout.rules, &inner.rules, |stats| stats.last_matched_epoch_ms)
+    }
+}
+
+/// Returns the statistics of the new paths, taking them from the old statistics
+/// when the path was already present.
+fn carry_over<T: Default>(
+    old_paths: &[StatsPath],
+    old_stats: Vec<T>,
+    new_paths: &[StatsPath],
+) -> Vec<T> {
+    let mut old: HashMap<&[String], T> =
+        old_paths.iter().map(|path| path.path.as_slice()).zip(old_stats).collect();
+    new_paths.iter().map(|path| old.remove(path.path.as_slice()).unwrap_or_default()).collect()
+}
+
+fn last_matched<T, F: Fn(&T) -> Option<i64>>(
+    paths: &[StatsPath],
+    stats: &[T],
+    timestamp: F,
+) -> Vec<(String, i64)> {
+    paths
+        .iter()
+        .zip(stats)
+        .filter_map(|(path, stats)| {
+            timestamp(stats).map(|timestamp| (path.label.to_owned(), timestamp))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::{ConfigAction, Constraint, Operator, Rule};
+    use crate::config::{Defaultable, MatcherConfig};
+    use crate::matcher::Matcher;
+    use serde_json::{json, Map, Value};
+    use std::collections::HashMap;
+    use tornado_common_api::Event;
+
+    #[test]
+    fn should_collect_the_updates_of_the_reached_nodes_and_rules() {
+        // Arrange
+        let config = config(vec![rule("email", true), rule("trap", true)]);
+        let layout = StatsLayout::new(&config);
+        let matcher = Matcher::build(&config).unwrap();
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        // Act
+        let updates = layout.updates(&result.result);
+
+        // Assert
+        assert_eq!(
+            vec![
+                StatsUpdate::Node { id: layout.node_id(&["root"]).unwrap(), matched: true },
+                StatsUpdate::Node {
+                    id: layout.node_id(&["root", "ruleset"]).unwrap(),
+                    matched: true
+                },
+                StatsUpdate::Rule {
+                    id: layout.rule_id(&["root", "ruleset"], "email").unwrap(),
+                    status: ProcessedRuleStatus::Matched,
+                    actions: 1
+                },
+                StatsUpdate::Rule {
+                    id: layout.rule_id(&["root", "ruleset"], "trap").unwrap(),
+                    status: ProcessedRuleStatus::NotMatched,
+                    actions: 0
+                },
+            ],
+            updates
+        );
+        assert_eq!("root,ruleset,email", layout.rules()[0].label);
+        assert_eq!(None, layout.node_id(&["root", "unknown"]));
+    }
+
+    #[test]
+    fn should_not_collect_the_updates_of_the_rules_not_processed() {
+        // Arrange
+        let config = config(vec![rule("email", false), rule("trap", true)]);
+        let layout = StatsLayout::new(&config);
+        let matcher = Matcher::build(&config).unwrap();
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        // Act
+        let updates = layout.updates(&result.result);
+
+        // Assert
+        assert_eq!(3, updates.len());
+        assert!(!updates.iter().any(|update| matches!(update, StatsUpdate::Rule { id, .. }
+            if Some(*id) == layout.rule_id(&["root", "ruleset"], "trap"))));
+    }
+
+    #[test]
+    fn should_record_the_counters_and_the_last_matched_timestamp() {
+        // Arrange
+        let config = config(vec![rule("email", true), rule("trap", true)]);
+        let layout = Arc::new(StatsLayout::new(&config));
+        let matcher = Matcher::build(&config).unwrap();
+        let stats = MatcherStats::new(layout.clone());
+
+        // Act
+        let email = matcher.process(json!(Event::new("email")), false);
+        stats.record(&layout.updates(&email.result), 100);
+        let trap = matcher.process(json!(Event::new("trap")), false);
+        stats.record(&layout.updates(&trap.result), 200);
+        let other = matcher.process(json!(Event::new("other")), false);
+        stats.record(&layout.updates(&other.result), 300);
+
+        // Assert
+        assert_eq!(
+            NodeStats { evaluated: 3, matched: 2, last_matched_epoch_ms: Some(200) },
+            stats.node_stats(&["root", "ruleset"])
+        );
+        assert_eq!(
+            RuleStats {
+                evaluated: 3,
+                matched: 1,
+                partially_matched: 0,
+                actions_emitted: 1,
+                last_matched_epoch_ms: Some(100)
+            },
+            stats.rule_stats(&["root", "ruleset"], "email")
+        );
+        let mut rules_last_matched = stats.rules_last_matched();
+        rules_last_matched.sort();
+        assert_eq!(
+            vec![("root,ruleset,email".to_owned(), 100), ("root,ruleset,trap".to_owned(), 200)],
+            rules_last_matched
+        );
+        assert_eq!(RuleStats::default(), stats.rule_stats(&["root", "ruleset"], "unknown"));
+        assert_eq!(NodeStats::default(), stats.node_stats(&["root", "unknown"]));
+    }
+
+    #[test]
+    fn should_keep_only_the_statistics_of_the_existing_nodes_on_reconfiguration() {
+        // Arrange
+        let old_config = config(vec![rule("email", true), rule("trap", true)]);
+        let layout = Arc::new(StatsLayout::new(&old_config));
+        let matcher = Matcher::build(&old_config).unwrap();
+        let stats = MatcherStats::new(layout.clone());
+        let email = matcher.process(json!(Event::new("email")), false);
+        stats.record(&layout.updates(&email.result), 100);
+        let trap = matcher.process(json!(Event::new("trap")), false);
+        stats.record(&layout.updates(&trap.result), 200);
+
+        // Act
+        let new_layout = StatsLayout::new(&config(vec![rule("new", true), rule("email", true)]));
+        stats.reconfigure(Arc::new(new_layout));
+
+        // Assert
+        assert_eq!(2, stats.node_stats(&["root", "ruleset"]).evaluated);
+        assert_eq!(2, stats.rule_stats(&["root", "ruleset"], "email").evaluated);
+        assert_eq!(RuleStats::default(), stats.rule_stats(&["root", "ruleset"], "new"));
+        assert_eq!(RuleStats::default(), stats.rule_stats(&["root", "ruleset"], "trap"));
+        assert_eq!(vec![("root,ruleset,email".to_owned(), 100)], stats.rules_last_matched());
+    }
+
+    fn config(rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes: vec![MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules }],
+        }
+    }
+
+    fn rule(event_type: &str, do_continue: bool) -> Rule {
+        Rule {
+            name: event_type.to_owned(),
+            description: "".to_owned(),
+            do_continue,
+            active: true,
+            constraint: Constraint {
+                where_operator: Some(Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String(event_type.to_owned()),
+                }),
+                with: HashMap::new(),
+            },
+            actions: vec![ConfigAction {
+                id: "logger".to_owned(),
+                payload: Map::new(),
+                when: None,
+            }],
+        }
+    }
+}
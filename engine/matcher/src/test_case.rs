@@ -0,0 +1,346 @@
+//! The test_case module contains the test fixtures that can be stored next to a ruleset
+//! and the logic to verify them against a Matcher.
+//!
+//! A test case is a sample event together with the expected outcome of the ruleset:
+//! the rules that match it and the actions they produce.
+
+use crate::error::MatcherError;
+use crate::matcher::Matcher;
+use crate::model::{ProcessedNode, ProcessedRuleStatus, ProcessedRules};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A test case of a ruleset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesetTestCase {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub event: Value,
+    pub expected: ExpectedOutcome,
+}
+
+/// The expected outcome of a ruleset for the event of a test case.
+/// `matched_rules` lists, in order, all the rules expected to match; `actions` lists, in order,
+/// all the actions expected to be produced. The payload of an expected action is a fragment:
+/// only the listed entries are compared, recursively for nested maps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedOutcome {
+    pub matched_rules: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<ExpectedAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedAction {
+    pub id: String,
+    #[serde(default)]
+    pub payload: Map<String, Value>,
+}
+
+/// The test cases of the ruleset at the given path of the processing tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RulesetTests {
+    pub ruleset_path: Vec<String>,
+    pub tests: Vec<RulesetTestCase>,
+}
+
+/// The outcome of a test case. The test case succeeded if there are no failures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCaseResult {
+    pub ruleset_path: Vec<String>,
+    pub test_name: String,
+    pub failures: Vec<String>,
+}
+
+impl TestCaseResult {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Runs all the test cases against the Matcher.
+pub fn run_tests(matcher: &Matcher, tests: &[RulesetTests]) -> Vec<TestCaseResult> {
+    tests
+        .iter()
+        .flat_map(|ruleset_tests| {
+            ruleset_tests.tests.iter().map(move |test| TestCaseResult {
+                ruleset_path: ruleset_tests.ruleset_path.clone(),
+                test_name: test.name.clone(),
+                failures: test_failures(matcher, &ruleset_tests.ruleset_path, test),
+            })
+        })
+        .collect()
+}
+
+/// Runs all the test cases against the Matcher and returns an error describing the failed ones, if any.
+pub fn check_tests(matcher: &Matcher, tests: &[RulesetTests]) -> Result<(), MatcherError> {
+    let failed: Vec<String> = run_tests(matcher, tests)
+        .into_iter()
+        .filter(|result| !result.is_success())
+        .map(|result| {
+            format!(
+                "Test [{}] of ruleset [{}] failed: {}",
+                result.test_name,
+                result.ruleset_path.join(","),
+                result.failures.join(" ")
+            )
+        })
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(MatcherError::RuleTestsFailedError { message: failed.join("\n") })
+    }
+}
+
+fn test_failures(
+    matcher: &Matcher,
+    ruleset_path: &[String],
+    test: &RulesetTestCase,
+) -> Vec<String> {
+    let processed = matcher.process(test.event.clone(), false);
+    let mut reached = vec![];
+    collect_processed_rules(&processed.result, ruleset_path, &mut reached);
+
+    let mut failures = vec![];
+    let matched_rules: Vec<&str> = reached
+        .iter()
+        .flat_map(|rules| rules.rules.iter())
+        .filter(|rule| rule.status == ProcessedRuleStatus::Matched)
+        .map(|rule| rule.name.as_str())
+        .collect();
+    if matched_rules != test.expected.matched_rules {
+        failures.push(format!(
+            "Expected matched rules {:?}, found {:?}.",
+            test.expected.matched_rules, matched_rules
+        ));
+    }
+
+    let actions: Vec<_> = reached
+        .iter()
+        .flat_map(|rules| rules.rules.iter())
+        .flat_map(|rule| rule.actions.iter())
+        .collect();
+    let action_ids: Vec<&str> = actions.iter().map(|action| action.id.as_str()).collect();
+    let expected_ids: Vec<&str> =
+        test.expected.actions.iter().map(|action| action.id.as_str()).collect();
+    if action_ids != expected_ids {
+        failures.push(format!("Expected actions {:?}, found {:?}.", expected_ids, action_ids));
+    } else {
+        for (index, (expected, action)) in test.expected.actions.iter().zip(actions).enumerate() {
+            let expected_payload = Value::Object(expected.payload.clone());
+            let payload = Value::Object(action.payload.clone());
+            if !contains_fragment(&payload, &expected_payload) {
+                failures.push(format!(
+                    "The payload of action {} [{}] does not contain {}. Found: {}.",
+                    index, action.id, expected_payload, payload
+                ));
+            }
+        }
+    }
+    failures
+}
+
+/// Collects the results of the ruleset at the given path. A ruleset below an iterator
+/// is reached once per iteration.
+fn collect_processed_rules<'a>(
+    node: &'a ProcessedNode,
+    path: &[String],
+    reached: &mut Vec<&'a ProcessedRules>,
+) {
+    let (name, children_path) = match path.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    match node {
+        ProcessedNode::Filter { name: node_name, nodes, .. } if node_name == name => {
+            nodes.iter().for_each(|child| collect_processed_rules(child, children_path, reached))
+        }
+        ProcessedNode::Iterator { name: node_name, events, .. } if node_name == name => events
+            .iter()
+            .flat_map(|iteration| iteration.result.iter())
+            .for_each(|child| collect_processed_rules(child, children_path, reached)),
+        ProcessedNode::Ruleset { name: node_name, rules }
+            if node_name == name && children_path.is_empty() =>
+        {
+            reached.push(rules)
+        }
+        _ => {}
+    }
+}
+
+fn contains_fragment(value: &Value, fragment: &Value) -> bool {
+    match (value, fragment) {
+        (Value::Object(value), Value::Object(fragment)) => {
+            fragment.iter().all(|(key, expected)| {
+                value.get(key).map(|actual| contains_fragment(actual, expected)).unwrap_or(false)
+            })
+        }
+        _ => value == fragment,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::{ConfigAction, Constraint, Operator, Rule};
+    use crate::config::{Defaultable, MatcherConfig};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_succeed_if_the_outcome_is_the_expected_one() {
+        // Arrange
+        let matcher = matcher();
+        let tests = ruleset_tests(json!({
+            "name": "email",
+            "event": { "type": "email", "created_ms": 0, "payload": { "subject": "hello" } },
+            "expected": {
+                "matched_rules": ["email", "all"],
+                "actions": [
+                    { "id": "archive", "payload": { "event": { "payload": { "subject": "hello" } } } },
+                    { "id": "log" }
+                ]
+            }
+        }));
+
+        // Act
+        let results = run_tests(&matcher, &tests);
+
+        // Assert
+        assert_eq!(1, results.len());
+        assert_eq!("email", results[0].test_name);
+        assert!(results[0].is_success(), "{:?}", results[0].failures);
+    }
+
+    #[test]
+    fn should_fail_if_the_rules_or_the_actions_differ() {
+        // Arrange
+        let matcher = matcher();
+        let tests = ruleset_tests(json!({
+            "name": "trap",
+            "event": { "type": "trap", "created_ms": 0, "payload": {} },
+            "expected": {
+                "matched_rules": ["email", "all"],
+                "actions": [ { "id": "archive" }, { "id": "log" } ]
+            }
+        }));
+        let wrong_payload = ruleset_tests(json!({
+            "name": "wrong_payload",
+            "event": { "type": "email", "created_ms": 0, "payload": { "subject": "hello" } },
+            "expected": {
+                "matched_rules": ["email", "all"],
+                "actions": [
+                    { "id": "archive", "payload": { "event": { "type": "trap" } } },
+                    { "id": "log" }
+                ]
+            }
+        }));
+
+        // Act
+        let results = run_tests(&matcher, &tests);
+        let wrong_payload_results = run_tests(&matcher, &wrong_payload);
+
+        // Assert
+        assert_eq!(
+            vec![
+                r#"Expected matched rules ["email", "all"], found ["all"]."#.to_owned(),
+                r#"Expected actions ["archive", "log"], found ["log"]."#.to_owned(),
+            ],
+            results[0].failures
+        );
+        assert_eq!(1, wrong_payload_results[0].failures.len());
+        assert!(
+            wrong_payload_results[0].failures[0].starts_with("The payload of action 0 [archive]")
+        );
+    }
+
+    #[test]
+    fn should_not_match_any_rule_if_the_ruleset_is_not_reached() {
+        // Arrange
+        let matcher = matcher();
+        let mut tests = ruleset_tests(json!({
+            "name": "unknown_ruleset",
+            "event": { "type": "email", "created_ms": 0, "payload": {} },
+            "expected": { "matched_rules": [] }
+        }));
+        tests[0].ruleset_path = vec!["root".to_owned(), "unknown".to_owned()];
+
+        // Act
+        let results = run_tests(&matcher, &tests);
+
+        // Assert
+        assert!(results[0].is_success());
+    }
+
+    #[test]
+    fn check_tests_should_describe_the_failed_tests() {
+        // Arrange
+        let matcher = matcher();
+        let mut tests = ruleset_tests(json!({
+            "name": "trap",
+            "event": { "type": "trap", "created_ms": 0, "payload": {} },
+            "expected": { "matched_rules": ["all"], "actions": [ { "id": "log" } ] }
+        }));
+
+        // Act
+        let success = check_tests(&matcher, &tests);
+        tests[0].tests[0].expected.matched_rules = vec![];
+        let failure = check_tests(&matcher, &tests);
+
+        // Assert
+        assert!(success.is_ok());
+        assert_eq!(
+            Err(MatcherError::RuleTestsFailedError {
+                message: r#"Test [trap] of ruleset [root,ruleset] failed: Expected matched rules [], found ["all"]."#.to_owned()
+            }),
+            failure
+        );
+    }
+
+    fn ruleset_tests(test: Value) -> Vec<RulesetTests> {
+        vec![RulesetTests {
+            ruleset_path: vec!["root".to_owned(), "ruleset".to_owned()],
+            tests: vec![serde_json::from_value(test).unwrap()],
+        }]
+    }
+
+    fn matcher() -> Matcher {
+        let rule = |name: &str, event_type: Option<&str>, action_id: &str| {
+            let mut payload = Map::new();
+            payload.insert("event".to_owned(), Value::String("${event}".to_owned()));
+            Rule {
+                name: name.to_owned(),
+                description: "".to_owned(),
+                do_continue: true,
+                active: true,
+                constraint: Constraint {
+                    where_operator: event_type.map(|event_type| Operator::Equals {
+                        first: Value::String("${event.type}".to_owned()),
+                        second: Value::String(event_type.to_owned()),
+                    }),
+                    with: HashMap::new(),
+                },
+                actions: vec![ConfigAction { id: action_id.to_owned(), payload, when: None }],
+            }
+        };
+        Matcher::build(&MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes: vec![MatcherConfig::Ruleset {
+                name: "ruleset".to_owned(),
+                rules: vec![rule("email", Some("email"), "archive"), rule("all", None, "log")],
+            }],
+        })
+        .unwrap()
+    }
+}
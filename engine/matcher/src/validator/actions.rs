@@ -0,0 +1,237 @@
+//! Checks that an executor is configured for each action produced by the rules.
+
+use crate::config::rule::Rule;
+use crate::config::MatcherConfig;
+use crate::error::MatcherError;
+use crate::validator::errors::ConfigValidationError;
+use serde_json::Value;
+
+/// The ids of the actions that the configured executors can execute.
+/// The payload of the actions sent to a foreach executor contains more actions to check.
+pub struct ActionIds<'a> {
+    pub action_ids: &'a [&'a str],
+    pub foreach_action_ids: &'a [&'a str],
+}
+
+impl ActionIds<'_> {
+    fn is_known(&self, action_id: &str) -> bool {
+        self.action_ids.contains(&action_id)
+    }
+
+    fn is_foreach(&self, action_id: &str) -> bool {
+        self.foreach_action_ids.contains(&action_id)
+    }
+}
+
+/// Returns an error for each action of the active nodes and rules without an executor.
+pub fn unknown_action_errors(
+    config: &MatcherConfig,
+    action_ids: &ActionIds,
+) -> Vec<ConfigValidationError> {
+    let mut errors = vec![];
+    node_errors(config, action_ids, &mut vec![], &mut errors);
+    errors
+}
+
+/// Returns an error listing the actions of the active nodes and rules without an executor,
+/// if there are any.
+pub fn validate_action_ids(
+    config: &MatcherConfig,
+    action_ids: &ActionIds,
+) -> Result<(), MatcherError> {
+    let errors = unknown_action_errors(config, action_ids);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let details = errors
+        .iter()
+        .map(|error| {
+            format!(
+                "[{}] rule [{}] {}: {}",
+                error.node_path.join(" -> "),
+                error.rule.as_deref().unwrap_or_default(),
+                error.pointer,
+                error.message
+            )
+        })
+        .collect::<Vec<_>>();
+    Err(MatcherError::ConfigurationError {
+        message: format!("{} actions have no executor. {}", errors.len(), details.join("; ")),
+    })
+}
+
+fn node_errors(
+    config: &MatcherConfig,
+    action_ids: &ActionIds,
+    path: &mut Vec<String>,
+    errors: &mut Vec<ConfigValidationError>,
+) {
+    path.push(config.get_name().to_owned());
+    match config {
+        MatcherConfig::Filter { filter, nodes, .. } if filter.active => {
+            nodes.iter().for_each(|node| node_errors(node, action_ids, path, errors));
+        }
+        MatcherConfig::Iterator { iterator, nodes, .. } if iterator.is_active() => {
+            nodes.iter().for_each(|node| node_errors(node, action_ids, path, errors));
+        }
+        MatcherConfig::Ruleset { rules, .. } => {
+            for rule in rules.iter().filter(|rule| rule.active) {
+                rule_errors(rule, action_ids, path, errors);
+            }
+        }
+        MatcherConfig::Filter { .. } | MatcherConfig::Iterator { .. } => {}
+    }
+    path.pop();
+}
+
+fn rule_errors(
+    rule: &Rule,
+    action_ids: &ActionIds,
+    path: &[String],
+    errors: &mut Vec<ConfigValidationError>,
+) {
+    let mut unknown = vec![];
+    for (index, action) in rule.actions.iter().enumerate() {
+        let pointer = format!("/actions/{}", index);
+        if !action_ids.is_known(&action.id) {
+            unknown.push((format!("{}/id", pointer), action.id.clone()));
+        } else if action_ids.is_foreach(&action.id) {
+            if let Some(Value::Array(actions)) = action.payload.get("actions") {
+                foreach_errors(actions, action_ids, &format!("{}/payload", pointer), &mut unknown);
+            }
+        }
+    }
+    errors.extend(unknown.into_iter().map(|(pointer, action_id)| ConfigValidationError {
+        node_path: path.to_vec(),
+        rule: Some(rule.name.clone()),
+        pointer,
+        message: format!("There is no executor for the action id [{}]", action_id),
+    }));
+}
+
+/// Checks the actions in the payload of a foreach action, which can contain foreach actions too
+fn foreach_errors(
+    actions: &[Value],
+    action_ids: &ActionIds,
+    payload_pointer: &str,
+    unknown: &mut Vec<(String, String)>,
+) {
+    for (index, action) in actions.iter().enumerate() {
+        let pointer = format!("{}/actions/{}", payload_pointer, index);
+        match action.get("id").and_then(Value::as_str) {
+            Some(action_id) if !action_ids.is_known(action_id) => {
+                unknown.push((format!("{}/id", pointer), action_id.to_owned()));
+            }
+            Some(action_id) if action_ids.is_foreach(action_id) => {
+                if let Some(Value::Array(actions)) =
+                    action.get("payload").and_then(|payload| payload.get("actions"))
+                {
+                    foreach_errors(actions, action_ids, &format!("{}/payload", pointer), unknown);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::ConfigAction;
+    use crate::config::Defaultable;
+    use serde_json::json;
+
+    fn action(id: &str, payload: Value) -> ConfigAction {
+        serde_json::from_value(json!({ "id": id, "payload": payload })).unwrap()
+    }
+
+    fn ruleset(rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: Filter {
+                description: "".to_owned(),
+                active: true,
+                filter: Defaultable::Default {},
+            },
+            nodes: vec![MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules }],
+        }
+    }
+
+    #[test]
+    fn should_report_the_actions_without_executor() {
+        // Arrange
+        let config = ruleset(vec![
+            Rule {
+                name: "known".to_owned(),
+                active: true,
+                actions: vec![action("icinga2:dc1", json!({})), action("logger", json!({}))],
+                ..Default::default()
+            },
+            Rule {
+                name: "unknown".to_owned(),
+                active: true,
+                actions: vec![
+                    action("icinga2:dc2", json!({})),
+                    action(
+                        "foreach",
+                        json!({ "target": "${event.payload.list}", "actions": [
+                            { "id": "logger", "payload": {} },
+                            { "id": "foreach", "payload": { "actions": [ { "id": "archive" } ] } },
+                        ]}),
+                    ),
+                ],
+                ..Default::default()
+            },
+            Rule {
+                name: "inactive".to_owned(),
+                active: false,
+                actions: vec![action("archive", json!({}))],
+                ..Default::default()
+            },
+        ]);
+        let action_ids = ActionIds {
+            action_ids: &["icinga2:dc1", "logger", "foreach"],
+            foreach_action_ids: &["foreach"],
+        };
+
+        // Act
+        let errors = unknown_action_errors(&config, &action_ids);
+
+        // Assert
+        assert_eq!(2, errors.len());
+        assert_eq!(vec!["root", "ruleset"], errors[0].node_path);
+        assert_eq!(Some("unknown".to_owned()), errors[0].rule);
+        assert_eq!("/actions/0/id", errors[0].pointer);
+        assert_eq!("There is no executor for the action id [icinga2:dc2]", errors[0].message);
+        assert_eq!("/actions/1/payload/actions/1/payload/actions/0/id", errors[1].pointer);
+    }
+
+    #[test]
+    fn should_reject_a_config_with_actions_without_executor() {
+        // Arrange
+        let rule = |action_id: &str| Rule {
+            name: "rule".to_owned(),
+            active: true,
+            actions: vec![action(action_id, json!({}))],
+            ..Default::default()
+        };
+        let action_ids = ActionIds { action_ids: &["logger"], foreach_action_ids: &[] };
+
+        // Act
+        let known = validate_action_ids(&ruleset(vec![rule("logger")]), &action_ids);
+        let unknown = validate_action_ids(&ruleset(vec![rule("archive")]), &action_ids);
+
+        // Assert
+        assert!(known.is_ok());
+        assert_eq!(
+            Err(MatcherError::ConfigurationError {
+                message:
+                    "1 actions have no executor. [root -> ruleset] rule [rule] /actions/0/id: \
+                          There is no executor for the action id [archive]"
+                        .to_owned()
+            }),
+            unknown
+        );
+    }
+}
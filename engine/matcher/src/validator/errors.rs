@@ -0,0 +1,342 @@
+//! Collects all the errors of a MatcherConfig, instead of stopping at the first one,
+//! so that a configuration can be fixed in a single pass.
+
+use crate::accessor::AccessorBuilder;
+use crate::config::rule::Rule;
+use crate::config::MatcherConfig;
+use crate::error::MatcherError;
+use crate::matcher::action::ActionResolverBuilder;
+use crate::matcher::extractor::MatcherExtractorBuilder;
+use crate::matcher::operator::OperatorBuilder;
+use crate::validator::id::IdValidator;
+use crate::validator::variables::{escape_pointer, ruleset_reference_errors};
+use crate::validator::{MatcherConfigValidator, NodePath};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// An error of the configuration. The node path contains the names of the nodes starting from the root;
+/// if the error concerns a rule, the path is the one of its ruleset.
+/// The pointer is the JSON pointer of the wrong entry, relative to the rule if present, otherwise to the node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigValidationError {
+    pub node_path: Vec<String>,
+    pub rule: Option<String>,
+    pub pointer: String,
+    pub message: String,
+}
+
+/// Returns the errors of the whole configuration tree.
+/// The operators, extractors and actions are built only for the active nodes and rules,
+/// as the Matcher does.
+pub fn config_validation_errors(
+    id: &IdValidator,
+    config: &MatcherConfig,
+) -> Vec<ConfigValidationError> {
+    let mut collector = ErrorCollector { id, errors: vec![] };
+    collector.node_errors(config, &NodePath::Root, &mut vec![], true);
+    collector.errors
+}
+
+struct ErrorCollector<'a> {
+    id: &'a IdValidator,
+    errors: Vec<ConfigValidationError>,
+}
+
+impl ErrorCollector<'_> {
+    fn check<T>(
+        &mut self,
+        node_path: &[String],
+        rule: Option<&str>,
+        pointer: &str,
+        result: Result<T, MatcherError>,
+    ) {
+        if let Err(error) = result {
+            self.errors.push(ConfigValidationError {
+                node_path: node_path.to_vec(),
+                rule: rule.map(str::to_owned),
+                pointer: pointer.to_owned(),
+                message: error.to_string(),
+            });
+        }
+    }
+
+    fn node_errors(
+        &mut self,
+        config: &MatcherConfig,
+        parent: &NodePath,
+        path: &mut Vec<String>,
+        build: bool,
+    ) {
+        path.push(config.get_name().to_owned());
+        match config {
+            MatcherConfig::Filter { name, filter, nodes } => {
+                let node_path = NodePath::Parent { name, parent, is_iterator: false };
+                self.check(path, None, "/name", self.id.validate_filter_name(parent, name));
+                if build {
+                    let operator =
+                        OperatorBuilder::new().build_option(name, &filter.filter.clone().into());
+                    self.check(path, None, "/filter", operator);
+                }
+                for node in nodes {
+                    self.node_errors(node, &node_path, path, build && filter.active);
+                }
+            }
+            MatcherConfig::Iterator { name, iterator, nodes } => {
+                let node_path = NodePath::Parent { name, parent, is_iterator: true };
+                self.check(path, None, "/name", self.id.validate_iterator_name(parent, name));
+                self.check(
+                    path,
+                    None,
+                    "",
+                    MatcherConfigValidator::check_no_iterator_ancestor(&node_path, parent),
+                );
+                if build {
+                    let target = AccessorBuilder::new()
+                        .build(name, iterator.target())
+                        .and_then(|accessor| accessor.try_as_expression());
+                    self.check(path, None, "/target", target);
+                }
+                for node in nodes {
+                    self.node_errors(node, &node_path, path, build && iterator.is_active());
+                }
+            }
+            MatcherConfig::Ruleset { name, rules } => {
+                let node_path = NodePath::Parent { name, parent, is_iterator: false };
+                self.check(path, None, "/name", self.id.validate_ruleset_name(parent, name));
+                self.ruleset_errors(&node_path, rules, path, build);
+            }
+        }
+        path.pop();
+    }
+
+    fn ruleset_errors(
+        &mut self,
+        ruleset_path: &NodePath,
+        rules: &[Rule],
+        path: &[String],
+        build: bool,
+    ) {
+        let mut rule_names = vec![];
+        for rule in rules.iter().filter(|rule| rule.active) {
+            let name = Some(rule.name.as_str());
+            let rule_path =
+                NodePath::Parent { name: &rule.name, parent: ruleset_path, is_iterator: false };
+            self.check(path, name, "/name", self.id.validate_rule_name(ruleset_path, &rule.name));
+            self.check(
+                path,
+                name,
+                "/name",
+                MatcherConfigValidator::check_unique_name(&mut rule_names, &rule.name),
+            );
+
+            let mut variables: Vec<_> = rule.constraint.with.iter().collect();
+            variables.sort_by_key(|(variable, _)| *variable);
+            for (variable, _) in &variables {
+                let pointer = format!("/constraint/WITH/{}", escape_pointer(variable));
+                self.check(
+                    path,
+                    name,
+                    &pointer,
+                    self.id.validate_extracted_var_name(&rule_path, variable),
+                );
+            }
+            for (index, action) in rule.actions.iter().enumerate() {
+                let pointer = format!("/actions/{}/id", index);
+                self.check(
+                    path,
+                    name,
+                    &pointer,
+                    self.id.validate_action_id(&rule_path, &action.id),
+                );
+            }
+
+            if build {
+                let operator = OperatorBuilder::new()
+                    .build_option(&rule.name, &rule.constraint.where_operator);
+                self.check(path, name, "/constraint/WHERE", operator);
+                for (variable, extractor) in variables {
+                    let pointer = format!("/constraint/WITH/{}", escape_pointer(variable));
+                    let config = HashMap::from([(variable.to_owned(), extractor.clone())]);
+                    let extractor = MatcherExtractorBuilder::new().build(&rule.name, &config);
+                    self.check(path, name, &pointer, extractor);
+                }
+                for (index, action) in rule.actions.iter().enumerate() {
+                    let pointer = format!("/actions/{}", index);
+                    let action = ActionResolverBuilder::new().build(&rule.name, action);
+                    self.check(path, name, &pointer, action);
+                }
+            }
+        }
+
+        for reference_error in ruleset_reference_errors(ruleset_path, rules) {
+            self.check::<()>(
+                path,
+                Some(&reference_error.rule.name),
+                &reference_error.pointer,
+                Err(reference_error.error),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::{Filter, MatcherIterator};
+    use crate::config::rule::{ConfigAction, Constraint, Extractor, ExtractorRegex, Operator};
+    use crate::config::Defaultable;
+    use crate::matcher::Matcher;
+    use serde_json::{Map, Value};
+
+    #[test]
+    fn should_return_no_errors_for_a_valid_config() {
+        // Arrange
+        let config = filter(
+            "root",
+            true,
+            vec![ruleset("ruleset", vec![rule("rule_1", "${event.type}", "var", "[a-z]+", "id")])],
+        );
+
+        // Act
+        let errors = MatcherConfigValidator::new().validation_errors(&config);
+
+        // Assert
+        assert!(errors.is_empty());
+        assert!(Matcher::build(&config).is_ok());
+    }
+
+    #[test]
+    fn should_return_all_the_errors_with_their_location() {
+        // Arrange
+        let mut undefined_reference = rule("rule_4", "${event.type}", "var", "[a-z]+", "id");
+        undefined_reference.actions[0]
+            .payload
+            .insert("text".to_owned(), Value::String("${_variables.missing}".to_owned()));
+        let config = filter(
+            "root",
+            true,
+            vec![
+                ruleset(
+                    "ruleset",
+                    vec![
+                        rule("rule_1", "${unknown.type}", "var", "[a-z]+", "id"),
+                        rule("rule_2", "${event.type}", "var", "[a-z", "wrong id"),
+                        rule("rule_2", "${event.type}", "var", "[a-z]+", "id"),
+                        undefined_reference,
+                    ],
+                ),
+                ruleset("wrong name", vec![]),
+            ],
+        );
+
+        // Act
+        let errors = MatcherConfigValidator::new().validation_errors(&config);
+
+        // Assert
+        let locations: Vec<_> = errors
+            .iter()
+            .map(|error| (error.node_path.join(","), error.rule.as_deref(), error.pointer.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("root,ruleset".to_owned(), Some("rule_1"), "/constraint/WHERE"),
+                ("root,ruleset".to_owned(), Some("rule_2"), "/actions/0/id"),
+                ("root,ruleset".to_owned(), Some("rule_2"), "/constraint/WITH/var"),
+                ("root,ruleset".to_owned(), Some("rule_2"), "/name"),
+                ("root,ruleset".to_owned(), Some("rule_4"), "/actions/0/payload/text"),
+                ("root,wrong name".to_owned(), None, "/name"),
+            ],
+            locations
+        );
+        assert!(errors[4].message.contains("${_variables.missing}"));
+        assert!(Matcher::build(&config).is_err());
+    }
+
+    #[test]
+    fn should_not_build_the_children_of_inactive_nodes() {
+        // Arrange
+        let config = filter(
+            "root",
+            false,
+            vec![ruleset(
+                "ruleset",
+                vec![
+                    rule("rule_1", "${unknown.type}", "var", "[a-z]+", "id"),
+                    rule("wrong name", "${event.type}", "var", "[a-z]+", "id"),
+                ],
+            )],
+        );
+
+        // Act
+        let errors = MatcherConfigValidator::new().validation_errors(&config);
+
+        // Assert
+        assert_eq!(1, errors.len());
+        assert_eq!(Some("wrong name".to_owned()), errors[0].rule);
+    }
+
+    #[test]
+    fn should_return_the_errors_of_nested_iterators() {
+        // Arrange
+        let iterator = |name: &str, nodes: Vec<MatcherConfig>| MatcherConfig::Iterator {
+            name: name.to_owned(),
+            iterator: MatcherIterator::new("".to_owned(), true, "${event.payload}".to_owned()),
+            nodes,
+        };
+        let config = iterator("outer", vec![iterator("inner", vec![])]);
+
+        // Act
+        let errors = MatcherConfigValidator::new().validation_errors(&config);
+
+        // Assert
+        assert_eq!(1, errors.len());
+        assert_eq!(vec!["outer".to_owned(), "inner".to_owned()], errors[0].node_path);
+        assert_eq!("", errors[0].pointer);
+    }
+
+    fn filter(name: &str, active: bool, nodes: Vec<MatcherConfig>) -> MatcherConfig {
+        MatcherConfig::Filter {
+            name: name.to_owned(),
+            filter: Filter { description: "".to_owned(), active, filter: Defaultable::Default {} },
+            nodes,
+        }
+    }
+
+    fn ruleset(name: &str, rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Ruleset { name: name.to_owned(), rules }
+    }
+
+    fn rule(name: &str, first: &str, variable: &str, regex: &str, action_id: &str) -> Rule {
+        let mut with = HashMap::new();
+        with.insert(
+            variable.to_owned(),
+            Extractor {
+                from: "${event.type}".to_owned(),
+                regex: ExtractorRegex::Regex {
+                    regex: regex.to_owned(),
+                    group_match_idx: Some(0),
+                    all_matches: false,
+                },
+                modifiers_post: vec![],
+            },
+        );
+        Rule {
+            name: name.to_owned(),
+            description: "".to_owned(),
+            do_continue: true,
+            active: true,
+            constraint: Constraint {
+                where_operator: Some(Operator::Equals {
+                    first: Value::String(first.to_owned()),
+                    second: Value::String("email".to_owned()),
+                }),
+                with,
+            },
+            actions: vec![ConfigAction {
+                id: action_id.to_owned(),
+                payload: Map::new(),
+                when: None,
+            }],
+        }
+    }
+}
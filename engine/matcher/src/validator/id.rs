@@ -3,11 +3,14 @@ use crate::validator::NodePath;
 use regex::Regex as RustRegex;
 
 const ID_REGEX_PATTERN: &str = "^[a-zA-Z0-9_]+$";
+/// An action id can be followed by the name of the executor instance, e.g. `icinga2:dc1`
+const ACTION_ID_REGEX_PATTERN: &str = "^[a-zA-Z0-9_]+(:[a-zA-Z0-9_]+)?$";
 
 /// A validator for name and ID
 /// It checks that a string is composed only of alphabetical characters, numbers, and the '_' character.
 pub struct IdValidator {
     regex: RustRegex,
+    action_id_regex: RustRegex,
 }
 
 impl Default for IdValidator {
@@ -18,7 +21,10 @@ impl Default for IdValidator {
 
 impl IdValidator {
     pub fn new() -> IdValidator {
-        IdValidator { regex: RustRegex::new(ID_REGEX_PATTERN).unwrap() }
+        IdValidator {
+            regex: RustRegex::new(ID_REGEX_PATTERN).unwrap(),
+            action_id_regex: RustRegex::new(ACTION_ID_REGEX_PATTERN).unwrap(),
+        }
     }
 
     /// Validates a generic ID or name.
@@ -88,11 +94,15 @@ impl IdValidator {
         parent: &NodePath,
         action_id: &str,
     ) -> Result<(), MatcherError> {
-        let error_message = format!(
-            "Action id [{}] for rule [{}] is not valid. It should respect the pattern {}",
-            action_id, parent, ID_REGEX_PATTERN
-        );
-        self.validate(action_id, error_message)
+        if !self.action_id_regex.is_match(action_id) {
+            return Err(MatcherError::NotValidIdOrNameError {
+                message: format!(
+                    "Action id [{}] for rule [{}] is not valid. It should respect the pattern {}",
+                    action_id, parent, ACTION_ID_REGEX_PATTERN
+                ),
+            });
+        }
+        Ok(())
     }
 }
 
@@ -140,8 +150,11 @@ mod test {
         assert!(id.validate_action_id(&NodePath::Root, "hello").is_ok());
         assert!(id.validate_action_id(&NodePath::Root, "helloWorld").is_ok());
         assert!(id.validate_action_id(&NodePath::Root, "Hello_WORLD").is_ok());
+        assert!(id.validate_action_id(&NodePath::Root, "icinga2:dc_1").is_ok());
 
         assert!(id.validate_action_id(&NodePath::Root, "").is_err());
+        assert!(id.validate_action_id(&NodePath::Root, "icinga2:").is_err());
+        assert!(id.validate_action_id(&NodePath::Root, "icinga2:dc1:a").is_err());
         assert!(id.validate_action_id(&NodePath::Root, " ").is_err());
         assert!(id.validate_action_id(&NodePath::Root, "!").is_err());
     }
@@ -1,4 +1,8 @@
+pub mod actions;
+pub mod errors;
 pub mod id;
+mod variables;
+pub mod warnings;
 
 use crate::config::nodes::{Filter, MatcherIterator};
 use crate::config::rule::Rule;
@@ -46,6 +50,50 @@ impl MatcherConfigValidator {
         self.validate_inner(config, &NodePath::Root)
     }
 
+    /// Validates that the extracted variables referenced by each rule are defined by the rule itself
+    /// or by a previous active rule of the same ruleset.
+    /// Contrary to `validate`, this check is not required to build a Matcher, as the missing variables
+    /// only cause the rules to be partially matched; it is performed when a configuration is deployed.
+    /// All the undefined references are reported, not only the first one.
+    pub fn validate_variable_references(&self, config: &MatcherConfig) -> Result<(), MatcherError> {
+        variables::validate_variable_references(config, &NodePath::Root)
+    }
+
+    /// Returns all the errors that prevent a configuration from being deployed.
+    /// Contrary to `validate`, which stops at the first error, every node and rule is checked;
+    /// in addition to the checks of `validate`, the operators, extractors and actions are built
+    /// and the references to extracted variables are verified.
+    pub fn validation_errors(&self, config: &MatcherConfig) -> Vec<errors::ConfigValidationError> {
+        errors::config_validation_errors(&self.id, config)
+    }
+
+    /// Returns an error for each action of the active nodes and rules that no configured
+    /// executor can execute, including the actions in the payload of the foreach actions.
+    pub fn unknown_action_errors(
+        &self,
+        config: &MatcherConfig,
+        action_ids: &actions::ActionIds,
+    ) -> Vec<errors::ConfigValidationError> {
+        actions::unknown_action_errors(config, action_ids)
+    }
+
+    /// Validates that each action of the active nodes and rules can be executed by a configured
+    /// executor. Like `validate_variable_references`, it is performed when a configuration
+    /// is deployed.
+    pub fn validate_action_ids(
+        &self,
+        config: &MatcherConfig,
+        action_ids: &actions::ActionIds,
+    ) -> Result<(), MatcherError> {
+        actions::validate_action_ids(config, action_ids)
+    }
+
+    /// Returns the warnings of a configuration, e.g. the rules and nodes that can never be reached.
+    /// Contrary to `validate`, the warnings do not prevent the configuration from being used.
+    pub fn warnings(&self, config: &MatcherConfig) -> Vec<warnings::ConfigWarning> {
+        warnings::config_warnings(config)
+    }
+
     fn validate_inner(
         &self,
         config: &MatcherConfig,
@@ -93,11 +141,7 @@ impl MatcherConfigValidator {
         let node_path = NodePath::Parent { name, parent, is_iterator: true };
         self.id.validate_iterator_name(parent, name)?;
 
-        if let Some(ancestor) = parent.get_iterator_ancestor() {
-            return Err(MatcherError::ConfigurationError {
-                message: format!("Iterator in path [{node_path}] has already a iterator as its ancestor on [{ancestor}]"),
-            });
-        }
+        MatcherConfigValidator::check_no_iterator_ancestor(&node_path, parent)?;
 
         for node in nodes {
             self.validate_inner(node, &node_path)?;
@@ -154,6 +198,18 @@ impl MatcherConfigValidator {
         Ok(())
     }
 
+    fn check_no_iterator_ancestor(
+        node_path: &NodePath,
+        parent: &NodePath,
+    ) -> Result<(), MatcherError> {
+        match parent.get_iterator_ancestor() {
+            Some(ancestor) => Err(MatcherError::ConfigurationError {
+                message: format!("Iterator in path [{node_path}] has already a iterator as its ancestor on [{ancestor}]"),
+            }),
+            None => Ok(()),
+        }
+    }
+
     fn check_unique_name(rule_names: &mut Vec<String>, name: &str) -> Result<(), MatcherError> {
         let name_string = name.to_owned();
         debug!(
@@ -365,6 +421,7 @@ mod test {
         rule_1.actions.push(ConfigAction {
             id: "id.with.dot.and.question.mark?".to_owned(),
             payload: Map::new(),
+            when: None,
         });
 
         // Act
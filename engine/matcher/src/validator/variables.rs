@@ -0,0 +1,391 @@
+//! Cross-checks the extracted variables referenced by the rules of a ruleset
+//! against the variables defined by their WITH clauses.
+//!
+//! A reference `${_variables.name}` resolves to the variable `name` of the current rule,
+//! or to all the variables extracted by the rule called `name`;
+//! a reference `${_variables.rule_name.name}` resolves to the variable `name` of the rule `rule_name`.
+//! The variables of a rule are extracted after its WHERE operator is evaluated, so they are available
+//! only to its actions, while the variables of the previous rules of the ruleset are available everywhere.
+
+use crate::config::rule::Rule;
+use crate::config::MatcherConfig;
+use crate::error::MatcherError;
+use crate::validator::NodePath;
+use serde_json::Value;
+use std::collections::HashMap;
+use tornado_common_parser::{
+    Parser, ParserBuilder, Template, ValueGetter, EXTRACTED_VARIABLES_KEY,
+};
+
+/// An extracted variable referenced by a rule that is not defined.
+/// The pointer is the JSON pointer of the string containing the reference, relative to the rule.
+pub struct ReferenceError<'rule> {
+    pub rule: &'rule Rule,
+    pub pointer: String,
+    pub error: MatcherError,
+}
+
+/// Validates that all the extracted variables referenced by the rules of the configuration are defined
+/// by the rule itself or by a previous active rule of the same ruleset.
+/// All the undefined references are reported in the error, one per line.
+pub fn validate_variable_references(
+    config: &MatcherConfig,
+    parent: &NodePath,
+) -> Result<(), MatcherError> {
+    let mut messages = vec![];
+    collect_reference_messages(config, parent, &mut messages);
+    if messages.is_empty() {
+        return Ok(());
+    }
+    Err(MatcherError::ConfigurationError { message: messages.join("\n") })
+}
+
+fn collect_reference_messages(
+    config: &MatcherConfig,
+    parent: &NodePath,
+    messages: &mut Vec<String>,
+) {
+    match config {
+        MatcherConfig::Ruleset { name, rules } => {
+            let ruleset_path = NodePath::Parent { name, parent, is_iterator: false };
+            for reference_error in ruleset_reference_errors(&ruleset_path, rules) {
+                messages.push(match reference_error.error {
+                    MatcherError::ConfigurationError { message } => message,
+                    error => error.to_string(),
+                });
+            }
+        }
+        MatcherConfig::Filter { name, nodes, .. } => {
+            let node_path = NodePath::Parent { name, parent, is_iterator: false };
+            nodes.iter().for_each(|node| collect_reference_messages(node, &node_path, messages))
+        }
+        MatcherConfig::Iterator { name, nodes, .. } => {
+            let node_path = NodePath::Parent { name, parent, is_iterator: true };
+            nodes.iter().for_each(|node| collect_reference_messages(node, &node_path, messages))
+        }
+    }
+}
+
+/// Returns all the undefined extracted variables referenced by the active rules of a ruleset.
+pub fn ruleset_reference_errors<'rule>(
+    ruleset_path: &NodePath,
+    rules: &'rule [Rule],
+) -> Vec<ReferenceError<'rule>> {
+    let mut errors = vec![];
+    // The variable names defined by the active rules already evaluated, by rule name
+    let mut previous_rules: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for rule in rules.iter().filter(|rule| rule.active) {
+        let own_variables: Vec<&str> = rule.constraint.with.keys().map(String::as_str).collect();
+        let rule_path =
+            NodePath::Parent { name: &rule.name, parent: ruleset_path, is_iterator: false };
+        let rule_value = match serde_json::to_value(rule) {
+            Ok(rule_value) => rule_value,
+            Err(err) => {
+                errors.push(ReferenceError {
+                    rule,
+                    pointer: "".to_owned(),
+                    error: MatcherError::InternalSystemError {
+                        message: format!(
+                            "Cannot serialize the rule [{}]. Err: {:?}",
+                            rule_path, err
+                        ),
+                    },
+                });
+                continue;
+            }
+        };
+
+        let mut before_extraction = vec![];
+        let mut pointers = vec!["/constraint/WHERE".to_owned()];
+        for variable in rule.constraint.with.keys() {
+            pointers.push(format!("/constraint/WITH/{}/from", escape_pointer(variable)));
+        }
+        for pointer in pointers {
+            if let Some(value) = rule_value.pointer(&pointer) {
+                collect_references(value, pointer, &mut before_extraction);
+            }
+        }
+        let mut after_extraction = vec![];
+        if let Some(actions) = rule_value.get("actions") {
+            collect_references(actions, "/actions".to_owned(), &mut after_extraction);
+        }
+
+        for reference in before_extraction {
+            if let Err(error) = check_reference(&rule_path, &reference, &[], &previous_rules) {
+                errors.push(ReferenceError { rule, pointer: reference.pointer, error });
+            }
+        }
+        if !own_variables.is_empty() {
+            previous_rules.insert(&rule.name, own_variables.clone());
+        }
+        for reference in after_extraction {
+            if let Err(error) =
+                check_reference(&rule_path, &reference, &own_variables, &previous_rules)
+            {
+                errors.push(ReferenceError { rule, pointer: reference.pointer, error });
+            }
+        }
+    }
+    errors
+}
+
+/// A reference to an extracted variable, with the accessor keys that follow the `_variables` root.
+struct Reference {
+    pointer: String,
+    text: String,
+    keys: Vec<ValueGetter>,
+}
+
+fn check_reference(
+    rule_path: &NodePath,
+    reference: &Reference,
+    own_variables: &[&str],
+    previous_rules: &HashMap<&str, Vec<&str>>,
+) -> Result<(), MatcherError> {
+    let (name, variable) = match reference.keys.as_slice() {
+        [ValueGetter::Map { key }, rest @ ..] => (key.as_str(), rest.first()),
+        _ => return Ok(()),
+    };
+
+    if own_variables.contains(&name) {
+        return Ok(());
+    }
+
+    let message = match (previous_rules.get(name), variable) {
+        (Some(variables), Some(ValueGetter::Map { key })) if !variables.contains(&key.as_str()) => {
+            format!(
+                "Rule [{}] references [{}], but the rule [{}] does not extract a variable named [{}].",
+                rule_path, reference.text, name, key
+            )
+        }
+        (Some(_), _) => return Ok(()),
+        (None, _) => format!(
+            "Rule [{}] references [{}], but no variable or previous rule of the ruleset named [{}] defines it.",
+            rule_path, reference.text, name
+        ),
+    };
+    Err(MatcherError::ConfigurationError { message })
+}
+
+/// Collects the extracted variable references contained in all the strings of a value.
+/// References with fallback alternatives are ignored, as they resolve even if the variable is missing.
+fn collect_references(value: &Value, pointer: String, references: &mut Vec<Reference>) {
+    match value {
+        Value::String(text) => {
+            for reference in Template::from(text.as_str()).matches() {
+                if let Ok(Parser::Exp(expression)) =
+                    ParserBuilder::default().build_parser(reference.as_str())
+                {
+                    if let [ValueGetter::Map { key }, keys @ ..] = expression.keys.as_slice() {
+                        if key == EXTRACTED_VARIABLES_KEY {
+                            references.push(Reference {
+                                pointer: pointer.clone(),
+                                text: reference.as_str().to_owned(),
+                                keys: keys.to_vec(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_references(value, format!("{}/{}", pointer, index), references)
+            }
+        }
+        Value::Object(map) => {
+            for (key, value) in map {
+                collect_references(
+                    value,
+                    format!("{}/{}", pointer, escape_pointer(key)),
+                    references,
+                )
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Escapes a key to be used as a JSON pointer token, as defined by RFC 6901.
+pub fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::rule::{ConfigAction, Constraint, Extractor, ExtractorRegex, Operator};
+    use serde_json::Map;
+
+    #[test]
+    fn should_accept_references_to_own_and_previous_variables() {
+        // Arrange
+        let config = ruleset(vec![
+            rule("first", &["var"], None, &[]),
+            rule(
+                "second",
+                &["var"],
+                Some("${_variables.first.var}"),
+                &["${_variables.var}", "${_variables.second.var}", "${_variables.first}"],
+            ),
+        ]);
+
+        // Act
+        let result = validate_variable_references(&config, &NodePath::Root);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn should_fail_if_a_variable_is_not_defined() {
+        // Arrange
+        let config =
+            ruleset(vec![rule("first", &["var"], None, &["The value is ${_variables.missing}"])]);
+
+        // Act
+        let result = validate_variable_references(&config, &NodePath::Root);
+
+        // Assert
+        assert_eq!(
+            Err(MatcherError::ConfigurationError {
+                message: "Rule [root.ruleset.first] references [${_variables.missing}], but no variable or previous rule of the ruleset named [missing] defines it.".to_owned()
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn should_fail_if_a_previous_rule_does_not_extract_the_variable() {
+        // Arrange
+        let config = ruleset(vec![
+            rule("first", &["var"], None, &[]),
+            rule("second", &[], None, &["${_variables.first.other}"]),
+        ]);
+
+        // Act
+        let result = validate_variable_references(&config, &NodePath::Root);
+
+        // Assert
+        assert_eq!(
+            Err(MatcherError::ConfigurationError {
+                message: "Rule [root.ruleset.second] references [${_variables.first.other}], but the rule [first] does not extract a variable named [other].".to_owned()
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn should_report_all_the_undefined_variables() {
+        // Arrange
+        let config = ruleset(vec![
+            rule("first", &[], None, &["${_variables.missing}"]),
+            rule("second", &[], None, &["${_variables.first.var}"]),
+        ]);
+
+        // Act
+        let result = validate_variable_references(&config, &NodePath::Root);
+
+        // Assert
+        assert_eq!(
+            Err(MatcherError::ConfigurationError {
+                message: "Rule [root.ruleset.first] references [${_variables.missing}], but no variable or previous rule of the ruleset named [missing] defines it.\n\
+                          Rule [root.ruleset.second] references [${_variables.first.var}], but no variable or previous rule of the ruleset named [first] defines it.".to_owned()
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn should_fail_if_the_where_operator_references_its_own_variables() {
+        // Arrange
+        let config = ruleset(vec![rule("first", &["var"], Some("${_variables.var}"), &[])]);
+
+        // Act
+        let result = validate_variable_references(&config, &NodePath::Root);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_fail_if_referencing_a_following_or_inactive_rule() {
+        // Arrange
+        let mut inactive = rule("inactive", &["var"], None, &[]);
+        inactive.active = false;
+        let following = ruleset(vec![
+            rule("first", &[], None, &["${_variables.second.var}"]),
+            rule("second", &["var"], None, &[]),
+        ]);
+        let not_active =
+            ruleset(vec![inactive, rule("second", &[], None, &["${_variables.inactive.var}"])]);
+
+        // Act & Assert
+        assert!(validate_variable_references(&following, &NodePath::Root).is_err());
+        assert!(validate_variable_references(&not_active, &NodePath::Root).is_err());
+    }
+
+    #[test]
+    fn should_ignore_references_with_fallback_and_inactive_rules() {
+        // Arrange
+        let mut inactive = rule("inactive", &[], None, &["${_variables.missing}"]);
+        inactive.active = false;
+        let config = ruleset(vec![
+            inactive,
+            rule("first", &[], None, &[r#"${_variables.missing ?? "default"}"#, "${event.type}"]),
+        ]);
+
+        // Act
+        let result = validate_variable_references(&config, &NodePath::Root);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    fn ruleset(rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules }
+    }
+
+    fn rule(name: &str, variables: &[&str], where_first: Option<&str>, payload: &[&str]) -> Rule {
+        let with = variables
+            .iter()
+            .map(|variable| {
+                (
+                    variable.to_string(),
+                    Extractor {
+                        from: "${event.type}".to_owned(),
+                        regex: ExtractorRegex::Regex {
+                            regex: "[a-z]+".to_owned(),
+                            group_match_idx: Some(0),
+                            all_matches: false,
+                        },
+                        modifiers_post: vec![],
+                    },
+                )
+            })
+            .collect();
+        let mut action_payload = Map::new();
+        for (index, value) in payload.iter().enumerate() {
+            action_payload.insert(format!("value_{}", index), Value::String(value.to_string()));
+        }
+        Rule {
+            name: name.to_owned(),
+            description: "".to_owned(),
+            do_continue: true,
+            active: true,
+            constraint: Constraint {
+                where_operator: where_first.map(|first| Operator::Equals {
+                    first: Value::String(first.to_owned()),
+                    second: Value::String("value".to_owned()),
+                }),
+                with,
+            },
+            actions: vec![ConfigAction {
+                id: "action".to_owned(),
+                payload: action_payload,
+                when: None,
+            }],
+        }
+    }
+}
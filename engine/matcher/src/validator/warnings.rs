@@ -0,0 +1,598 @@
+//! Static analysis of a MatcherConfig that reports configurations that are valid
+//! but most likely not what the user intended, such as rules that can never be reached.
+//!
+//! The analysis is conservative: it only reports what can be deduced from the configuration
+//! without knowing the events, so the absence of warnings does not guarantee that
+//! every rule is reachable.
+
+use crate::config::rule::{ConfigAction, Operator, Rule};
+use crate::config::{Defaultable, MatcherConfig};
+use crate::matcher::action::expression::expression_text;
+use crate::matcher::operator::OperatorBuilder;
+use crate::model::InternalEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typescript_definitions::TypeScriptify;
+
+/// A finding of the static analysis. The path contains the names of the nodes starting
+/// from the root and, for rules, the rule name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub struct ConfigWarning {
+    pub path: Vec<String>,
+    pub kind: ConfigWarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
+pub enum ConfigWarningKind {
+    UnreachableRule,
+    UnreachableNode,
+    ContradictoryCondition,
+    InactiveParent,
+}
+
+/// Returns the warnings of the whole configuration tree.
+pub fn config_warnings(config: &MatcherConfig) -> Vec<ConfigWarning> {
+    let mut warnings = vec![];
+    node_warnings(config, &mut vec![], &mut warnings);
+    warnings
+}
+
+fn node_warnings(
+    config: &MatcherConfig,
+    path: &mut Vec<String>,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    path.push(config.get_name().to_owned());
+    match config {
+        MatcherConfig::Filter { filter, nodes, .. } => {
+            if !filter.active {
+                inactive_node_warnings(nodes, path, warnings);
+            } else {
+                let never_matches = match &filter.filter {
+                    Defaultable::Value(operator) => {
+                        condition_warnings(operator, path, warnings);
+                        constant_result(operator) == Some(false)
+                    }
+                    Defaultable::Default {} => false,
+                };
+                if never_matches && !nodes.is_empty() {
+                    warnings.push(ConfigWarning {
+                        path: path.clone(),
+                        kind: ConfigWarningKind::UnreachableNode,
+                        message: format!(
+                            "The filter condition can never be satisfied: its {} child nodes are unreachable.",
+                            nodes.len()
+                        ),
+                    });
+                } else {
+                    nodes.iter().for_each(|node| node_warnings(node, path, warnings));
+                }
+            }
+        }
+        MatcherConfig::Iterator { iterator, nodes, .. } => {
+            if !iterator.is_active() {
+                inactive_node_warnings(nodes, path, warnings);
+            } else {
+                nodes.iter().for_each(|node| node_warnings(node, path, warnings));
+            }
+        }
+        MatcherConfig::Ruleset { rules, .. } => ruleset_warnings(rules, path, warnings),
+    }
+    path.pop();
+}
+
+/// Reports an inactive node if it hides active rules.
+/// The nested nodes are not analyzed, as they are never processed.
+fn inactive_node_warnings(
+    nodes: &[MatcherConfig],
+    path: &[String],
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    let active_rules: usize = nodes.iter().map(active_rules_count).sum();
+    if active_rules > 0 {
+        warnings.push(ConfigWarning {
+            path: path.to_vec(),
+            kind: ConfigWarningKind::InactiveParent,
+            message: format!(
+                "The node is inactive: the {} active rules it contains are never evaluated.",
+                active_rules
+            ),
+        });
+    }
+}
+
+fn active_rules_count(config: &MatcherConfig) -> usize {
+    match config {
+        MatcherConfig::Filter { filter, nodes, .. } if filter.active => {
+            nodes.iter().map(active_rules_count).sum()
+        }
+        MatcherConfig::Iterator { iterator, nodes, .. } if iterator.is_active() => {
+            nodes.iter().map(active_rules_count).sum()
+        }
+        MatcherConfig::Filter { .. } | MatcherConfig::Iterator { .. } => 0,
+        MatcherConfig::Ruleset { rules, .. } => rules.iter().filter(|rule| rule.active).count(),
+    }
+}
+
+/// Reports the rules that can never match and the rules shadowed by a previous rule
+/// that always matches before them and stops the processing of the ruleset.
+fn ruleset_warnings(rules: &[Rule], path: &mut Vec<String>, warnings: &mut Vec<ConfigWarning>) {
+    // The previous rules that stop the processing whenever their conditions are satisfied.
+    // Rules with extracted variables or with actions that could fail to resolve are ignored,
+    // as a failed extraction or action resolution does not stop the processing.
+    let mut stopping_rules: Vec<(&str, Vec<&Operator>)> = vec![];
+
+    for rule in rules.iter().filter(|rule| rule.active) {
+        path.push(rule.name.to_owned());
+        let where_operator = rule.constraint.where_operator.as_ref();
+        if let Some(operator) = where_operator {
+            condition_warnings(operator, path, warnings);
+        }
+
+        let never_matches = where_operator.and_then(constant_result) == Some(false);
+        let conditions = where_operator.map(conjunction).unwrap_or_default();
+
+        if never_matches {
+            warnings.push(ConfigWarning {
+                path: path.clone(),
+                kind: ConfigWarningKind::UnreachableRule,
+                message: "The rule condition can never be satisfied.".to_owned(),
+            });
+        } else if let Some((stopping_rule, _)) = stopping_rules
+            .iter()
+            .find(|(_, stopping)| stopping.iter().all(|condition| conditions.contains(condition)))
+        {
+            warnings.push(ConfigWarning {
+                path: path.clone(),
+                kind: ConfigWarningKind::UnreachableRule,
+                message: format!(
+                    "The rule is unreachable: every event it matches is matched first by the rule [{}], which does not continue.",
+                    stopping_rule
+                ),
+            });
+        }
+
+        if !rule.do_continue
+            && rule.constraint.with.is_empty()
+            && rule.actions.iter().all(always_resolves)
+            && !never_matches
+        {
+            stopping_rules.push((&rule.name, conditions));
+        }
+        path.pop();
+    }
+}
+
+/// Returns whether the action is produced for every event matched by its rule, that is,
+/// it has no `when` condition and its payload contains no accessors or expressions.
+fn always_resolves(action: &ConfigAction) -> bool {
+    action.when.is_none() && action.payload.values().all(is_literal)
+}
+
+/// Returns whether the payload value contains no accessors and no computed-value expressions.
+fn is_literal(value: &Value) -> bool {
+    match value {
+        Value::String(text) => is_constant(value) && expression_text(text).is_none(),
+        Value::Array(values) => values.iter().all(is_literal),
+        Value::Object(map) => map.values().all(is_literal),
+        Value::Null | Value::Bool(_) | Value::Number(_) => true,
+    }
+}
+
+/// Returns the conditions that must all be satisfied for the operator to match,
+/// ignoring those that are always satisfied.
+fn conjunction(operator: &Operator) -> Vec<&Operator> {
+    match operator {
+        Operator::And { operators } => operators.iter().flat_map(conjunction).collect(),
+        _ if constant_result(operator) == Some(true) => vec![],
+        _ => vec![operator],
+    }
+}
+
+/// Reports the AND operators whose conditions contradict each other.
+fn condition_warnings(operator: &Operator, path: &[String], warnings: &mut Vec<ConfigWarning>) {
+    match operator {
+        Operator::And { .. } => {
+            let conditions = conjunction(operator);
+            if let Some((first, second)) = find_contradiction(&conditions) {
+                warnings.push(ConfigWarning {
+                    path: path.to_vec(),
+                    kind: ConfigWarningKind::ContradictoryCondition,
+                    message: format!(
+                        "The conditions {} and {} of the AND operator can never be both satisfied.",
+                        serde_json::to_string(first).unwrap_or_default(),
+                        serde_json::to_string(second).unwrap_or_default()
+                    ),
+                });
+            }
+            conditions.iter().for_each(|condition| condition_warnings(condition, path, warnings));
+        }
+        Operator::Or { operators } => {
+            operators.iter().for_each(|operator| condition_warnings(operator, path, warnings))
+        }
+        Operator::Not { operator } => condition_warnings(operator, path, warnings),
+        _ => {}
+    }
+}
+
+/// Returns two conditions that compare the same accessor with different constants using `equals`,
+/// or with the same constant using both `equals` and `ne`.
+fn find_contradiction<'a>(conditions: &[&'a Operator]) -> Option<(&'a Operator, &'a Operator)> {
+    for (index, first) in conditions.iter().enumerate() {
+        for second in &conditions[index + 1..] {
+            let contradiction = match (first, second) {
+                (Operator::Equals { .. }, Operator::Equals { .. }) => {
+                    match (accessor_and_constant(first), accessor_and_constant(second)) {
+                        (
+                            Some((first_accessor, first_value)),
+                            Some((second_accessor, second_value)),
+                        ) => first_accessor == second_accessor && first_value != second_value,
+                        _ => false,
+                    }
+                }
+                (Operator::Equals { .. }, Operator::NotEquals { .. })
+                | (Operator::NotEquals { .. }, Operator::Equals { .. }) => {
+                    let first = accessor_and_constant(first);
+                    first.is_some() && first == accessor_and_constant(second)
+                }
+                _ => false,
+            };
+            if contradiction {
+                return Some((first, second));
+            }
+        }
+    }
+    None
+}
+
+/// Returns the accessor and the constant of a comparison between an accessor and a constant.
+fn accessor_and_constant(operator: &Operator) -> Option<(&str, &Value)> {
+    let (first, second) = match operator {
+        Operator::Equals { first, second } | Operator::NotEquals { first, second } => {
+            (first, second)
+        }
+        _ => return None,
+    };
+    match (first, second) {
+        (Value::String(accessor), constant) if !is_constant(first) && is_constant(constant) => {
+            Some((accessor, constant))
+        }
+        (constant, Value::String(accessor)) if !is_constant(second) && is_constant(constant) => {
+            Some((accessor, constant))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the result of the operator if it does not depend on the event.
+fn constant_result(operator: &Operator) -> Option<bool> {
+    match operator {
+        Operator::And { operators } => {
+            if find_contradiction(&conjunction(operator)).is_some() {
+                return Some(false);
+            }
+            let results: Vec<_> = operators.iter().map(constant_result).collect();
+            if results.contains(&Some(false)) {
+                Some(false)
+            } else if results.iter().all(|result| *result == Some(true)) {
+                Some(true)
+            } else {
+                None
+            }
+        }
+        Operator::Or { operators } => {
+            let results: Vec<_> = operators.iter().map(constant_result).collect();
+            if results.contains(&Some(true)) {
+                Some(true)
+            } else if results.iter().all(|result| *result == Some(false)) {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        Operator::Not { operator } => constant_result(operator).map(|result| !result),
+        Operator::Regex { target, .. } => {
+            evaluate_if_constant(operator, &[&Value::String(target.to_owned())])
+        }
+        Operator::Contains { first, second }
+        | Operator::ContainsIgnoreCase { first, second }
+        | Operator::Equals { first, second }
+        | Operator::EqualsIgnoreCase { first, second }
+        | Operator::GreaterEqualThan { first, second }
+        | Operator::GreaterThan { first, second }
+        | Operator::LessEqualThan { first, second }
+        | Operator::LessThan { first, second }
+        | Operator::NotEquals { first, second } => evaluate_if_constant(operator, &[first, second]),
+    }
+}
+
+fn evaluate_if_constant(operator: &Operator, operands: &[&Value]) -> Option<bool> {
+    if !operands.iter().all(|operand| is_constant(operand)) {
+        return None;
+    }
+    let operator = OperatorBuilder::new().build("", operator).ok()?;
+    let event = Value::Null;
+    let mut extracted_variables = Value::Null;
+    Some(
+        operator.evaluate(&InternalEvent {
+            event: &event,
+            extracted_variables: &mut extracted_variables,
+        }),
+    )
+}
+
+/// Returns whether the value contains no accessor expressions.
+fn is_constant(value: &Value) -> bool {
+    match value {
+        Value::String(text) => !text.contains("${"),
+        Value::Array(values) => values.iter().all(is_constant),
+        Value::Object(map) => map.values().all(is_constant),
+        Value::Null | Value::Bool(_) | Value::Number(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::nodes::Filter;
+    use crate::config::rule::{Constraint, Extractor, ExtractorRegex};
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_return_no_warnings_for_reachable_rules() {
+        // Arrange
+        let config = ruleset(vec![
+            rule("email", Some(equals("${event.type}", "email")), false),
+            rule("trap", Some(equals("${event.type}", "trap")), false),
+            rule("all", None, true),
+        ]);
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn should_detect_rules_shadowed_by_a_previous_rule_that_does_not_continue() {
+        // Arrange
+        let config = ruleset(vec![
+            rule("email", Some(equals("${event.type}", "email")), false),
+            rule(
+                "email_from_host",
+                Some(Operator::And {
+                    operators: vec![
+                        equals("${event.payload.host}", "host"),
+                        equals("${event.type}", "email"),
+                    ],
+                }),
+                true,
+            ),
+            rule("catch_all", None, false),
+            rule("trap", Some(equals("${event.type}", "trap")), true),
+        ]);
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert_eq!(2, warnings.len());
+        assert_eq!(path(&["ruleset", "email_from_host"]), warnings[0].path);
+        assert_eq!(ConfigWarningKind::UnreachableRule, warnings[0].kind);
+        assert!(warnings[0].message.contains("[email]"));
+        assert_eq!(path(&["ruleset", "trap"]), warnings[1].path);
+        assert!(warnings[1].message.contains("[catch_all]"));
+    }
+
+    #[test]
+    fn should_not_consider_rules_with_extracted_variables_as_shadowing() {
+        // Arrange
+        let mut catch_all = rule("catch_all", None, false);
+        catch_all.constraint.with.insert(
+            "var".to_owned(),
+            Extractor {
+                from: "${event.type}".to_owned(),
+                regex: ExtractorRegex::Regex {
+                    regex: "[0-9]+".to_owned(),
+                    group_match_idx: Some(0),
+                    all_matches: false,
+                },
+                modifiers_post: vec![],
+            },
+        );
+        let config = ruleset(vec![catch_all, rule("trap", None, true)]);
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn should_not_consider_rules_with_actions_that_can_fail_to_resolve_as_shadowing() {
+        // Arrange
+        let mut with_accessor = rule("with_accessor", None, false);
+        with_accessor.actions.push(action(
+            "host",
+            Value::String("${event.payload.missing}".to_owned()),
+            None,
+        ));
+        let mut with_expression = rule("with_expression", None, false);
+        with_expression.actions.push(action("ratio", Value::String("$= 1 / 0".to_owned()), None));
+        let mut with_when = rule("with_when", None, false);
+        with_when.actions.push(action(
+            "host",
+            Value::String("host".to_owned()),
+            Some(equals("${event.type}", "email")),
+        ));
+        let mut with_literals = rule("with_literals", None, false);
+        with_literals.actions.push(action("host", Value::String("host".to_owned()), None));
+        let config = ruleset(vec![
+            with_accessor,
+            with_expression,
+            with_when,
+            with_literals,
+            rule("next", None, true),
+        ]);
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert_eq!(1, warnings.len());
+        assert_eq!(path(&["ruleset", "next"]), warnings[0].path);
+        assert!(warnings[0].message.contains("[with_literals]"));
+    }
+
+    #[test]
+    fn should_detect_contradictory_and_operators() {
+        // Arrange
+        let config = ruleset(vec![rule(
+            "contradiction",
+            Some(Operator::And {
+                operators: vec![
+                    equals("${event.type}", "email"),
+                    Operator::And { operators: vec![equals("trap", "${event.type}")] },
+                ],
+            }),
+            true,
+        )]);
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert_eq!(2, warnings.len());
+        assert_eq!(ConfigWarningKind::ContradictoryCondition, warnings[0].kind);
+        assert_eq!(path(&["ruleset", "contradiction"]), warnings[0].path);
+        assert_eq!(ConfigWarningKind::UnreachableRule, warnings[1].kind);
+    }
+
+    #[test]
+    fn should_detect_equals_and_not_equals_on_the_same_value() {
+        // Arrange
+        let config = ruleset(vec![rule(
+            "contradiction",
+            Some(Operator::And {
+                operators: vec![
+                    equals("${event.type}", "email"),
+                    Operator::NotEquals {
+                        first: Value::String("${event.type}".to_owned()),
+                        second: Value::String("email".to_owned()),
+                    },
+                ],
+            }),
+            true,
+        )]);
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert_eq!(ConfigWarningKind::ContradictoryCondition, warnings[0].kind);
+    }
+
+    #[test]
+    fn should_detect_filters_that_never_match() {
+        // Arrange
+        let config = MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: filter(true, Some(equals("a", "b"))),
+            nodes: vec![ruleset(vec![rule("all", None, true)])],
+        };
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert_eq!(
+            vec![ConfigWarning {
+                path: path(&["root"]),
+                kind: ConfigWarningKind::UnreachableNode,
+                message:
+                    "The filter condition can never be satisfied: its 1 child nodes are unreachable."
+                        .to_owned()
+            }],
+            warnings
+        );
+    }
+
+    #[test]
+    fn should_detect_inactive_parents_of_active_rules() {
+        // Arrange
+        let config = MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: filter(true, None),
+            nodes: vec![
+                MatcherConfig::Filter {
+                    name: "inactive".to_owned(),
+                    filter: filter(false, None),
+                    nodes: vec![ruleset(vec![rule("one", None, true), rule("two", None, true)])],
+                },
+                MatcherConfig::Filter {
+                    name: "inactive_and_empty".to_owned(),
+                    filter: filter(false, None),
+                    nodes: vec![],
+                },
+            ],
+        };
+
+        // Act
+        let warnings = config_warnings(&config);
+
+        // Assert
+        assert_eq!(
+            vec![ConfigWarning {
+                path: path(&["root", "inactive"]),
+                kind: ConfigWarningKind::InactiveParent,
+                message:
+                    "The node is inactive: the 2 active rules it contains are never evaluated."
+                        .to_owned()
+            }],
+            warnings
+        );
+    }
+
+    fn path(nodes: &[&str]) -> Vec<String> {
+        nodes.iter().map(|node| node.to_string()).collect()
+    }
+
+    fn equals(first: &str, second: &str) -> Operator {
+        Operator::Equals {
+            first: Value::String(first.to_owned()),
+            second: Value::String(second.to_owned()),
+        }
+    }
+
+    fn filter(active: bool, operator: Option<Operator>) -> Filter {
+        Filter {
+            description: "".to_owned(),
+            active,
+            filter: operator.map(Defaultable::Value).unwrap_or(Defaultable::Default {}),
+        }
+    }
+
+    fn action(key: &str, value: Value, when: Option<Operator>) -> ConfigAction {
+        let mut payload = tornado_common_api::Payload::new();
+        payload.insert(key.to_owned(), value);
+        ConfigAction { id: "logger".to_owned(), payload, when }
+    }
+
+    fn ruleset(rules: Vec<Rule>) -> MatcherConfig {
+        MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules }
+    }
+
+    fn rule(name: &str, where_operator: Option<Operator>, do_continue: bool) -> Rule {
+        Rule {
+            name: name.to_owned(),
+            description: "".to_owned(),
+            do_continue,
+            active: true,
+            constraint: Constraint { where_operator, with: HashMap::new() },
+            actions: vec![],
+        }
+    }
+}
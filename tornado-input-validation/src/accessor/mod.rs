@@ -18,6 +18,7 @@ pub enum AccessorType {
     Expression,
     StringInterpolator,
     Static,
+    Fallback,
 }
 
 #[wasm_bindgen]
@@ -38,6 +39,9 @@ pub fn validate_accessor(input: &str) -> AccessorValidationResult {
             AccessorValidationResult { r#type: AccessorType::StringInterpolator, ..result }
         }
         Ok(Parser::Val(_)) => AccessorValidationResult { r#type: AccessorType::Static, ..result },
+        Ok(Parser::Fallback { .. }) => {
+            AccessorValidationResult { r#type: AccessorType::Fallback, ..result }
+        }
         Err(error) => AccessorValidationResult {
             is_valid: false,
             r#type: AccessorType::None,
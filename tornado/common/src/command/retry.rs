@@ -3,6 +3,7 @@ use core::fmt::Debug;
 use core::marker::PhantomData;
 use log::*;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tornado_common_api::RetriableError;
 
@@ -125,10 +126,17 @@ impl BackoffPolicy {
     }
 }
 
+/// Receives the messages that a RetryCommand failed to process and will not retry any more.
+/// The sink is called synchronously by the RetryCommand, on the thread of the executor.
+pub trait DeadLetterSink<I, E> {
+    fn push(&self, message: &I, error: &E, attempts: u32, first_attempt_epoch_ms: i64);
+}
+
 /// A Command that reties a failing operation based on the specified RetryStrategy
 pub struct RetryCommand<I: Clone + Debug, O, E: RetriableError, T: Command<I, Result<O, E>>> {
     command: T,
     retry_strategy: RetryStrategy,
+    dead_letter_sink: Option<Arc<dyn DeadLetterSink<I, E>>>,
     phantom_i: PhantomData<I>,
     phantom_o: PhantomData<O>,
     phantom_e: PhantomData<E>,
@@ -139,11 +147,33 @@ impl<I: Clone + Debug, O, E: RetriableError, T: Command<I, Result<O, E>>> RetryC
         Self {
             retry_strategy,
             command,
+            dead_letter_sink: None,
             phantom_i: PhantomData,
             phantom_o: PhantomData,
             phantom_e: PhantomData,
         }
     }
+
+    /// Sets where the messages are sent when the command gives up processing them
+    pub fn with_dead_letter_sink(
+        mut self,
+        dead_letter_sink: Arc<dyn DeadLetterSink<I, E>>,
+    ) -> Self {
+        self.dead_letter_sink = Some(dead_letter_sink);
+        self
+    }
+
+    fn send_to_dead_letter_sink(
+        &self,
+        message: &I,
+        error: &E,
+        attempts: u32,
+        first_attempt_epoch_ms: i64,
+    ) {
+        if let Some(dead_letter_sink) = &self.dead_letter_sink {
+            dead_letter_sink.push(message, error, attempts, first_attempt_epoch_ms);
+        }
+    }
 }
 
 #[async_trait::async_trait(?Send)]
@@ -158,6 +188,7 @@ impl<I: Clone + Debug, O, E: RetriableError + Debug, T: Command<I, Result<O, E>>
 
         let mut should_retry = true;
         let mut failed_attempts = 0;
+        let first_attempt_epoch_ms = chrono::Utc::now().timestamp_millis();
         while should_retry {
             let result = command.execute(message.clone()).await;
             match result {
@@ -167,6 +198,12 @@ impl<I: Clone + Debug, O, E: RetriableError + Debug, T: Command<I, Result<O, E>>
                 Err(err) => {
                     if !err.can_retry() {
                         warn!("The failed message will not be retried as the error is not recoverable.");
+                        self.send_to_dead_letter_sink(
+                            &message,
+                            &err,
+                            failed_attempts + 1,
+                            first_attempt_epoch_ms,
+                        );
                         return Err(err);
                     } else {
                         failed_attempts += 1;
@@ -182,6 +219,12 @@ impl<I: Clone + Debug, O, E: RetriableError + Debug, T: Command<I, Result<O, E>>
                             }
                         } else {
                             warn!("The failed message will not be retried any more in respect of the current RetryPolicy. Failed attempts: {}. Message: {:?}", failed_attempts, message);
+                            self.send_to_dead_letter_sink(
+                                &message,
+                                &err,
+                                failed_attempts,
+                                first_attempt_epoch_ms,
+                            );
                             return Err(err);
                         }
                     }
@@ -390,6 +433,51 @@ pub mod test {
         }
     }
 
+    #[derive(Default)]
+    struct CollectingDeadLetterSink {
+        dead_letters: std::sync::Mutex<Vec<(String, u32)>>,
+    }
+
+    impl DeadLetterSink<Arc<Action>, ExecutorError> for CollectingDeadLetterSink {
+        fn push(&self, message: &Arc<Action>, _error: &ExecutorError, attempts: u32, _: i64) {
+            self.dead_letters.lock().unwrap().push((message.id.to_owned(), attempts));
+        }
+    }
+
+    #[actix_rt::test]
+    async fn should_send_to_the_dead_letter_sink_the_messages_not_retried() {
+        // Arrange
+        let (sender, _receiver) = unbounded_channel();
+        let dead_letter_sink = Arc::new(CollectingDeadLetterSink::default());
+        let retry_strategy = RetryStrategy {
+            retry_policy: RetryPolicy::MaxRetries { retries: 2 },
+            backoff_policy: BackoffPolicy::None,
+        };
+        let command = |can_retry| {
+            RetryCommand::new(
+                retry_strategy.clone(),
+                StatelessExecutorCommand::new(
+                    Arc::new(ActionMeter::new("test_meter")),
+                    AlwaysFailExecutor { sender: sender.clone(), can_retry },
+                ),
+            )
+            .with_dead_letter_sink(dead_letter_sink.clone())
+        };
+
+        // Act
+        let exhausted = command(true).execute(Arc::new(Action::new("exhausted"))).await;
+        let not_recoverable =
+            command(false).execute(Arc::new(Action::new("not_recoverable"))).await;
+
+        // Assert
+        assert!(exhausted.is_err());
+        assert!(not_recoverable.is_err());
+        assert_eq!(
+            vec![("exhausted".to_owned(), 3), ("not_recoverable".to_owned(), 1)],
+            *dead_letter_sink.dead_letters.lock().unwrap()
+        );
+    }
+
     #[actix_rt::test]
     async fn should_not_retry_if_ok() {
         let (sender, mut receiver) = unbounded_channel();
@@ -0,0 +1,421 @@
+//! The dead-letter queue stores, one JSON file per entry, the actions whose execution failed
+//! and that will not be retried any more, so that they can be inspected and retried later.
+//!
+//! The entries are written to a temporary file which is then renamed, so that a crash never
+//! leaves a partially written entry. The entries that cannot be parsed anyway, for example
+//! because they were edited by hand, are renamed with the `corrupted` extension when
+//! they are found, so that they do not prevent listing and purging the queue.
+//!
+//! The queue has a maximum number of entries: when it is full, the oldest entries are dropped
+//! to make room for the new ones, and a warning is logged for each dropped entry.
+
+use crate::command::retry::DeadLetterSink;
+use crate::TornadoError;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tornado_common_api::Action;
+
+const DEAD_LETTER_FILE_EXTENSION: &str = "json";
+const TEMP_FILE_EXTENSION: &str = "tmp";
+const CORRUPTED_FILE_EXTENSION: &str = "corrupted";
+
+/// An action that could not be executed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeadLetter {
+    pub id: u64,
+    pub action: Action,
+    pub error: String,
+    /// The number of execution attempts performed
+    pub attempts: u32,
+    pub first_attempt_epoch_ms: i64,
+    pub last_attempt_epoch_ms: i64,
+}
+
+pub struct DeadLetterQueue {
+    dir: PathBuf,
+    max_entries: usize,
+    last_id: Mutex<u64>,
+    size: AtomicUsize,
+}
+
+impl DeadLetterQueue {
+    /// Opens the queue stored in the given folder, which is created if missing.
+    /// If the queue has more than `max_entries` entries, the oldest ones are dropped.
+    pub fn new<P: Into<PathBuf>>(dir: P, max_entries: usize) -> Result<Self, TornadoError> {
+        let dir = dir.into();
+        if max_entries == 0 {
+            return Err(TornadoError::ConfigurationError {
+                message: "The maximum number of entries of the dead-letter queue must be greater than zero".to_owned(),
+            });
+        }
+        std::fs::create_dir_all(&dir).map_err(|err| TornadoError::ConfigurationError {
+            message: format!(
+                "Cannot create the dead-letter queue folder {:?}. Err: {:?}",
+                dir, err
+            ),
+        })?;
+        let ids = read_ids(&dir)?;
+        let queue = Self {
+            last_id: Mutex::new(ids.iter().max().copied().unwrap_or_default()),
+            size: AtomicUsize::new(ids.len()),
+            max_entries,
+            dir,
+        };
+        queue.drop_oldest(max_entries)?;
+        Ok(queue)
+    }
+
+    /// Stores a failed action and returns the new entry
+    pub fn push(
+        &self,
+        action: &Action,
+        error: String,
+        attempts: u32,
+        first_attempt_epoch_ms: i64,
+    ) -> Result<DeadLetter, TornadoError> {
+        let last_attempt_epoch_ms = chrono::Utc::now().timestamp_millis();
+        // The lock is held until the entry is written, so that concurrent pushes
+        // cannot exceed the maximum number of entries
+        let mut last_id = self.last_id.lock().map_err(|err| dead_letter_error(&err))?;
+        if self.size() >= self.max_entries {
+            self.drop_oldest(self.max_entries - 1)?;
+        }
+        // The ids are increasing, so that the entries are listed in insertion order
+        *last_id = (*last_id + 1).max(last_attempt_epoch_ms as u64);
+        let id = *last_id;
+        let dead_letter = DeadLetter {
+            id,
+            action: action.clone(),
+            error,
+            attempts,
+            first_attempt_epoch_ms,
+            last_attempt_epoch_ms,
+        };
+        let content =
+            serde_json::to_vec_pretty(&dead_letter).map_err(|err| dead_letter_error(&err))?;
+        self.write_atomically(id, &content).map_err(|err| dead_letter_error(&err))?;
+        self.size.fetch_add(1, Ordering::SeqCst);
+        Ok(dead_letter)
+    }
+
+    /// Returns all the entries, the oldest first.
+    /// The entries that cannot be read are logged and skipped.
+    pub fn list(&self) -> Result<Vec<DeadLetter>, TornadoError> {
+        let mut dead_letters = vec![];
+        for id in read_ids(&self.dir)? {
+            match self.read(id) {
+                Ok(Some(Ok(dead_letter))) => dead_letters.push(dead_letter),
+                Ok(Some(Err(err))) => self.quarantine(id, &err),
+                Ok(None) => {}
+                Err(err) => {
+                    warn!("Cannot read the dead-letter queue entry [{}]. Err: {:?}", id, err)
+                }
+            }
+        }
+        dead_letters.sort_by_key(|dead_letter| dead_letter.id);
+        Ok(dead_letters)
+    }
+
+    pub fn get(&self, id: u64) -> Result<Option<DeadLetter>, TornadoError> {
+        match self.read(id)? {
+            Some(dead_letter) => dead_letter.map(Some).map_err(|err| dead_letter_error(&err)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads an entry. The outer error is an I/O error, the inner one a parsing error.
+    fn read(&self, id: u64) -> Result<Option<Result<DeadLetter, serde_json::Error>>, TornadoError> {
+        match std::fs::read(self.path(id)) {
+            Ok(content) => Ok(Some(serde_json::from_slice(&content))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(dead_letter_error(&err)),
+        }
+    }
+
+    /// Removes an entry and returns it
+    pub fn remove(&self, id: u64) -> Result<Option<DeadLetter>, TornadoError> {
+        let dead_letter = self.get(id)?;
+        if dead_letter.is_some() {
+            match std::fs::remove_file(self.path(id)) {
+                Ok(()) => {
+                    self.size.fetch_sub(1, Ordering::SeqCst);
+                }
+                // Removed in the meantime by another request
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(dead_letter_error(&err)),
+            }
+        }
+        Ok(dead_letter)
+    }
+
+    /// Removes all the entries and returns how many they were.
+    /// The entries that cannot be read are logged and skipped.
+    pub fn purge(&self) -> Result<usize, TornadoError> {
+        let mut purged = 0;
+        for id in read_ids(&self.dir)? {
+            match self.read(id) {
+                Ok(Some(Err(err))) => self.quarantine(id, &err),
+                Ok(_) => match self.remove(id) {
+                    Ok(Some(_)) => purged += 1,
+                    Ok(None) => {}
+                    Err(err) => {
+                        warn!("Cannot remove the dead-letter queue entry [{}]. Err: {:?}", id, err)
+                    }
+                },
+                Err(err) => {
+                    warn!("Cannot read the dead-letter queue entry [{}]. Err: {:?}", id, err)
+                }
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Returns the number of entries in the queue
+    pub fn size(&self) -> usize {
+        self.size.load(Ordering::SeqCst)
+    }
+
+    /// Removes the oldest entries until the queue has at most `max_size` entries
+    fn drop_oldest(&self, max_size: usize) -> Result<(), TornadoError> {
+        let mut ids = read_ids(&self.dir)?;
+        ids.sort_unstable();
+        let exceeding = ids.len().saturating_sub(max_size);
+        for id in ids.into_iter().take(exceeding) {
+            match std::fs::remove_file(self.path(id)) {
+                Ok(()) => {
+                    self.size.fetch_sub(1, Ordering::SeqCst);
+                    warn!(
+                        "The dead-letter queue reached its maximum of [{}] entries. The oldest entry [{}] was dropped.",
+                        self.max_entries, id
+                    );
+                }
+                // Removed in the meantime by another request
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(dead_letter_error(&err)),
+            }
+        }
+        Ok(())
+    }
+
+    fn path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{}.{}", id, DEAD_LETTER_FILE_EXTENSION))
+    }
+
+    /// Writes the entry to a temporary file in the same folder, syncs it to disk
+    /// and renames it, so that the entry is either fully written or missing
+    fn write_atomically(&self, id: u64, content: &[u8]) -> std::io::Result<()> {
+        let path = self.path(id);
+        let temp_path =
+            path.with_extension(format!("{}.{}", DEAD_LETTER_FILE_EXTENSION, TEMP_FILE_EXTENSION));
+        let result = (|| {
+            let mut file = std::fs::File::create(&temp_path)?;
+            file.write_all(content)?;
+            file.sync_all()?;
+            std::fs::rename(&temp_path, &path)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
+
+    /// Moves an unparsable entry out of the queue, keeping it on disk for inspection
+    fn quarantine(&self, id: u64, err: &serde_json::Error) {
+        let path = self.path(id);
+        let corrupted_path = path
+            .with_extension(format!("{}.{}", DEAD_LETTER_FILE_EXTENSION, CORRUPTED_FILE_EXTENSION));
+        match std::fs::rename(&path, &corrupted_path) {
+            Ok(()) => {
+                self.size.fetch_sub(1, Ordering::SeqCst);
+                warn!(
+                    "The dead-letter queue entry [{}] cannot be parsed and was moved to {:?}. Err: {:?}",
+                    id, corrupted_path, err
+                );
+            }
+            Err(rename_err) => error!(
+                "The dead-letter queue entry [{}] cannot be parsed and cannot be moved to {:?}. Err: {:?}. Rename err: {:?}",
+                id, corrupted_path, err, rename_err
+            ),
+        }
+    }
+}
+
+/// The entry is written synchronously on the thread of the calling executor, which is
+/// blocked until the file is synced to disk. This happens only when an action is given up.
+impl<E: Display> DeadLetterSink<Arc<Action>, E> for DeadLetterQueue {
+    fn push(&self, message: &Arc<Action>, error: &E, attempts: u32, first_attempt_epoch_ms: i64) {
+        match DeadLetterQueue::push(
+            self,
+            message,
+            error.to_string(),
+            attempts,
+            first_attempt_epoch_ms,
+        ) {
+            Ok(dead_letter) => warn!(
+                "The failed action [{}] was stored in the dead-letter queue with id [{}]",
+                message.id, dead_letter.id
+            ),
+            Err(err) => error!(
+                "Cannot store the failed action [{}] in the dead-letter queue. The action is lost. Err: {:?}",
+                message.id, err
+            ),
+        }
+    }
+}
+
+/// Returns the ids of the entries stored in the folder
+fn read_ids(dir: &Path) -> Result<Vec<u64>, TornadoError> {
+    let mut ids = vec![];
+    for entry in std::fs::read_dir(dir).map_err(|err| dead_letter_error(&err))? {
+        let path = entry.map_err(|err| dead_letter_error(&err))?.path();
+        if path.extension().and_then(|extension| extension.to_str())
+            == Some(DEAD_LETTER_FILE_EXTENSION)
+        {
+            if let Some(id) =
+                path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok())
+            {
+                ids.push(id);
+            }
+        }
+    }
+    Ok(ids)
+}
+
+fn dead_letter_error<E: std::fmt::Debug>(err: &E) -> TornadoError {
+    TornadoError::DeadLetterQueueError { message: format!("{:?}", err) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_store_list_and_remove_the_dead_letters() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let queue = DeadLetterQueue::new(tempdir.path(), 10).unwrap();
+
+        // Act
+        let first = queue.push(&Action::new("first"), "error one".to_owned(), 3, 100).unwrap();
+        let second = queue.push(&Action::new("second"), "error two".to_owned(), 1, 200).unwrap();
+
+        // Assert
+        assert!(first.id < second.id);
+        assert_eq!(2, queue.size());
+        assert_eq!(vec![first.clone(), second.clone()], queue.list().unwrap());
+        assert_eq!(Some(first.clone()), queue.get(first.id).unwrap());
+        assert_eq!("error one", first.error);
+        assert_eq!(3, first.attempts);
+        assert_eq!(100, first.first_attempt_epoch_ms);
+
+        assert_eq!(Some(first.clone()), queue.remove(first.id).unwrap());
+        assert_eq!(None, queue.remove(first.id).unwrap());
+        assert_eq!(None, queue.get(first.id).unwrap());
+        assert_eq!(1, queue.size());
+    }
+
+    #[test]
+    fn should_reopen_and_purge_the_queue() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let last = {
+            let queue = DeadLetterQueue::new(tempdir.path(), 10).unwrap();
+            queue.push(&Action::new("first"), "error".to_owned(), 1, 0).unwrap();
+            queue.push(&Action::new("second"), "error".to_owned(), 1, 0).unwrap()
+        };
+        std::fs::write(tempdir.path().join("not_a_dead_letter.txt"), "").unwrap();
+
+        // Act
+        let queue = DeadLetterQueue::new(tempdir.path(), 10).unwrap();
+        let new = queue.push(&Action::new("third"), "error".to_owned(), 1, 0).unwrap();
+
+        // Assert
+        assert!(new.id > last.id);
+        assert_eq!(3, queue.size());
+        assert_eq!(3, queue.purge().unwrap());
+        assert_eq!(0, queue.size());
+        assert!(queue.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_drop_the_oldest_entries_when_the_queue_is_full() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let queue = DeadLetterQueue::new(tempdir.path(), 2).unwrap();
+        let first = queue.push(&Action::new("first"), "error".to_owned(), 1, 0).unwrap();
+        let second = queue.push(&Action::new("second"), "error".to_owned(), 1, 0).unwrap();
+
+        // Act
+        let third = queue.push(&Action::new("third"), "error".to_owned(), 1, 0).unwrap();
+
+        // Assert
+        assert_eq!(2, queue.size());
+        assert_eq!(None, queue.get(first.id).unwrap());
+        assert_eq!(vec![second, third], queue.list().unwrap());
+    }
+
+    #[test]
+    fn should_drop_the_oldest_entries_exceeding_the_maximum_when_opened() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let queue = DeadLetterQueue::new(tempdir.path(), 10).unwrap();
+        queue.push(&Action::new("first"), "error".to_owned(), 1, 0).unwrap();
+        queue.push(&Action::new("second"), "error".to_owned(), 1, 0).unwrap();
+        let third = queue.push(&Action::new("third"), "error".to_owned(), 1, 0).unwrap();
+
+        // Act
+        let queue = DeadLetterQueue::new(tempdir.path(), 1).unwrap();
+
+        // Assert
+        assert_eq!(1, queue.size());
+        assert_eq!(vec![third], queue.list().unwrap());
+        assert!(DeadLetterQueue::new(tempdir.path(), 0).is_err());
+    }
+
+    #[test]
+    fn should_quarantine_the_unparsable_entries() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let queue = DeadLetterQueue::new(tempdir.path(), 10).unwrap();
+        let first = queue.push(&Action::new("first"), "error".to_owned(), 1, 0).unwrap();
+        let second = queue.push(&Action::new("second"), "error".to_owned(), 1, 0).unwrap();
+        std::fs::write(queue.path(first.id), "{ \"id\": ").unwrap();
+        let queue = DeadLetterQueue::new(tempdir.path(), 10).unwrap();
+
+        // Act
+        let get_result = queue.get(first.id);
+        let dead_letters = queue.list().unwrap();
+
+        // Assert
+        assert!(get_result.is_err());
+        assert_eq!(vec![second], dead_letters);
+        assert_eq!(1, queue.size());
+        assert!(tempdir.path().join(format!("{}.json.corrupted", first.id)).exists());
+        assert_eq!(1, queue.purge().unwrap());
+        assert_eq!(0, queue.size());
+    }
+
+    #[test]
+    fn should_purge_the_queue_with_unparsable_entries() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let queue = DeadLetterQueue::new(tempdir.path(), 10).unwrap();
+        let first = queue.push(&Action::new("first"), "error".to_owned(), 1, 0).unwrap();
+        queue.push(&Action::new("second"), "error".to_owned(), 1, 0).unwrap();
+        std::fs::write(queue.path(first.id), "").unwrap();
+
+        // Act
+        let purged = queue.purge().unwrap();
+
+        // Assert
+        assert_eq!(1, purged);
+        assert_eq!(0, queue.size());
+        assert!(queue.list().unwrap().is_empty());
+        assert_eq!(1, std::fs::read_dir(tempdir.path()).unwrap().count());
+    }
+}
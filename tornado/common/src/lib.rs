@@ -2,6 +2,7 @@ use thiserror::Error;
 
 pub mod actors;
 pub mod command;
+pub mod dead_letter;
 pub mod metrics;
 
 #[derive(Error, Debug)]
@@ -14,6 +15,8 @@ pub enum TornadoError {
     ConfigurationError { message: String },
     #[error("ExecutionError: {message}")]
     ExecutionError { message: String },
+    #[error("DeadLetterQueueError: {message}")]
+    DeadLetterQueueError { message: String },
 }
 
 #[cfg(test)]
@@ -1,5 +1,8 @@
 use crate::actor::dispatcher::ProcessedEventMessage;
-use crate::monitoring::metrics::{TornadoMeter, EVENT_TYPE_LABEL_KEY};
+use crate::actor::shadow::ShadowDeployment;
+use crate::monitoring::metrics::{
+    MatcherStatsLabels, MatcherStatsObservers, TornadoMeter, EVENT_TYPE_LABEL_KEY,
+};
 use actix::prelude::*;
 use log::*;
 use std::collections::HashMap;
@@ -12,6 +15,8 @@ use tornado_engine_matcher::config::{MatcherConfig, MatcherConfigEditor};
 use tornado_engine_matcher::error::MatcherError;
 use tornado_engine_matcher::matcher::Matcher;
 use tornado_engine_matcher::model::ProcessedEvent;
+use tornado_engine_matcher::shadow::ShadowStats;
+use tornado_engine_matcher::stats::{MatcherStats, StatsLayout};
 use tornado_engine_matcher::{error, matcher};
 use tracing::{instrument, Span};
 
@@ -22,6 +27,7 @@ pub struct EventMessageWithReply {
     pub config_filter: HashMap<String, NodeFilter>,
     pub process_type: ProcessType,
     pub include_metadata: bool,
+    pub explain: bool,
     pub span: Span,
 }
 
@@ -32,6 +38,7 @@ pub struct EventMessageAndConfigWithReply {
     pub matcher_config: MatcherConfig,
     pub process_type: ProcessType,
     pub include_metadata: bool,
+    pub explain: bool,
 }
 
 #[derive(Message)]
@@ -49,12 +56,40 @@ pub struct ReconfigureMessage {}
 #[rtype(result = "Arc<MatcherConfig>")]
 pub struct GetCurrentConfigMessage {}
 
+#[derive(Message)]
+#[rtype(result = "Arc<MatcherStats>")]
+pub struct GetMatcherStatsMessage {}
+
+/// Starts processing the live events also with the configuration of a draft.
+/// A shadow deployment already running is replaced.
+#[derive(Message)]
+#[rtype(result = "Result<(), error::MatcherError>")]
+pub struct StartShadowMessage {
+    pub draft_id: String,
+    pub matcher_config: MatcherConfig,
+}
+
+/// Stops the shadow deployment and returns its statistics, if it was running.
+#[derive(Message)]
+#[rtype(result = "Option<Arc<ShadowStats>>")]
+pub struct StopShadowMessage {}
+
+#[derive(Message)]
+#[rtype(result = "Option<Arc<ShadowStats>>")]
+pub struct GetShadowStatsMessage {}
+
 pub struct MatcherActor {
     dispatcher_addr: Recipient<ProcessedEventMessage>,
     matcher_config_manager: Arc<dyn MatcherConfigEditor>,
     matcher_config: Arc<MatcherConfig>,
     matcher: Arc<matcher::Matcher>,
     meter: Arc<TornadoMeter>,
+    stats: Arc<MatcherStats>,
+    stats_layout: Arc<StatsLayout>,
+    stats_labels: MatcherStatsLabels,
+    _stats_observers: MatcherStatsObservers,
+    message_mailbox_capacity: usize,
+    shadow: Option<ShadowDeployment>,
 }
 
 impl MatcherActor {
@@ -66,21 +101,46 @@ impl MatcherActor {
     ) -> Result<Addr<MatcherActor>, MatcherError> {
         let matcher_config = Arc::new(matcher_config_manager.get_config().await?);
         let matcher = Arc::new(Matcher::build(&matcher_config)?);
+        let stats_layout = Arc::new(StatsLayout::new(&matcher_config));
+        let stats = Arc::new(MatcherStats::new(stats_layout.clone()));
+        let stats_labels = MatcherStatsLabels::new(&stats_layout);
+        let stats_observers = MatcherStatsObservers::new(stats.clone());
 
         Ok(actix::Supervisor::start(move |ctx: &mut Context<MatcherActor>| {
             ctx.set_mailbox_capacity(message_mailbox_capacity);
-            MatcherActor { dispatcher_addr, matcher_config_manager, matcher_config, matcher, meter }
+            MatcherActor {
+                dispatcher_addr,
+                matcher_config_manager,
+                matcher_config,
+                matcher,
+                meter,
+                stats,
+                stats_layout,
+                stats_labels,
+                _stats_observers: stats_observers,
+                message_mailbox_capacity,
+                shadow: None,
+            }
         }))
     }
 
+    /// Updates the per-node and per-rule statistics with the result of an event processed
+    /// by the active configuration.
+    fn record_stats(&self, processed_event: &ProcessedEvent) {
+        let updates = self.stats_layout.updates(&processed_event.result);
+        self.stats.record(&updates, chrono::Utc::now().timestamp_millis());
+        self.meter.record_matcher_stats(&self.stats_labels, &updates);
+    }
+
     fn process_event_with_reply(
         &self,
         matcher: &Matcher,
         event: Value,
         process_type: ProcessType,
         include_metadata: bool,
+        explain: bool,
     ) -> ProcessedEvent {
-        let processed_event = self.process(matcher, event, include_metadata);
+        let processed_event = self.process(matcher, event, include_metadata, explain);
 
         match process_type {
             ProcessType::Full => self
@@ -94,7 +154,13 @@ impl MatcherActor {
 
     #[inline]
     #[instrument(level = "info", name = "Match against Processing Tree", skip_all)]
-    fn process(&self, matcher: &Matcher, event: Value, include_metadata: bool) -> ProcessedEvent {
+    fn process(
+        &self,
+        matcher: &Matcher,
+        event: Value,
+        include_metadata: bool,
+        explain: bool,
+    ) -> ProcessedEvent {
         let timer = SystemTime::now();
         let labels = [EVENT_TYPE_LABEL_KEY.string(
             event
@@ -103,7 +169,8 @@ impl MatcherActor {
                 .unwrap_or_else(|| "".to_owned()),
         )];
 
-        let process = matcher.process(event, include_metadata);
+        let process =
+            if explain { matcher.explain(event) } else { matcher.process(event, include_metadata) };
 
         self.meter.events_processed_counter.add(1, &labels);
         self.meter
@@ -134,7 +201,11 @@ impl Handler<EventMessage> for MatcherActor {
         let _g = msg.span.clone().entered();
         trace!("MatcherActor - received new EventMessage [{:?}]", &msg.event);
 
-        let processed_event = self.process(&self.matcher, msg.event, false);
+        let processed_event = self.process(&self.matcher, msg.event, false, false);
+        self.record_stats(&processed_event);
+        if let Some(shadow) = &self.shadow {
+            shadow.send(processed_event.clone());
+        }
         self.dispatcher_addr.try_send(ProcessedEventMessage { span: msg.span, event: processed_event }).unwrap_or_else(|err| error!("MatcherActor -  Error while sending ProcessedEventMessage to DispatcherActor. Error: {}", err));
         Ok(())
     }
@@ -153,12 +224,19 @@ impl Handler<EventMessageWithReply> for MatcherActor {
             })?;
         let matcher = Matcher::build(&filtered_config)?;
 
-        Ok(self.process_event_with_reply(
+        // Test events that skip the actions are not taken into account by the statistics
+        let record_stats = msg.process_type == ProcessType::Full;
+        let processed_event = self.process_event_with_reply(
             &matcher,
             msg.event,
             msg.process_type,
             msg.include_metadata,
-        ))
+            msg.explain,
+        );
+        if record_stats {
+            self.record_stats(&processed_event);
+        }
+        Ok(processed_event)
     }
 }
 
@@ -178,6 +256,7 @@ impl Handler<EventMessageAndConfigWithReply> for MatcherActor {
             msg.event,
             msg.process_type,
             msg.include_metadata,
+            msg.explain,
         ))
     }
 }
@@ -191,6 +270,45 @@ impl Handler<GetCurrentConfigMessage> for MatcherActor {
     }
 }
 
+impl Handler<GetMatcherStatsMessage> for MatcherActor {
+    type Result = Arc<MatcherStats>;
+
+    fn handle(&mut self, _msg: GetMatcherStatsMessage, _: &mut Context<Self>) -> Self::Result {
+        trace!("MatcherActor - received new GetMatcherStatsMessage");
+        self.stats.clone()
+    }
+}
+
+impl Handler<StartShadowMessage> for MatcherActor {
+    type Result = Result<(), error::MatcherError>;
+
+    fn handle(&mut self, msg: StartShadowMessage, _: &mut Context<Self>) -> Self::Result {
+        info!("MatcherActor - Starting the shadow deployment of draft [{}]", msg.draft_id);
+        let matcher = Matcher::build(&msg.matcher_config)?;
+        let stats = Arc::new(ShadowStats::new(msg.draft_id, chrono::Utc::now().timestamp_millis()));
+        self.shadow = Some(ShadowDeployment::start(matcher, stats, self.message_mailbox_capacity));
+        Ok(())
+    }
+}
+
+impl Handler<StopShadowMessage> for MatcherActor {
+    type Result = Option<Arc<ShadowStats>>;
+
+    fn handle(&mut self, _msg: StopShadowMessage, _: &mut Context<Self>) -> Self::Result {
+        trace!("MatcherActor - received new StopShadowMessage");
+        self.shadow.take().map(|shadow| shadow.stats.clone())
+    }
+}
+
+impl Handler<GetShadowStatsMessage> for MatcherActor {
+    type Result = Option<Arc<ShadowStats>>;
+
+    fn handle(&mut self, _msg: GetShadowStatsMessage, _: &mut Context<Self>) -> Self::Result {
+        trace!("MatcherActor - received new GetShadowStatsMessage");
+        self.shadow.as_ref().map(|shadow| shadow.stats.clone())
+    }
+}
+
 impl Handler<ReconfigureMessage> for MatcherActor {
     type Result = ResponseActFuture<Self, Result<Arc<MatcherConfig>, error::MatcherError>>;
 
@@ -202,13 +320,18 @@ impl Handler<ReconfigureMessage> for MatcherActor {
             async move {
                 let matcher_config = Arc::new(matcher_config_manager.get_config().await?);
                 let matcher = Arc::new(Matcher::build(&matcher_config)?);
-                Ok((matcher, matcher_config))
+                let stats_layout = Arc::new(StatsLayout::new(&matcher_config));
+                Ok((matcher, matcher_config, stats_layout))
             }
             .into_actor(self) // converts future to ActorFuture
             .map(|result, this, _ctx| match result {
-                Ok((matcher, matcher_config)) => {
+                Ok((matcher, matcher_config, stats_layout)) => {
                     this.matcher_config = matcher_config.clone();
                     this.matcher = matcher;
+                    // The statistics of the removed nodes and rules are dropped
+                    this.stats.reconfigure(stats_layout.clone());
+                    this.stats_labels = MatcherStatsLabels::new(&stats_layout);
+                    this.stats_layout = stats_layout;
                     info!("MatcherActor - Tornado configuration updated successfully.");
                     Ok(matcher_config)
                 }
@@ -263,6 +386,57 @@ mod test {
         assert_eq!(config_from_response, draft.config);
     }
 
+    #[actix::test]
+    async fn should_process_the_live_events_with_the_draft_in_shadow() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let config_manager = configs.matcher_config.clone();
+        let dispatcher_addr = FakeDispatcher {}.start().recipient();
+        let matcher_actor =
+            MatcherActor::start(dispatcher_addr, config_manager.clone(), 10, Default::default())
+                .await
+                .unwrap();
+
+        let draft_id = config_manager.create_draft("user_1".to_owned()).await.unwrap();
+        let draft = config_manager.get_draft(&draft_id).await.unwrap();
+
+        // Act
+        matcher_actor
+            .send(StartShadowMessage { draft_id: draft_id.clone(), matcher_config: draft.config })
+            .await
+            .unwrap()
+            .unwrap();
+        matcher_actor
+            .send(EventMessage { event: json!(Event::new("test")), span: Span::current() })
+            .await
+            .unwrap()
+            .unwrap();
+
+        let stats = matcher_actor.send(GetShadowStatsMessage {}).await.unwrap().unwrap();
+        let mut report = stats.report(&[]);
+        for _ in 0..100 {
+            if report.processed_events + report.skipped_events > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            report = stats.report(&[]);
+        }
+
+        let stopped = matcher_actor.send(StopShadowMessage {}).await.unwrap();
+        let after_stop = matcher_actor.send(GetShadowStatsMessage {}).await.unwrap();
+
+        // Assert
+        assert_eq!(draft_id, report.draft_id);
+        assert_eq!(1, report.processed_events);
+        assert_eq!(0, report.changed_events);
+        assert!(stopped.is_some());
+        assert!(after_stop.is_none());
+    }
+
     #[actix::test]
     async fn should_return_the_current_config() {
         // Arrange
@@ -312,6 +486,7 @@ mod test {
                 event,
                 config_filter,
                 include_metadata: false,
+                explain: false,
                 process_type: ProcessType::Full,
                 span: Span::current(),
             })
@@ -373,6 +548,7 @@ mod test {
                 event: event_tenant_alpha,
                 config_filter: config_filter.clone(),
                 include_metadata: false,
+                explain: false,
                 process_type: ProcessType::Full,
                 span: Span::current(),
             })
@@ -385,6 +561,7 @@ mod test {
                 event: event_tenant_beta,
                 config_filter: config_filter.clone(),
                 include_metadata: false,
+                explain: false,
                 process_type: ProcessType::Full,
                 span: Span::current(),
             })
@@ -451,6 +628,7 @@ mod test {
                     ]),
                 ],
                 include_metadata: false,
+                explain: false,
                 process_type: ProcessType::Full,
                 span: Span::current(),
             })
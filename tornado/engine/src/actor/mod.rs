@@ -1,3 +1,4 @@
 pub mod dispatcher;
 pub mod foreach;
 pub mod matcher;
+pub mod shadow;
@@ -0,0 +1,93 @@
+use actix::prelude::*;
+use log::*;
+use std::sync::Arc;
+use tornado_engine_matcher::matcher::Matcher;
+use tornado_engine_matcher::model::ProcessedEvent;
+use tornado_engine_matcher::shadow::ShadowStats;
+
+/// A live event together with the outcome of the active configuration.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct ShadowEventMessage {
+    pub processed_event: ProcessedEvent,
+    pub received_epoch_ms: i64,
+}
+
+/// Processes the live events with the Matcher of a draft and compares the outcome with the one
+/// of the active configuration. The actions of the draft are never dispatched.
+pub struct ShadowActor {
+    matcher: Matcher,
+    stats: Arc<ShadowStats>,
+}
+
+/// A draft running in shadow. The ShadowActor runs in its own Arbiter, so that it does not
+/// slow down the processing of the active configuration; when its mailbox is full,
+/// the events are skipped.
+pub struct ShadowDeployment {
+    addr: Addr<ShadowActor>,
+    arbiter: Arbiter,
+    pub stats: Arc<ShadowStats>,
+}
+
+impl ShadowDeployment {
+    pub fn start(
+        matcher: Matcher,
+        stats: Arc<ShadowStats>,
+        message_mailbox_capacity: usize,
+    ) -> Self {
+        let arbiter = Arbiter::new();
+        let actor_stats = stats.clone();
+        let addr = ShadowActor::start_in_arbiter(&arbiter.handle(), move |ctx| {
+            ctx.set_mailbox_capacity(message_mailbox_capacity);
+            ShadowActor { matcher, stats: actor_stats }
+        });
+        Self { addr, arbiter, stats }
+    }
+
+    pub fn send(&self, processed_event: ProcessedEvent) {
+        let message = ShadowEventMessage {
+            processed_event,
+            received_epoch_ms: chrono::Utc::now().timestamp_millis(),
+        };
+        if let Err(err) = self.addr.try_send(message) {
+            trace!(
+                "ShadowDeployment - Skipping event for draft [{}]: {}",
+                self.stats.draft_id(),
+                err
+            );
+            self.stats.record_skipped();
+        }
+    }
+}
+
+impl Drop for ShadowDeployment {
+    fn drop(&mut self) {
+        info!(
+            "ShadowDeployment - Stopping the shadow deployment of draft [{}]",
+            self.stats.draft_id()
+        );
+        self.arbiter.stop();
+    }
+}
+
+impl Actor for ShadowActor {
+    type Context = Context<Self>;
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        debug!("ShadowActor started for draft [{}].", self.stats.draft_id());
+    }
+}
+
+impl Handler<ShadowEventMessage> for ShadowActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: ShadowEventMessage, _: &mut Context<Self>) -> Self::Result {
+        let processed_event = msg.processed_event;
+        let shadow = self.matcher.process(processed_event.event.clone(), false);
+        self.stats.record(
+            &processed_event.event,
+            &processed_event.result,
+            &shadow.result,
+            msg.received_epoch_ms,
+        );
+    }
+}
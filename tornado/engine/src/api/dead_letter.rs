@@ -0,0 +1,159 @@
+use crate::executor::ExecutorRegistry;
+use async_trait::async_trait;
+use log::*;
+use std::sync::Arc;
+use tornado_common::actors::message::ActionMessage;
+use tornado_common::dead_letter::{DeadLetter, DeadLetterQueue};
+use tornado_common::TornadoError;
+use tornado_common_api::TracedAction;
+use tornado_engine_api::dead_letter::api::DeadLetterApiHandler;
+use tornado_engine_api::error::ApiError;
+use tornado_engine_api_dto::dead_letter::{DeadLetterDto, DeadLetterSummaryDto};
+
+#[derive(Clone)]
+pub struct DeadLetterApiHandlerImpl {
+    dead_letter_queue: Arc<DeadLetterQueue>,
+    executor_registry: Arc<ExecutorRegistry>,
+}
+
+impl DeadLetterApiHandlerImpl {
+    pub fn new(
+        dead_letter_queue: Arc<DeadLetterQueue>,
+        executor_registry: Arc<ExecutorRegistry>,
+    ) -> Self {
+        Self { dead_letter_queue, executor_registry }
+    }
+}
+
+#[async_trait(?Send)]
+impl DeadLetterApiHandler for DeadLetterApiHandlerImpl {
+    async fn get_dead_letters(&self) -> Result<Vec<DeadLetterSummaryDto>, ApiError> {
+        Ok(self
+            .dead_letter_queue
+            .list()
+            .map_err(into_api_error)?
+            .into_iter()
+            .map(dead_letter_into_summary_dto)
+            .collect())
+    }
+
+    async fn get_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError> {
+        Ok(self.dead_letter_queue.get(id).map_err(into_api_error)?.map(dead_letter_into_dto))
+    }
+
+    async fn retry_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError> {
+        // The entry is removed before sending the action, so that it is never retried twice
+        let dead_letter = match self.dead_letter_queue.remove(id).map_err(into_api_error)? {
+            Some(dead_letter) => dead_letter,
+            None => return Ok(None),
+        };
+        info!(
+            "DeadLetterApiHandlerImpl - retry action [{}] of dead letter [{}]",
+            dead_letter.action.id, id
+        );
+        let message = ActionMessage(TracedAction {
+            span: tracing::info_span!("Retry action of the dead-letter queue", dead_letter_id = id),
+            action: Arc::new(dead_letter.action.clone()),
+        });
+        if let Err(cause) = self.executor_registry.send(message) {
+            self.dead_letter_queue
+                .push(
+                    &dead_letter.action,
+                    cause.clone(),
+                    dead_letter.attempts,
+                    dead_letter.first_attempt_epoch_ms,
+                )
+                .map_err(into_api_error)?;
+            return Err(ApiError::BadRequestError { cause });
+        }
+        Ok(Some(dead_letter_into_dto(dead_letter)))
+    }
+
+    async fn remove_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError> {
+        info!("DeadLetterApiHandlerImpl - remove dead letter [{}]", id);
+        Ok(self.dead_letter_queue.remove(id).map_err(into_api_error)?.map(dead_letter_into_dto))
+    }
+
+    async fn purge_dead_letters(&self) -> Result<usize, ApiError> {
+        info!("DeadLetterApiHandlerImpl - purge the dead-letter queue");
+        self.dead_letter_queue.purge().map_err(into_api_error)
+    }
+}
+
+fn into_api_error(err: TornadoError) -> ApiError {
+    ApiError::InternalServerError { cause: format!("{}", err) }
+}
+
+fn dead_letter_into_summary_dto(dead_letter: DeadLetter) -> DeadLetterSummaryDto {
+    DeadLetterSummaryDto {
+        id: dead_letter.id,
+        action_id: dead_letter.action.id,
+        error: dead_letter.error,
+        attempts: dead_letter.attempts,
+        first_attempt_epoch_ms: dead_letter.first_attempt_epoch_ms,
+        last_attempt_epoch_ms: dead_letter.last_attempt_epoch_ms,
+    }
+}
+
+fn dead_letter_into_dto(dead_letter: DeadLetter) -> DeadLetterDto {
+    DeadLetterDto {
+        id: dead_letter.id,
+        action_id: dead_letter.action.id,
+        action_payload: serde_json::Value::Object(dead_letter.action.payload),
+        action_created_ms: dead_letter.action.created_ms,
+        error: dead_letter.error,
+        attempts: dead_letter.attempts,
+        first_attempt_epoch_ms: dead_letter.first_attempt_epoch_ms,
+        last_attempt_epoch_ms: dead_letter.last_attempt_epoch_ms,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::executor::{ExecutorConfig, ExecutorSettings, ExecutorType};
+    use tornado_common::command::retry::RetryStrategy;
+    use tornado_common::metrics::ActionMeter;
+    use tornado_common_api::Action;
+
+    #[actix_rt::test]
+    async fn should_retry_the_dead_letters() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dead_letter_queue = Arc::new(DeadLetterQueue::new(tempdir.path(), 10).unwrap());
+        let configs = crate::config::parse_config_files("./config", "/rules.d", "/drafts").unwrap();
+        let executor_registry = ExecutorRegistry::start(
+            &[ExecutorConfig::new("logger", ExecutorType::Logger)],
+            &configs,
+            &ExecutorSettings {
+                retry_strategy: RetryStrategy::default(),
+                threads_per_queue: 1,
+                message_queue_size: 10,
+                action_meter: Arc::new(ActionMeter::new("test")),
+                dead_letter_queue: Some(dead_letter_queue.clone()),
+            },
+        )
+        .await
+        .unwrap();
+        let handler =
+            DeadLetterApiHandlerImpl::new(dead_letter_queue.clone(), Arc::new(executor_registry));
+        let logger =
+            dead_letter_queue.push(&Action::new("logger"), "err".to_owned(), 3, 0).unwrap();
+        let unknown =
+            dead_letter_queue.push(&Action::new("unknown"), "err".to_owned(), 3, 0).unwrap();
+
+        // Act
+        let retried = handler.retry_dead_letter(logger.id).await.unwrap();
+        let not_found = handler.retry_dead_letter(logger.id).await.unwrap();
+        let failed = handler.retry_dead_letter(unknown.id).await;
+
+        // Assert
+        assert_eq!(Some(logger.id), retried.map(|dto| dto.id));
+        assert!(not_found.is_none());
+        assert!(failed.is_err());
+        let dead_letters = handler.get_dead_letters().await.unwrap();
+        assert_eq!(1, dead_letters.len());
+        assert_eq!("unknown", dead_letters[0].action_id);
+        assert_eq!(3, dead_letters[0].attempts);
+    }
+}
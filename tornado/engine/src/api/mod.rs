@@ -1,5 +1,6 @@
 use crate::actor::matcher::{
-    EventMessageAndConfigWithReply, EventMessageWithReply, MatcherActor, ReconfigureMessage,
+    EventMessageAndConfigWithReply, EventMessageWithReply, GetMatcherStatsMessage,
+    GetShadowStatsMessage, MatcherActor, ReconfigureMessage, StartShadowMessage, StopShadowMessage,
 };
 use crate::monitoring::metrics::{TornadoMeter, EVENT_SOURCE_LABEL_KEY, EVENT_TYPE_LABEL_KEY};
 use actix::Addr;
@@ -13,7 +14,10 @@ use tornado_engine_api::event::api::{EventApiHandler, SendEventRequest};
 use tornado_engine_matcher::config::operation::NodeFilter;
 use tornado_engine_matcher::config::MatcherConfig;
 use tornado_engine_matcher::model::ProcessedEvent;
+use tornado_engine_matcher::shadow::ShadowStats;
+use tornado_engine_matcher::stats::MatcherStats;
 
+pub mod dead_letter;
 pub mod runtime_config;
 
 #[derive(Clone)]
@@ -44,6 +48,7 @@ impl EventApiHandler for MatcherApiHandler {
                 config_filter,
                 process_type: event.process_type,
                 include_metadata: true,
+                explain: event.explain,
                 span: span.clone(),
             })
             .await?;
@@ -74,6 +79,7 @@ impl EventApiHandler for MatcherApiHandler {
                 process_type: event.process_type,
                 matcher_config,
                 include_metadata: true,
+                explain: event.explain,
             })
             .await?;
 
@@ -96,6 +102,26 @@ impl ConfigApiHandler for MatcherApiHandler {
             .as_ref()
             .clone())
     }
+
+    async fn get_matcher_stats(&self) -> Result<Arc<MatcherStats>, ApiError> {
+        Ok(self.matcher.send(GetMatcherStatsMessage {}).await?)
+    }
+
+    async fn start_shadow(&self, draft_id: &str, config: MatcherConfig) -> Result<(), ApiError> {
+        let request = self
+            .matcher
+            .send(StartShadowMessage { draft_id: draft_id.to_owned(), matcher_config: config })
+            .await?;
+        Ok(request?)
+    }
+
+    async fn stop_shadow(&self) -> Result<Option<Arc<ShadowStats>>, ApiError> {
+        Ok(self.matcher.send(StopShadowMessage {}).await?)
+    }
+
+    async fn get_shadow_stats(&self) -> Result<Option<Arc<ShadowStats>>, ApiError> {
+        Ok(self.matcher.send(GetShadowStatsMessage {}).await?)
+    }
 }
 
 impl MatcherApiHandler {
@@ -148,6 +174,7 @@ mod test {
         let send_event_request = SendEventRequest {
             process_type: ProcessType::SkipActions,
             event: Event::new("test-type"),
+            explain: false,
         };
 
         let mut config_filter = HashMap::new();
@@ -245,6 +272,7 @@ mod test {
         let send_event_request = SendEventRequest {
             process_type: ProcessType::SkipActions,
             event: Event::new("test-type-custom"),
+            explain: false,
         };
 
         let config = MatcherConfig::Ruleset {
@@ -281,6 +309,73 @@ mod test {
         }
     }
 
+    #[actix_rt::test]
+    async fn should_send_an_event_to_the_draft_and_return_the_explanation() {
+        // Arrange
+        let path = "./config/rules.d";
+        let config_manager = Arc::new(FsMatcherConfigManagerV2::new(path, ""));
+
+        let event_bus = Arc::new(ActixEventBus { callback: |_| {} });
+
+        let dispatcher_addr =
+            DispatcherActor::start_new(1, Dispatcher::build(event_bus.clone()).unwrap());
+
+        let matcher_addr = MatcherActor::start(
+            dispatcher_addr.clone().recipient(),
+            config_manager,
+            47,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let api = MatcherApiHandler { matcher: matcher_addr, meter: Default::default() };
+
+        let send_event_request = SendEventRequest {
+            process_type: ProcessType::SkipActions,
+            event: Event::new("test-type-custom"),
+            explain: true,
+        };
+
+        let config = MatcherConfig::Ruleset {
+            name: "custom_ruleset".to_owned(),
+            rules: vec![Rule {
+                name: "rule_1".to_owned(),
+                actions: vec![],
+                active: true,
+                description: "".to_owned(),
+                do_continue: true,
+                constraint: Constraint {
+                    where_operator: Some(Operator::Equals {
+                        first: Value::String("${event.type}".to_owned()),
+                        second: Value::String("another-type".to_owned()),
+                    }),
+                    with: HashMap::new(),
+                },
+            }],
+        };
+
+        // Act
+        let res = api.send_event_to_config(send_event_request, config).await.unwrap();
+
+        // Assert
+        match res.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                assert_eq!(ProcessedRuleStatus::NotMatched, rules.rules[0].status);
+                let explanation = rules.rules[0].meta.as_ref().unwrap().explain.as_ref().unwrap();
+                assert!(!explanation.operator.result);
+                assert_eq!(
+                    vec![
+                        Value::String("test-type-custom".to_owned()),
+                        Value::String("another-type".to_owned())
+                    ],
+                    explanation.operator.operands
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[actix_rt::test]
     async fn send_an_event_should_include_metadata() {
         // Arrange
@@ -308,7 +403,8 @@ mod test {
         event.metadata = Map::new();
         event.metadata.insert("tenant_id".to_owned(), Value::String("beta".to_owned()));
 
-        let send_event_request = SendEventRequest { process_type: ProcessType::SkipActions, event };
+        let send_event_request =
+            SendEventRequest { process_type: ProcessType::SkipActions, event, explain: false };
 
         let mut config_filter = HashMap::new();
         config_filter.insert(ROOT_NODE_NAME.to_owned(), NodeFilter::AllChildren);
@@ -0,0 +1,121 @@
+use crate::config::{parse_config_files, ExportOpt, ImportOpt};
+use tornado_engine_matcher::config::bundle::MatcherConfigBundle;
+
+pub async fn export_bundle(
+    config_dir: &str,
+    rules_dir: &str,
+    drafts_dir: &str,
+    opts: &ExportOpt,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let configs = parse_config_files(config_dir, rules_dir, drafts_dir)?;
+    let config = configs.matcher_config.get_config().await?;
+    let node_path = opts.node_path.split(',').collect::<Vec<_>>();
+    let bundle = MatcherConfigBundle::export(&config, &node_path)?;
+    let json = serde_json::to_string_pretty(&bundle)?;
+
+    match &opts.output {
+        Some(output) => {
+            std::fs::write(output, json)?;
+            println!(
+                "Exported the node {} with {} rules to {}",
+                opts.node_path, bundle.manifest.rules_count, output
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+pub async fn import_bundle(
+    config_dir: &str,
+    rules_dir: &str,
+    drafts_dir: &str,
+    opts: &ImportOpt,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let content = std::fs::read_to_string(&opts.file)?;
+    let bundle: MatcherConfigBundle = serde_json::from_str(&content)?;
+    println!(
+        "Importing the node {} with {} rules from {}",
+        bundle.manifest.node_path.join(","),
+        bundle.manifest.rules_count,
+        opts.file
+    );
+
+    let configs = parse_config_files(config_dir, rules_dir, drafts_dir)?;
+    let config_manager = configs.matcher_config;
+    let config = bundle.import_into(config_manager.get_config().await?)?;
+    config_manager.deploy_config(&config).await?;
+
+    println!("The bundle was imported and deployed.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::upgrade_rules::test::prepare_temp_dirs;
+
+    #[tokio::test]
+    async fn should_export_and_import_a_subtree() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let bundle_file = format!("{}/bundle.json", tempdir.path().display());
+        let config_manager =
+            parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap().matcher_config;
+        let config = config_manager.get_config().await.unwrap();
+
+        // Act
+        let export_opts = ExportOpt {
+            node_path: "root,tenant_id_alpha".to_owned(),
+            output: Some(bundle_file.clone()),
+        };
+        export_bundle(&config_dir, &rules_dir, &drafts_dir, &export_opts).await.unwrap();
+        config_manager
+            .deploy_config(&{
+                let mut config = config.clone();
+                config.delete_node_in_path(&["root", "tenant_id_alpha"]).unwrap();
+                config
+            })
+            .await
+            .unwrap();
+        let import_opts = ImportOpt { file: bundle_file.clone() };
+        import_bundle(&config_dir, &rules_dir, &drafts_dir, &import_opts).await.unwrap();
+
+        // Assert
+        let bundle: MatcherConfigBundle =
+            serde_json::from_str(&std::fs::read_to_string(&bundle_file).unwrap()).unwrap();
+        assert_eq!(vec!["root", "tenant_id_alpha"], bundle.manifest.node_path);
+        assert_eq!(
+            config.get_node_by_path(&["root", "tenant_id_alpha"]),
+            config_manager
+                .get_config()
+                .await
+                .unwrap()
+                .get_node_by_path(&["root", "tenant_id_alpha"])
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_import_a_corrupted_bundle() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let bundle_file = format!("{}/bundle.json", tempdir.path().display());
+        let config_manager =
+            parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap().matcher_config;
+        let config = config_manager.get_config().await.unwrap();
+        let mut bundle = MatcherConfigBundle::export(&config, &["root"]).unwrap();
+        bundle.config.delete_node_in_path(&["root", "tenant_id_alpha"]).unwrap();
+        std::fs::write(&bundle_file, serde_json::to_string(&bundle).unwrap()).unwrap();
+
+        // Act
+        let result =
+            import_bundle(&config_dir, &rules_dir, &drafts_dir, &ImportOpt { file: bundle_file })
+                .await;
+
+        // Assert
+        assert!(result.is_err());
+        assert_eq!(config, config_manager.get_config().await.unwrap());
+    }
+}
@@ -1,5 +1,10 @@
-use crate::config::parse_config_files;
+use crate::config::{build_config, parse_config_files};
+use crate::executor::{enabled_action_ids, validate_executors};
+use tornado_engine_matcher::error::MatcherError;
 use tornado_engine_matcher::matcher::Matcher;
+use tornado_engine_matcher::test_case::run_tests;
+use tornado_engine_matcher::validator::actions::ActionIds;
+use tornado_engine_matcher::validator::MatcherConfigValidator;
 
 pub async fn check(
     config_dir: &str,
@@ -8,8 +13,61 @@ pub async fn check(
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     println!("Check Tornado configuration");
     let configs = parse_config_files(config_dir, rules_dir, drafts_dir)?;
-    let _matcher =
-        configs.matcher_config.get_config().await.and_then(|config| Matcher::build(&config))?;
+    let executors = build_config(config_dir)?.tornado.daemon.get_executors();
+    validate_executors(&executors, &configs)?;
+    let config = configs.matcher_config.get_config().await?;
+    let matcher = Matcher::build(&config)?;
+    let validator = MatcherConfigValidator::new();
+    validator.validate_variable_references(&config)?;
+
+    let (action_ids, foreach_action_ids) = enabled_action_ids(&executors);
+    let action_ids = action_ids.iter().map(String::as_str).collect::<Vec<_>>();
+    let foreach_action_ids = foreach_action_ids.iter().map(String::as_str).collect::<Vec<_>>();
+    let unknown_actions = validator.unknown_action_errors(
+        &config,
+        &ActionIds { action_ids: &action_ids, foreach_action_ids: &foreach_action_ids },
+    );
+    for error in &unknown_actions {
+        println!(
+            "Error: [{}] rule [{}] {}: {}",
+            error.node_path.join(" -> "),
+            error.rule.as_deref().unwrap_or_default(),
+            error.pointer,
+            error.message
+        );
+    }
+    if !unknown_actions.is_empty() {
+        return Err(MatcherError::ConfigurationError {
+            message: format!("{} actions have no executor.", unknown_actions.len()),
+        }
+        .into());
+    }
+    for warning in validator.warnings(&config) {
+        println!("Warning: [{}] {}", warning.path.join(" -> "), warning.message);
+    }
+
+    let tests = configs.matcher_config.get_config_tests().await?;
+    let results = run_tests(&matcher, &tests);
+    let failed = results.iter().filter(|result| !result.is_success()).count();
+    for result in &results {
+        let outcome = if result.is_success() { "ok" } else { "FAILED" };
+        println!(
+            "Test [{}] of ruleset [{}]: {}",
+            result.test_name,
+            result.ruleset_path.join(" -> "),
+            outcome
+        );
+        for failure in &result.failures {
+            println!("    {}", failure);
+        }
+    }
+    if failed > 0 {
+        return Err(MatcherError::RuleTestsFailedError {
+            message: format!("{} of {} test cases failed.", failed, results.len()),
+        }
+        .into());
+    }
+
     println!("The configuration is correct.");
     Ok(())
 }
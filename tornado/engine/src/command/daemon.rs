@@ -1,28 +1,28 @@
 use crate::actor::dispatcher::{ActixEventBus, DispatcherActor};
-use crate::actor::foreach::{ForEachExecutorActor, ForEachExecutorActorInitMessage};
 use crate::actor::matcher::{EventMessage, MatcherActor};
+use crate::api::dead_letter::DeadLetterApiHandlerImpl;
 use crate::api::runtime_config::RuntimeConfigApiHandlerImpl;
 use crate::api::MatcherApiHandler;
 use crate::config;
-use crate::config::build_config;
+use crate::config::{build_config, MatcherConfigStorage};
+use crate::executor::{enabled_action_ids, ExecutorRegistry, ExecutorSettings};
 use crate::monitoring::endpoint::monitoring_endpoints;
 use crate::monitoring::metrics::{
-    TornadoMeter, EVENT_SOURCE_LABEL_KEY, EVENT_TYPE_LABEL_KEY, TORNADO_APP,
+    DeadLetterQueueObserver, TornadoMeter, EVENT_SOURCE_LABEL_KEY, EVENT_TYPE_LABEL_KEY,
+    TORNADO_APP,
 };
+use crate::rules_watcher::RulesWatcher;
 use actix_web::middleware::Logger;
 use actix_web::{web, App, HttpServer};
 use log::*;
 use serde_json::json;
-use std::rc::Rc;
 use std::sync::Arc;
-use tornado_common::actors::command::CommandExecutorActor;
+use std::time::Duration;
 use tornado_common::actors::json_event_reader::JsonEventReaderActor;
-use tornado_common::actors::message::TornadoCommonActorError;
+use tornado_common::actors::message::{ActionMessage, TornadoCommonActorError};
 use tornado_common::actors::nats_subscriber::subscribe_to_nats;
 use tornado_common::actors::tcp_server::listen_to_tcp;
-use tornado_common::command::pool::{CommandMutPool, CommandPool};
-use tornado_common::command::retry::RetryCommand;
-use tornado_common::command::{StatefulExecutorCommand, StatelessExecutorCommand};
+use tornado_common::dead_letter::DeadLetterQueue;
 use tornado_common::metrics::{ActionMeter, ACTION_ID_LABEL_KEY};
 use tornado_common::TornadoError;
 use tornado_common_api::Event;
@@ -34,17 +34,16 @@ use tornado_common_metrics::Metrics;
 use tornado_engine_api::auth::auth_v2::AuthServiceV2;
 use tornado_engine_api::auth::{roles_map_to_permissions_map, AuthService};
 use tornado_engine_api::config::api::ConfigApi;
+use tornado_engine_api::dead_letter::api::DeadLetterApi;
 use tornado_engine_api::event::api_v2::EventApiV2;
 use tornado_engine_api::model::{ApiData, ApiDataV2};
 use tornado_engine_api::runtime_config::api::RuntimeConfigApi;
 use tornado_engine_matcher::dispatcher::Dispatcher;
+use tornado_engine_matcher::validator::actions::ActionIds;
+use tornado_engine_matcher::validator::MatcherConfigValidator;
 use tracing_actix_web::TracingLogger;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
-pub const ACTION_ID_SMART_MONITORING_CHECK_RESULT: &str = "smart_monitoring_check_result";
-pub const ACTION_ID_FOREACH: &str = "foreach";
-pub const ACTION_ID_LOGGER: &str = "logger";
-
 // 64*1024*1024 byte = 64MB limit
 const MAX_JSON_PAYLOAD_SIZE: usize = 67_108_860;
 
@@ -74,6 +73,7 @@ pub async fn daemon(
     let tornado_meter = Arc::new(TornadoMeter::default());
     let action_meter = Arc::new(ActionMeter::new(TORNADO_APP));
 
+    let matcher_config_storage = global_config.tornado.matcher_config_storage;
     let daemon_config = global_config.tornado.daemon;
     let thread_pool_config = daemon_config.thread_pool_config.clone().unwrap_or_default();
     let threads_per_queue = thread_pool_config.get_threads_count();
@@ -87,192 +87,66 @@ pub async fn daemon(
 
     let message_queue_size = daemon_config.message_queue_size;
 
-    // Start ForEach executor actor
-    let foreach_executor_addr = ForEachExecutorActor::start_new(message_queue_size);
-
-    let archive_action_meter = action_meter.clone();
-    // Start archive executor actor
-    let archive_executor_addr = {
-        let archive_config = configs.archive_executor_config.clone();
-        CommandExecutorActor::start_new(
-            message_queue_size,
-            Rc::new(RetryCommand::new(
-                retry_strategy.clone(),
-                CommandMutPool::new(1, move || {
-                    StatefulExecutorCommand::new(
-                        archive_action_meter.clone(),
-                        tornado_executor_archive::ArchiveExecutor::new(&archive_config),
-                    )
-                }),
-            )),
-            action_meter.clone(),
-        )
-    };
-
-    // Start script executor actor
-    let script_executor_addr = {
-        let executor = tornado_executor_script::ScriptExecutor::new();
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
-            message_queue_size,
-            Rc::new(RetryCommand::new(
-                retry_strategy.clone(),
-                CommandPool::new(threads_per_queue, stateless_executor_command),
-            )),
-            action_meter.clone(),
-        )
-    };
-
-    // Start logger executor actor
-    let logger_executor_addr = {
-        let executor = tornado_executor_logger::LoggerExecutor::new();
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
-            message_queue_size,
-            Rc::new(RetryCommand::new(
-                retry_strategy.clone(),
-                CommandPool::new(threads_per_queue, stateless_executor_command),
-            )),
-            action_meter.clone(),
-        )
-    };
-
-    // Start elasticsearch executor actor
-    let elasticsearch_executor_addr = {
-        let es_authentication = configs.elasticsearch_executor_config.default_auth.clone();
-        let executor =
-            tornado_executor_elasticsearch::ElasticsearchExecutor::new(es_authentication)
-                .await
-                .expect("Cannot start the Elasticsearch Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
-            message_queue_size,
-            Rc::new(RetryCommand::new(
-                retry_strategy.clone(),
-                CommandPool::new(threads_per_queue, stateless_executor_command),
-            )),
-            action_meter.clone(),
-        )
-    };
-
-    // Start icinga2 executor actor
-    let icinga2_executor_addr = {
-        let executor =
-            tornado_executor_icinga2::Icinga2Executor::new(configs.icinga2_executor_config.clone())
-                .expect("Cannot start the Icinga2Executor Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
-            message_queue_size,
-            Rc::new(RetryCommand::new(
-                retry_strategy.clone(),
-                CommandPool::new(threads_per_queue, stateless_executor_command),
-            )),
-            action_meter.clone(),
-        )
-    };
-
-    // Start director executor actor
-    let director_client_config = configs.director_executor_config.clone();
-    let director_executor_addr = {
-        let executor =
-            tornado_executor_director::DirectorExecutor::new(director_client_config.clone())
-                .expect("Cannot start the DirectorExecutor Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
-            message_queue_size,
-            Rc::new(RetryCommand::new(
-                retry_strategy.clone(),
-                CommandPool::new(threads_per_queue, stateless_executor_command),
-            )),
-            action_meter.clone(),
-        )
+    // Open the dead-letter queue
+    let dead_letter_queue = match &daemon_config.dead_letter_queue_dir {
+        Some(dead_letter_queue_dir) => {
+            let max_entries = daemon_config.get_dead_letter_queue_max_entries();
+            let dead_letter_queue =
+                Arc::new(DeadLetterQueue::new(dead_letter_queue_dir, max_entries)?);
+            info!(
+                "Dead-letter queue opened in [{}] with {} actions. Maximum number of actions: {}",
+                dead_letter_queue_dir,
+                dead_letter_queue.size(),
+                max_entries
+            );
+            Some(dead_letter_queue)
+        }
+        None => {
+            info!("The dead-letter queue is disabled. The failed actions will be only logged");
+            None
+        }
     };
+    let _dead_letter_queue_observer = dead_letter_queue.clone().map(DeadLetterQueueObserver::new);
 
-    // Start smart_monitoring_check_result executor actor
-    let smart_monitoring_check_result_executor_addr = {
-        let executor =
-            tornado_executor_smart_monitoring_check_result::SmartMonitoringExecutor::new(
-                configs.icinga2_executor_config.clone(),
-                configs.director_executor_config.clone(),
-            )
-            .expect("Cannot start the SmartMonitoringExecutor Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
-            message_queue_size,
-            Rc::new(RetryCommand::new(
-                retry_strategy.clone(),
-                CommandPool::new(threads_per_queue, stateless_executor_command),
-            )),
-            action_meter.clone(),
-        )
+    // Start the executors
+    let executors = daemon_config.get_executors();
+    let executor_settings = ExecutorSettings {
+        retry_strategy,
+        threads_per_queue,
+        message_queue_size,
+        action_meter: action_meter.clone(),
+        dead_letter_queue: dead_letter_queue.clone(),
     };
+    let executor_registry =
+        Arc::new(ExecutorRegistry::start(&executors, &configs, &executor_settings).await?);
+    info!("Executors started for action ids: {:?}", executor_registry.action_ids());
+    let (action_ids, foreach_action_ids) = enabled_action_ids(&executors);
+    {
+        let action_ids = action_ids.iter().map(String::as_str).collect::<Vec<_>>();
+        let foreach_action_ids = foreach_action_ids.iter().map(String::as_str).collect::<Vec<_>>();
+        for error in MatcherConfigValidator::new().unknown_action_errors(
+            &configs.matcher_config.get_config().await?,
+            &ActionIds { action_ids: &action_ids, foreach_action_ids: &foreach_action_ids },
+        ) {
+            warn!(
+                "Rule [{}] of node {:?}: {}",
+                error.rule.unwrap_or_default(),
+                error.node_path,
+                error.message
+            );
+        }
+    }
 
     // Configure action dispatcher
-    let foreach_executor_addr_clone = foreach_executor_addr.clone();
     let event_bus = {
+        let executor_registry = executor_registry.clone();
         let event_bus = ActixEventBus {
-            callback: move |message| {
+            callback: move |message: ActionMessage| {
                 action_meter
                     .actions_received_counter
                     .add(1, &[ACTION_ID_LABEL_KEY.string(message.0.action.id.to_owned())]);
 
-                let send_result = match message.0.action.id.as_ref() {
-                    "archive" => {
-                        archive_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'archive' executor. Err: {:?}", err)
-                        })
-                    }
-                    "icinga2" => {
-                        icinga2_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'icinga2' executor. Err: {:?}", err)
-                        })
-                    }
-                    "director" => {
-                        director_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'director' executor. Err: {:?}", err)
-                        })
-                    }
-                    ACTION_ID_SMART_MONITORING_CHECK_RESULT => {
-                        smart_monitoring_check_result_executor_addr.try_send(message).map_err(|err| {
-                            format!(
-                                "Error sending message to 'smart_monitoring_check_result' executor. Err: {:?}",
-                                err
-                            )
-                        })
-                    }
-                    "script" => {
-                        script_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'script' executor. Err: {:?}", err)
-                        })
-                    }
-                    ACTION_ID_FOREACH => foreach_executor_addr_clone
-                        .try_send(message)
-                        .map_err(|err| {
-                            format!("Error sending message to 'foreach' executor. Err: {:?}", err)
-                        }),
-                    ACTION_ID_LOGGER => {
-                        logger_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'logger' executor. Err: {:?}", err)
-                        })
-                    }
-                    "elasticsearch" => elasticsearch_executor_addr
-                        .try_send(message)
-                        .map_err(|err| {
-                            format!(
-                                "Error sending message to 'elasticsearch' executor. Err: {:?}",
-                                err
-                            )
-                        }),
-
-                    _ => Err(format!("There are not executors for action id [{}]", &message.0.action.id)),
-                };
-                if let Err(error_message) = send_result {
+                if let Err(error_message) = executor_registry.send(message) {
                     error!("{}", error_message)
                 }
             },
@@ -280,10 +154,7 @@ pub async fn daemon(
         Arc::new(event_bus)
     };
 
-    let event_bus_clone = event_bus.clone();
-    foreach_executor_addr.try_send(ForEachExecutorActorInitMessage {
-        init: move || tornado_executor_foreach::ForEachExecutor::new(event_bus_clone.clone()),
-    })?;
+    executor_registry.init_foreach_executors(event_bus.clone())?;
 
     // Start dispatcher actor
     let dispatcher_addr = DispatcherActor::start_new(
@@ -300,6 +171,21 @@ pub async fn daemon(
     )
     .await?;
 
+    // Start the watcher of the rules directory
+    let _rules_watcher = if !daemon_config.is_rules_watcher_enabled() {
+        None
+    } else if matcher_config_storage != MatcherConfigStorage::Fs {
+        warn!("The rules watcher is not started, as the configuration is not stored in the rules directory.");
+        None
+    } else {
+        Some(RulesWatcher::start(
+            &format!("{}/{}", config_dir, rules_dir),
+            Duration::from_millis(daemon_config.get_rules_watcher_debounce_ms()),
+            configs.matcher_config.clone(),
+            matcher_addr.clone(),
+        )?)
+    };
+
     if daemon_config.is_nats_enabled() {
         info!("NATS connection is enabled. Starting it...");
 
@@ -435,6 +321,9 @@ pub async fn daemon(
     let api_handler = MatcherApiHandler::new(matcher_addr, tornado_meter.clone());
     let daemon_config = daemon_config.clone();
     let matcher_config = configs.matcher_config.clone();
+    let dead_letter_api_handler = dead_letter_queue.map(|dead_letter_queue| {
+        DeadLetterApiHandlerImpl::new(dead_letter_queue, executor_registry.clone())
+    });
 
     // Start API and monitoring endpoint
     let service_logger_guard = logger_guard.clone();
@@ -443,7 +332,8 @@ pub async fn daemon(
 
         let v2_config_api = ApiDataV2 {
             auth: auth_service_v2.clone(),
-            api: ConfigApi::new(api_handler.clone(), matcher_config.clone()),
+            api: ConfigApi::new(api_handler.clone(), matcher_config.clone())
+                .with_executor_action_ids(action_ids.clone(), foreach_action_ids.clone()),
         };
         let event_api_v2 = ApiDataV2 {
             auth: auth_service_v2.clone(),
@@ -455,6 +345,10 @@ pub async fn daemon(
                 service_logger_guard.clone(),
             )),
         };
+        let dead_letter_api = dead_letter_api_handler.clone().map(|handler| ApiData {
+            auth: auth_service.clone(),
+            api: DeadLetterApi::new(handler),
+        });
         let metrics = metrics.clone();
         App::new()
             .wrap(Logger::default())
@@ -474,6 +368,15 @@ pub async fn daemon(
                             runtime_config_api,
                         ),
                     )
+                    .configure(|cfg| {
+                        if let Some(dead_letter_api) = dead_letter_api {
+                            cfg.service(
+                                tornado_engine_api::dead_letter::web::build_dead_letter_endpoints(
+                                    dead_letter_api,
+                                ),
+                            );
+                        }
+                    })
                     .service(
                         web::scope("/v2_beta")
                             .service(tornado_engine_api::config::web::build_config_v2_endpoints(
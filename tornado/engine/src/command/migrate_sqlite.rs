@@ -0,0 +1,63 @@
+use crate::config::{
+    build_config, build_fs_matcher_config, build_sqlite_matcher_config, MatcherConfigStorage,
+};
+
+pub async fn migrate_sqlite(
+    config_dir: &str,
+    rules_dir: &str,
+    drafts_dir: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    println!("Migrate the Tornado configuration to SQLite");
+    let tornado_config = build_config(config_dir)?.tornado;
+    let MatcherConfigStorage::Sqlite { database_file } = tornado_config.matcher_config_storage
+    else {
+        return Err(
+            "The tornado.matcher_config_storage entry of tornado.toml is not of type Sqlite".into(),
+        );
+    };
+
+    let source = build_fs_matcher_config(config_dir, rules_dir, drafts_dir, &tornado_config.daemon);
+    let target = build_sqlite_matcher_config(config_dir, &database_file, &tornado_config.daemon);
+    target.import_from_fs(&source).await?;
+
+    println!("The configuration was migrated to [{}/{}].", config_dir, database_file);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::upgrade_rules::test::prepare_temp_dirs;
+    use crate::config::parse_config_files;
+    use tornado_engine_matcher::config::MatcherConfigReader;
+
+    #[tokio::test]
+    async fn should_migrate_the_configuration_to_sqlite() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let daemon_config = build_config(&config_dir).unwrap().tornado.daemon;
+        let fs_config =
+            build_fs_matcher_config(&config_dir, &rules_dir, &drafts_dir, &daemon_config)
+                .get_config()
+                .await
+                .unwrap();
+
+        // Act
+        let not_configured = migrate_sqlite(&config_dir, &rules_dir, &drafts_dir).await;
+        let tornado_toml = format!("{}/tornado.toml", config_dir);
+        let content = std::fs::read_to_string(&tornado_toml).unwrap().replacen(
+            "[tornado]\n",
+            "[tornado]\nmatcher_config_storage = { type = \"Sqlite\", database_file = \"rules.db\" }\n",
+            1,
+        );
+        std::fs::write(&tornado_toml, content).unwrap();
+        migrate_sqlite(&config_dir, &rules_dir, &drafts_dir).await.unwrap();
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        // Assert
+        assert!(not_configured.is_err());
+        assert_eq!(fs_config, configs.matcher_config.get_config().await.unwrap());
+        assert!(std::path::Path::new(&format!("{}/rules.db", config_dir)).is_file());
+    }
+}
@@ -1,5 +1,10 @@
 pub mod apm_tracing;
+pub mod bundle;
 pub mod check;
 pub mod create_filter;
 pub mod daemon;
+pub mod migrate_sqlite;
+pub mod process_event;
+pub mod render;
+pub mod replay;
 pub mod upgrade_rules;
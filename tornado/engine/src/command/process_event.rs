@@ -0,0 +1,199 @@
+use crate::config::{parse_config_files, ProcessEventOpt};
+use serde_json::json;
+use std::io::Write;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tornado_common_api::Event;
+use tornado_engine_api::event::convert::processed_event_into_dto;
+use tornado_engine_matcher::matcher::Matcher;
+use tornado_engine_matcher::model::{ProcessedEvent, ProcessedNode};
+
+pub async fn process_event(
+    config_dir: &str,
+    rules_dir: &str,
+    drafts_dir: &str,
+    opts: &ProcessEventOpt,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let configs = parse_config_files(config_dir, rules_dir, drafts_dir)?;
+    let config = match &opts.draft_id {
+        Some(draft_id) => configs.matcher_config.get_draft(draft_id).await?.config,
+        None => configs.matcher_config.get_config().await?,
+    };
+    let matcher = Matcher::build(&config)?;
+    let mut out = std::io::stdout();
+    let mut invalid_lines = 0;
+
+    if opts.files.is_empty() {
+        let stdin = BufReader::new(tokio::io::stdin());
+        invalid_lines += process_reader(&matcher, opts.json, stdin, "stdin", &mut out).await?;
+    } else {
+        for path in &opts.files {
+            let file = tokio::fs::File::open(path)
+                .await
+                .map_err(|err| format!("Cannot open file [{}]: {}", path, err))?;
+            invalid_lines +=
+                process_reader(&matcher, opts.json, BufReader::new(file), path, &mut out).await?;
+        }
+    }
+    check_invalid_lines(invalid_lines)
+}
+
+/// Fails if some lines did not contain a valid event, so that the command exits
+/// with a non-zero status.
+fn check_invalid_lines(
+    invalid_lines: usize,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    if invalid_lines > 0 {
+        return Err(format!("{} lines do not contain a valid event", invalid_lines).into());
+    }
+    Ok(())
+}
+
+/// Processes the events read from the reader, one JSON event per line, and writes the
+/// results to `out`. Invalid lines are reported on stderr and do not stop the processing;
+/// their number is returned.
+async fn process_reader<R: AsyncBufRead + Unpin, W: Write>(
+    matcher: &Matcher,
+    json: bool,
+    reader: R,
+    source: &str,
+    out: &mut W,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut lines = reader.lines();
+    let mut line_number = 0;
+    let mut invalid_lines = 0;
+    while let Some(line) =
+        lines.next_line().await.map_err(|err| format!("Cannot read [{}]: {}", source, err))?
+    {
+        line_number += 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let event = match serde_json::from_str::<Event>(line) {
+            Ok(event) => event,
+            Err(err) => {
+                invalid_lines += 1;
+                eprintln!("[{}] line {}: invalid event. {}", source, line_number, err);
+                continue;
+            }
+        };
+
+        let processed = matcher.process(json!(event), false);
+        if json {
+            writeln!(out, "{}", serde_json::to_string(&processed_event_into_dto(processed)?)?)?;
+        } else {
+            writeln!(out, "[{}] line {}:", source, line_number)?;
+            write_processed_event(out, &processed)?;
+        }
+    }
+    Ok(invalid_lines)
+}
+
+fn write_processed_event<W: Write>(out: &mut W, processed: &ProcessedEvent) -> std::io::Result<()> {
+    writeln!(out, "    Event: {}", processed.event)?;
+    write_node(out, &processed.result, 1)
+}
+
+fn write_node<W: Write>(out: &mut W, node: &ProcessedNode, depth: usize) -> std::io::Result<()> {
+    let indent = "    ".repeat(depth);
+    match node {
+        ProcessedNode::Filter { name, filter, nodes } => {
+            writeln!(out, "{}Filter [{}]: {:?}", indent, name, filter.status)?;
+            for node in nodes {
+                write_node(out, node, depth + 1)?;
+            }
+        }
+        ProcessedNode::Iterator { name, iterator, events } => {
+            writeln!(out, "{}Iterator [{}]: {:?}", indent, name, iterator)?;
+            for (index, iteration) in events.iter().enumerate() {
+                writeln!(out, "{}    Iteration {}: {}", indent, index, iteration.event)?;
+                for node in &iteration.result {
+                    write_node(out, node, depth + 2)?;
+                }
+            }
+        }
+        ProcessedNode::Ruleset { name, rules } => {
+            writeln!(out, "{}Ruleset [{}]", indent, name)?;
+            for rule in &rules.rules {
+                writeln!(out, "{}    Rule [{}]: {:?}", indent, rule.name, rule.status)?;
+                if let Some(message) = &rule.message {
+                    writeln!(out, "{}        {}", indent, message)?;
+                }
+                for action in &rule.actions {
+                    writeln!(
+                        out,
+                        "{}        Action [{}]: {}",
+                        indent,
+                        action.id,
+                        json!(action.payload)
+                    )?;
+                }
+            }
+            if rules.extracted_vars.as_object().is_some_and(|vars| !vars.is_empty()) {
+                writeln!(out, "{}    Extracted variables: {}", indent, rules.extracted_vars)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::upgrade_rules::test::prepare_temp_dirs;
+
+    const EVENTS: &str =
+        "{\"type\": \"some\", \"created_ms\": 0, \"payload\": {}}\n\nnot an event\n";
+
+    #[tokio::test]
+    async fn should_print_the_processed_events() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+        let matcher = Matcher::build(&configs.matcher_config.get_config().await.unwrap()).unwrap();
+        let mut out = vec![];
+
+        // Act
+        let invalid_lines =
+            process_reader(&matcher, false, EVENTS.as_bytes(), "test", &mut out).await.unwrap();
+
+        // Assert
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(1, invalid_lines);
+        assert!(out.starts_with("[test] line 1:\n"));
+        assert!(out.contains("Filter [root]: Matched"));
+        assert!(out.contains("Rule [archive_all]: Matched"));
+        assert!(out.contains("Action [archive]:"));
+    }
+
+    #[test]
+    fn should_fail_only_if_there_are_invalid_lines() {
+        assert!(check_invalid_lines(0).is_ok());
+        assert_eq!(
+            "2 lines do not contain a valid event",
+            check_invalid_lines(2).unwrap_err().to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_write_one_json_result_per_event() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+        let matcher = Matcher::build(&configs.matcher_config.get_config().await.unwrap()).unwrap();
+        let mut out = vec![];
+
+        // Act
+        process_reader(&matcher, true, EVENTS.as_bytes(), "test", &mut out).await.unwrap();
+
+        // Assert
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(1, lines.len());
+        let processed: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!("some", processed["event"]["type"]);
+        assert_eq!("Filter", processed["result"]["type"]);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::config::{parse_config_files, RenderOpt};
+use tornado_engine_matcher::config::MatcherConfig;
+
+pub async fn render(
+    config_dir: &str,
+    rules_dir: &str,
+    drafts_dir: &str,
+    opts: &RenderOpt,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let configs = parse_config_files(config_dir, rules_dir, drafts_dir)?;
+    let config = match &opts.draft_id {
+        Some(draft_id) => configs.matcher_config.get_draft(draft_id).await?.config,
+        None => configs.matcher_config.get_config().await?,
+    };
+    let output = render_node(&config, opts)?;
+
+    match &opts.output {
+        Some(file) => {
+            std::fs::write(file, output)?;
+            println!("Rendered the node {} in {} format to {}", opts.node_path, opts.format, file);
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+fn render_node(
+    config: &MatcherConfig,
+    opts: &RenderOpt,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let node_path = opts.node_path.split(',').collect::<Vec<_>>();
+    let node = config
+        .get_node_by_path(&node_path)
+        .ok_or_else(|| format!("Node for path {:?} not found", node_path))?;
+    Ok(opts.format.render(node))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::upgrade_rules::test::prepare_temp_dirs;
+    use tornado_engine_matcher::config::render::TreeRenderFormat;
+
+    #[tokio::test]
+    async fn should_render_a_subtree() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+        let config = configs.matcher_config.get_config().await.unwrap();
+        let opts = |node_path: &str| RenderOpt {
+            node_path: node_path.to_owned(),
+            format: TreeRenderFormat::Dot,
+            draft_id: None,
+            output: None,
+        };
+
+        // Act
+        let dot = render_node(&config, &opts("root,ruleset_01")).unwrap();
+
+        // Assert
+        assert!(dot.starts_with("digraph processing_tree {"));
+        assert!(dot.contains("Ruleset: ruleset_01"));
+        assert!(!dot.contains("Filter: root"));
+        assert!(render_node(&config, &opts("root,none")).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::config::{parse_config_files, ReplayOpt};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tornado_engine_matcher::matcher::Matcher;
+use tornado_engine_matcher::replay::{Replay, ReplayReport};
+
+pub async fn replay(
+    config_dir: &str,
+    rules_dir: &str,
+    drafts_dir: &str,
+    opts: &ReplayOpt,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let configs = parse_config_files(config_dir, rules_dir, drafts_dir)?;
+    let active = Matcher::build(&configs.matcher_config.get_config().await?)?;
+    let draft = Matcher::build(&configs.matcher_config.get_draft(&opts.draft_id).await?.config)?;
+    let mut replay = Replay::new(active, draft);
+
+    for file in &opts.files {
+        let report = replay_file(&mut replay, file).await?;
+        if opts.json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            print_report(file, &report);
+        }
+    }
+    Ok(())
+}
+
+async fn replay_file(
+    replay: &mut Replay,
+    path: &str,
+) -> Result<ReplayReport, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|err| format!("Cannot open file [{}]: {}", path, err))?;
+    let mut lines = BufReader::new(file).lines();
+    while let Some(line) =
+        lines.next_line().await.map_err(|err| format!("Cannot read file [{}]: {}", path, err))?
+    {
+        replay.replay_line(&line);
+    }
+    Ok(replay.take_report())
+}
+
+fn print_report(file: &str, report: &ReplayReport) {
+    println!(
+        "Replayed {} events from [{}]: {} with differences, {} invalid lines.",
+        report.replayed_events, file, report.changed_events, report.invalid_lines
+    );
+    for diff in &report.differences {
+        println!("Line {}:", diff.line);
+        for rule in &diff.rules {
+            println!(
+                "    {:?} rule [{}] of ruleset [{}]",
+                rule.change,
+                rule.rule,
+                rule.ruleset_path.join(" -> ")
+            );
+        }
+        for action in &diff.actions {
+            println!(
+                "    {:?} action [{}] of rule [{}] of ruleset [{}]",
+                action.change,
+                action.action_id,
+                action.rule,
+                action.ruleset_path.join(" -> ")
+            );
+            if let Some(payload) = &action.active_payload {
+                println!("        current payload: {}", payload);
+            }
+            if let Some(payload) = &action.draft_payload {
+                println!("        draft payload:   {}", payload);
+            }
+        }
+    }
+    if report.changed_events > report.differences.len() as u64 {
+        println!(
+            "... {} more events with differences are not shown.",
+            report.changed_events - report.differences.len() as u64
+        );
+    }
+    for error in &report.line_errors {
+        println!("Line {}: invalid event. {}", error.line, error.message);
+    }
+    if report.invalid_lines > report.line_errors.len() as u64 {
+        println!(
+            "... {} more invalid lines are not shown.",
+            report.invalid_lines - report.line_errors.len() as u64
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::upgrade_rules::test::prepare_temp_dirs;
+    use tornado_engine_matcher::config::MatcherConfig;
+    use tornado_engine_matcher::replay::ReplayChange;
+
+    #[tokio::test]
+    async fn should_report_the_rules_removed_by_the_draft() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+        let draft_id = configs.matcher_config.create_draft("user".to_owned()).await.unwrap();
+        let mut draft = configs.matcher_config.get_draft(&draft_id).await.unwrap();
+        if let MatcherConfig::Filter { nodes, .. } = &mut draft.config {
+            nodes.retain(|node| node.get_name() != "ruleset_01");
+        }
+        configs
+            .matcher_config
+            .update_draft(&draft_id, "user".to_owned(), &draft.config)
+            .await
+            .unwrap();
+
+        let events_file = tempdir.path().join("events.jsonl");
+        std::fs::write(
+            &events_file,
+            "{\"type\": \"some\", \"created_ms\": 0, \"payload\": {}}\n\nnot an event\n",
+        )
+        .unwrap();
+
+        let active = Matcher::build(&configs.matcher_config.get_config().await.unwrap()).unwrap();
+        let mut replay = Replay::new(active, Matcher::build(&draft.config).unwrap());
+
+        // Act
+        let report = replay_file(&mut replay, events_file.to_str().unwrap()).await.unwrap();
+
+        // Assert
+        assert_eq!(1, report.replayed_events);
+        assert_eq!(1, report.changed_events);
+        assert_eq!(1, report.differences[0].line);
+        assert!(report.differences[0]
+            .rules
+            .iter()
+            .any(|rule| rule.rule == "archive_all" && rule.change == ReplayChange::Removed));
+        assert_eq!(1, report.invalid_lines);
+        assert_eq!(3, report.line_errors[0].line);
+    }
+}
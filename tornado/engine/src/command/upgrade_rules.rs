@@ -1,7 +1,9 @@
+use crate::config::RulesUpgradeOpt;
 use std::path::{Path, PathBuf};
 use tornado_engine_matcher::config::v1::fs::FsMatcherConfigManager;
 use tornado_engine_matcher::config::v2::{
-    gather_dir_entries, get_config_version, FsMatcherConfigManagerV2, Version,
+    convert_config_format, gather_dir_entries, get_config_version, FsMatcherConfigManagerV2,
+    Version,
 };
 use tornado_engine_matcher::config::{MatcherConfig, MatcherConfigEditor, MatcherConfigReader};
 
@@ -9,6 +11,7 @@ pub async fn upgrade_rules(
     config_dir: &str,
     rules_dir: &str,
     drafts_dir: &str,
+    opts: &RulesUpgradeOpt,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     println!("Upgrade Tornado configuration rules");
     let rules_dir = {
@@ -24,14 +27,24 @@ pub async fn upgrade_rules(
     };
 
     let mut upgraded = upgrade_config(&rules_dir).await?;
+    let mut config_dirs = vec![rules_dir];
 
     if tokio::fs::try_exists(&drafts_dir).await.unwrap_or(false) {
         let entries = gather_dir_entries(&drafts_dir).await?;
         for entry in entries {
             upgraded |= upgrade_draft(&entry.path()).await?;
+            config_dirs.push(entry.path().join("config"));
         }
     }
 
+    if let Some(format) = opts.format {
+        for config_dir in &config_dirs {
+            println!("Converting config in {} to {}", config_dir.display(), format);
+        }
+        convert_config_format(&config_dirs, format).await?;
+        upgraded = true;
+    }
+
     if upgraded {
         println!("Everything upgraded and good to go.")
     } else {
@@ -96,7 +109,35 @@ fn fixup_empty_config(config: MatcherConfig) -> MatcherConfig {
 
 #[cfg(test)]
 pub mod test {
+    use super::*;
     use tempfile::TempDir;
+    use tornado_engine_matcher::config::v2::ConfigFileFormat;
+    use tornado_engine_matcher::config::MatcherConfigReader;
+
+    #[tokio::test]
+    async fn should_convert_the_configuration_to_yaml() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, _, _) = prepare_temp_dirs(&tempdir);
+        let rules_path = Path::new(&config_dir).join("rules.d");
+        let config_manager = FsMatcherConfigManagerV2::new(&rules_path, Path::new(""));
+        let config = config_manager.get_config().await.unwrap();
+
+        // Act
+        upgrade_rules(
+            &config_dir,
+            "rules.d",
+            "drafts",
+            &RulesUpgradeOpt { format: Some(ConfigFileFormat::Yaml) },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+        assert!(rules_path.join("ruleset_01/ruleset.yaml").is_file());
+        assert!(!rules_path.join("ruleset_01/ruleset.json").exists());
+        assert_eq!(config, config_manager.get_config().await.unwrap());
+    }
 
     pub fn prepare_temp_dirs(tempdir: &TempDir) -> (String, String, String) {
         let source_config_dir = "./config/".to_owned();
@@ -1,6 +1,8 @@
 use crate::enrich::nats::NatsExtractor;
+use crate::executor::{default_executors, ExecutorConfig};
 use clap::Parser;
 use config_rs::{Config, ConfigError, File};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::sync::Arc;
@@ -9,7 +11,11 @@ use tornado_common::{
 };
 use tornado_common_logger::LoggerConfig;
 use tornado_engine_api::auth::Permission;
-use tornado_engine_matcher::config::v2::FsMatcherConfigManagerV2;
+use tornado_engine_matcher::config::render::TreeRenderFormat;
+use tornado_engine_matcher::config::v2::{
+    ConfigFileFormat, FsMatcherConfigManagerV2, GitMatcherConfigManagerV2,
+    SqliteMatcherConfigManagerV2,
+};
 use tornado_engine_matcher::config::MatcherConfigEditor;
 use tornado_executor_archive::config::ArchiveConfig;
 use tornado_executor_director::config::DirectorClientConfig;
@@ -18,6 +24,12 @@ use tornado_executor_icinga2::config::Icinga2ClientConfig;
 
 pub const CONFIG_DIR_DEFAULT: Option<&'static str> = option_env!("TORNADO_CONFIG_DIR_DEFAULT");
 
+pub const DEFAULT_RULES_WATCHER_DEBOUNCE_MS: u64 = 2000;
+
+pub const DEFAULT_DEPLOY_HISTORY_DIR: &str = "history/";
+pub const DEFAULT_DEPLOY_HISTORY_SIZE: usize = 10;
+pub const DEFAULT_DEAD_LETTER_QUEUE_MAX_ENTRIES: usize = 10000;
+
 #[derive(Parser, Debug)]
 #[clap(name = "tornado")]
 pub struct Opt {
@@ -75,11 +87,35 @@ pub enum SubCommand {
     Daemon,
 
     /// Starts the Tornado Rules upgrade process
-    RulesUpgrade,
+    RulesUpgrade(RulesUpgradeOpt),
 
     /// Creates a Filter in Tornado configuration
     FilterCreate(FilterCreateOpt),
 
+    /// Replays archived events against the current configuration and a draft,
+    /// and reports the rules and actions that differ. No action is executed.
+    Replay(ReplayOpt),
+
+    /// Processes events against the current configuration, or a draft, and prints
+    /// the result of the processing. No action is executed.
+    ProcessEvent(ProcessEventOpt),
+
+    /// Renders the processing tree, or one of its subtrees, as a Graphviz DOT graph,
+    /// a Mermaid flowchart or a static HTML report
+    Render(RenderOpt),
+
+    /// Exports the processing tree, or one of its subtrees, in a single JSON bundle
+    Export(ExportOpt),
+
+    /// Imports a bundle created by the `export` command into the processing tree,
+    /// at the node path stored in the bundle, and deploys the result
+    Import(ImportOpt),
+
+    /// Imports the configuration, the drafts and the deploy history stored in the
+    /// `rules-dir`, `drafts-dir` and history folders into the SQLite database
+    /// configured in the `tornado.matcher_config_storage` entry
+    RulesMigrateSqlite,
+
     /// Enable or disable the APM logger priority configuration.
     /// When used with `enable`, it:
     /// - enables the elastic-APM logger output
@@ -97,6 +133,14 @@ pub enum SubCommand {
     },
 }
 
+#[derive(Parser, Debug)]
+pub struct RulesUpgradeOpt {
+    /// Rewrites all the node and rule files in the given format: json, yaml or toml.
+    /// The comments of the files are not preserved.
+    #[clap(long)]
+    pub format: Option<ConfigFileFormat>,
+}
+
 #[derive(Parser, Debug)]
 pub struct FilterCreateOpt {
     /// The name of the Filter to be created.
@@ -108,6 +152,73 @@ pub struct FilterCreateOpt {
     pub json_definition: String,
 }
 
+#[derive(Parser, Debug)]
+pub struct ReplayOpt {
+    /// The id of the draft to compare with the current configuration.
+    #[clap(short, long)]
+    pub draft_id: String,
+
+    /// Prints the reports in JSON format.
+    #[clap(long)]
+    pub json: bool,
+
+    /// The files containing the events to replay, one JSON event per line,
+    /// as written by the archive executor.
+    #[clap(required = true)]
+    pub files: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ProcessEventOpt {
+    /// The id of the draft to use instead of the current configuration.
+    #[clap(short, long)]
+    pub draft_id: Option<String>,
+
+    /// Prints the processed events in JSON format, one per line.
+    #[clap(long)]
+    pub json: bool,
+
+    /// The files containing the events to process, one JSON event per line.
+    /// If not set, the events are read from stdin.
+    pub files: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ExportOpt {
+    /// The comma separated path of the node to export, starting from the root node.
+    #[clap(short, long, default_value = "root")]
+    pub node_path: String,
+
+    /// The file the bundle is written to. If not set, the bundle is printed to stdout.
+    #[clap(short, long)]
+    pub output: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct RenderOpt {
+    /// The comma separated path of the node to render, starting from the root node.
+    #[clap(short, long, default_value = "root")]
+    pub node_path: String,
+
+    /// The output format: dot, mermaid or html.
+    #[clap(short, long, default_value = "dot")]
+    pub format: TreeRenderFormat,
+
+    /// The id of the draft to render instead of the current configuration.
+    #[clap(short, long)]
+    pub draft_id: Option<String>,
+
+    /// The file the result is written to. If not set, it is printed to stdout.
+    #[clap(short, long)]
+    pub output: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ImportOpt {
+    /// The bundle file to import.
+    pub file: String,
+}
+
 #[derive(Parser, Debug)]
 pub enum EnableOrDisableSubCommand {
     Enable,
@@ -161,10 +272,29 @@ pub struct DaemonCommandConfig {
 
     pub message_queue_size: usize,
 
+    pub rules_watcher_enabled: Option<bool>,
+    pub rules_watcher_debounce_ms: Option<u64>,
+
     pub thread_pool_config: Option<ThreadPoolConfig>,
     #[serde(default)]
     pub retry_strategy: RetryStrategy,
 
+    /// The executors to start. When not provided, one executor of each type is started,
+    /// bound to the action id with the name of its type.
+    pub executors: Option<Vec<ExecutorConfig>>,
+
+    /// The folder of the dead-letter queue. When not provided, the actions that fail
+    /// after all the retry attempts are only logged.
+    pub dead_letter_queue_dir: Option<String>,
+    /// The maximum number of entries of the dead-letter queue. When the queue is full,
+    /// the oldest entries are dropped.
+    pub dead_letter_queue_max_entries: Option<usize>,
+
+    /// The folder, relative to the `config-dir`, where the deployed configurations are kept
+    pub deploy_history_dir: Option<String>,
+    /// The number of deployed configurations kept in the deploy history
+    pub deploy_history_size: Option<usize>,
+
     pub auth: AuthConfig,
 }
 
@@ -176,6 +306,30 @@ impl DaemonCommandConfig {
     pub fn is_nats_enabled(&self) -> bool {
         self.nats_enabled.unwrap_or(false)
     }
+
+    pub fn is_rules_watcher_enabled(&self) -> bool {
+        self.rules_watcher_enabled.unwrap_or(false)
+    }
+
+    pub fn get_rules_watcher_debounce_ms(&self) -> u64 {
+        self.rules_watcher_debounce_ms.unwrap_or(DEFAULT_RULES_WATCHER_DEBOUNCE_MS)
+    }
+
+    pub fn get_deploy_history_dir(&self) -> &str {
+        self.deploy_history_dir.as_deref().unwrap_or(DEFAULT_DEPLOY_HISTORY_DIR)
+    }
+
+    pub fn get_deploy_history_size(&self) -> usize {
+        self.deploy_history_size.unwrap_or(DEFAULT_DEPLOY_HISTORY_SIZE)
+    }
+
+    pub fn get_dead_letter_queue_max_entries(&self) -> usize {
+        self.dead_letter_queue_max_entries.unwrap_or(DEFAULT_DEAD_LETTER_QUEUE_MAX_ENTRIES)
+    }
+
+    pub fn get_executors(&self) -> Vec<ExecutorConfig> {
+        self.executors.clone().unwrap_or_else(default_executors)
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Default)]
@@ -192,45 +346,56 @@ pub struct GlobalConfig {
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct TornadoConfig {
+    #[serde(default)]
+    pub matcher_config_storage: MatcherConfigStorage,
     pub daemon: DaemonCommandConfig,
 }
 
-pub fn build_config(config_dir: &str) -> Result<GlobalConfig, ConfigError> {
-    let config_file_path = format!("{}/tornado.toml", config_dir);
-    let mut s = Config::new();
-    s.merge(File::with_name(&config_file_path))?;
-    s.try_into()
+/// Where the processing tree configuration, its drafts and its deploy history are stored
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(tag = "type")]
+pub enum MatcherConfigStorage {
+    /// The `rules-dir`, `drafts-dir` and deploy history folders
+    #[default]
+    Fs,
+    /// A local git repository. The `repository_dir` is relative to the `config-dir`.
+    /// When the repository is empty, the configuration in the `rules-dir` is imported.
+    Git { repository_dir: String },
+    /// An embedded SQLite database. The `database_file` is relative to the `config-dir`.
+    /// The configuration in the `rules-dir` can be imported with the `rules-migrate-sqlite` command.
+    Sqlite { database_file: String },
 }
 
-fn build_archive_config(config_dir: &str) -> Result<ArchiveConfig, ConfigError> {
-    let config_file_path = format!("{}/archive_executor.toml", config_dir);
+pub fn build_config(config_dir: &str) -> Result<GlobalConfig, ConfigError> {
+    let config_file_path = format!("{}/tornado.toml", config_dir);
     let mut s = Config::new();
     s.merge(File::with_name(&config_file_path))?;
     s.try_into()
 }
 
-fn build_icinga2_client_config(config_dir: &str) -> Result<Icinga2ClientConfig, ConfigError> {
-    let config_file_path = format!("{}/icinga2_client_executor.toml", config_dir);
-    let mut s = Config::new();
-    s.merge(File::with_name(&config_file_path))?;
-    s.try_into()
-}
+pub const ARCHIVE_EXECUTOR_CONFIG_FILE: &str = "archive_executor";
+pub const ICINGA2_EXECUTOR_CONFIG_FILE: &str = "icinga2_client_executor";
+pub const DIRECTOR_EXECUTOR_CONFIG_FILE: &str = "director_client_executor";
+pub const ELASTICSEARCH_EXECUTOR_CONFIG_FILE: &str = "elasticsearch_executor";
 
-fn build_director_client_config(config_dir: &str) -> Result<DirectorClientConfig, ConfigError> {
-    let config_file_path = format!("{}/director_client_executor.toml", config_dir);
-    let mut s = Config::new();
-    s.merge(File::with_name(&config_file_path))?;
-    s.try_into()
-}
-
-fn build_elasticsearch_config(config_dir: &str) -> Result<ElasticsearchConfig, ConfigError> {
-    let config_file_path = format!("{}/elasticsearch_executor.toml", config_dir);
+/// Reads the configuration of an executor from the `<file_name>.toml` file of the `config_dir`,
+/// or from the `<file_name>.<instance>.toml` file for a named executor instance.
+pub fn build_executor_config<T: DeserializeOwned>(
+    config_dir: &str,
+    file_name: &str,
+    instance: Option<&str>,
+) -> Result<T, ConfigError> {
+    let config_file_path = match instance {
+        Some(instance) => format!("{}/{}.{}.toml", config_dir, file_name, instance),
+        None => format!("{}/{}.toml", config_dir, file_name),
+    };
     let mut s = Config::new();
     s.merge(File::with_name(&config_file_path))?;
     s.try_into()
 }
 
 pub struct ComponentsConfig {
+    pub config_dir: String,
     pub matcher_config: Arc<dyn MatcherConfigEditor>,
     pub archive_executor_config: ArchiveConfig,
     pub icinga2_executor_config: Icinga2ClientConfig,
@@ -243,12 +408,24 @@ pub fn parse_config_files(
     rules_dir: &str,
     drafts_dir: &str,
 ) -> Result<ComponentsConfig, Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let matcher_config = Arc::new(build_matcher_config(config_dir, rules_dir, drafts_dir));
-    let archive_executor_config = build_archive_config(config_dir)?;
-    let icinga2_executor_config = build_icinga2_client_config(config_dir)?;
-    let director_executor_config = build_director_client_config(config_dir)?;
-    let elasticsearch_executor_config = build_elasticsearch_config(config_dir)?;
+    let tornado_config = build_config(config_dir)?.tornado;
+    let matcher_config = build_matcher_config(
+        config_dir,
+        rules_dir,
+        drafts_dir,
+        &tornado_config.matcher_config_storage,
+        &tornado_config.daemon,
+    );
+    let archive_executor_config =
+        build_executor_config(config_dir, ARCHIVE_EXECUTOR_CONFIG_FILE, None)?;
+    let icinga2_executor_config =
+        build_executor_config(config_dir, ICINGA2_EXECUTOR_CONFIG_FILE, None)?;
+    let director_executor_config =
+        build_executor_config(config_dir, DIRECTOR_EXECUTOR_CONFIG_FILE, None)?;
+    let elasticsearch_executor_config =
+        build_executor_config(config_dir, ELASTICSEARCH_EXECUTOR_CONFIG_FILE, None)?;
     Ok(ComponentsConfig {
+        config_dir: config_dir.to_owned(),
         matcher_config,
         archive_executor_config,
         icinga2_executor_config,
@@ -261,11 +438,46 @@ fn build_matcher_config(
     config_dir: &str,
     rules_dir: &str,
     drafts_dir: &str,
+    storage: &MatcherConfigStorage,
+    daemon_config: &DaemonCommandConfig,
+) -> Arc<dyn MatcherConfigEditor> {
+    match storage {
+        MatcherConfigStorage::Fs => {
+            Arc::new(build_fs_matcher_config(config_dir, rules_dir, drafts_dir, daemon_config))
+        }
+        MatcherConfigStorage::Git { repository_dir } => Arc::new(
+            GitMatcherConfigManagerV2::new(format!("{}/{}", config_dir, repository_dir))
+                .with_initial_config_dir(format!("{}/{}", config_dir, rules_dir)),
+        ),
+        MatcherConfigStorage::Sqlite { database_file } => {
+            Arc::new(build_sqlite_matcher_config(config_dir, database_file, daemon_config))
+        }
+    }
+}
+
+pub fn build_fs_matcher_config(
+    config_dir: &str,
+    rules_dir: &str,
+    drafts_dir: &str,
+    daemon_config: &DaemonCommandConfig,
 ) -> FsMatcherConfigManagerV2 {
     FsMatcherConfigManagerV2::new(
         format!("{}/{}", config_dir, rules_dir),
         format!("{}/{}", config_dir, drafts_dir),
     )
+    .with_history(
+        format!("{}/{}", config_dir, daemon_config.get_deploy_history_dir()),
+        daemon_config.get_deploy_history_size(),
+    )
+}
+
+pub fn build_sqlite_matcher_config(
+    config_dir: &str,
+    database_file: &str,
+    daemon_config: &DaemonCommandConfig,
+) -> SqliteMatcherConfigManagerV2 {
+    SqliteMatcherConfigManagerV2::new(format!("{}/{}", config_dir, database_file))
+        .with_history_size(daemon_config.get_deploy_history_size())
 }
 
 #[cfg(test)]
@@ -283,6 +495,7 @@ mod test {
         let config = build_config(path).unwrap();
 
         // Assert
+        assert_eq!(MatcherConfigStorage::Fs, config.tornado.matcher_config_storage);
         assert_eq!(
             vec![
                 Permission::ConfigEdit,
@@ -331,13 +544,38 @@ mod test {
         )
     }
 
+    #[tokio::test]
+    async fn should_store_the_configuration_in_git() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let config_dir = tempdir.path().to_str().unwrap();
+        let storage = MatcherConfigStorage::Git { repository_dir: "rules.git/".to_owned() };
+        let fs_config = FsMatcherConfigManagerV2::new("./config/rules.d", "./config/drafts")
+            .get_config()
+            .await
+            .unwrap();
+        fs_extra::dir::copy("./config/rules.d", config_dir, &fs_extra::dir::CopyOptions::new())
+            .unwrap();
+
+        // Act
+        let daemon_config = build_config("./config").unwrap().tornado.daemon;
+        let matcher_config =
+            build_matcher_config(config_dir, "rules.d", "drafts", &storage, &daemon_config);
+        let config = matcher_config.get_config().await.unwrap();
+
+        // Assert
+        assert_eq!(fs_config, config);
+        assert!(tempdir.path().join("rules.git/refs/heads/main").is_file());
+    }
+
     #[test]
     fn should_read_archiver_configurations_from_file() {
         // Arrange
         let config_dir = "./config";
 
         // Act
-        let config = build_archive_config(config_dir).unwrap();
+        let config: ArchiveConfig =
+            build_executor_config(config_dir, ARCHIVE_EXECUTOR_CONFIG_FILE, None).unwrap();
 
         // Assert
         assert_eq!("./target/tornado-log", config.base_path)
@@ -349,7 +587,8 @@ mod test {
         let config_dir = "./config";
 
         // Act
-        let config = build_icinga2_client_config(config_dir).unwrap();
+        let config: Icinga2ClientConfig =
+            build_executor_config(config_dir, ICINGA2_EXECUTOR_CONFIG_FILE, None).unwrap();
 
         // Assert
         assert_eq!("https://localhost:5665/v1/actions", config.server_api_url)
@@ -361,7 +600,8 @@ mod test {
         let config_dir = "./config";
 
         // Act
-        let config = build_director_client_config(config_dir).unwrap();
+        let config: DirectorClientConfig =
+            build_executor_config(config_dir, DIRECTOR_EXECUTOR_CONFIG_FILE, None).unwrap();
 
         // Assert
         assert_eq!("https://localhost/neteye/director", config.server_api_url)
@@ -381,8 +621,15 @@ mod test {
             web_server_port: 0,
             web_max_json_payload_size: None,
             message_queue_size: 0,
+            rules_watcher_enabled: None,
+            rules_watcher_debounce_ms: None,
             thread_pool_config: None,
             retry_strategy: Default::default(),
+            executors: None,
+            dead_letter_queue_dir: None,
+            dead_letter_queue_max_entries: None,
+            deploy_history_dir: None,
+            deploy_history_size: None,
             auth: AuthConfig::default(),
         };
 
@@ -409,18 +656,35 @@ mod test {
             web_server_port: 0,
             web_max_json_payload_size: None,
             message_queue_size: 0,
+            rules_watcher_enabled: None,
+            rules_watcher_debounce_ms: None,
             thread_pool_config: None,
             retry_strategy: Default::default(),
+            executors: None,
+            dead_letter_queue_dir: None,
+            dead_letter_queue_max_entries: None,
+            deploy_history_dir: None,
+            deploy_history_size: None,
             auth: AuthConfig::default(),
         };
 
         // Act
         let event_tcp_socket_enabled = daemon_configs.is_event_tcp_socket_enabled();
         let nats_enabled = daemon_configs.is_nats_enabled();
+        let rules_watcher_enabled = daemon_configs.is_rules_watcher_enabled();
+        let rules_watcher_debounce_ms = daemon_configs.get_rules_watcher_debounce_ms();
+        let deploy_history_dir = daemon_configs.get_deploy_history_dir();
+        let deploy_history_size = daemon_configs.get_deploy_history_size();
+        let dead_letter_queue_max_entries = daemon_configs.get_dead_letter_queue_max_entries();
 
         // Assert
         assert!(event_tcp_socket_enabled);
         assert!(!nats_enabled);
+        assert!(!rules_watcher_enabled);
+        assert_eq!(DEFAULT_RULES_WATCHER_DEBOUNCE_MS, rules_watcher_debounce_ms);
+        assert_eq!(DEFAULT_DEPLOY_HISTORY_DIR, deploy_history_dir);
+        assert_eq!(DEFAULT_DEPLOY_HISTORY_SIZE, deploy_history_size);
+        assert_eq!(DEFAULT_DEAD_LETTER_QUEUE_MAX_ENTRIES, dead_letter_queue_max_entries);
     }
 
     #[test]
@@ -0,0 +1,690 @@
+//! The registry of the executors started by the daemon.
+//! The executors are configured in the `tornado.daemon.executors` entries of the
+//! `tornado.toml` file; each entry binds an action id to an executor instance.
+//! More instances of the same executor type are distinguished by action ids in the
+//! `<type>:<instance>` form, e.g. `icinga2:dc1`.
+
+use crate::actor::foreach::{ForEachExecutorActor, ForEachExecutorActorInitMessage};
+use crate::config::{
+    build_executor_config, ComponentsConfig, ARCHIVE_EXECUTOR_CONFIG_FILE,
+    DIRECTOR_EXECUTOR_CONFIG_FILE, ELASTICSEARCH_EXECUTOR_CONFIG_FILE,
+    ICINGA2_EXECUTOR_CONFIG_FILE,
+};
+use actix::{Addr, Recipient};
+use log::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+use tornado_common::actors::command::CommandExecutorActor;
+use tornado_common::actors::message::ActionMessage;
+use tornado_common::command::pool::{CommandMutPool, CommandPool};
+use tornado_common::command::retry::{RetryCommand, RetryStrategy};
+use tornado_common::command::{Command, StatefulExecutorCommand, StatelessExecutorCommand};
+use tornado_common::dead_letter::DeadLetterQueue;
+use tornado_common::metrics::ActionMeter;
+use tornado_common_api::Action;
+use tornado_executor_archive::config::ArchiveConfig;
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tornado_executor_director::config::DirectorClientConfig;
+use tornado_executor_elasticsearch::config::ElasticsearchConfig;
+use tornado_executor_icinga2::config::Icinga2ClientConfig;
+use tornado_network_common::EventBus;
+
+pub const ACTION_ID_SMART_MONITORING_CHECK_RESULT: &str = "smart_monitoring_check_result";
+pub const ACTION_ID_FOREACH: &str = "foreach";
+pub const ACTION_ID_LOGGER: &str = "logger";
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutorType {
+    Archive,
+    Director,
+    Elasticsearch,
+    Foreach,
+    Icinga2,
+    Logger,
+    Script,
+    SmartMonitoringCheckResult,
+}
+
+impl ExecutorType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExecutorType::Archive => "archive",
+            ExecutorType::Director => "director",
+            ExecutorType::Elasticsearch => "elasticsearch",
+            ExecutorType::Foreach => "foreach",
+            ExecutorType::Icinga2 => "icinga2",
+            ExecutorType::Logger => "logger",
+            ExecutorType::Script => "script",
+            ExecutorType::SmartMonitoringCheckResult => "smart_monitoring_check_result",
+        }
+    }
+}
+
+/// An executor instance and the id of the actions it executes
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ExecutorConfig {
+    pub action_id: String,
+    #[serde(rename = "type")]
+    pub executor_type: ExecutorType,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// The executor configuration. When not provided, the archive, director, elasticsearch,
+    /// icinga2 and smart_monitoring_check_result executors use the configuration files
+    /// of the `config-dir`; a named instance reads the `<file>.<instance>.toml` files.
+    pub config: Option<Value>,
+    /// Overrides the global retry strategy
+    pub retry_strategy: Option<RetryStrategy>,
+    /// The number of actions executed in parallel. Defaults to 1 for the archive executor
+    /// and to the number of threads per queue for the others.
+    pub pool_size: Option<usize>,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+impl ExecutorConfig {
+    pub fn new(action_id: &str, executor_type: ExecutorType) -> Self {
+        ExecutorConfig {
+            action_id: action_id.to_owned(),
+            executor_type,
+            enabled: true,
+            config: None,
+            retry_strategy: None,
+            pool_size: None,
+        }
+    }
+
+    /// The name of the instance, when the action id is in the `<type>:<instance>` form
+    pub fn instance_name(&self) -> Option<&str> {
+        self.action_id.split_once(':').map(|(_, instance)| instance)
+    }
+
+    fn check_instance_type(&self) -> Result<(), BoxedError> {
+        match self.action_id.split_once(':') {
+            Some((executor_type, _)) if executor_type != self.executor_type.name() => Err(format!(
+                "The action id [{}] of a named instance must start with the executor type [{}]",
+                self.action_id,
+                self.executor_type.name()
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the inline configuration, if provided. Otherwise, a named instance reads its
+    /// own configuration file, while the other executors use the default configuration.
+    fn parse_config<T: DeserializeOwned + Clone>(
+        &self,
+        default: &T,
+        components: &ComponentsConfig,
+        file_name: &str,
+    ) -> Result<T, BoxedError> {
+        match (&self.config, self.instance_name()) {
+            (Some(config), _) => serde_json::from_value(config.clone()).map_err(|err| {
+                format!(
+                    "Invalid configuration of the executor of action id [{}]. Err: {}",
+                    self.action_id, err
+                )
+                .into()
+            }),
+            (None, Some(instance)) => self.read_instance_config(components, file_name, instance),
+            (None, None) => Ok(default.clone()),
+        }
+    }
+
+    fn read_instance_config<T: DeserializeOwned>(
+        &self,
+        components: &ComponentsConfig,
+        file_name: &str,
+        instance: &str,
+    ) -> Result<T, BoxedError> {
+        build_executor_config(&components.config_dir, file_name, Some(instance)).map_err(|err| {
+            format!(
+                "Cannot read the configuration file [{}.{}.toml] of the executor of action id [{}]. Err: {}",
+                file_name, instance, self.action_id, err
+            )
+            .into()
+        })
+    }
+
+    fn check_no_config(&self) -> Result<(), BoxedError> {
+        match &self.config {
+            Some(_) => Err(format!(
+                "The {:?} executor of action id [{}] does not accept a configuration",
+                self.executor_type, self.action_id
+            )
+            .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The configuration of the smart_monitoring_check_result executor
+#[derive(Deserialize, Clone)]
+struct SmartMonitoringConfig {
+    icinga2: Icinga2ClientConfig,
+    director: DirectorClientConfig,
+}
+
+/// The executors started when the `tornado.daemon.executors` entry is not provided
+pub fn default_executors() -> Vec<ExecutorConfig> {
+    vec![
+        ExecutorConfig::new("archive", ExecutorType::Archive),
+        ExecutorConfig::new("icinga2", ExecutorType::Icinga2),
+        ExecutorConfig::new("director", ExecutorType::Director),
+        ExecutorConfig::new(
+            ACTION_ID_SMART_MONITORING_CHECK_RESULT,
+            ExecutorType::SmartMonitoringCheckResult,
+        ),
+        ExecutorConfig::new("script", ExecutorType::Script),
+        ExecutorConfig::new(ACTION_ID_FOREACH, ExecutorType::Foreach),
+        ExecutorConfig::new(ACTION_ID_LOGGER, ExecutorType::Logger),
+        ExecutorConfig::new("elasticsearch", ExecutorType::Elasticsearch),
+    ]
+}
+
+/// Returns the action ids of the enabled executors and, among them, the ones of
+/// the foreach executors.
+pub fn enabled_action_ids(executors: &[ExecutorConfig]) -> (Vec<String>, Vec<String>) {
+    let enabled = executors.iter().filter(|executor| executor.enabled);
+    let action_ids = enabled.clone().map(|executor| executor.action_id.clone()).collect();
+    let foreach_action_ids = enabled
+        .filter(|executor| executor.executor_type == ExecutorType::Foreach)
+        .map(|executor| executor.action_id.clone())
+        .collect();
+    (action_ids, foreach_action_ids)
+}
+
+/// Checks that the action ids of the enabled executors are unique and that
+/// their configurations are valid, without starting them.
+pub fn validate_executors(
+    executors: &[ExecutorConfig],
+    components: &ComponentsConfig,
+) -> Result<(), BoxedError> {
+    let mut action_ids = HashSet::new();
+    for executor in executors.iter().filter(|executor| executor.enabled) {
+        if !action_ids.insert(executor.action_id.as_str()) {
+            return Err(format!(
+                "More than one enabled executor for action id [{}]",
+                executor.action_id
+            )
+            .into());
+        }
+        executor.check_instance_type()?;
+        match executor.executor_type {
+            ExecutorType::Archive => {
+                archive_config(executor, components)?;
+            }
+            ExecutorType::Director => {
+                director_config(executor, components)?;
+            }
+            ExecutorType::Elasticsearch => {
+                elasticsearch_config(executor, components)?;
+            }
+            ExecutorType::Icinga2 => {
+                icinga2_config(executor, components)?;
+            }
+            ExecutorType::SmartMonitoringCheckResult => {
+                smart_monitoring_config(executor, components)?;
+            }
+            ExecutorType::Foreach | ExecutorType::Logger | ExecutorType::Script => {
+                executor.check_no_config()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn archive_config(
+    executor: &ExecutorConfig,
+    components: &ComponentsConfig,
+) -> Result<ArchiveConfig, BoxedError> {
+    executor.parse_config(
+        &components.archive_executor_config,
+        components,
+        ARCHIVE_EXECUTOR_CONFIG_FILE,
+    )
+}
+
+fn director_config(
+    executor: &ExecutorConfig,
+    components: &ComponentsConfig,
+) -> Result<DirectorClientConfig, BoxedError> {
+    executor.parse_config(
+        &components.director_executor_config,
+        components,
+        DIRECTOR_EXECUTOR_CONFIG_FILE,
+    )
+}
+
+fn elasticsearch_config(
+    executor: &ExecutorConfig,
+    components: &ComponentsConfig,
+) -> Result<ElasticsearchConfig, BoxedError> {
+    executor.parse_config(
+        &components.elasticsearch_executor_config,
+        components,
+        ELASTICSEARCH_EXECUTOR_CONFIG_FILE,
+    )
+}
+
+fn icinga2_config(
+    executor: &ExecutorConfig,
+    components: &ComponentsConfig,
+) -> Result<Icinga2ClientConfig, BoxedError> {
+    executor.parse_config(
+        &components.icinga2_executor_config,
+        components,
+        ICINGA2_EXECUTOR_CONFIG_FILE,
+    )
+}
+
+/// A named smart_monitoring_check_result instance reads both the icinga2 and the director
+/// configuration files of the instance.
+fn smart_monitoring_config(
+    executor: &ExecutorConfig,
+    components: &ComponentsConfig,
+) -> Result<SmartMonitoringConfig, BoxedError> {
+    match (&executor.config, executor.instance_name()) {
+        (None, Some(instance)) => Ok(SmartMonitoringConfig {
+            icinga2: executor.read_instance_config(
+                components,
+                ICINGA2_EXECUTOR_CONFIG_FILE,
+                instance,
+            )?,
+            director: executor.read_instance_config(
+                components,
+                DIRECTOR_EXECUTOR_CONFIG_FILE,
+                instance,
+            )?,
+        }),
+        _ => executor.parse_config(
+            &SmartMonitoringConfig {
+                icinga2: components.icinga2_executor_config.clone(),
+                director: components.director_executor_config.clone(),
+            },
+            components,
+            ICINGA2_EXECUTOR_CONFIG_FILE,
+        ),
+    }
+}
+
+/// The settings shared by all the executors
+pub struct ExecutorSettings {
+    pub retry_strategy: RetryStrategy,
+    pub threads_per_queue: usize,
+    pub message_queue_size: usize,
+    pub action_meter: Arc<ActionMeter>,
+    /// Where the actions are stored when their execution fails after all the retry attempts
+    pub dead_letter_queue: Option<Arc<DeadLetterQueue>>,
+}
+
+/// The running executors, indexed by the id of the actions they execute
+pub struct ExecutorRegistry {
+    executors: HashMap<String, Recipient<ActionMessage>>,
+    foreach_executors: Vec<Addr<ForEachExecutorActor>>,
+}
+
+impl ExecutorRegistry {
+    /// Starts the enabled executors. The foreach executors have to be initialized
+    /// with `init_foreach_executors` before they can execute actions.
+    pub async fn start(
+        executors: &[ExecutorConfig],
+        components: &ComponentsConfig,
+        settings: &ExecutorSettings,
+    ) -> Result<Self, BoxedError> {
+        validate_executors(executors, components)?;
+        let mut registry =
+            ExecutorRegistry { executors: HashMap::new(), foreach_executors: vec![] };
+
+        for executor in executors {
+            if !executor.enabled {
+                info!(
+                    "The {:?} executor of action id [{}] is disabled",
+                    executor.executor_type, executor.action_id
+                );
+                continue;
+            }
+            info!(
+                "Start the {:?} executor of action id [{}]",
+                executor.executor_type, executor.action_id
+            );
+            let recipient = match executor.executor_type {
+                ExecutorType::Archive => {
+                    let archive_config = archive_config(executor, components)?;
+                    let action_meter = settings.action_meter.clone();
+                    CommandExecutorActor::start_new(
+                        settings.message_queue_size,
+                        Rc::new(retry_command(
+                            executor,
+                            settings,
+                            CommandMutPool::new(executor.pool_size.unwrap_or(1), move || {
+                                StatefulExecutorCommand::new(
+                                    action_meter.clone(),
+                                    tornado_executor_archive::ArchiveExecutor::new(&archive_config),
+                                )
+                            }),
+                        )),
+                        settings.action_meter.clone(),
+                    )
+                    .recipient()
+                }
+                ExecutorType::Director => start_stateless(
+                    executor,
+                    settings,
+                    tornado_executor_director::DirectorExecutor::new(director_config(
+                        executor, components,
+                    )?)?,
+                ),
+                ExecutorType::Elasticsearch => start_stateless(
+                    executor,
+                    settings,
+                    tornado_executor_elasticsearch::ElasticsearchExecutor::new(
+                        elasticsearch_config(executor, components)?.default_auth,
+                    )
+                    .await?,
+                ),
+                ExecutorType::Foreach => {
+                    let addr = ForEachExecutorActor::start_new(settings.message_queue_size);
+                    registry.foreach_executors.push(addr.clone());
+                    addr.recipient()
+                }
+                ExecutorType::Icinga2 => start_stateless(
+                    executor,
+                    settings,
+                    tornado_executor_icinga2::Icinga2Executor::new(icinga2_config(
+                        executor, components,
+                    )?)?,
+                ),
+                ExecutorType::Logger => start_stateless(
+                    executor,
+                    settings,
+                    tornado_executor_logger::LoggerExecutor::new(),
+                ),
+                ExecutorType::Script => start_stateless(
+                    executor,
+                    settings,
+                    tornado_executor_script::ScriptExecutor::new(),
+                ),
+                ExecutorType::SmartMonitoringCheckResult => {
+                    let config = smart_monitoring_config(executor, components)?;
+                    start_stateless(
+                        executor,
+                        settings,
+                        tornado_executor_smart_monitoring_check_result::SmartMonitoringExecutor::new(
+                            config.icinga2,
+                            config.director,
+                        )?,
+                    )
+                }
+            };
+            registry.executors.insert(executor.action_id.clone(), recipient);
+        }
+        Ok(registry)
+    }
+
+    /// Initializes the foreach executors, which publish the actions of each iteration
+    /// to the event bus
+    pub fn init_foreach_executors<B: EventBus + Send + Sync + 'static>(
+        &self,
+        event_bus: Arc<B>,
+    ) -> Result<(), BoxedError> {
+        for foreach_executor in &self.foreach_executors {
+            let event_bus = event_bus.clone();
+            foreach_executor
+                .try_send(ForEachExecutorActorInitMessage {
+                    init: move || tornado_executor_foreach::ForEachExecutor::new(event_bus.clone()),
+                })
+                .map_err(|err| format!("Cannot initialize the foreach executor. Err: {:?}", err))?;
+        }
+        Ok(())
+    }
+
+    /// Sends the action to the executor registered for its action id
+    pub fn send(&self, message: ActionMessage) -> Result<(), String> {
+        let action_id = message.0.action.id.to_owned();
+        match self.executors.get(&action_id) {
+            Some(executor) => executor.try_send(message).map_err(|err| {
+                format!("Error sending message to '{}' executor. Err: {:?}", action_id, err)
+            }),
+            None => Err(format!("There are not executors for action id [{}]", action_id)),
+        }
+    }
+
+    pub fn action_ids(&self) -> Vec<&str> {
+        let mut action_ids = self.executors.keys().map(String::as_str).collect::<Vec<_>>();
+        action_ids.sort_unstable();
+        action_ids
+    }
+}
+
+/// Wraps the command with the retry strategy of the executor, sending the actions that
+/// are not retried any more to the dead-letter queue
+fn retry_command<T: Command<Arc<Action>, Result<(), ExecutorError>>>(
+    executor: &ExecutorConfig,
+    settings: &ExecutorSettings,
+    command: T,
+) -> RetryCommand<Arc<Action>, (), ExecutorError, T> {
+    let retry_strategy =
+        executor.retry_strategy.clone().unwrap_or_else(|| settings.retry_strategy.clone());
+    let retry_command = RetryCommand::new(retry_strategy, command);
+    match &settings.dead_letter_queue {
+        Some(dead_letter_queue) => retry_command.with_dead_letter_sink(dead_letter_queue.clone()),
+        None => retry_command,
+    }
+}
+
+fn start_stateless<T: StatelessExecutor + 'static>(
+    executor_config: &ExecutorConfig,
+    settings: &ExecutorSettings,
+    executor: T,
+) -> Recipient<ActionMessage> {
+    let stateless_executor_command =
+        StatelessExecutorCommand::new(settings.action_meter.clone(), executor);
+    CommandExecutorActor::start_new(
+        settings.message_queue_size,
+        Rc::new(retry_command(
+            executor_config,
+            settings,
+            CommandPool::new(
+                executor_config.pool_size.unwrap_or(settings.threads_per_queue),
+                stateless_executor_command,
+            ),
+        )),
+        settings.action_meter.clone(),
+    )
+    .recipient()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::{build_config, parse_config_files};
+    use config_rs::{Config, File, FileFormat};
+    use serde_json::json;
+    use tornado_common_api::TracedAction;
+
+    fn components() -> ComponentsConfig {
+        parse_config_files("./config", "/rules.d", "/drafts").unwrap()
+    }
+
+    fn settings() -> ExecutorSettings {
+        ExecutorSettings {
+            retry_strategy: RetryStrategy::default(),
+            threads_per_queue: 1,
+            message_queue_size: 10,
+            action_meter: Arc::new(ActionMeter::new("test")),
+            dead_letter_queue: None,
+        }
+    }
+
+    #[test]
+    fn should_read_the_executors_from_the_configuration() {
+        // Arrange
+        let config = r#"
+            [[executors]]
+            action_id = "archive_audit"
+            type = "archive"
+            pool_size = 2
+            config = { base_path = "/tmp/audit", default_path = "/default.log", paths = {}, file_cache_size = 10, file_cache_ttl_secs = 1 }
+
+            [[executors]]
+            action_id = "icinga2"
+            type = "icinga2"
+            enabled = false
+            retry_strategy.retry_policy = { type = "None" }
+            retry_strategy.backoff_policy = { type = "None" }
+        "#;
+
+        // Act
+        let mut s = Config::new();
+        s.merge(File::from_str(config, FileFormat::Toml)).unwrap();
+        let executors: Vec<ExecutorConfig> = s.get("executors").unwrap();
+
+        // Assert
+        assert_eq!(2, executors.len());
+        assert_eq!(ExecutorType::Archive, executors[0].executor_type);
+        assert!(executors[0].enabled);
+        assert_eq!(Some(2), executors[0].pool_size);
+        assert_eq!(
+            Some(json!("/tmp/audit")),
+            executors[0].config.as_ref().map(|c| c["base_path"].clone())
+        );
+        assert_eq!(ExecutorType::Icinga2, executors[1].executor_type);
+        assert!(!executors[1].enabled);
+        assert!(executors[1].retry_strategy.is_some());
+        assert!(validate_executors(&executors, &components()).is_ok());
+    }
+
+    #[test]
+    fn should_use_the_default_executors() {
+        // Act
+        let config = build_config("./config").unwrap();
+
+        // Assert
+        let executors = config.tornado.daemon.get_executors();
+        assert_eq!(8, executors.len());
+        assert!(validate_executors(&executors, &components()).is_ok());
+    }
+
+    #[test]
+    fn should_reject_invalid_executors() {
+        // Arrange
+        let duplicated = vec![
+            ExecutorConfig::new("logger", ExecutorType::Logger),
+            ExecutorConfig::new("logger", ExecutorType::Script),
+        ];
+        let mut disabled = duplicated.clone();
+        disabled[1].enabled = false;
+        let mut with_config = ExecutorConfig::new("logger", ExecutorType::Logger);
+        with_config.config = Some(json!({}));
+        let mut invalid_config = ExecutorConfig::new("archive", ExecutorType::Archive);
+        invalid_config.config = Some(json!({ "base_path": 1 }));
+
+        // Assert
+        assert!(validate_executors(&duplicated, &components()).is_err());
+        assert!(validate_executors(&disabled, &components()).is_ok());
+        assert!(validate_executors(&[with_config], &components()).is_err());
+        assert!(validate_executors(&[invalid_config], &components()).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn should_start_named_instances() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tempdir.path().join("icinga2_client_executor.dc1.toml"),
+            r#"
+                server_api_url = "https://dc1:5665/v1/actions"
+                username = "dc1"
+                password = ""
+                disable_ssl_verification = true
+            "#,
+        )
+        .unwrap();
+        let mut components = components();
+        components.config_dir = tempdir.path().to_str().unwrap().to_owned();
+        let mut inline = ExecutorConfig::new("icinga2:dc2", ExecutorType::Icinga2);
+        inline.config = Some(json!({
+            "server_api_url": "https://dc2:5665/v1/actions",
+            "username": "dc2",
+            "password": "",
+            "disable_ssl_verification": true
+        }));
+        let executors = vec![
+            ExecutorConfig::new("icinga2", ExecutorType::Icinga2),
+            ExecutorConfig::new("icinga2:dc1", ExecutorType::Icinga2),
+            inline,
+            ExecutorConfig::new("foreach:nested", ExecutorType::Foreach),
+        ];
+
+        // Act
+        let registry = ExecutorRegistry::start(&executors, &components, &settings()).await.unwrap();
+
+        // Assert
+        assert_eq!(Some("dc1"), executors[1].instance_name());
+        assert_eq!("dc1", icinga2_config(&executors[1], &components).unwrap().username);
+        assert_eq!(
+            vec!["foreach:nested", "icinga2", "icinga2:dc1", "icinga2:dc2"],
+            registry.action_ids()
+        );
+        assert_eq!(
+            (
+                vec![
+                    "icinga2".to_owned(),
+                    "icinga2:dc1".to_owned(),
+                    "icinga2:dc2".to_owned(),
+                    "foreach:nested".to_owned()
+                ],
+                vec!["foreach:nested".to_owned()]
+            ),
+            enabled_action_ids(&executors)
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_named_instances() {
+        // Arrange
+        let wrong_type = ExecutorConfig::new("icinga2:dc1", ExecutorType::Director);
+        let missing_file = ExecutorConfig::new("elasticsearch:audit", ExecutorType::Elasticsearch);
+
+        // Assert
+        assert!(validate_executors(&[wrong_type], &components()).is_err());
+        assert!(validate_executors(&[missing_file], &components()).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn should_route_the_actions_by_action_id() {
+        // Arrange
+        let mut disabled = ExecutorConfig::new("script", ExecutorType::Script);
+        disabled.enabled = false;
+        let executors = vec![
+            ExecutorConfig::new("logger", ExecutorType::Logger),
+            ExecutorConfig::new("second_logger", ExecutorType::Logger),
+            disabled,
+        ];
+        let message = |id: &str| {
+            ActionMessage(TracedAction {
+                span: tracing::Span::current(),
+                action: Arc::new(Action::new(id)),
+            })
+        };
+
+        // Act
+        let registry =
+            ExecutorRegistry::start(&executors, &components(), &settings()).await.unwrap();
+
+        // Assert
+        assert_eq!(vec!["logger", "second_logger"], registry.action_ids());
+        assert!(registry.send(message("second_logger")).is_ok());
+        assert!(registry.send(message("script")).is_err());
+    }
+}
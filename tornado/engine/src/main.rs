@@ -7,7 +7,9 @@ mod api;
 mod command;
 pub mod config;
 mod enrich;
+mod executor;
 mod monitoring;
+mod rules_watcher;
 
 #[actix_web::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
@@ -20,12 +22,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>
     match &opt.command {
         SubCommand::Check => command::check::check(config_dir, rules_dir, drafts_dir).await,
         SubCommand::Daemon => command::daemon::daemon(config_dir, rules_dir, drafts_dir).await,
-        SubCommand::RulesUpgrade => {
-            command::upgrade_rules::upgrade_rules(config_dir, rules_dir, drafts_dir).await
+        SubCommand::RulesUpgrade(opts) => {
+            command::upgrade_rules::upgrade_rules(config_dir, rules_dir, drafts_dir, opts).await
         }
         SubCommand::FilterCreate(opts) => {
             command::create_filter::create_filter(config_dir, rules_dir, drafts_dir, opts).await
         }
+        SubCommand::Replay(opts) => {
+            command::replay::replay(config_dir, rules_dir, drafts_dir, opts).await
+        }
+        SubCommand::ProcessEvent(opts) => {
+            command::process_event::process_event(config_dir, rules_dir, drafts_dir, opts).await
+        }
+        SubCommand::Export(opts) => {
+            command::bundle::export_bundle(config_dir, rules_dir, drafts_dir, opts).await
+        }
+        SubCommand::Render(opts) => {
+            command::render::render(config_dir, rules_dir, drafts_dir, opts).await
+        }
+        SubCommand::Import(opts) => {
+            command::bundle::import_bundle(config_dir, rules_dir, drafts_dir, opts).await
+        }
+        SubCommand::RulesMigrateSqlite => {
+            command::migrate_sqlite::migrate_sqlite(config_dir, rules_dir, drafts_dir).await
+        }
         SubCommand::ApmTracing { command } => apm_tracing(config_dir, command).await,
     }
 }
@@ -87,8 +87,15 @@ mod test {
             web_server_port: 0,
             web_max_json_payload_size: None,
             message_queue_size: 0,
+            rules_watcher_enabled: None,
+            rules_watcher_debounce_ms: None,
             thread_pool_config: None,
             retry_strategy: Default::default(),
+            executors: None,
+            dead_letter_queue_dir: None,
+            dead_letter_queue_max_entries: None,
+            deploy_history_dir: None,
+            deploy_history_size: None,
             auth: AuthConfig::default(),
         };
         let srv = test::init_service(App::new().service(monitoring_endpoints(
@@ -124,8 +131,15 @@ mod test {
             web_server_port: 0,
             web_max_json_payload_size: None,
             message_queue_size: 0,
+            rules_watcher_enabled: None,
+            rules_watcher_debounce_ms: None,
             thread_pool_config: None,
             retry_strategy: Default::default(),
+            executors: None,
+            dead_letter_queue_dir: None,
+            dead_letter_queue_max_entries: None,
+            deploy_history_dir: None,
+            deploy_history_size: None,
             auth: AuthConfig::default(),
         };
         let srv = test::init_service(App::new().service(monitoring_endpoints(
@@ -161,8 +175,15 @@ mod test {
             web_server_port: 0,
             web_max_json_payload_size: None,
             message_queue_size: 0,
+            rules_watcher_enabled: None,
+            rules_watcher_debounce_ms: None,
             thread_pool_config: None,
             retry_strategy: Default::default(),
+            executors: None,
+            dead_letter_queue_dir: None,
+            dead_letter_queue_max_entries: None,
+            deploy_history_dir: None,
+            deploy_history_size: None,
             auth: AuthConfig::default(),
         };
         let srv = test::init_service(App::new().service(monitoring_endpoints(
@@ -203,8 +224,15 @@ mod test {
                 web_server_port: 0,
                 web_max_json_payload_size: None,
                 message_queue_size: 0,
+                rules_watcher_enabled: None,
+                rules_watcher_debounce_ms: None,
                 thread_pool_config: None,
                 retry_strategy: Default::default(),
+                executors: None,
+                dead_letter_queue_dir: None,
+                dead_letter_queue_max_entries: None,
+                deploy_history_dir: None,
+                deploy_history_size: None,
                 auth: AuthConfig::default(),
             };
             let metrics = Arc::new(Metrics::new("aa"));
@@ -1,9 +1,15 @@
-use tornado_common_metrics::opentelemetry::metrics::{Counter, Unit, ValueRecorder};
-use tornado_common_metrics::opentelemetry::Key;
+use std::sync::Arc;
+use tornado_common::dead_letter::DeadLetterQueue;
+use tornado_common_metrics::opentelemetry::metrics::{Counter, Unit, ValueObserver, ValueRecorder};
+use tornado_common_metrics::opentelemetry::{Key, KeyValue};
+use tornado_engine_matcher::model::ProcessedRuleStatus;
+use tornado_engine_matcher::stats::{MatcherStats, StatsLayout, StatsUpdate};
 
 pub const TORNADO_APP: &str = "tornado";
 pub const EVENT_TYPE_LABEL_KEY: Key = Key::from_static_str("event_type");
 pub const EVENT_SOURCE_LABEL_KEY: Key = Key::from_static_str("source");
+pub const NODE_PATH_LABEL_KEY: Key = Key::from_static_str("node_path");
+pub const RULE_PATH_LABEL_KEY: Key = Key::from_static_str("rule_path");
 
 pub struct TornadoMeter {
     /// Counts the total invalid events received
@@ -18,6 +24,129 @@ pub struct TornadoMeter {
     pub http_requests_counter: Counter<u64>,
     /// Counts the total http requests processing seconds
     pub http_requests_duration_seconds: ValueRecorder<f64>,
+    /// Counts the times a processing tree node is reached by an event
+    pub node_evaluated_counter: Counter<u64>,
+    /// Counts the times a processing tree node matches an event
+    pub node_matched_counter: Counter<u64>,
+    /// Counts the times a rule is evaluated
+    pub rule_evaluated_counter: Counter<u64>,
+    /// Counts the times a rule matches an event
+    pub rule_matched_counter: Counter<u64>,
+    /// Counts the times a rule partially matches an event
+    pub rule_partially_matched_counter: Counter<u64>,
+    /// Counts the actions emitted by a rule
+    pub rule_actions_emitted_counter: Counter<u64>,
+}
+
+/// The labels of the node and rule counters, built once per configuration
+/// and indexed by the ids of the StatsLayout.
+pub struct MatcherStatsLabels {
+    nodes: Vec<[KeyValue; 1]>,
+    rules: Vec<[KeyValue; 1]>,
+}
+
+impl MatcherStatsLabels {
+    pub fn new(layout: &StatsLayout) -> Self {
+        Self {
+            nodes: layout
+                .nodes()
+                .iter()
+                .map(|node| [NODE_PATH_LABEL_KEY.string(node.label.clone())])
+                .collect(),
+            rules: layout
+                .rules()
+                .iter()
+                .map(|rule| [RULE_PATH_LABEL_KEY.string(rule.label.clone())])
+                .collect(),
+        }
+    }
+}
+
+impl TornadoMeter {
+    /// Adds the outcomes of a processed event to the node and rule counters.
+    /// The labels must be built from the layout that produced the updates.
+    pub fn record_matcher_stats(&self, labels: &MatcherStatsLabels, updates: &[StatsUpdate]) {
+        for update in updates {
+            match update {
+                StatsUpdate::Node { id, matched } => {
+                    let Some(node_labels) = labels.nodes.get(*id) else { continue };
+                    self.node_evaluated_counter.add(1, node_labels);
+                    if *matched {
+                        self.node_matched_counter.add(1, node_labels);
+                    }
+                }
+                StatsUpdate::Rule { id, status, actions } => {
+                    let Some(rule_labels) = labels.rules.get(*id) else { continue };
+                    self.rule_evaluated_counter.add(1, rule_labels);
+                    match status {
+                        ProcessedRuleStatus::Matched => {
+                            self.rule_matched_counter.add(1, rule_labels);
+                            self.rule_actions_emitted_counter.add(*actions as u64, rule_labels);
+                        }
+                        ProcessedRuleStatus::PartiallyMatched => {
+                            self.rule_partially_matched_counter.add(1, rule_labels)
+                        }
+                        ProcessedRuleStatus::NotMatched | ProcessedRuleStatus::NotProcessed => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The observers exporting the last-matched timestamps of the MatcherStats.
+/// The timestamps are exported only while this struct is alive.
+pub struct MatcherStatsObservers {
+    _node_last_matched: ValueObserver<i64>,
+    _rule_last_matched: ValueObserver<i64>,
+}
+
+impl MatcherStatsObservers {
+    pub fn new(stats: Arc<MatcherStats>) -> Self {
+        let meter = tornado_common_metrics::opentelemetry::global::meter("tornado");
+
+        let node_stats = stats.clone();
+        let node_last_matched = meter
+            .i64_value_observer("node_last_matched_epoch_ms", move |observer| {
+                for (path, timestamp) in node_stats.nodes_last_matched() {
+                    observer.observe(timestamp, &[NODE_PATH_LABEL_KEY.string(path)]);
+                }
+            })
+            .with_description("Last time a processing tree node matched an event")
+            .with_unit(Unit::new("milliseconds"))
+            .init();
+
+        let rule_last_matched = meter
+            .i64_value_observer("rule_last_matched_epoch_ms", move |observer| {
+                for (path, timestamp) in stats.rules_last_matched() {
+                    observer.observe(timestamp, &[RULE_PATH_LABEL_KEY.string(path)]);
+                }
+            })
+            .with_description("Last time a rule matched an event")
+            .with_unit(Unit::new("milliseconds"))
+            .init();
+
+        Self { _node_last_matched: node_last_matched, _rule_last_matched: rule_last_matched }
+    }
+}
+
+/// The observer exporting the number of actions in the dead-letter queue.
+/// The size is exported only while this struct is alive.
+pub struct DeadLetterQueueObserver {
+    _size: ValueObserver<u64>,
+}
+
+impl DeadLetterQueueObserver {
+    pub fn new(dead_letter_queue: Arc<DeadLetterQueue>) -> Self {
+        let meter = tornado_common_metrics::opentelemetry::global::meter("tornado");
+        let size = meter
+            .u64_value_observer("dead_letter_queue_size", move |observer| {
+                observer.observe(dead_letter_queue.size() as u64, &[]);
+            })
+            .with_description("Number of actions in the dead-letter queue")
+            .init();
+        Self { _size: size }
+    }
 }
 
 impl Default for TornadoMeter {
@@ -56,6 +185,34 @@ impl Default for TornadoMeter {
             .with_unit(Unit::new("seconds"))
             .init();
 
+        let node_evaluated_counter = meter
+            .u64_counter("node_evaluated_counter")
+            .with_description("Processing tree node evaluations count")
+            .init();
+
+        let node_matched_counter = meter
+            .u64_counter("node_matched_counter")
+            .with_description("Processing tree node matches count")
+            .init();
+
+        let rule_evaluated_counter = meter
+            .u64_counter("rule_evaluated_counter")
+            .with_description("Rule evaluations count")
+            .init();
+
+        let rule_matched_counter =
+            meter.u64_counter("rule_matched_counter").with_description("Rule matches count").init();
+
+        let rule_partially_matched_counter = meter
+            .u64_counter("rule_partially_matched_counter")
+            .with_description("Rule partial matches count")
+            .init();
+
+        let rule_actions_emitted_counter = meter
+            .u64_counter("rule_actions_emitted_counter")
+            .with_description("Actions emitted by rules count")
+            .init();
+
         Self {
             invalid_events_received_counter,
             events_received_counter,
@@ -63,6 +220,12 @@ impl Default for TornadoMeter {
             events_processed_duration_seconds,
             http_requests_counter,
             http_requests_duration_seconds,
+            node_evaluated_counter,
+            node_matched_counter,
+            rule_evaluated_counter,
+            rule_matched_counter,
+            rule_partially_matched_counter,
+            rule_actions_emitted_counter,
         }
     }
 }
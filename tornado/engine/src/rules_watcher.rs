@@ -0,0 +1,117 @@
+use crate::actor::matcher::{MatcherActor, ReconfigureMessage};
+use actix::Addr;
+use log::*;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tornado_engine_matcher::config::MatcherConfigEditor;
+use tornado_engine_matcher::error::MatcherError;
+use tornado_engine_matcher::matcher::Matcher;
+use tornado_engine_matcher::test_case::check_tests;
+use tornado_engine_matcher::validator::MatcherConfigValidator;
+
+/// Watches the rules directory and reloads the configuration of the matcher when the files
+/// change. The changes are debounced, so that a burst of writes causes a single reload.
+/// The new configuration is applied only if it is valid; otherwise the current one is kept.
+///
+/// The parent of the rules directory is watched, instead of the rules directory itself,
+/// because a deploy replaces the whole directory.
+/// The watcher stops when this struct is dropped.
+pub struct RulesWatcher {
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl RulesWatcher {
+    pub fn start(
+        rules_dir: &str,
+        debounce: Duration,
+        config_manager: Arc<dyn MatcherConfigEditor>,
+        matcher_addr: Addr<MatcherActor>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let rules_path = Path::new(rules_dir)
+            .canonicalize()
+            .map_err(|err| format!("Cannot watch the rules directory [{}]: {}", rules_dir, err))?;
+        let watched_path = rules_path.parent().unwrap_or_else(|| Path::new("/")).to_path_buf();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let changed_path = rules_path.clone();
+        let mut debouncer =
+            new_debouncer(debounce, move |result: DebounceEventResult| match result {
+                Ok(events) => {
+                    if events.iter().any(|event| event.path.starts_with(&changed_path)) {
+                        let _ = sender.send(());
+                    }
+                }
+                Err(err) => error!("RulesWatcher - Error while watching the rules: {}", err),
+            })?;
+        debouncer.watcher().watch(&watched_path, RecursiveMode::Recursive)?;
+        info!("RulesWatcher - Watching the rules directory [{}]", rules_path.display());
+
+        actix::spawn(async move {
+            while receiver.recv().await.is_some() {
+                // Changes notified while the previous ones were handled are already on disk
+                while receiver.try_recv().is_ok() {}
+                reload_if_valid(config_manager.as_ref(), &matcher_addr).await;
+            }
+        });
+
+        Ok(Self { _debouncer: debouncer })
+    }
+}
+
+async fn reload_if_valid(
+    config_manager: &dyn MatcherConfigEditor,
+    matcher_addr: &Addr<MatcherActor>,
+) {
+    info!("RulesWatcher - The rules changed on disk. Validating the new configuration.");
+    if let Err(err) = validate_rules(config_manager).await {
+        error!(
+            "RulesWatcher - The new configuration is not valid, the current one is kept. Err: {}",
+            err
+        );
+        return;
+    }
+    match matcher_addr.send(ReconfigureMessage {}).await {
+        Ok(Ok(_)) => info!("RulesWatcher - The configuration was reloaded."),
+        Ok(Err(err)) => error!("RulesWatcher - Cannot reload the configuration. Err: {}", err),
+        Err(err) => error!("RulesWatcher - Cannot send the ReconfigureMessage. Err: {}", err),
+    }
+}
+
+/// Performs on the rules on disk the same checks of a deploy.
+async fn validate_rules(config_manager: &dyn MatcherConfigEditor) -> Result<(), MatcherError> {
+    let config = config_manager.get_config().await?;
+    let matcher = Matcher::build(&config)?;
+    MatcherConfigValidator::new().validate_variable_references(&config)?;
+    check_tests(&matcher, &config_manager.get_config_tests().await?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::upgrade_rules::test::prepare_temp_dirs;
+    use crate::config::parse_config_files;
+
+    #[actix::test]
+    async fn should_validate_the_rules_on_disk() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        // Act
+        let valid = validate_rules(configs.matcher_config.as_ref()).await;
+        std::fs::write(
+            format!("{}/{}/ruleset_01/ruleset.json", config_dir, rules_dir),
+            "not a ruleset",
+        )
+        .unwrap();
+        let invalid = validate_rules(configs.matcher_config.as_ref()).await;
+
+        // Assert
+        assert!(valid.is_ok());
+        assert!(invalid.is_err());
+    }
+}
@@ -1,17 +1,30 @@
 use crate::auth::auth_v2::AuthContextV2;
 use crate::auth::{AuthContext, AuthContextTrait, Permission};
-use crate::config::convert::{dto_into_rule, rule_into_dto};
+use crate::config::convert::{
+    dto_into_rule, node_stats_into_dto, rule_into_dto, rule_stats_into_dto,
+};
 use crate::error::ApiError;
 use log::*;
 use std::sync::Arc;
 use tornado_engine_api_dto::common::Id;
 use tornado_engine_api_dto::config::{
-    ProcessingTreeNodeConfigDto, ProcessingTreeNodeDetailsDto, RuleDto, TreeInfoDto,
+    ConfigChangeDto, ConfigValidationErrorDto, DraftRebaseDto, ProcessingTreeNodeConfigDto,
+    ProcessingTreeNodeDetailsDto, ProcessingTreeNodeStatsDto, RuleDto, TreeInfoDto,
 };
+use tornado_engine_matcher::config::bundle::MatcherConfigBundle;
+use tornado_engine_matcher::config::diff::diff_configs;
 use tornado_engine_matcher::config::operation::{matcher_config_filter, NodeFilter};
+use tornado_engine_matcher::config::render::TreeRenderFormat;
 use tornado_engine_matcher::config::{
-    MatcherConfig, MatcherConfigDraft, MatcherConfigEditor, MatcherConfigReader,
+    DeployedConfigData, MatcherConfig, MatcherConfigDraft, MatcherConfigEditor, MatcherConfigReader,
 };
+use tornado_engine_matcher::matcher::Matcher;
+use tornado_engine_matcher::replay::Replay;
+use tornado_engine_matcher::shadow::{ShadowReport, ShadowStats};
+use tornado_engine_matcher::stats::MatcherStats;
+use tornado_engine_matcher::validator::actions::ActionIds;
+use tornado_engine_matcher::validator::warnings::ConfigWarning;
+use tornado_engine_matcher::validator::MatcherConfigValidator;
 
 const NODE_PATH_SEPARATOR: &str = ",";
 
@@ -21,16 +34,48 @@ const NODE_PATH_SEPARATOR: &str = ",";
 #[async_trait::async_trait(? Send)]
 pub trait ConfigApiHandler: Send + Sync {
     async fn reload_configuration(&self) -> Result<MatcherConfig, ApiError>;
+
+    /// Returns the statistics of the events processed by the active configuration
+    async fn get_matcher_stats(&self) -> Result<Arc<MatcherStats>, ApiError>;
+
+    /// Starts processing the live events also with the configuration of a draft,
+    /// replacing the draft currently running in shadow, if any
+    async fn start_shadow(&self, draft_id: &str, config: MatcherConfig) -> Result<(), ApiError>;
+
+    /// Stops the draft running in shadow and returns its statistics, if any
+    async fn stop_shadow(&self) -> Result<Option<Arc<ShadowStats>>, ApiError>;
+
+    /// Returns the statistics of the draft running in shadow, if any
+    async fn get_shadow_stats(&self) -> Result<Option<Arc<ShadowStats>>, ApiError>;
 }
 
 pub struct ConfigApi<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized> {
     handler: A,
     config_manager: Arc<CM>,
+    executor_action_ids: Option<ExecutorActionIds>,
+}
+
+/// The action ids handled by the executors of the running Tornado instance
+struct ExecutorActionIds {
+    action_ids: Vec<String>,
+    foreach_action_ids: Vec<String>,
 }
 
 impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized> ConfigApi<A, CM> {
     pub fn new(handler: A, config_manager: Arc<CM>) -> Self {
-        Self { handler, config_manager }
+        Self { handler, config_manager, executor_action_ids: None }
+    }
+
+    /// Sets the action ids handled by the executors, so that the validation of the drafts
+    /// reports the actions that no executor can execute, and the deploys of configurations
+    /// containing them are rejected.
+    pub fn with_executor_action_ids(
+        mut self,
+        action_ids: Vec<String>,
+        foreach_action_ids: Vec<String>,
+    ) -> Self {
+        self.executor_action_ids = Some(ExecutorActionIds { action_ids, foreach_action_ids });
+        self
     }
 
     /// Returns the current configuration of tornado
@@ -59,12 +104,79 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         self.get_authorized_child_nodes(&auth, relative_node_path, filtered_matcher).await
     }
 
+    /// Returns child processing tree nodes of a node found by a path
+    /// of the current configuration of tornado, along with the statistics of the
+    /// nodes and, for the rulesets, of their rules
+    pub async fn get_current_config_processing_tree_nodes_stats_by_path(
+        &self,
+        auth: AuthContextV2<'_>,
+        node_path: Option<&str>,
+    ) -> Result<Vec<ProcessingTreeNodeStatsDto>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let relative_node_path: Vec<_> = node_path
+            .map(|node_path| node_path.split(NODE_PATH_SEPARATOR).collect())
+            .unwrap_or_default();
+
+        let filtered_matcher =
+            get_filtered_matcher(&self.config_manager.get_config().await?, &auth).await?;
+        let (absolute_node_path, child_nodes, has_iterator_ancestor) =
+            Self::get_authorized_child_nodes_with_path(
+                &auth,
+                relative_node_path,
+                &filtered_matcher,
+            )?;
+        let stats = self.handler.get_matcher_stats().await?;
+
+        Ok(child_nodes
+            .iter()
+            .map(|node| {
+                let mut node_path = absolute_node_path.clone();
+                node_path.push(node.get_name());
+                let rules = match node {
+                    MatcherConfig::Ruleset { rules, .. } => rules
+                        .iter()
+                        .map(|rule| {
+                            rule_stats_into_dto(
+                                &rule.name,
+                                stats.rule_stats(&node_path, &rule.name),
+                            )
+                        })
+                        .collect(),
+                    MatcherConfig::Filter { .. } | MatcherConfig::Iterator { .. } => vec![],
+                };
+                ProcessingTreeNodeStatsDto {
+                    node: ProcessingTreeNodeConfigDto::convert(node, has_iterator_ancestor),
+                    stats: node_stats_into_dto(stats.node_stats(&node_path)),
+                    rules,
+                }
+            })
+            .collect())
+    }
+
     async fn get_authorized_child_nodes(
         &self,
         auth: &AuthContextV2<'_>,
         relative_node_path: Vec<&str>,
         filtered_matcher: MatcherConfig,
     ) -> Result<Vec<ProcessingTreeNodeConfigDto>, ApiError> {
+        let (_, child_nodes, has_iterator_ancestor) = Self::get_authorized_child_nodes_with_path(
+            auth,
+            relative_node_path,
+            &filtered_matcher,
+        )?;
+        Ok(child_nodes
+            .iter()
+            .map(|node| ProcessingTreeNodeConfigDto::convert(node, has_iterator_ancestor))
+            .collect())
+    }
+
+    /// Returns the absolute path of the node, its child nodes and whether the node
+    /// has an iterator among its ancestors.
+    fn get_authorized_child_nodes_with_path<'a>(
+        auth: &'a AuthContextV2<'_>,
+        relative_node_path: Vec<&'a str>,
+        filtered_matcher: &MatcherConfig,
+    ) -> Result<(Vec<&'a str>, Vec<MatcherConfig>, bool), ApiError> {
         let authorized_path =
             auth.auth.authorization.path.iter().map(|s| s as &str).collect::<Vec<_>>();
 
@@ -83,10 +195,7 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         })?;
         let has_iterator_ancestor =
             filtered_matcher.has_iterator_in_path(absolute_node_path.as_slice());
-        Ok(child_nodes
-            .iter()
-            .map(|node| ProcessingTreeNodeConfigDto::convert(node, has_iterator_ancestor))
-            .collect())
+        Ok((absolute_node_path, child_nodes.into_owned(), has_iterator_ancestor))
     }
 
     pub async fn get_authorized_tree_info(
@@ -388,6 +497,129 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         self.get_authorized_child_nodes(&auth, relative_node_path, filtered_matcher).await
     }
 
+    /// Returns the warnings of the static analysis of the draft configuration,
+    /// e.g. rules that can never be reached.
+    pub async fn get_draft_config_warnings(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<Vec<ConfigWarning>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
+        let filtered_matcher = get_filtered_matcher(&draft.config, &auth).await?;
+        Ok(MatcherConfigValidator::new().warnings(&filtered_matcher))
+    }
+
+    /// Returns all the errors that prevent the draft configuration from being deployed,
+    /// with the location of each error in the processing tree.
+    pub async fn get_draft_config_validation_errors(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<Vec<ConfigValidationErrorDto>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
+        let filtered_matcher = get_filtered_matcher(&draft.config, &auth).await?;
+        Ok(self.config_validation_errors(&filtered_matcher))
+    }
+
+    /// Returns all the errors that the draft configuration would have if the node at the path
+    /// were replaced by the given one, as an import does, without saving the draft.
+    /// A large configuration can then be fixed in one pass before it is imported.
+    pub async fn get_draft_config_node_import_validation_errors(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+        node_path: &str,
+        config: MatcherConfig,
+    ) -> Result<Vec<ConfigValidationErrorDto>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let mut draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
+        let absolute_node_path = self.get_absolute_path_from_relative(&auth, node_path)?;
+
+        draft.config.replace_node(&absolute_node_path, config)?;
+        let filtered_matcher = get_filtered_matcher(&draft.config, &auth).await?;
+        Ok(self.config_validation_errors(&filtered_matcher))
+    }
+
+    /// Returns a Replay that compares the outcome of the active configuration with the one
+    /// of the draft, both restricted to the authorized node.
+    pub async fn get_draft_replay(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<Replay, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
+        let active = get_filtered_matcher(&self.config_manager.get_config().await?, &auth).await?;
+        let draft = get_filtered_matcher(&draft.config, &auth).await?;
+        Ok(Replay::new(Matcher::build(&active)?, Matcher::build(&draft)?))
+    }
+
+    /// Starts the shadow deployment of a draft: the live events are processed also by the draft
+    /// and its actions are compared with the ones of the active configuration,
+    /// without being executed
+    pub async fn start_draft_shadow(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<(), ApiError> {
+        auth.has_permission(&Permission::ConfigEdit)?;
+        let draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
+        self.handler.start_shadow(draft_id, draft.config).await
+    }
+
+    /// Stops the shadow deployment of a draft and returns its final report
+    pub async fn stop_draft_shadow(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<ShadowReport, ApiError> {
+        auth.has_permission(&Permission::ConfigEdit)?;
+        self.get_draft_and_check_owner(&auth, draft_id).await?;
+        self.get_shadow_stats(draft_id).await?;
+        let stats =
+            self.handler.stop_shadow().await?.ok_or_else(|| shadow_not_running(draft_id))?;
+        Ok(stats.report(&auth.auth.authorization.path))
+    }
+
+    /// Returns the comparison of the draft running in shadow with the active configuration
+    pub async fn get_draft_shadow_report(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<ShadowReport, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        self.get_draft_and_check_owner(&auth, draft_id).await?;
+        let stats = self.get_shadow_stats(draft_id).await?;
+        Ok(stats.report(&auth.auth.authorization.path))
+    }
+
+    /// Returns the changes of a draft compared to the active configuration,
+    /// or to a deployed configuration of the history if the version is provided
+    pub async fn get_draft_diff(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+        version_id: Option<&str>,
+    ) -> Result<Vec<ConfigChangeDto>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
+        let base_config = match version_id {
+            Some(version_id) => self.config_manager.get_deployed_config(version_id).await?.config,
+            None => self.config_manager.get_config().await?,
+        };
+        get_filtered_diff(&base_config, &draft.config, &auth).await
+    }
+
+    async fn get_shadow_stats(&self, draft_id: &str) -> Result<Arc<ShadowStats>, ApiError> {
+        self.handler
+            .get_shadow_stats()
+            .await?
+            .filter(|stats| stats.draft_id() == draft_id)
+            .ok_or_else(|| shadow_not_running(draft_id))
+    }
+
     /// Returns the list of available drafts
     pub async fn get_drafts(&self, auth: AuthContext<'_>) -> Result<Vec<String>, ApiError> {
         auth.has_permission(&Permission::ConfigView)?;
@@ -449,7 +681,8 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         draft_id: &str,
     ) -> Result<MatcherConfig, ApiError> {
         auth.has_permission(&Permission::ConfigEdit)?;
-        self.get_draft_and_check_owner(&auth, draft_id).await?;
+        let draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
+        self.validate_action_ids(&draft.config)?;
         self.config_manager.deploy_draft(draft_id).await?;
         self.handler.reload_configuration().await
     }
@@ -464,10 +697,130 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         auth.has_permission(&Permission::ConfigEdit)?;
         let draft = self.config_manager.get_draft(draft_id).await?;
         auth.is_owner(&draft)?;
+        self.validate_action_ids(&draft.config)?;
         self.config_manager.deploy_draft(draft_id).await?;
         self.handler.reload_configuration().await
     }
 
+    /// Returns the deployed configurations kept in the history, the most recent first
+    pub async fn get_deployed_configs(
+        &self,
+        auth: AuthContextV2<'_>,
+    ) -> Result<Vec<DeployedConfigData>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        Ok(self.config_manager.get_deployed_configs().await?)
+    }
+
+    /// Returns the differences between two deployed configurations
+    /// in the part of the processing tree visible to the user
+    pub async fn get_deployed_configs_diff(
+        &self,
+        auth: AuthContextV2<'_>,
+        from_version_id: &str,
+        to_version_id: &str,
+    ) -> Result<Vec<ConfigChangeDto>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let from = self.config_manager.get_deployed_config(from_version_id).await?;
+        let to = self.config_manager.get_deployed_config(to_version_id).await?;
+        get_filtered_diff(&from.config, &to.config, &auth).await
+    }
+
+    /// Deploys again a configuration of the history and reload the tornado configuration.
+    /// The rollback replaces the whole processing tree, so the user must be authorized on its root.
+    pub async fn rollback_config(
+        &self,
+        auth: AuthContextV2<'_>,
+        version_id: &str,
+    ) -> Result<MatcherConfig, ApiError> {
+        auth.has_permission(&Permission::ConfigEdit)?;
+        let config = self.config_manager.get_config().await?;
+        if auth.auth.authorization.path != [config.get_name()] {
+            return Err(self.get_unauthorized_path_error());
+        }
+        let deployed = self.config_manager.get_deployed_config(version_id).await?;
+        self.validate_action_ids(&deployed.config)?;
+        self.config_manager.rollback_config(version_id, auth.auth.user.clone()).await?;
+        self.handler.reload_configuration().await
+    }
+
+    /// Exports a node of the active configuration, with all its children, in a bundle
+    pub async fn export_active_config_bundle(
+        &self,
+        auth: AuthContextV2<'_>,
+        relative_node_path: &str,
+    ) -> Result<MatcherConfigBundle, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let absolute_node_path = self.get_absolute_path_from_relative(&auth, relative_node_path)?;
+        let config = self.config_manager.get_config().await?;
+        let filtered_matcher = get_filtered_matcher(&config, &auth).await?;
+        if filtered_matcher.get_node_by_path(&absolute_node_path).is_none() {
+            return Err(ApiError::NodeNotFoundError {
+                message: format!("Node for relative path {:?} not found", relative_node_path),
+            });
+        }
+        Ok(MatcherConfigBundle::export(&filtered_matcher, &absolute_node_path)?)
+    }
+
+    /// Renders a node of the active configuration, with all its children, in the given format
+    pub async fn render_active_config(
+        &self,
+        auth: AuthContextV2<'_>,
+        relative_node_path: &str,
+        format: TreeRenderFormat,
+    ) -> Result<String, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let absolute_node_path = self.get_absolute_path_from_relative(&auth, relative_node_path)?;
+        let config = self.config_manager.get_config().await?;
+        let filtered_matcher = get_filtered_matcher(&config, &auth).await?;
+        let node = filtered_matcher.get_node_by_path(&absolute_node_path).ok_or_else(|| {
+            ApiError::NodeNotFoundError {
+                message: format!("Node for relative path {:?} not found", relative_node_path),
+            }
+        })?;
+        Ok(format.render(node))
+    }
+
+    /// Imports a bundle at the node path of its manifest, deploys the resulting configuration
+    /// and reloads it. The bundle is verified and the configuration validated before the deploy.
+    pub async fn import_config_bundle(
+        &self,
+        auth: AuthContextV2<'_>,
+        bundle: &MatcherConfigBundle,
+    ) -> Result<MatcherConfig, ApiError> {
+        auth.has_permission(&Permission::ConfigEdit)?;
+        if !bundle.manifest.node_path.starts_with(&auth.auth.authorization.path) {
+            return Err(self.get_unauthorized_path_error());
+        }
+        let config = self.config_manager.get_config().await?;
+        let config = bundle.import_into(config)?;
+        self.validate_action_ids(&config)?;
+        self.config_manager.deploy_config(&config).await?;
+        self.handler.reload_configuration().await
+    }
+
+    /// Returns whether the active configuration changed since the draft was created,
+    /// and the conflicts that a rebase would report
+    pub async fn get_draft_rebase(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<DraftRebaseDto, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        self.get_draft_and_check_owner(&auth, draft_id).await?;
+        Ok(self.config_manager.get_draft_rebase(draft_id).await?.into())
+    }
+
+    /// Merges into the draft the changes of the active configuration since the draft was created
+    pub async fn rebase_draft(
+        &self,
+        auth: AuthContextV2<'_>,
+        draft_id: &str,
+    ) -> Result<DraftRebaseDto, ApiError> {
+        auth.has_permission(&Permission::ConfigEdit)?;
+        self.get_draft_and_check_owner(&auth, draft_id).await?;
+        Ok(self.config_manager.rebase_draft(draft_id, auth.auth.user.clone()).await?.into())
+    }
+
     /// Deletes a draft by id
     pub async fn delete_draft(
         &self,
@@ -521,6 +874,36 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         Ok(draft)
     }
 
+    /// Returns all the errors of the configuration, including the actions that the executors
+    /// of the running Tornado instance cannot execute, when their action ids are set.
+    fn config_validation_errors(&self, config: &MatcherConfig) -> Vec<ConfigValidationErrorDto> {
+        let validator = MatcherConfigValidator::new();
+        let mut errors = validator.validation_errors(config);
+        if let Some(executor_action_ids) = &self.executor_action_ids {
+            let action_ids = as_str_vec(&executor_action_ids.action_ids);
+            let foreach_action_ids = as_str_vec(&executor_action_ids.foreach_action_ids);
+            errors.extend(validator.unknown_action_errors(
+                config,
+                &ActionIds { action_ids: &action_ids, foreach_action_ids: &foreach_action_ids },
+            ));
+        }
+        errors.into_iter().map(ConfigValidationErrorDto::from).collect()
+    }
+
+    /// Returns an error if the executors of the running Tornado instance cannot execute
+    /// some actions of the configuration. Nothing is checked when their action ids are not set.
+    fn validate_action_ids(&self, config: &MatcherConfig) -> Result<(), ApiError> {
+        let Some(executor_action_ids) = &self.executor_action_ids else {
+            return Ok(());
+        };
+        let action_ids = as_str_vec(&executor_action_ids.action_ids);
+        let foreach_action_ids = as_str_vec(&executor_action_ids.foreach_action_ids);
+        Ok(MatcherConfigValidator::new().validate_action_ids(
+            config,
+            &ActionIds { action_ids: &action_ids, foreach_action_ids: &foreach_action_ids },
+        )?)
+    }
+
     pub async fn create_draft_config_node(
         &self,
         auth: AuthContextV2<'_>,
@@ -580,6 +963,10 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
     }
 }
 
+fn as_str_vec(values: &[String]) -> Vec<&str> {
+    values.iter().map(String::as_str).collect()
+}
+
 pub async fn get_filtered_matcher(
     config: &MatcherConfig,
     auth: &AuthContextV2<'_>,
@@ -592,6 +979,26 @@ pub async fn get_filtered_matcher(
     })
 }
 
+/// Returns the differences between two configurations in the part of the processing tree
+/// visible to the user
+async fn get_filtered_diff(
+    from: &MatcherConfig,
+    to: &MatcherConfig,
+    auth: &AuthContextV2<'_>,
+) -> Result<Vec<ConfigChangeDto>, ApiError> {
+    let changes = diff_configs(
+        &get_filtered_matcher(from, auth).await?,
+        &get_filtered_matcher(to, auth).await?,
+    );
+    Ok(changes.into_iter().map(ConfigChangeDto::from).collect())
+}
+
+fn shadow_not_running(draft_id: &str) -> ApiError {
+    ApiError::BadRequestError {
+        cause: format!("The draft [{}] is not running in shadow", draft_id),
+    }
+}
+
 fn pop_authorized_path_and_append_relative_path<'a>(
     mut base_path: Vec<&'a str>,
     mut relative_path: Vec<&'a str>,
@@ -626,13 +1033,17 @@ mod test {
     use std::sync::Arc;
     use tornado_engine_api_dto::auth::Auth;
     use tornado_engine_api_dto::auth_v2::{AuthV2, Authorization};
-    use tornado_engine_api_dto::config::{ConstraintDto, RuleDetailsDto};
+    use tornado_engine_api_dto::config::{
+        ConfigChangeKindDto, ConstraintDto, NodeStatsDto, RuleDetailsDto, RuleStatsDto,
+    };
     use tornado_engine_matcher::config::nodes::Filter;
     use tornado_engine_matcher::config::rule::{Constraint, Rule};
     use tornado_engine_matcher::config::{
-        Defaultable, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData,
+        Defaultable, DeployedConfig, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData,
     };
     use tornado_engine_matcher::error::MatcherError;
+    use tornado_engine_matcher::model::ProcessedRuleStatus;
+    use tornado_engine_matcher::stats::{StatsLayout, StatsUpdate};
 
     const DRAFT_OWNER_ID: &str = "OWNER";
 
@@ -725,6 +1136,7 @@ mod test {
                     draft_id: draft_id.to_owned(),
                     created_ts_ms: 0,
                     updated_ts_ms: 0,
+                    base_version: None,
                 },
                 config: MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![] },
             })
@@ -761,9 +1173,50 @@ mod test {
 
         async fn deploy_config(
             &self,
-            _config: &MatcherConfig,
+            config: &MatcherConfig,
+        ) -> Result<MatcherConfig, MatcherError> {
+            Ok(config.clone())
+        }
+
+        async fn get_deployed_configs(&self) -> Result<Vec<DeployedConfigData>, MatcherError> {
+            Ok(vec![self.get_deployed_config("0000000001").await?.data])
+        }
+
+        async fn get_deployed_config(
+            &self,
+            version_id: &str,
+        ) -> Result<DeployedConfig, MatcherError> {
+            let mut config = self.get_config().await?;
+            match version_id {
+                "0000000001" => {}
+                "0000000002" => {
+                    config.delete_node_in_path(&["root", "root_1", "root_1_1"])?;
+                    config.delete_node_in_path(&["root", "root_2", "root_2_2"])?;
+                }
+                _ => {
+                    return Err(MatcherError::DeployedConfigNotFoundError {
+                        version_id: version_id.to_owned(),
+                    })
+                }
+            }
+            Ok(DeployedConfig {
+                data: DeployedConfigData {
+                    version_id: version_id.to_owned(),
+                    deployed_ts_ms: 0,
+                    user: None,
+                    draft_id: None,
+                    rollback_of: None,
+                },
+                config,
+            })
+        }
+
+        async fn rollback_config(
+            &self,
+            version_id: &str,
+            _user: String,
         ) -> Result<MatcherConfig, MatcherError> {
-            unimplemented!()
+            self.get_deployed_config(version_id).await.map(|deployed| deployed.config)
         }
     }
 
@@ -774,6 +1227,42 @@ mod test {
         async fn reload_configuration(&self) -> Result<MatcherConfig, ApiError> {
             Ok(MatcherConfig::Ruleset { name: "ruleset_new".to_owned(), rules: vec![] })
         }
+
+        async fn get_matcher_stats(&self) -> Result<Arc<MatcherStats>, ApiError> {
+            let layout = Arc::new(StatsLayout::new(&TestConfigManager {}.get_config().await?));
+            let stats = MatcherStats::new(layout.clone());
+            stats.record(
+                &[
+                    StatsUpdate::Node {
+                        id: layout.node_id(&["root", "root_1", "root_1_2"]).unwrap(),
+                        matched: true,
+                    },
+                    StatsUpdate::Rule {
+                        id: layout.rule_id(&["root", "root_1", "root_1_2"], "root_1_2_1").unwrap(),
+                        status: ProcessedRuleStatus::Matched,
+                        actions: 2,
+                    },
+                ],
+                1000,
+            );
+            Ok(Arc::new(stats))
+        }
+
+        async fn start_shadow(
+            &self,
+            _draft_id: &str,
+            _config: MatcherConfig,
+        ) -> Result<(), ApiError> {
+            Ok(())
+        }
+
+        async fn stop_shadow(&self) -> Result<Option<Arc<ShadowStats>>, ApiError> {
+            self.get_shadow_stats().await
+        }
+
+        async fn get_shadow_stats(&self) -> Result<Option<Arc<ShadowStats>>, ApiError> {
+            Ok(Some(Arc::new(ShadowStats::new("id".to_owned(), 1000))))
+        }
     }
 
     fn auth_permissions() -> BTreeMap<Permission, Vec<String>> {
@@ -783,6 +1272,25 @@ mod test {
         permission_roles_map
     }
 
+    fn auth_context<'a>(
+        permissions_map: &'a BTreeMap<Permission, Vec<String>>,
+        user: &str,
+        role: &str,
+        path: &[&str],
+    ) -> AuthContextV2<'a> {
+        AuthContextV2::new(
+            AuthV2 {
+                user: user.to_owned(),
+                authorization: Authorization {
+                    path: path.iter().map(|node| node.to_string()).collect(),
+                    roles: vec![role.to_owned()],
+                },
+                preferences: None,
+            },
+            permissions_map,
+        )
+    }
+
     fn create_users(
         permissions_map: &BTreeMap<Permission, Vec<String>>,
     ) -> (AuthContext, AuthContext, AuthContext, AuthContext) {
@@ -1229,6 +1737,68 @@ mod test {
         assert_eq!(res_authorized_child_nodes, expected);
     }
 
+    #[actix_rt::test]
+    async fn get_current_config_processing_tree_nodes_stats_by_path_should_overlay_the_stats() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user_root_1 = AuthContextV2::new(
+            AuthV2 {
+                user: DRAFT_OWNER_ID.to_owned(),
+                authorization: Authorization {
+                    path: vec!["root".to_owned(), "root_1".to_owned()],
+                    roles: vec!["view".to_owned()],
+                },
+                preferences: None,
+            },
+            &permissions_map,
+        );
+
+        // Act
+        let res = api
+            .get_current_config_processing_tree_nodes_stats_by_path(user_root_1, Some("root_1"))
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            res,
+            vec![
+                ProcessingTreeNodeStatsDto {
+                    node: ProcessingTreeNodeConfigDto::Filter {
+                        name: "root_1_1".to_string(),
+                        rules_count: 0,
+                        children_count: 0,
+                        description: "".to_string(),
+                        active: false,
+                        has_iterator_ancestor: false,
+                    },
+                    stats: NodeStatsDto::default(),
+                    rules: vec![],
+                },
+                ProcessingTreeNodeStatsDto {
+                    node: ProcessingTreeNodeConfigDto::Ruleset {
+                        name: "root_1_2".to_string(),
+                        rules_count: 1,
+                    },
+                    stats: NodeStatsDto {
+                        evaluated: 1,
+                        matched: 1,
+                        last_matched_epoch_ms: Some(1000),
+                    },
+                    rules: vec![RuleStatsDto {
+                        name: "root_1_2_1".to_owned(),
+                        evaluated: 1,
+                        matched: 1,
+                        partially_matched: 0,
+                        actions_emitted: 2,
+                        last_matched_epoch_ms: Some(1000),
+                    }],
+                },
+            ]
+        );
+    }
+
     #[actix_rt::test]
     async fn get_current_config_processing_tree_nodes_by_path_should_return_error_if_authorized_path_does_not_exist(
     ) {
@@ -1727,4 +2297,269 @@ mod test {
         let expected = MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![] };
         assert_eq!(expected, result);
     }
+
+    #[actix_rt::test]
+    async fn should_export_and_import_a_bundle_in_the_authorized_path() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user =
+            |path: &[&str], role: &str| auth_context(&permissions_map, DRAFT_OWNER_ID, role, path);
+
+        // Act
+        let bundle =
+            api.export_active_config_bundle(user(&["root"], "view"), "root,root_1").await.unwrap();
+        let mut tampered = bundle.clone();
+        tampered.config = MatcherConfig::Ruleset { name: "root_1".to_owned(), rules: vec![] };
+
+        // Assert
+        assert_eq!(vec!["root", "root_1"], bundle.manifest.node_path);
+        assert_eq!(1, bundle.manifest.rules_count);
+        assert!(api
+            .export_active_config_bundle(user(&["root"], "edit"), "root,root_1")
+            .await
+            .is_err());
+        assert!(api
+            .export_active_config_bundle(user(&["root"], "view"), "root,none")
+            .await
+            .is_err());
+        assert!(api.import_config_bundle(user(&["root", "root_1"], "edit"), &bundle).await.is_ok());
+        assert!(api.import_config_bundle(user(&["root"], "view"), &bundle).await.is_err());
+        assert!(api
+            .import_config_bundle(user(&["root", "root_2"], "edit"), &bundle)
+            .await
+            .is_err());
+        assert!(api.import_config_bundle(user(&["root"], "edit"), &tampered).await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn render_active_config_should_require_view_permission() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user = |role: &str| auth_context(&permissions_map, DRAFT_OWNER_ID, role, &["root"]);
+
+        // Act
+        let dot =
+            api.render_active_config(user("view"), "root,root_1", TreeRenderFormat::Dot).await;
+
+        // Assert
+        assert!(dot.unwrap().contains("Ruleset: root_1"));
+        assert!(api
+            .render_active_config(user("edit"), "root,root_1", TreeRenderFormat::Dot)
+            .await
+            .is_err());
+        assert!(matches!(
+            api.render_active_config(user("view"), "root,none", TreeRenderFormat::Html).await,
+            Err(ApiError::NodeNotFoundError { .. })
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn get_draft_config_warnings_should_require_view_permission_and_owner() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user =
+            |user: &str, role: &str| auth_context(&permissions_map, user, role, &["ruleset"]);
+
+        // Act & Assert
+        assert_eq!(
+            Vec::<ConfigWarning>::new(),
+            api.get_draft_config_warnings(user(DRAFT_OWNER_ID, "view"), "id").await.unwrap()
+        );
+        assert!(api.get_draft_config_warnings(user(DRAFT_OWNER_ID, "edit"), "id").await.is_err());
+        assert!(api.get_draft_config_warnings(user("another_user", "view"), "id").await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn get_draft_config_validation_errors_should_require_view_permission_and_owner() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user =
+            |user: &str, role: &str| auth_context(&permissions_map, user, role, &["ruleset"]);
+
+        // Act & Assert
+        assert_eq!(
+            Vec::<ConfigValidationErrorDto>::new(),
+            api.get_draft_config_validation_errors(user(DRAFT_OWNER_ID, "view"), "id")
+                .await
+                .unwrap()
+        );
+        assert!(api
+            .get_draft_config_validation_errors(user(DRAFT_OWNER_ID, "edit"), "id")
+            .await
+            .is_err());
+        assert!(api
+            .get_draft_config_validation_errors(user("another_user", "view"), "id")
+            .await
+            .is_err());
+    }
+
+    #[actix_rt::test]
+    async fn should_return_the_validation_errors_of_a_node_import_without_saving_it() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user =
+            |user: &str, role: &str| auth_context(&permissions_map, user, role, &["ruleset"]);
+        let rule = |name: &str| Rule {
+            name: name.to_owned(),
+            description: "".to_owned(),
+            do_continue: true,
+            active: true,
+            constraint: Constraint { where_operator: None, with: Default::default() },
+            actions: vec![],
+        };
+        let imported = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![rule("valid"), rule("not valid"), rule("not valid either")],
+        };
+
+        // Act
+        let errors = api
+            .get_draft_config_node_import_validation_errors(
+                user(DRAFT_OWNER_ID, "view"),
+                "id",
+                "ruleset",
+                imported.clone(),
+            )
+            .await
+            .unwrap();
+        let not_owner = api
+            .get_draft_config_node_import_validation_errors(
+                user("another_user", "view"),
+                "id",
+                "ruleset",
+                imported,
+            )
+            .await;
+
+        // Assert
+        assert_eq!(2, errors.len());
+        assert_eq!(Some("not valid".to_owned()), errors[0].rule);
+        assert_eq!(Some("not valid either".to_owned()), errors[1].rule);
+        assert!(not_owner.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn draft_shadow_should_require_permissions_owner_and_a_running_shadow() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user = |user: &str, role: &str| auth_context(&permissions_map, user, role, &["root"]);
+
+        // Act
+        let report = api.get_draft_shadow_report(user(DRAFT_OWNER_ID, "view"), "id").await.unwrap();
+        let other_draft_report =
+            api.get_draft_shadow_report(user(DRAFT_OWNER_ID, "view"), "other_id").await;
+
+        // Assert
+        assert_eq!("id", report.draft_id);
+        assert_eq!(1000, report.started_epoch_ms);
+        assert!(matches!(other_draft_report, Err(ApiError::BadRequestError { .. })));
+        assert!(api.get_draft_shadow_report(user("another_user", "view"), "id").await.is_err());
+        assert!(api.start_draft_shadow(user(DRAFT_OWNER_ID, "edit"), "id").await.is_ok());
+        assert!(api.start_draft_shadow(user(DRAFT_OWNER_ID, "view"), "id").await.is_err());
+        assert!(api.stop_draft_shadow(user(DRAFT_OWNER_ID, "edit"), "id").await.is_ok());
+        assert!(api.stop_draft_shadow(user(DRAFT_OWNER_ID, "edit"), "other_id").await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn deploy_history_should_require_permissions_and_be_filtered_by_the_authorized_path() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user = |role: &str| auth_context(&permissions_map, "a_user", role, &["root", "root_2"]);
+
+        // Act
+        let history = api.get_deployed_configs(user("view")).await.unwrap();
+        let diff =
+            api.get_deployed_configs_diff(user("view"), "0000000001", "0000000002").await.unwrap();
+
+        // Assert
+        assert_eq!(1, history.len());
+        assert_eq!(
+            vec![ConfigChangeDto {
+                node_path: vec!["root".to_owned(), "root_2".to_owned(), "root_2_2".to_owned()],
+                rule: None,
+                change: ConfigChangeKindDto::Removed,
+                moved_from: None,
+                from_position: None,
+                to_position: None,
+                fields: vec![],
+            }],
+            diff
+        );
+        assert!(api.get_deployed_configs(user("edit")).await.is_err());
+        assert!(api
+            .get_deployed_configs_diff(user("view"), "0000000001", "0000000003")
+            .await
+            .is_err());
+        assert!(api.rollback_config(user("view"), "0000000001").await.is_err());
+        assert!(matches!(
+            api.rollback_config(user("edit"), "0000000001").await,
+            Err(ApiError::ForbiddenError { .. })
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn rollback_config_should_require_the_root_authorized_path() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user = |role: &str| auth_context(&permissions_map, "a_user", role, &["root"]);
+
+        // Act
+        let result = api.rollback_config(user("edit"), "0000000001").await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(api.rollback_config(user("view"), "0000000001").await.is_err());
+        assert!(matches!(
+            api.rollback_config(user("edit"), "0000000003").await,
+            Err(ApiError::MatcherError { cause: MatcherError::DeployedConfigNotFoundError { .. } })
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn get_draft_diff_should_require_view_permission_and_owner() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user = |user: &str, role: &str| auth_context(&permissions_map, user, role, &["root"]);
+
+        // Act
+        let not_authorized = api.get_draft_diff(user(DRAFT_OWNER_ID, "edit"), "id", None).await;
+        let not_owner = api.get_draft_diff(user("another_user", "view"), "id", None).await;
+        let unknown_version =
+            api.get_draft_diff(user(DRAFT_OWNER_ID, "view"), "id", Some("0000000003")).await;
+
+        // Assert
+        assert!(matches!(not_authorized, Err(ApiError::ForbiddenError { .. })));
+        assert!(matches!(not_owner, Err(ApiError::ForbiddenError { .. })));
+        assert!(matches!(
+            unknown_version,
+            Err(ApiError::MatcherError { cause: MatcherError::DeployedConfigNotFoundError { .. } })
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn draft_rebase_should_require_permissions_and_owner() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let permissions_map = auth_permissions();
+        let user = |user: &str, role: &str| auth_context(&permissions_map, user, role, &["root"]);
+
+        // Act
+        let rebase = api.get_draft_rebase(user(DRAFT_OWNER_ID, "view"), "id").await.unwrap();
+
+        // Assert
+        assert_eq!(DraftRebaseDto { outdated: false, conflicts: vec![] }, rebase);
+        assert!(api.get_draft_rebase(user("another_user", "view"), "id").await.is_err());
+        assert!(api.rebase_draft(user(DRAFT_OWNER_ID, "edit"), "id").await.is_ok());
+        assert!(api.rebase_draft(user(DRAFT_OWNER_ID, "view"), "id").await.is_err());
+        assert!(api.rebase_draft(user("another_user", "edit"), "id").await.is_err());
+    }
 }
@@ -1,13 +1,14 @@
 use serde_json::Error;
 use tornado_engine_api_dto::config::{
-    ActionDto, ConstraintDto, ExtractorDto, ExtractorRegexDto, ModifierDto, OperatorDto,
-    ProcessingTreeNodeEditDto, RuleDto,
+    ActionDto, ConstraintDto, ExtractorDto, ExtractorRegexDto, ModifierDto, NodeStatsDto,
+    OperatorDto, ProcessingTreeNodeEditDto, RuleDto, RuleStatsDto,
 };
 use tornado_engine_matcher::config::nodes::{Filter, MatcherIterator};
 use tornado_engine_matcher::config::rule::{
     ConfigAction, Constraint, Extractor, ExtractorRegex, Modifier, Operator, Rule,
 };
 use tornado_engine_matcher::config::{Defaultable, MatcherConfig};
+use tornado_engine_matcher::stats::{NodeStats, RuleStats};
 
 pub fn rule_into_dto(rule: Rule) -> Result<RuleDto, Error> {
     Ok(RuleDto {
@@ -21,7 +22,11 @@ pub fn rule_into_dto(rule: Rule) -> Result<RuleDto, Error> {
 }
 
 fn action_into_dto(action: ConfigAction) -> Result<ActionDto, Error> {
-    Ok(ActionDto { id: action.id, payload: serde_json::to_value(action.payload)? })
+    Ok(ActionDto {
+        id: action.id,
+        payload: serde_json::to_value(action.payload)?,
+        when: action.when.as_ref().map(OperatorDto::from),
+    })
 }
 
 fn constraint_into_dto(constraint: Constraint) -> Result<ConstraintDto, Error> {
@@ -72,6 +77,25 @@ fn extractor_regex_into_dto(extractor_regex: ExtractorRegex) -> ExtractorRegexDt
     }
 }
 
+pub fn node_stats_into_dto(stats: NodeStats) -> NodeStatsDto {
+    NodeStatsDto {
+        evaluated: stats.evaluated,
+        matched: stats.matched,
+        last_matched_epoch_ms: stats.last_matched_epoch_ms,
+    }
+}
+
+pub fn rule_stats_into_dto(rule_name: &str, stats: RuleStats) -> RuleStatsDto {
+    RuleStatsDto {
+        name: rule_name.to_owned(),
+        evaluated: stats.evaluated,
+        matched: stats.matched,
+        partially_matched: stats.partially_matched,
+        actions_emitted: stats.actions_emitted,
+        last_matched_epoch_ms: stats.last_matched_epoch_ms,
+    }
+}
+
 pub fn processing_tree_node_details_dto_into_matcher_config(
     config: ProcessingTreeNodeEditDto,
 ) -> Result<MatcherConfig, Error> {
@@ -113,7 +137,11 @@ pub fn dto_into_rule(rule: RuleDto) -> Result<Rule, Error> {
 }
 
 fn dto_into_action(action: ActionDto) -> Result<ConfigAction, Error> {
-    Ok(ConfigAction { id: action.id, payload: serde_json::from_value(action.payload)? })
+    Ok(ConfigAction {
+        id: action.id,
+        payload: serde_json::from_value(action.payload)?,
+        when: action.when.map(dto_into_operator).transpose()?,
+    })
 }
 
 fn dto_into_constraint(constraint: ConstraintDto) -> Result<Constraint, Error> {
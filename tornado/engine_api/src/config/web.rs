@@ -15,10 +15,20 @@ use serde::Deserialize;
 use std::os::unix::ffi::OsStrExt;
 use tornado_engine_api_dto::common::Id;
 use tornado_engine_api_dto::config::{
-    ProcessingTreeNodeConfigDto, ProcessingTreeNodeDetailsDto, ProcessingTreeNodeEditDto, RuleDto,
+    ConfigChangeDto, ConfigValidationErrorDto, DraftRebaseDto, ProcessingTreeNodeConfigDto,
+    ProcessingTreeNodeDetailsDto, ProcessingTreeNodeEditDto, ProcessingTreeNodeStatsDto, RuleDto,
     RulePositionDto, TreeInfoDto,
 };
-use tornado_engine_matcher::config::MatcherConfigEditor;
+use tornado_engine_matcher::config::bundle::MatcherConfigBundle;
+use tornado_engine_matcher::config::render::TreeRenderFormat;
+use tornado_engine_matcher::config::{DeployedConfigData, MatcherConfigEditor};
+use tornado_engine_matcher::replay::{Replay, ReplayReport};
+use tornado_engine_matcher::shadow::ShadowReport;
+use tornado_engine_matcher::validator::warnings::ConfigWarning;
+
+/// The maximum length of a line of the files uploaded to replay events. A longer line is
+/// not an event of the archive, and it is not buffered in memory.
+const MAX_REPLAY_LINE_BYTES: usize = 1024 * 1024;
 
 pub fn build_config_v2_endpoints<
     A: ConfigApiHandler + 'static,
@@ -46,9 +56,29 @@ pub fn build_config_v2_endpoints<
                     web::resource("/tree/info/{param_auth}")
                         .route(web::get().to(get_current_tree_info::<A, CM>)),
                 )
+                .service(
+                    web::resource("/tree/stats/{param_auth}")
+                        .route(web::get().to(get_current_tree_node_stats::<A, CM>)),
+                )
+                .service(
+                    web::resource("/tree/stats/{param_auth}/{node_path}")
+                        .route(web::get().to(get_current_tree_node_stats_with_node_path::<A, CM>)),
+                )
+                .service(
+                    web::resource("/tree/render/{param_auth}/{node_path}/{format}")
+                        .route(web::get().to(render_active_config::<A, CM>)),
+                )
                 .service(
                     web::resource("/rule/details/{param_auth}/{ruleset_path}/{rule_name}")
                         .route(web::get().to(get_current_rule_details::<A, CM>)),
+                )
+                .service(
+                    web::resource("/bundle/{param_auth}")
+                        .route(web::post().to(import_config_bundle::<A, CM>)),
+                )
+                .service(
+                    web::resource("/bundle/{param_auth}/{node_path}")
+                        .route(web::get().to(export_active_config_bundle::<A, CM>)),
                 ),
         )
         .service(
@@ -61,6 +91,29 @@ pub fn build_config_v2_endpoints<
                     web::resource("/tree/children/{param_auth}/{draft_id}/{node_path}")
                         .route(web::get().to(get_draft_tree_node_with_node_path::<A, CM>)),
                 )
+                .service(
+                    web::resource("/warnings/{param_auth}/{draft_id}")
+                        .route(web::get().to(get_draft_config_warnings::<A, CM>)),
+                )
+                .service(
+                    web::resource("/validation/{param_auth}/{draft_id}")
+                        .route(web::get().to(get_draft_config_validation_errors::<A, CM>)),
+                )
+                .service(
+                    web::resource("/validation/{param_auth}/{draft_id}/{node_path}").route(
+                        web::post().to(get_draft_config_node_import_validation_errors::<A, CM>),
+                    ),
+                )
+                .service(
+                    web::resource("/replay/{param_auth}/{draft_id}")
+                        .route(web::post().to(replay_events_on_draft::<A, CM>)),
+                )
+                .service(
+                    web::resource("/shadow/{param_auth}/{draft_id}")
+                        .route(web::get().to(get_draft_shadow_report::<A, CM>))
+                        .route(web::post().to(start_draft_shadow::<A, CM>))
+                        .route(web::delete().to(stop_draft_shadow::<A, CM>)),
+                )
                 .service(
                     web::resource("/tree/details/{param_auth}/{draft_id}/{node_path}")
                         .route(web::get().to(get_draft_tree_node_details::<A, CM>))
@@ -107,10 +160,35 @@ pub fn build_config_v2_endpoints<
             web::resource("/drafts/{param_auth}/{draft_id}/deploy")
                 .route(web::post().to(deploy_draft_for_tenant::<A, CM>)),
         )
+        .service(
+            web::resource("/drafts/{param_auth}/{draft_id}/diff")
+                .route(web::get().to(get_draft_diff::<A, CM>)),
+        )
+        .service(
+            web::resource("/drafts/{param_auth}/{draft_id}/diff/{version_id}")
+                .route(web::get().to(get_draft_diff_with_version::<A, CM>)),
+        )
+        .service(
+            web::resource("/drafts/{param_auth}/{draft_id}/rebase")
+                .route(web::get().to(get_draft_rebase::<A, CM>))
+                .route(web::post().to(rebase_draft::<A, CM>)),
+        )
         .service(
             web::resource("/drafts/{param_auth}/{draft_id}/takeover")
                 .route(web::post().to(draft_take_over_for_tenant::<A, CM>)),
         )
+        .service(
+            web::resource("/history/{param_auth}")
+                .route(web::get().to(get_deployed_configs::<A, CM>)),
+        )
+        .service(
+            web::resource("/history/{param_auth}/{from_version_id}/diff/{to_version_id}")
+                .route(web::get().to(get_deployed_configs_diff::<A, CM>)),
+        )
+        .service(
+            web::resource("/history/{param_auth}/{version_id}/rollback")
+                .route(web::post().to(rollback_config::<A, CM>)),
+        )
 }
 
 #[derive(Deserialize)]
@@ -119,6 +197,13 @@ struct AuthAndNodePath {
     node_path: String,
 }
 
+#[derive(Deserialize)]
+struct AuthNodePathAndFormat {
+    param_auth: String,
+    node_path: String,
+    format: String,
+}
+
 #[derive(Deserialize)]
 struct RuleDetailsParams {
     param_auth: String,
@@ -147,6 +232,26 @@ struct DraftPath {
     draft_id: String,
 }
 
+#[derive(Deserialize)]
+struct DeployedVersionPath {
+    param_auth: String,
+    version_id: String,
+}
+
+#[derive(Deserialize)]
+struct DraftPathWithVersion {
+    param_auth: String,
+    draft_id: String,
+    version_id: String,
+}
+
+#[derive(Deserialize)]
+struct DeployedVersionsDiffPath {
+    param_auth: String,
+    from_version_id: String,
+    to_version_id: String,
+}
+
 #[derive(Deserialize)]
 struct DraftPathWithNode {
     param_auth: String,
@@ -189,6 +294,42 @@ async fn get_current_tree_node_with_node_path<
     Ok(Json(result))
 }
 
+async fn get_current_tree_node_stats<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    param_auth: Path<String>,
+) -> actix_web::Result<Json<Vec<ProcessingTreeNodeStatsDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+
+    let result =
+        data.api.get_current_config_processing_tree_nodes_stats_by_path(auth_ctx, None).await?;
+    Ok(Json(result))
+}
+
+async fn get_current_tree_node_stats_with_node_path<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    endpoint_params: Path<AuthAndNodePath>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<Vec<ProcessingTreeNodeStatsDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &endpoint_params.param_auth)?;
+    let result = data
+        .api
+        .get_current_config_processing_tree_nodes_stats_by_path(
+            auth_ctx,
+            Some(&endpoint_params.node_path),
+        )
+        .await?;
+    Ok(Json(result))
+}
+
 async fn get_current_tree_node_details<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
@@ -531,6 +672,170 @@ async fn get_draft_tree_node<
     Ok(Json(result))
 }
 
+async fn get_draft_config_warnings<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    path: Path<DraftPath>,
+) -> actix_web::Result<Json<Vec<ConfigWarning>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.get_draft_config_warnings(auth_ctx, &path.draft_id).await?;
+    Ok(Json(result))
+}
+
+async fn get_draft_config_validation_errors<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    path: Path<DraftPath>,
+) -> actix_web::Result<Json<Vec<ConfigValidationErrorDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.get_draft_config_validation_errors(auth_ctx, &path.draft_id).await?;
+    Ok(Json(result))
+}
+
+/// Returns the validation errors of the draft as if the uploaded file were imported
+/// at the node path, without importing it.
+async fn get_draft_config_node_import_validation_errors<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    endpoint_params: Path<DraftPathWithNode>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    body: Multipart,
+) -> actix_web::Result<Json<Vec<ConfigValidationErrorDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &endpoint_params.param_auth)?;
+    let config = match parse_uploaded_file(body).await? {
+        ExportVersionedMatcherConfig::V1(config) | ExportVersionedMatcherConfig::V1_1(config) => {
+            config
+        }
+    };
+    let result = data
+        .api
+        .get_draft_config_node_import_validation_errors(
+            auth_ctx,
+            &endpoint_params.draft_id,
+            &endpoint_params.node_path,
+            config,
+        )
+        .await?;
+    Ok(Json(result))
+}
+
+/// Replays the events of the uploaded archive files, one JSON event per line, and returns
+/// a report for each file. The files are processed while they are uploaded; the events
+/// of each received chunk are replayed on the blocking thread pool.
+/// The upload is rejected with `PayloadToLarge` when a line exceeds MAX_REPLAY_LINE_BYTES.
+async fn replay_events_on_draft<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    path: Path<DraftPath>,
+    mut body: Multipart,
+) -> actix_web::Result<Json<Vec<ReplayReport>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let mut replay = data.api.get_draft_replay(auth_ctx, &path.draft_id).await?;
+
+    let mut reports = vec![];
+    while let Some(mut field) = body.try_next().await? {
+        let Some("file") = field.content_disposition().get_name() else {
+            debug!(
+                "Skipping field with Content Disposition {:?} during file upload.",
+                field.content_disposition().get_name()
+            );
+            continue;
+        };
+        let mut buffer = Vec::<u8>::new();
+        while let Some(chunk) = field.try_next().await? {
+            buffer.extend_from_slice(&chunk);
+            if let Some(end) = buffer.iter().rposition(|byte| *byte == b'\n') {
+                let incomplete_line = buffer.split_off(end + 1);
+                let lines = std::mem::replace(&mut buffer, incomplete_line);
+                replay = replay_lines(replay, lines).await?;
+            }
+            if buffer.len() > MAX_REPLAY_LINE_BYTES {
+                warn!(
+                    "Rejecting the replay of a file with a line longer than {} bytes",
+                    MAX_REPLAY_LINE_BYTES
+                );
+                return Err(ApiError::PayloadToLarge.into());
+            }
+        }
+        replay = replay_lines(replay, buffer).await?;
+        reports.push(replay.take_report());
+    }
+    Ok(Json(reports))
+}
+
+/// Replays the newline separated lines on the blocking thread pool, as the processing of
+/// many events would otherwise stall the worker.
+async fn replay_lines(mut replay: Replay, lines: Vec<u8>) -> actix_web::Result<Replay> {
+    if lines.is_empty() {
+        return Ok(replay);
+    }
+    Ok(web::block(move || {
+        let lines = lines.strip_suffix(b"\n").unwrap_or(&lines);
+        for line in lines.split(|byte| *byte == b'\n') {
+            replay.replay_line(&String::from_utf8_lossy(line));
+        }
+        replay
+    })
+    .await?)
+}
+
+async fn get_draft_shadow_report<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    path: Path<DraftPath>,
+) -> actix_web::Result<Json<ShadowReport>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.get_draft_shadow_report(auth_ctx, &path.draft_id).await?;
+    Ok(Json(result))
+}
+
+async fn start_draft_shadow<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    path: Path<DraftPath>,
+) -> actix_web::Result<Json<()>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    data.api.start_draft_shadow(auth_ctx, &path.draft_id).await?;
+    Ok(Json(()))
+}
+
+async fn stop_draft_shadow<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    path: Path<DraftPath>,
+) -> actix_web::Result<Json<ShadowReport>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.stop_draft_shadow(auth_ctx, &path.draft_id).await?;
+    Ok(Json(result))
+}
+
 async fn get_draft_tree_node_with_node_path<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
@@ -586,6 +891,66 @@ async fn export_draft_tree_starting_from_node_path<
     Ok(response)
 }
 
+async fn export_active_config_bundle<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    endpoint_params: Path<AuthAndNodePath>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<HttpResponse> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &endpoint_params.param_auth)?;
+    let result = data.api.export_active_config_bundle(auth_ctx, &endpoint_params.node_path).await?;
+    let filename = format!(
+        "{}-{}-{}.bundle.json",
+        String::from_utf8_lossy(gethostname().as_bytes()),
+        result.config.get_name(),
+        Local::now().to_rfc3339_opts(SecondsFormat::Secs, false)
+    );
+    let response = HttpResponse::Ok()
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename: \"{}\"", filename),
+        ))
+        .content_type("application/json")
+        .json(result);
+    Ok(response)
+}
+
+async fn render_active_config<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    endpoint_params: Path<AuthNodePathAndFormat>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<HttpResponse> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &endpoint_params.param_auth)?;
+    let format: TreeRenderFormat =
+        endpoint_params.format.parse().map_err(|cause| ApiError::BadRequestError { cause })?;
+    let result =
+        data.api.render_active_config(auth_ctx, &endpoint_params.node_path, format).await?;
+    Ok(HttpResponse::Ok().content_type(format.content_type()).body(result))
+}
+
+async fn import_config_bundle<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    param_auth: Path<String>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    body: Multipart,
+) -> actix_web::Result<Json<()>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+    let bundle: MatcherConfigBundle = parse_uploaded_file(body).await?;
+    data.api.import_config_bundle(auth_ctx, &bundle).await?;
+    Ok(Json(()))
+}
+
 async fn get_drafts_by_tenant<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
@@ -656,31 +1021,132 @@ async fn draft_take_over_for_tenant<
     Ok(Json(()))
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::auth::auth_v2::AuthServiceV2;
-    use crate::error::ApiError;
-    use crate::{auth::auth_v2::test::test_auth_service_v2, test_root::start_context};
-    use actix_web::http::header::HeaderName;
-    use actix_web::{
-        http::{header, StatusCode},
-        test, App,
-    };
-    use async_trait::async_trait;
-    use std::collections::HashMap;
-    use std::sync::Arc;
-    use tornado_engine_api_dto::auth_v2::{AuthHeaderV2, Authorization};
-    use tornado_engine_api_dto::config::ConstraintDto;
-    use tornado_engine_matcher::config::nodes::Filter;
-    use tornado_engine_matcher::config::rule::{Constraint, Rule};
-    use tornado_engine_matcher::config::{
-        Defaultable, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData, MatcherConfigReader,
-    };
-    use tornado_engine_matcher::error::MatcherError;
-
-    struct ConfigManager {}
-
+async fn get_draft_diff<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    path: Path<DraftPath>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<Vec<ConfigChangeDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.get_draft_diff(auth_ctx, &path.draft_id, None).await?;
+    Ok(Json(result))
+}
+
+async fn get_draft_diff_with_version<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    path: Path<DraftPathWithVersion>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<Vec<ConfigChangeDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.get_draft_diff(auth_ctx, &path.draft_id, Some(&path.version_id)).await?;
+    Ok(Json(result))
+}
+
+async fn get_draft_rebase<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    path: Path<DraftPath>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<DraftRebaseDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.get_draft_rebase(auth_ctx, &path.draft_id).await?;
+    Ok(Json(result))
+}
+
+async fn rebase_draft<A: ConfigApiHandler + 'static, CM: MatcherConfigEditor + ?Sized + 'static>(
+    req: HttpRequest,
+    path: Path<DraftPath>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<DraftRebaseDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data.api.rebase_draft(auth_ctx, &path.draft_id).await?;
+    Ok(Json(result))
+}
+
+async fn get_deployed_configs<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    param_auth: Path<String>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<Vec<DeployedConfigData>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+    let result = data.api.get_deployed_configs(auth_ctx).await?;
+    Ok(Json(result))
+}
+
+async fn get_deployed_configs_diff<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    path: Path<DeployedVersionsDiffPath>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<Vec<ConfigChangeDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let result = data
+        .api
+        .get_deployed_configs_diff(auth_ctx, &path.from_version_id, &path.to_version_id)
+        .await?;
+    Ok(Json(result))
+}
+
+async fn rollback_config<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    path: Path<DeployedVersionPath>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<()>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    data.api.rollback_config(auth_ctx, &path.version_id).await?;
+    Ok(Json(()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::auth_v2::AuthServiceV2;
+    use crate::error::ApiError;
+    use crate::{auth::auth_v2::test::test_auth_service_v2, test_root::start_context};
+    use actix_web::http::header::HeaderName;
+    use actix_web::{
+        http::{header, StatusCode},
+        test, App,
+    };
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tornado_engine_api_dto::auth_v2::{AuthHeaderV2, Authorization};
+    use tornado_engine_api_dto::config::{ConfigChangeKindDto, ConstraintDto};
+    use tornado_engine_matcher::config::nodes::Filter;
+    use tornado_engine_matcher::config::rule::{Constraint, Rule};
+    use tornado_engine_matcher::config::{
+        Defaultable, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData, MatcherConfigReader,
+    };
+    use tornado_engine_matcher::error::MatcherError;
+    use tornado_engine_matcher::shadow::ShadowStats;
+    use tornado_engine_matcher::stats::MatcherStats;
+    use tornado_engine_matcher::validator::warnings::ConfigWarningKind;
+
+    struct ConfigManager {}
+
     #[async_trait::async_trait(? Send)]
     impl MatcherConfigReader for ConfigManager {
         async fn get_config(&self) -> Result<MatcherConfig, MatcherError> {
@@ -733,6 +1199,7 @@ mod test {
                     draft_id: draft_id.to_owned(),
                     created_ts_ms: 0,
                     updated_ts_ms: 0,
+                    base_version: None,
                 },
                 config: MatcherConfig::Filter {
                     name: "root".to_owned(),
@@ -809,9 +1276,9 @@ mod test {
 
         async fn deploy_config(
             &self,
-            _config: &MatcherConfig,
+            config: &MatcherConfig,
         ) -> Result<MatcherConfig, MatcherError> {
-            unimplemented!()
+            Ok(config.clone())
         }
     }
 
@@ -822,6 +1289,26 @@ mod test {
         async fn reload_configuration(&self) -> Result<MatcherConfig, ApiError> {
             Ok(MatcherConfig::Ruleset { name: "ruleset_new".to_owned(), rules: vec![] })
         }
+
+        async fn get_matcher_stats(&self) -> Result<Arc<MatcherStats>, ApiError> {
+            Ok(Arc::new(MatcherStats::default()))
+        }
+
+        async fn start_shadow(
+            &self,
+            _draft_id: &str,
+            _config: MatcherConfig,
+        ) -> Result<(), ApiError> {
+            Ok(())
+        }
+
+        async fn stop_shadow(&self) -> Result<Option<Arc<ShadowStats>>, ApiError> {
+            self.get_shadow_stats().await
+        }
+
+        async fn get_shadow_stats(&self) -> Result<Option<Arc<ShadowStats>>, ApiError> {
+            Ok(Some(Arc::new(ShadowStats::new("draft123".to_owned(), 1000))))
+        }
     }
 
     fn auth_map(name: &str, auth: Authorization) -> HashMap<String, Authorization> {
@@ -891,6 +1378,130 @@ mod test {
         Ok(())
     }
 
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_a_get_draft_warnings_endpoint() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/draft/warnings/auth1/draft123")
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let warnings: Vec<ConfigWarning> = test::read_body_json(response).await;
+        assert_eq!(1, warnings.len());
+        assert_eq!(vec!["root".to_owned()], warnings[0].path);
+        assert_eq!(ConfigWarningKind::InactiveParent, warnings[0].kind);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_a_get_draft_validation_errors_endpoint() -> Result<(), ApiError>
+    {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/draft/validation/auth1/draft123")
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let errors: Vec<ConfigValidationErrorDto> = test::read_body_json(response).await;
+        assert!(errors.is_empty());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_a_replay_draft_endpoint() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+        let body = "--boundary\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"archive.log\"\r\n\
+            Content-Type: application/octet-stream\r\n\r\n\
+            {\"type\": \"email\", \"created_ms\": 0, \"payload\": {}}\n\
+            not an event\r\n\
+            --boundary--\r\n";
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .insert_header((header::CONTENT_TYPE, "multipart/form-data; boundary=boundary"))
+            .uri("/config/draft/replay/auth1/draft123")
+            .set_payload(body)
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let reports: Vec<ReplayReport> = test::read_body_json(response).await;
+        assert_eq!(1, reports.len());
+        assert_eq!(1, reports[0].replayed_events);
+        assert_eq!(1, reports[0].invalid_lines);
+        assert_eq!(2, reports[0].line_errors[0].line);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_draft_shadow_endpoints() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let start_request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/draft/shadow/auth1/draft123")
+            .to_request();
+        let start_response = test::call_service(&srv, start_request).await;
+
+        let report_request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/draft/shadow/auth1/draft123")
+            .to_request();
+        let report_response = test::call_service(&srv, report_request).await;
+
+        let stop_request = test::TestRequest::delete()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/draft/shadow/auth1/draft123")
+            .to_request();
+        let stop_response = test::call_service(&srv, stop_request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, start_response.status());
+        assert_eq!(StatusCode::OK, report_response.status());
+        let report: ShadowReport = test::read_body_json(report_response).await;
+        assert_eq!("draft123", report.draft_id);
+        assert_eq!(0, report.processed_events);
+        assert_eq!(StatusCode::OK, stop_response.status());
+        Ok(())
+    }
+
     #[actix_rt::test]
     async fn v2_endpoint_should_have_a_get_draft_single_node_with_path_get_endpoint(
     ) -> Result<(), ApiError> {
@@ -1012,6 +1623,184 @@ mod test {
         Ok(())
     }
 
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_draft_diff_endpoints() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123/diff")
+            .to_request();
+        let response = test::call_service(&srv, request).await;
+
+        let version_request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123/diff/0000000001")
+            .to_request();
+        let version_response = test::call_service(&srv, version_request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let changes: Vec<ConfigChangeDto> = test::read_body_json(response).await;
+        assert_eq!(
+            vec![ConfigChangeDto {
+                node_path: vec!["root".to_owned(), "child_1".to_owned(), "child_1_1".to_owned()],
+                rule: None,
+                change: ConfigChangeKindDto::Added,
+                moved_from: None,
+                from_position: None,
+                to_position: None,
+                fields: vec![],
+            }],
+            changes
+        );
+        assert_eq!(StatusCode::NOT_FOUND, version_response.status());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_draft_rebase_endpoints() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let preview = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123/rebase")
+            .to_request();
+        let preview_response = test::call_service(&srv, preview).await;
+
+        let rebase = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123/rebase")
+            .to_request();
+        let rebase_response = test::call_service(&srv, rebase).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, preview_response.status());
+        let preview: DraftRebaseDto = test::read_body_json(preview_response).await;
+        assert!(!preview.outdated);
+        assert_eq!(StatusCode::OK, rebase_response.status());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_deploy_history_endpoints() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let history = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/history/auth1")
+            .to_request();
+        let history_response = test::call_service(&srv, history).await;
+
+        let diff = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/history/auth1/0000000001/diff/0000000002")
+            .to_request();
+        let diff_response = test::call_service(&srv, diff).await;
+
+        let rollback = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/history/auth1/0000000001/rollback")
+            .to_request();
+        let rollback_response = test::call_service(&srv, rollback).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, history_response.status());
+        let deployed: Vec<DeployedConfigData> = test::read_body_json(history_response).await;
+        assert!(deployed.is_empty());
+        assert_eq!(StatusCode::NOT_FOUND, diff_response.status());
+        assert_eq!(StatusCode::NOT_FOUND, rollback_response.status());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_bundle_export_and_import_endpoints() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let export = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/active/bundle/auth1/root,child_2")
+            .to_request();
+        let export_response = test::call_service(&srv, export).await;
+        assert_eq!(StatusCode::OK, export_response.status());
+        let bundle: MatcherConfigBundle = test::read_body_json(export_response).await;
+
+        let body = format!(
+            "--boundary\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"bundle.json\"\r\n\
+            Content-Type: application/json\r\n\r\n\
+            {}\r\n\
+            --boundary--\r\n",
+            serde_json::to_string(&bundle).unwrap()
+        );
+        let import = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .insert_header((header::CONTENT_TYPE, "multipart/form-data; boundary=boundary"))
+            .uri("/config/active/bundle/auth1")
+            .set_payload(body)
+            .to_request();
+        let import_response = test::call_service(&srv, import).await;
+
+        // Assert
+        assert_eq!(vec!["root", "child_2"], bundle.manifest.node_path);
+        assert_eq!(1, bundle.manifest.rules_count);
+        assert_eq!(StatusCode::OK, import_response.status());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_render_the_active_config() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/active/tree/render/auth1/root/mermaid")
+            .to_request();
+        let response = test::call_service(&srv, request).await;
+        let invalid_format = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/active/tree/render/auth1/root/svg")
+            .to_request();
+        let invalid_format_response = test::call_service(&srv, invalid_format).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let body = test::read_body(response).await;
+        assert!(String::from_utf8_lossy(&body).starts_with("flowchart TD\n"));
+        assert_eq!(StatusCode::BAD_REQUEST, invalid_format_response.status());
+        Ok(())
+    }
+
     #[actix_rt::test]
     async fn v2_endpoint_should_have_a_draft_take_over_for_tenant_post_endpoint(
     ) -> Result<(), ApiError> {
@@ -1106,6 +1895,42 @@ mod test {
         Ok(())
     }
 
+    #[actix_rt::test]
+    async fn v2_endpoint_get_stats_by_node_path_should_return_status_code_ok(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header((
+                header::AUTHORIZATION,
+                AuthServiceV2::auth_to_token_header(&AuthHeaderV2 {
+                    user: "admin".to_string(),
+                    auths: auth_map(
+                        "auth1",
+                        Authorization {
+                            path: vec!["root".to_owned()],
+                            roles: vec!["view".to_owned()],
+                        },
+                    ),
+                    preferences: None,
+                })?,
+            ))
+            .uri("/config/active/tree/stats/auth1/root")
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        Ok(())
+    }
+
     #[actix_rt::test]
     async fn v2_endpoint_get_details_by_node_path_should_return_status_code_ok(
     ) -> Result<(), ApiError> {
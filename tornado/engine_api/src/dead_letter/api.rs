@@ -0,0 +1,204 @@
+use crate::auth::{AuthContext, Permission};
+use crate::error::ApiError;
+use tornado_engine_api_dto::dead_letter::{
+    DeadLetterDto, DeadLetterSummaryDto, PurgeDeadLettersResponseDto,
+};
+
+/// The DeadLetterApiHandler gives access to the actions whose execution failed
+/// and that are kept in the dead-letter queue.
+#[async_trait::async_trait(?Send)]
+pub trait DeadLetterApiHandler: Send + Sync {
+    async fn get_dead_letters(&self) -> Result<Vec<DeadLetterSummaryDto>, ApiError>;
+
+    async fn get_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError>;
+
+    /// Sends the action to its executor again and removes it from the queue
+    async fn retry_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError>;
+
+    async fn remove_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError>;
+
+    /// Removes all the actions from the queue and returns how many they were
+    async fn purge_dead_letters(&self) -> Result<usize, ApiError>;
+}
+
+pub struct DeadLetterApi<A: DeadLetterApiHandler> {
+    handler: A,
+}
+
+impl<A: DeadLetterApiHandler> DeadLetterApi<A> {
+    pub fn new(handler: A) -> Self {
+        Self { handler }
+    }
+
+    /// Returns the actions of the dead-letter queue, the oldest first
+    pub async fn get_dead_letters(
+        &self,
+        auth: AuthContext<'_>,
+    ) -> Result<Vec<DeadLetterSummaryDto>, ApiError> {
+        auth.has_permission(&Permission::RuntimeConfigView)?;
+        self.handler.get_dead_letters().await
+    }
+
+    /// Returns an action of the dead-letter queue, including its payload
+    pub async fn get_dead_letter(
+        &self,
+        auth: AuthContext<'_>,
+        id: u64,
+    ) -> Result<DeadLetterDto, ApiError> {
+        auth.has_permission(&Permission::RuntimeConfigView)?;
+        self.handler.get_dead_letter(id).await?.ok_or(ApiError::DeadLetterNotFoundError { id })
+    }
+
+    /// Executes an action of the dead-letter queue again.
+    /// If the execution fails, the action is added back to the queue with a new id.
+    pub async fn retry_dead_letter(
+        &self,
+        auth: AuthContext<'_>,
+        id: u64,
+    ) -> Result<DeadLetterDto, ApiError> {
+        auth.has_permission(&Permission::RuntimeConfigEdit)?;
+        self.handler.retry_dead_letter(id).await?.ok_or(ApiError::DeadLetterNotFoundError { id })
+    }
+
+    /// Removes an action from the dead-letter queue without executing it
+    pub async fn remove_dead_letter(
+        &self,
+        auth: AuthContext<'_>,
+        id: u64,
+    ) -> Result<DeadLetterDto, ApiError> {
+        auth.has_permission(&Permission::RuntimeConfigEdit)?;
+        self.handler.remove_dead_letter(id).await?.ok_or(ApiError::DeadLetterNotFoundError { id })
+    }
+
+    /// Removes all the actions from the dead-letter queue
+    pub async fn purge_dead_letters(
+        &self,
+        auth: AuthContext<'_>,
+    ) -> Result<PurgeDeadLettersResponseDto, ApiError> {
+        auth.has_permission(&Permission::RuntimeConfigEdit)?;
+        Ok(PurgeDeadLettersResponseDto { purged: self.handler.purge_dead_letters().await? })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use tornado_engine_api_dto::auth::Auth;
+
+    /// A handler with a single dead letter of id 1
+    pub struct TestDeadLetterApiHandler {}
+
+    fn dead_letter(id: u64) -> Option<DeadLetterDto> {
+        if id == 1 {
+            Some(DeadLetterDto {
+                id,
+                action_id: "logger".to_owned(),
+                action_payload: json!({}),
+                action_created_ms: 0,
+                error: "error".to_owned(),
+                attempts: 3,
+                first_attempt_epoch_ms: 0,
+                last_attempt_epoch_ms: 0,
+            })
+        } else {
+            None
+        }
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl DeadLetterApiHandler for TestDeadLetterApiHandler {
+        async fn get_dead_letters(&self) -> Result<Vec<DeadLetterSummaryDto>, ApiError> {
+            Ok(dead_letter(1)
+                .map(|dto| DeadLetterSummaryDto {
+                    id: dto.id,
+                    action_id: dto.action_id,
+                    error: dto.error,
+                    attempts: dto.attempts,
+                    first_attempt_epoch_ms: dto.first_attempt_epoch_ms,
+                    last_attempt_epoch_ms: dto.last_attempt_epoch_ms,
+                })
+                .into_iter()
+                .collect())
+        }
+
+        async fn get_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError> {
+            Ok(dead_letter(id))
+        }
+
+        async fn retry_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError> {
+            Ok(dead_letter(id))
+        }
+
+        async fn remove_dead_letter(&self, id: u64) -> Result<Option<DeadLetterDto>, ApiError> {
+            Ok(dead_letter(id))
+        }
+
+        async fn purge_dead_letters(&self) -> Result<usize, ApiError> {
+            Ok(1)
+        }
+    }
+
+    fn auth_permissions() -> BTreeMap<Permission, Vec<String>> {
+        let mut permission_roles_map = BTreeMap::new();
+        permission_roles_map.insert(Permission::RuntimeConfigEdit, vec!["edit".to_owned()]);
+        permission_roles_map.insert(Permission::RuntimeConfigView, vec!["view".to_owned()]);
+        permission_roles_map
+    }
+
+    #[actix_rt::test]
+    async fn get_dead_letters_should_require_view_permission() {
+        // Arrange
+        let api = DeadLetterApi::new(TestDeadLetterApiHandler {});
+        let permissions_map = &auth_permissions();
+
+        let auth_view = AuthContext::new(
+            Auth { user: "1".to_owned(), roles: vec!["view".to_owned()], preferences: None },
+            permissions_map,
+        );
+
+        let auth_edit = AuthContext::new(
+            Auth { user: "1".to_owned(), roles: vec!["edit".to_owned()], preferences: None },
+            permissions_map,
+        );
+
+        // Act & Assert
+        assert_eq!(1, api.get_dead_letters(auth_view.clone()).await.unwrap().len());
+        assert!(api.get_dead_letter(auth_view.clone(), 1).await.is_ok());
+        assert_eq!(
+            Err(ApiError::DeadLetterNotFoundError { id: 2 }),
+            api.get_dead_letter(auth_view, 2).await
+        );
+        assert!(api.get_dead_letters(auth_edit.clone()).await.is_err());
+        assert!(api.get_dead_letter(auth_edit, 1).await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn retry_and_purge_should_require_edit_permission() {
+        // Arrange
+        let api = DeadLetterApi::new(TestDeadLetterApiHandler {});
+        let permissions_map = &auth_permissions();
+
+        let auth_view = AuthContext::new(
+            Auth { user: "1".to_owned(), roles: vec!["view".to_owned()], preferences: None },
+            permissions_map,
+        );
+
+        let auth_edit = AuthContext::new(
+            Auth { user: "1".to_owned(), roles: vec!["edit".to_owned()], preferences: None },
+            permissions_map,
+        );
+
+        // Act & Assert
+        assert!(api.retry_dead_letter(auth_view.clone(), 1).await.is_err());
+        assert!(api.remove_dead_letter(auth_view.clone(), 1).await.is_err());
+        assert!(api.purge_dead_letters(auth_view).await.is_err());
+        assert!(api.retry_dead_letter(auth_edit.clone(), 1).await.is_ok());
+        assert_eq!(
+            Err(ApiError::DeadLetterNotFoundError { id: 2 }),
+            api.remove_dead_letter(auth_edit.clone(), 2).await
+        );
+        assert_eq!(1, api.purge_dead_letters(auth_edit).await.unwrap().purged);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::dead_letter::api::{DeadLetterApi, DeadLetterApiHandler};
+use crate::model::ApiData;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{web, HttpRequest, Scope};
+use log::*;
+use tornado_engine_api_dto::dead_letter::{
+    DeadLetterDto, DeadLetterSummaryDto, PurgeDeadLettersResponseDto,
+};
+
+pub const DEAD_LETTER_ENDPOINT_V1_BASE: &str = "/v1_beta/dead_letters";
+
+pub fn build_dead_letter_endpoints<A: DeadLetterApiHandler + 'static>(
+    data: ApiData<DeadLetterApi<A>>,
+) -> Scope {
+    web::scope(DEAD_LETTER_ENDPOINT_V1_BASE)
+        .app_data(Data::new(data))
+        .service(
+            web::resource("")
+                .route(web::get().to(get_dead_letters::<A>))
+                .route(web::delete().to(purge_dead_letters::<A>)),
+        )
+        .service(
+            web::resource("/{id}")
+                .route(web::get().to(get_dead_letter::<A>))
+                .route(web::delete().to(remove_dead_letter::<A>)),
+        )
+        .service(web::resource("/{id}/retry").route(web::post().to(retry_dead_letter::<A>)))
+}
+
+async fn get_dead_letters<A: DeadLetterApiHandler + 'static>(
+    req: HttpRequest,
+    data: Data<ApiData<DeadLetterApi<A>>>,
+) -> actix_web::Result<Json<Vec<DeadLetterSummaryDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    let result = data.api.get_dead_letters(auth_ctx).await?;
+    Ok(Json(result))
+}
+
+async fn get_dead_letter<A: DeadLetterApiHandler + 'static>(
+    req: HttpRequest,
+    id: Path<u64>,
+    data: Data<ApiData<DeadLetterApi<A>>>,
+) -> actix_web::Result<Json<DeadLetterDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    let result = data.api.get_dead_letter(auth_ctx, id.into_inner()).await?;
+    Ok(Json(result))
+}
+
+async fn retry_dead_letter<A: DeadLetterApiHandler + 'static>(
+    req: HttpRequest,
+    id: Path<u64>,
+    data: Data<ApiData<DeadLetterApi<A>>>,
+) -> actix_web::Result<Json<DeadLetterDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    let result = data.api.retry_dead_letter(auth_ctx, id.into_inner()).await?;
+    Ok(Json(result))
+}
+
+async fn remove_dead_letter<A: DeadLetterApiHandler + 'static>(
+    req: HttpRequest,
+    id: Path<u64>,
+    data: Data<ApiData<DeadLetterApi<A>>>,
+) -> actix_web::Result<Json<DeadLetterDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    let result = data.api.remove_dead_letter(auth_ctx, id.into_inner()).await?;
+    Ok(Json(result))
+}
+
+async fn purge_dead_letters<A: DeadLetterApiHandler + 'static>(
+    req: HttpRequest,
+    data: Data<ApiData<DeadLetterApi<A>>>,
+) -> actix_web::Result<Json<PurgeDeadLettersResponseDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    let result = data.api.purge_dead_letters(auth_ctx).await?;
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::{AuthService, Permission};
+    use crate::dead_letter::api::test::TestDeadLetterApiHandler;
+    use actix_web::{http::header, http::StatusCode, test, App};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use tornado_engine_api_dto::auth::Auth;
+
+    fn test_auth_service() -> AuthService {
+        let mut permission_roles_map = BTreeMap::new();
+        permission_roles_map
+            .insert(Permission::RuntimeConfigEdit, vec!["runtime_config_edit".to_owned()]);
+        permission_roles_map
+            .insert(Permission::RuntimeConfigView, vec!["runtime_config_view".to_owned()]);
+        AuthService::new(Arc::new(permission_roles_map))
+    }
+
+    fn auth_header(role: &str) -> (header::HeaderName, String) {
+        (
+            header::AUTHORIZATION,
+            AuthService::auth_to_token_header(&Auth::new("user", vec![role])).unwrap(),
+        )
+    }
+
+    #[actix_rt::test]
+    async fn should_return_the_dead_letters() {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_dead_letter_endpoints(ApiData {
+            auth: test_auth_service(),
+            api: DeadLetterApi::new(TestDeadLetterApiHandler {}),
+        })))
+        .await;
+
+        // Act
+        let list_request = test::TestRequest::get()
+            .insert_header(auth_header("runtime_config_view"))
+            .uri("/v1_beta/dead_letters")
+            .to_request();
+        let list: Vec<DeadLetterSummaryDto> =
+            test::call_and_read_body_json(&srv, list_request).await;
+
+        let unauthorized_request =
+            test::TestRequest::get().uri("/v1_beta/dead_letters/1").to_request();
+        let unauthorized_response = test::call_service(&srv, unauthorized_request).await;
+
+        let not_found_request = test::TestRequest::get()
+            .insert_header(auth_header("runtime_config_view"))
+            .uri("/v1_beta/dead_letters/2")
+            .to_request();
+        let not_found_response = test::call_service(&srv, not_found_request).await;
+
+        // Assert
+        assert_eq!(1, list.len());
+        assert_eq!("logger", list[0].action_id);
+        assert_eq!(StatusCode::UNAUTHORIZED, unauthorized_response.status());
+        assert_eq!(StatusCode::NOT_FOUND, not_found_response.status());
+    }
+
+    #[actix_rt::test]
+    async fn should_retry_and_purge_the_dead_letters() {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_dead_letter_endpoints(ApiData {
+            auth: test_auth_service(),
+            api: DeadLetterApi::new(TestDeadLetterApiHandler {}),
+        })))
+        .await;
+
+        // Act
+        let retry_request = test::TestRequest::post()
+            .insert_header(auth_header("runtime_config_edit"))
+            .uri("/v1_beta/dead_letters/1/retry")
+            .to_request();
+        let retried: DeadLetterDto = test::call_and_read_body_json(&srv, retry_request).await;
+
+        let purge_request = test::TestRequest::delete()
+            .insert_header(auth_header("runtime_config_edit"))
+            .uri("/v1_beta/dead_letters")
+            .to_request();
+        let purged: PurgeDeadLettersResponseDto =
+            test::call_and_read_body_json(&srv, purge_request).await;
+
+        let forbidden_request = test::TestRequest::delete()
+            .insert_header(auth_header("runtime_config_view"))
+            .uri("/v1_beta/dead_letters/1")
+            .to_request();
+        let forbidden_response = test::call_service(&srv, forbidden_request).await;
+
+        // Assert
+        assert_eq!(1, retried.id);
+        assert_eq!(1, purged.purged);
+        assert_eq!(StatusCode::FORBIDDEN, forbidden_response.status());
+    }
+}
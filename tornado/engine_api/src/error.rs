@@ -41,6 +41,8 @@ pub enum ApiError {
 
     #[error("NodeNotFoundError [{message}]")]
     NodeNotFoundError { message: String },
+    #[error("DeadLetterNotFoundError [{id}]")]
+    DeadLetterNotFoundError { id: u64 },
 }
 
 impl From<MatcherError> for ApiError {
@@ -63,6 +65,8 @@ impl From<serde_json::Error> for ApiError {
 
 const VALIDATION_ERROR: &str = "VALIDATION_ERROR";
 const NESTED_ITERATOR_ERROR: &str = "NESTED_ITERATOR_ERROR";
+const RULE_TESTS_FAILED: &str = "RULE_TESTS_FAILED";
+const DRAFT_OUTDATED: &str = "DRAFT_OUTDATED";
 
 // Use default implementation for `error_response()` method.
 impl actix_web::error::ResponseError for ApiError {
@@ -92,6 +96,23 @@ impl actix_web::error::ResponseError for ApiError {
                         params: HashMap::new(),
                     })
                 }
+                MatcherError::RuleTestsFailedError { message } => {
+                    HttpResponseBuilder::new(http::StatusCode::BAD_REQUEST).json(WebError {
+                        code: RULE_TESTS_FAILED.to_owned(),
+                        message: Some(message.to_owned()),
+                        params: HashMap::new(),
+                    })
+                }
+                MatcherError::DraftOutdatedError { .. } => {
+                    HttpResponseBuilder::new(http::StatusCode::CONFLICT).json(WebError {
+                        code: DRAFT_OUTDATED.to_owned(),
+                        message: Some(format!("{}", cause)),
+                        params: HashMap::new(),
+                    })
+                }
+                MatcherError::DeployedConfigNotFoundError { .. } => {
+                    HttpResponse::NotFound().finish()
+                }
                 _ => HttpResponse::BadRequest().finish(),
             },
             ApiError::ActixMailboxError { .. }
@@ -99,7 +120,9 @@ impl actix_web::error::ResponseError for ApiError {
             | ApiError::InternalServerError { .. } => HttpResponse::InternalServerError().finish(),
             ApiError::BadRequestError { .. } => HttpResponse::BadRequest().finish(),
             ApiError::PayloadToLarge => HttpResponse::PayloadTooLarge().finish(),
-            ApiError::NodeNotFoundError { .. } => HttpResponse::NotFound().finish(),
+            ApiError::NodeNotFoundError { .. } | ApiError::DeadLetterNotFoundError { .. } => {
+                HttpResponse::NotFound().finish()
+            }
             ApiError::InvalidTokenError { .. }
             | ApiError::ExpiredTokenError { .. }
             | ApiError::MissingAuthTokenError { .. }
@@ -31,6 +31,8 @@ pub trait EventApiHandler: Send + Sync {
 pub struct SendEventRequest {
     pub event: Event,
     pub process_type: ProcessType,
+    /// Whether the processed rules should contain the evaluation trace of their operators and extractors
+    pub explain: bool,
 }
 
 impl SendEventRequest {
@@ -131,6 +133,7 @@ pub mod test {
                     draft_id: draft_id.to_owned(),
                     created_ts_ms: 0,
                     updated_ts_ms: 0,
+                    base_version: None,
                 },
                 config: MatcherConfig::Filter {
                     name: "root".to_owned(),
@@ -19,6 +19,7 @@ pub fn dto_into_send_event_request(dto: SendEventRequestDto) -> Result<SendEvent
             ProcessType::SkipActions => crate::event::api::ProcessType::SkipActions,
         },
         event: serde_json::from_value(serde_json::to_value(dto.event)?)?,
+        explain: dto.explain,
     })
 }
 
@@ -96,7 +97,7 @@ pub fn processed_rule_status_into_dto(node: ProcessedRuleStatus) -> ProcessedRul
 }
 
 pub fn action_into_dto(action: Action) -> Result<ActionDto, Error> {
-    Ok(ActionDto { id: action.id, payload: serde_json::to_value(action.payload)? })
+    Ok(ActionDto { id: action.id, payload: serde_json::to_value(action.payload)?, when: None })
 }
 
 pub fn processed_filter_into_dto(node: ProcessedFilter) -> ProcessedFilterDto {
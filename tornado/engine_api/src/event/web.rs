@@ -138,6 +138,7 @@ mod test {
                 iterator: None,
             },
             process_type: ProcessType::SkipActions,
+            explain: false,
         };
 
         // Act
@@ -192,6 +193,7 @@ mod test {
                 iterator: None,
             },
             process_type: ProcessType::SkipActions,
+            explain: false,
         };
 
         // Act
@@ -241,6 +243,7 @@ mod test {
                 iterator: None,
             },
             process_type: ProcessType::SkipActions,
+            explain: false,
         };
 
         // Act
@@ -1,5 +1,6 @@
 pub mod auth;
 pub mod config;
+pub mod dead_letter;
 pub mod error;
 pub mod event;
 pub mod model;
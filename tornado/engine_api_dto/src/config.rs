@@ -3,9 +3,14 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::iter::Sum;
 use std::ops::Add;
+use tornado_engine_matcher::config::diff::{ConfigChange, ConfigChangeKind, FieldChange};
+use tornado_engine_matcher::config::merge::{MergeConflict, MergeConflictKind};
 use tornado_engine_matcher::config::nodes::Filter;
 use tornado_engine_matcher::config::rule::{Operator, Rule};
-use tornado_engine_matcher::config::{deserialize_null_default, Defaultable, MatcherConfig};
+use tornado_engine_matcher::config::{
+    deserialize_null_default, Defaultable, DraftRebase, MatcherConfig,
+};
+use tornado_engine_matcher::validator::errors::ConfigValidationError;
 use typescript_definitions::TypeScriptify;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
@@ -197,6 +202,8 @@ impl From<&Operator> for OperatorDto {
 pub struct ActionDto {
     pub id: String,
     pub payload: Value,
+    #[serde(default)]
+    pub when: Option<OperatorDto>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
@@ -225,6 +232,7 @@ pub struct MatcherConfigDraftDataDto {
     pub created_ts_ms: i64,
     pub updated_ts_ms: i64,
     pub draft_id: String,
+    pub base_version: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
@@ -351,3 +359,164 @@ impl Sum for TreeInfoDto {
         iter.fold(TreeInfoDto::default(), Add::add)
     }
 }
+
+/// A processing tree node of the active configuration with the statistics collected
+/// since the Tornado startup. The rules are populated only for Ruleset nodes.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct ProcessingTreeNodeStatsDto {
+    pub node: ProcessingTreeNodeConfigDto,
+    pub stats: NodeStatsDto,
+    pub rules: Vec<RuleStatsDto>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, TypeScriptify)]
+pub struct NodeStatsDto {
+    pub evaluated: u64,
+    pub matched: u64,
+    pub last_matched_epoch_ms: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, TypeScriptify)]
+pub struct RuleStatsDto {
+    pub name: String,
+    pub evaluated: u64,
+    pub matched: u64,
+    pub partially_matched: u64,
+    pub actions_emitted: u64,
+    pub last_matched_epoch_ms: Option<i64>,
+}
+
+/// A node, or a rule of the ruleset at `node_path` when `rule` is set, that differs
+/// between two versions of the processing tree.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct ConfigChangeDto {
+    pub node_path: Vec<String>,
+    pub rule: Option<String>,
+    pub change: ConfigChangeKindDto,
+    pub moved_from: Option<Vec<String>>,
+    pub from_position: Option<usize>,
+    pub to_position: Option<usize>,
+    pub fields: Vec<FieldChangeDto>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub enum ConfigChangeKindDto {
+    Added,
+    Removed,
+    Changed,
+    Moved,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct FieldChangeDto {
+    pub field: String,
+    pub from: Option<Value>,
+    pub to: Option<Value>,
+}
+
+impl From<ConfigChange> for ConfigChangeDto {
+    fn from(change: ConfigChange) -> Self {
+        ConfigChangeDto {
+            node_path: change.node_path,
+            rule: change.rule,
+            change: change.change.into(),
+            moved_from: change.moved_from,
+            from_position: change.from_position,
+            to_position: change.to_position,
+            fields: change.fields.into_iter().map(FieldChangeDto::from).collect(),
+        }
+    }
+}
+
+impl From<ConfigChangeKind> for ConfigChangeKindDto {
+    fn from(kind: ConfigChangeKind) -> Self {
+        match kind {
+            ConfigChangeKind::Added => ConfigChangeKindDto::Added,
+            ConfigChangeKind::Removed => ConfigChangeKindDto::Removed,
+            ConfigChangeKind::Changed => ConfigChangeKindDto::Changed,
+            ConfigChangeKind::Moved => ConfigChangeKindDto::Moved,
+        }
+    }
+}
+
+impl From<FieldChange> for FieldChangeDto {
+    fn from(field: FieldChange) -> Self {
+        FieldChangeDto { field: field.field, from: field.from, to: field.to }
+    }
+}
+
+/// The changes of the active configuration since a draft was created, or last rebased.
+/// The `conflicts` are the changes that cannot be merged automatically into the draft.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct DraftRebaseDto {
+    pub outdated: bool,
+    pub conflicts: Vec<MergeConflictDto>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct MergeConflictDto {
+    pub node_path: Vec<String>,
+    pub rule: Option<String>,
+    pub kind: MergeConflictKindDto,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub enum MergeConflictKindDto {
+    BothChanged,
+    BothAdded,
+    RemovedInActive,
+    RemovedInDraft,
+}
+
+impl From<DraftRebase> for DraftRebaseDto {
+    fn from(rebase: DraftRebase) -> Self {
+        DraftRebaseDto {
+            outdated: rebase.outdated,
+            conflicts: rebase.conflicts.into_iter().map(MergeConflictDto::from).collect(),
+        }
+    }
+}
+
+impl From<MergeConflict> for MergeConflictDto {
+    fn from(conflict: MergeConflict) -> Self {
+        MergeConflictDto {
+            node_path: conflict.node_path,
+            rule: conflict.rule,
+            kind: conflict.kind.into(),
+        }
+    }
+}
+
+impl From<MergeConflictKind> for MergeConflictKindDto {
+    fn from(kind: MergeConflictKind) -> Self {
+        match kind {
+            MergeConflictKind::BothChanged => MergeConflictKindDto::BothChanged,
+            MergeConflictKind::BothAdded => MergeConflictKindDto::BothAdded,
+            MergeConflictKind::RemovedInActive => MergeConflictKindDto::RemovedInActive,
+            MergeConflictKind::RemovedInDraft => MergeConflictKindDto::RemovedInDraft,
+        }
+    }
+}
+
+/// An error that prevents a configuration from being deployed. The `node_path` contains the
+/// names of the nodes starting from the root; if the error concerns a rule, it is the path
+/// of its ruleset. The `pointer` is the JSON pointer of the wrong entry, relative to the rule
+/// if present, otherwise to the node.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct ConfigValidationErrorDto {
+    pub node_path: Vec<String>,
+    pub rule: Option<String>,
+    pub pointer: String,
+    pub message: String,
+}
+
+impl From<ConfigValidationError> for ConfigValidationErrorDto {
+    fn from(error: ConfigValidationError) -> Self {
+        ConfigValidationErrorDto {
+            node_path: error.node_path,
+            rule: error.rule,
+            pointer: error.pointer,
+            message: error.message,
+        }
+    }
+}
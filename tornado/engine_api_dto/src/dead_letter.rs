@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typescript_definitions::TypeScriptify;
+
+/// An action of the dead-letter queue, without its payload
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct DeadLetterSummaryDto {
+    pub id: u64,
+    pub action_id: String,
+    pub error: String,
+    pub attempts: u32,
+    pub first_attempt_epoch_ms: i64,
+    pub last_attempt_epoch_ms: i64,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct DeadLetterDto {
+    pub id: u64,
+    pub action_id: String,
+    pub action_payload: Value,
+    pub action_created_ms: u64,
+    pub error: String,
+    pub attempts: u32,
+    pub first_attempt_epoch_ms: i64,
+    pub last_attempt_epoch_ms: i64,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct PurgeDeadLettersResponseDto {
+    pub purged: usize,
+}
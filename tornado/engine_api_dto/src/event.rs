@@ -9,6 +9,8 @@ use typescript_definitions::TypeScriptify;
 pub struct SendEventRequestDto {
     pub process_type: ProcessType,
     pub event: EventDto,
+    #[serde(default)]
+    pub explain: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize, TypeScriptify)]
@@ -26,7 +28,7 @@ pub struct EventDto {
     pub metadata: HashMap<String, Value>,
     pub payload: HashMap<String, Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub iterator: Option<EventIteratorDataDto>
+    pub iterator: Option<EventIteratorDataDto>,
 }
 
 #[derive(Clone, Serialize, Deserialize, TypeScriptify)]
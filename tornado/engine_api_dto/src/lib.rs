@@ -2,5 +2,6 @@ pub mod auth;
 pub mod auth_v2;
 pub mod common;
 pub mod config;
+pub mod dead_letter;
 pub mod event;
 pub mod runtime_config;
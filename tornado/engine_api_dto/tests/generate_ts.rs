@@ -131,7 +131,29 @@ export type Value = any;"#;
     push_ts(&mut ts_code, &config::ProcessingTreeNodeDetailsDto::type_script_ify());
     push_ts(&mut ts_code, &config::RuleDetailsDto::type_script_ify());
     push_ts(&mut ts_code, &config::TreeInfoDto::type_script_ify());
+    push_ts(&mut ts_code, &config::ProcessingTreeNodeStatsDto::type_script_ify());
+    push_ts(&mut ts_code, &config::NodeStatsDto::type_script_ify());
+    push_ts(&mut ts_code, &config::RuleStatsDto::type_script_ify());
     push_ts(&mut ts_code, &config::RulePositionDto::type_script_ify());
+    push_ts(&mut ts_code, &config::ConfigChangeDto::type_script_ify());
+    push_ts(&mut ts_code, &config::ConfigChangeKindDto::type_script_ify());
+    push_ts(&mut ts_code, &config::FieldChangeDto::type_script_ify());
+    push_ts(&mut ts_code, &config::DraftRebaseDto::type_script_ify());
+    push_ts(&mut ts_code, &config::MergeConflictDto::type_script_ify());
+    push_ts(&mut ts_code, &config::MergeConflictKindDto::type_script_ify());
+    push_ts(&mut ts_code, &config::ConfigValidationErrorDto::type_script_ify());
+
+    // Push 'dead_letter' ts types
+    push_ts(
+        &mut ts_code,
+        r#"
+/* ------------------- */
+/* 'dead_letter' types */
+/* ------------------- */"#,
+    );
+    push_ts(&mut ts_code, &dead_letter::DeadLetterDto::type_script_ify());
+    push_ts(&mut ts_code, &dead_letter::DeadLetterSummaryDto::type_script_ify());
+    push_ts(&mut ts_code, &dead_letter::PurgeDeadLettersResponseDto::type_script_ify());
 
     // Push 'event' ts types
     push_ts(
@@ -165,11 +187,28 @@ export type Value = any;"#;
 /* 'matcher' types   */
 /* ---------------- */"#,
     );
+    push_ts(&mut ts_code, &matcher::model::ActionConditionMetaData::type_script_ify());
+    push_ts(&mut ts_code, &matcher::model::RuleExplanation::type_script_ify());
+    push_ts(&mut ts_code, &matcher::model::OperatorExplanation::type_script_ify());
+    push_ts(&mut ts_code, &matcher::model::ExtractorExplanation::type_script_ify());
+    push_ts(&mut ts_code, &matcher::model::ModifierExplanation::type_script_ify());
     push_ts(&mut ts_code, &matcher::model::ActionMetaData::type_script_ify());
     push_ts(&mut ts_code, &matcher::model::EnrichedValue::type_script_ify());
     push_ts(&mut ts_code, &matcher::model::EnrichedValueContent::type_script_ify());
     push_ts(&mut ts_code, &matcher::model::ProcessedRuleMetaData::type_script_ify());
     push_ts(&mut ts_code, &matcher::model::ValueMetaData::type_script_ify());
+    push_ts(&mut ts_code, &matcher::validator::warnings::ConfigWarning::type_script_ify());
+    push_ts(&mut ts_code, &matcher::validator::warnings::ConfigWarningKind::type_script_ify());
+    push_ts(&mut ts_code, &matcher::replay::ReplayReport::type_script_ify());
+    push_ts(&mut ts_code, &matcher::replay::EventReplayDiff::type_script_ify());
+    push_ts(&mut ts_code, &matcher::replay::RuleReplayDiff::type_script_ify());
+    push_ts(&mut ts_code, &matcher::replay::ActionReplayDiff::type_script_ify());
+    push_ts(&mut ts_code, &matcher::replay::ReplayChange::type_script_ify());
+    push_ts(&mut ts_code, &matcher::replay::ReplayLineError::type_script_ify());
+    push_ts(&mut ts_code, &matcher::shadow::ShadowReport::type_script_ify());
+    push_ts(&mut ts_code, &matcher::shadow::ShadowActionStats::type_script_ify());
+    push_ts(&mut ts_code, &matcher::shadow::ShadowEventDiff::type_script_ify());
+    push_ts(&mut ts_code, &matcher::config::DeployedConfigData::type_script_ify());
 
     // Push 'runtime_config' ts types
     push_ts(
@@ -39,7 +39,7 @@ export type UserPreferences = { language: string | null };
 /* 'config' types */
 /* -------------- */
 
-export type ActionDto = { id: string; payload: Value };
+export type ActionDto = { id: string; payload: Value; when: OperatorDto | null };
 
 export type ConstraintDto = { WHERE: OperatorDto | null; WITH: { [key: string]: ExtractorDto } };
 
@@ -52,7 +52,7 @@ export type ExtractorRegexDto =
 
 export type FilterDto = { description: string; active: boolean; filter: OperatorDto | null };
 
-export type MatcherConfigDraftDataDto = {     user: string; created_ts_ms: number; updated_ts_ms: number; draft_id:     string };
+export type MatcherConfigDraftDataDto = {     user: string; created_ts_ms: number; updated_ts_ms: number; draft_id:     string; base_version: string | null };
 
 export type ModifierDto = 
  | { type: "Lowercase" } 
@@ -98,8 +98,50 @@ export type RuleDetailsDto = {     name: string; description: string; continue:
 
 export type TreeInfoDto = { rules_count: number; filters_count: number; iterators_count: number };
 
+// A processing tree node of the active configuration with the statistics collected
+// since the Tornado startup. The rules are populated only for Ruleset nodes.
+export type ProcessingTreeNodeStatsDto = {     node: ProcessingTreeNodeConfigDto; stats: NodeStatsDto; rules:     RuleStatsDto [] };
+
+export type NodeStatsDto = {     evaluated: number; matched: number; last_matched_epoch_ms: number |     null };
+
+export type RuleStatsDto = {     name: string; evaluated: number; matched: number; partially_matched:     number; actions_emitted: number; last_matched_epoch_ms: number | null };
+
 export type RulePositionDto = { position: number };
 
+// A node, or a rule of the ruleset at `node_path` when `rule` is set, that differs
+// between two versions of the processing tree.
+export type ConfigChangeDto = {     node_path: string []; rule: string | null; change: ConfigChangeKindDto;     moved_from: string [] | null; from_position: number | null; to_position: number | null; fields: FieldChangeDto [] };
+
+export enum ConfigChangeKindDto { Added = "Added", Removed = "Removed", Changed = "Changed", Moved = "Moved" };
+
+export type FieldChangeDto = { field: string; from: Value | null; to: Value | null };
+
+// The changes of the active configuration since a draft was created, or last rebased.
+// The `conflicts` are the changes that cannot be merged automatically into the draft.
+export type DraftRebaseDto = { outdated: boolean; conflicts: MergeConflictDto [] };
+
+export type MergeConflictDto = { node_path: string []; rule: string | null; kind: MergeConflictKindDto };
+
+export enum MergeConflictKindDto {     BothChanged = "BothChanged", BothAdded = "BothAdded", RemovedInActive =     "RemovedInActive", RemovedInDraft = "RemovedInDraft" };
+
+// An error that prevents a configuration from being deployed. The `node_path` contains the
+// names of the nodes starting from the root; if the error concerns a rule, it is the path
+// of its ruleset. The `pointer` is the JSON pointer of the wrong entry, relative to the rule
+// if present, otherwise to the node.
+export type ConfigValidationErrorDto = {     node_path: string []; rule: string | null; pointer: string; message:     string };
+
+
+/* ------------------- */
+/* 'dead_letter' types */
+/* ------------------- */
+
+export type DeadLetterDto = {     id: number; action_id: string; action_payload: Value; action_created_ms: number; error: string; attempts: number; first_attempt_epoch_ms:     number; last_attempt_epoch_ms: number };
+
+// An action of the dead-letter queue, without its payload
+export type DeadLetterSummaryDto = {     id: number; action_id: string; error: string; attempts: number;     first_attempt_epoch_ms: number; last_attempt_epoch_ms: number };
+
+export type PurgeDeadLettersResponseDto = { purged: number };
+
 
 /* ------------- */
 /* 'event' types */
@@ -138,13 +180,34 @@ export type ProcessedRulesDto = { rules: ProcessedRuleDto []; extracted_vars: Va
 
 export enum ProcessedRuleStatusDto {     Matched = "Matched", PartiallyMatched = "PartiallyMatched", NotMatched =     "NotMatched", NotProcessed = "NotProcessed" };
 
-export type SendEventRequestDto = { process_type: ProcessType; event: EventDto };
+export type SendEventRequestDto = { process_type: ProcessType; event: EventDto; explain: boolean };
 
 
 /* ---------------- */
 /* 'matcher' types   */
 /* ---------------- */
 
+// The outcome of the `when` condition of a rule's action.
+// The index is the position of the action in the rule's actions list.
+export type ActionConditionMetaData = { index: number; id: string; matched: boolean };
+
+// The evaluation trace of a rule, produced only when an event is processed in explain mode.
+// The extractors are evaluated only if the rule's operator matches.
+export type RuleExplanation = { operator: OperatorExplanation; extractors: ExtractorExplanation [] };
+
+// The evaluation trace of an operator.
+// The operands are the values the operator arguments resolved to, `null` if they could not be resolved;
+// the operators are the traces of the nested operators, if any.
+export type OperatorExplanation = {     operator: string; result: boolean; operands: Value []; operators:     OperatorExplanation [] };
+
+// The evaluation trace of the extractor of a variable.
+// The input is the value read from the `from` field of the extractor, the modifiers contain the
+// output of each modifier of the chain in order of application.
+// If the variable cannot be extracted, `matched` is false and the message contains the reason.
+export type ExtractorExplanation = {     variable: string; input: Value; matched: boolean; value: Value | null;     modifiers: ModifierExplanation []; message: string | null };
+
+export type ModifierExplanation = { modifier: string; output: Value };
+
 export type ActionMetaData = { id: string; payload: { [key: string]: EnrichedValue } };
 
 export type EnrichedValue = { content: EnrichedValueContent; meta: ValueMetaData };
@@ -154,10 +217,54 @@ export type EnrichedValueContent =
  | { type: "Map"; content: { [key: string]: EnrichedValue } } 
  | { type: "Array"; content: EnrichedValue [] };
 
-export type ProcessedRuleMetaData = { actions: ActionMetaData [] };
+export type ProcessedRuleMetaData = {     actions: ActionMetaData []; action_conditions: ActionConditionMetaData     []; explain: RuleExplanation | null };
 
 export type ValueMetaData = { modified: boolean; is_leaf: boolean };
 
+// A finding of the static analysis. The path contains the names of the nodes starting
+// from the root and, for rules, the rule name.
+export type ConfigWarning = { path: string []; kind: ConfigWarningKind; message: string };
+
+export enum ConfigWarningKind {     UnreachableRule = "UnreachableRule", UnreachableNode = "UnreachableNode",     ContradictoryCondition = "ContradictoryCondition", InactiveParent =     "InactiveParent" };
+
+// The outcome of a replay. `changed_events` and `invalid_lines` count all the events with
+// a different outcome and all the invalid lines, while only the first
+// MAX_REPORTED_DIFFERENCES of them are reported in `differences` and `line_errors`.
+export type ReplayReport = {     replayed_events: number; changed_events: number; invalid_lines: number;     differences: EventReplayDiff []; line_errors: ReplayLineError [] };
+
+// The differences produced by an event. The line is the position of the event in its source,
+// starting from 1.
+export type EventReplayDiff = {     line: number; event: Value; rules: RuleReplayDiff []; actions:     ActionReplayDiff [] };
+
+// A rule that is matched by the event with only one of the two configurations.
+export type RuleReplayDiff = { ruleset_path: string []; rule: string; change: ReplayChange };
+
+// An action produced by only one of the two configurations, or with a different payload.
+export type ActionReplayDiff = {     ruleset_path: string []; rule: string; action_id: string; change:     ReplayChange; active_payload: Value | null; draft_payload: Value | null };
+
+// The kind of difference between the outcome of the active configuration and the one of the draft.
+export enum ReplayChange { Added = "Added", Removed = "Removed", Changed = "Changed" };
+
+// A line that could not be replayed because it does not contain a valid event.
+export type ReplayLineError = { line: number; message: string };
+
+// The comparison of the draft running in shadow with the active configuration since it was started.
+// The skipped events are the ones not processed by the draft because it could not keep up
+// with the live traffic.
+export type ShadowReport = {     draft_id: string; started_epoch_ms: number; processed_events: number;     changed_events: number; skipped_events: number; actions:     ShadowActionStats []; recent_differences: ShadowEventDiff [] };
+
+// The counters of an action. `active` and `shadow` count the times the action was produced
+// by the active configuration and by the draft; `changed` counts the times it was produced
+// by both with a different payload.
+export type ShadowActionStats = {     ruleset_path: string []; rule: string; action_id: string; active:     number; shadow: number; changed: number };
+
+// The differences produced by a live event.
+export type ShadowEventDiff = {     received_epoch_ms: number; event: Value; rules: RuleReplayDiff [];     actions: ActionReplayDiff [] };
+
+// The details of a deployment. `user` and `draft_id` are not set when the configuration
+// was not deployed from a draft; `rollback_of` is set when it was restored from a previous version.
+export type DeployedConfigData = {     version_id: string; deployed_ts_ms: number; user: string | null;     draft_id: string | null; rollback_of: string | null };
+
 
 /* -------------- */
 /* 'runtime_config' types */